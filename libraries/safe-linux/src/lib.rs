@@ -16,6 +16,8 @@ extern crate alloc;
 pub mod aio;
 pub mod errno;
 #[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+pub mod mman;
+#[cfg(all(target_os = "linux", target_arch = "x86_64"))]
 pub mod sys;
 #[cfg(all(target_os = "linux", target_arch = "x86_64"))]
 pub mod syscall;
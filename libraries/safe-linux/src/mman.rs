@@ -0,0 +1,209 @@
+// SPDX-License-Identifier: MIT
+// Copyright 2025 IROX Contributors
+//
+
+//!
+//! Syscalls from `mman.h`, memory mapping.
+
+use crate::errno::Errno;
+use crate::syscall::{syscall_x64_2, syscall_x64_3, syscall_x64_6};
+
+pub const SYSCALL_MMAP: u64 = 9;
+pub const SYSCALL_MUNMAP: u64 = 11;
+pub const SYSCALL_MADVISE: u64 = 28;
+
+/// Pages may be read
+pub const PROT_READ: u64 = 0x1;
+/// Pages may be written
+pub const PROT_WRITE: u64 = 0x2;
+
+/// Changes are visible to other mappings of the file, and carried through to it
+pub const MAP_SHARED: u64 = 0x01;
+/// Copy-on-write mapping, changes aren't visible to other mappings or carried through to the file
+pub const MAP_PRIVATE: u64 = 0x02;
+/// Not backed by a file, the contents are zeroed
+pub const MAP_ANONYMOUS: u64 = 0x20;
+/// Prefault the page tables, for a file this reads ahead the whole mapping
+pub const MAP_POPULATE: u64 = 0x8000;
+
+/// No special treatment
+pub const MADV_NORMAL: u64 = 0;
+/// Expect page references in random order
+pub const MADV_RANDOM: u64 = 1;
+/// Expect page references in sequential order, read ahead aggressively
+pub const MADV_SEQUENTIAL: u64 = 2;
+/// Expect access in the near future
+pub const MADV_WILLNEED: u64 = 3;
+
+fn check(ret: u64) -> Result<u64, Errno> {
+    let ret = ret as i64;
+    if ret < 0 {
+        return Err(ret.into());
+    }
+    Ok(ret as u64)
+}
+
+///
+/// Linux `mmap` Syscall, maps `length` bytes of the file `fd` from `offset` into memory, or
+/// anonymous memory with [`MAP_ANONYMOUS`].  Returns the address of the mapping.
+///
+/// # Safety
+/// The mapping is outside of Rust's ownership rules, see the man page for the many ways it can
+/// alias or vanish.
+pub unsafe fn mmap(
+    addr: *mut u8,
+    length: usize,
+    prot: u64,
+    flags: u64,
+    fd: i32,
+    offset: u64,
+) -> Result<*mut u8, Errno> {
+    let ret = syscall_x64_6(
+        SYSCALL_MMAP,
+        addr as u64,
+        length as u64,
+        prot,
+        flags,
+        fd as u64,
+        offset,
+    );
+    Ok(check(ret)? as *mut u8)
+}
+
+///
+/// Linux `munmap` Syscall, removes the mappings covering `length` bytes from `addr`
+///
+/// # Safety
+/// Nothing may reference the unmapped memory afterward.
+pub unsafe fn munmap(addr: *mut u8, length: usize) -> Result<(), Errno> {
+    check(syscall_x64_2(SYSCALL_MUNMAP, addr as u64, length as u64))?;
+    Ok(())
+}
+
+///
+/// Linux `madvise` Syscall, hints how the `length` bytes from `addr` will be used
+///
+/// # Safety
+/// `addr` must be page aligned.  Most advice is harmless, but some (like `MADV_DONTNEED`) changes
+/// the contents of the memory.
+pub unsafe fn madvise(addr: *mut u8, length: usize, advice: u64) -> Result<(), Errno> {
+    check(syscall_x64_3(
+        SYSCALL_MADVISE,
+        addr as u64,
+        length as u64,
+        advice,
+    ))?;
+    Ok(())
+}
+
+///
+/// A read-only, private mapping of a whole file, unmapped on drop.
+pub struct MappedFile {
+    addr: *mut u8,
+    len: usize,
+}
+
+impl MappedFile {
+    ///
+    /// Maps the first `len` bytes of the open file `fd`, usually its whole length, and advises
+    /// the kernel it'll be read sequentially.  An empty file maps to an empty slice.
+    ///
+    /// # Safety
+    /// The file mustn't be modified or truncated while it's mapped.  Changes would show through
+    /// the slice this hands out, and reading past a truncated end raises `SIGBUS`.
+    pub unsafe fn map_readonly(fd: i32, len: usize) -> Result<Self, Errno> {
+        if len == 0 {
+            return Ok(Self {
+                addr: core::ptr::null_mut(),
+                len,
+            });
+        }
+        let addr = mmap(core::ptr::null_mut(), len, PROT_READ, MAP_PRIVATE, fd, 0)?;
+        // purely a hint, the mapping works the same without it.
+        let _ = madvise(addr, len, MADV_SEQUENTIAL);
+        Ok(Self { addr, len })
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    #[must_use]
+    pub fn as_slice(&self) -> &[u8] {
+        if self.addr.is_null() {
+            return &[];
+        }
+        unsafe { core::slice::from_raw_parts(self.addr, self.len) }
+    }
+}
+
+impl AsRef<[u8]> for MappedFile {
+    fn as_ref(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
+
+impl Drop for MappedFile {
+    fn drop(&mut self) {
+        if !self.addr.is_null() {
+            unsafe {
+                let _ = munmap(self.addr, self.len);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mman::*;
+    use std::io::Write;
+    use std::os::fd::AsRawFd;
+
+    #[test]
+    pub fn test_anonymous() {
+        let len = 3 * 4096;
+        let addr = unsafe {
+            mmap(
+                core::ptr::null_mut(),
+                len,
+                PROT_READ | PROT_WRITE,
+                MAP_PRIVATE | MAP_ANONYMOUS,
+                -1,
+                0,
+            )
+        };
+        assert!(addr.is_ok(), "{addr:?}");
+        let Ok(addr) = addr else {
+            return;
+        };
+        let mem = unsafe { core::slice::from_raw_parts_mut(addr, len) };
+        assert!(mem.iter().all(|v| *v == 0));
+        mem.fill(0xA5);
+        assert!(unsafe { munmap(addr, len) }.is_ok());
+    }
+
+    #[test]
+    pub fn test_map_file() {
+        let path = std::env::temp_dir().join(format!("irox-mman-{}", std::process::id()));
+        let data: Vec<u8> = (0..10_000u32).map(|v| (v % 251) as u8).collect();
+        let written = std::fs::File::create(&path).and_then(|mut f| f.write_all(&data));
+        assert!(written.is_ok(), "{written:?}");
+        let file = std::fs::File::open(&path);
+        let _ = std::fs::remove_file(&path);
+        assert!(file.is_ok(), "{file:?}");
+        let Ok(file) = file else {
+            return;
+        };
+
+        let map = unsafe { MappedFile::map_readonly(file.as_raw_fd(), data.len()) };
+        assert!(map.as_ref().is_ok_and(|m| m.as_slice() == data.as_slice()));
+        let empty = unsafe { MappedFile::map_readonly(file.as_raw_fd(), 0) };
+        assert!(empty.is_ok_and(|m| m.is_empty()));
+    }
+}
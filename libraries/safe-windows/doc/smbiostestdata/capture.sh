@@ -0,0 +1,16 @@
+#!/bin/bash
+# SPDX-License-Identifier: MIT
+# Copyright 2025 IROX Contributors
+#
+
+# Captures this machine's SMBIOS tables as a test fixture, along with what dmidecode makes of
+# them.  The report tests compare every fixture directory with a dmidecode.txt against it.
+#   usage: sudo ./capture.sh <name>, such as qemu-8.2-q35
+set -e
+name=${1:?usage: $0 <name>}
+dir=$(dirname "$0")/$name
+mkdir -p "$dir"
+cp /sys/firmware/dmi/tables/smbios_entry_point /sys/firmware/dmi/tables/DMI "$dir"/
+dmidecode --dump-bin "$dir"/dump.bin > /dev/null
+dmidecode --from-dump "$dir"/dump.bin > "$dir"/dmidecode.txt
+dmidecode --version > "$dir"/dmidecode.version
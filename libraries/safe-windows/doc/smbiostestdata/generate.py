@@ -0,0 +1,202 @@
+#!/usr/bin/env python3
+# SPDX-License-Identifier: MIT
+# Copyright 2025 IROX Contributors
+#
+
+"""
+Writes the synthetic SMBIOS fixtures next to this script, laid out like linux's
+/sys/firmware/dmi/tables (smbios_entry_point and DMI) plus the dump.bin dmidecode --dump-bin
+would write for them.  They aren't captures of real machines: every structure, string and
+handle is made up here to exercise the decoders, so keep the values plausible for the
+spec version the table claims and regenerate rather than editing the binaries.  Tables from
+real firmware are captured with capture.sh instead, and checked against dmidecode's output.
+
+  * synthetic-qemu-2.8: a 2.8 table shaped like what QEMU/OVMF reports.
+  * synthetic-desktop-3.4: a 3.4 table shaped like a desktop with two DIMMs, expansion slots,
+    onboard devices, IPMI and an OEM structure.
+"""
+
+import os
+import struct
+import uuid
+
+
+def u8(v): return struct.pack('<B',v)
+def u16(v): return struct.pack('<H',v)
+def u32(v): return struct.pack('<I',v)
+def u64(v): return struct.pack('<Q',v)
+
+
+def structure(t, handle, body, strings=()):
+    fmt = u8(t) + u8(len(body)+4) + u16(handle) + body
+    if strings:
+        s = b''.join(x.encode()+b'\0' for x in strings) + b'\0'
+    else:
+        s = b'\0\0'
+    return fmt + s
+
+
+def uuid_bytes(u):
+    # smbios 2.6+ stores the first three fields little endian
+    b = uuid.UUID(u).bytes
+    return b[0:4][::-1] + b[4:6][::-1] + b[6:8][::-1] + b[8:]
+
+
+def qemu():
+    s = []
+    # type 0, 2.4 layout, length 0x18
+    s.append(structure(0, 0x0000,
+        u8(1)+u8(2)+u16(0xE800)+u8(3)+u8(0)+u64(0x0000000000000008 | (1<<3))+u8(0)+u8(0x1C)+u8(0)+u8(0)+u8(0xFF)+u8(0xFF),
+        ["EFI Development Kit II / OVMF","0.0.0","02/06/2015"]))
+    # type 1, length 0x1B
+    s.append(structure(1, 0x0100,
+        u8(1)+u8(2)+u8(3)+u8(0)+uuid_bytes("6c1f7b34-0f0e-4a1e-9d73-2b6e38c4a0d1")+u8(6)+u8(0)+u8(0),
+        ["QEMU","Standard PC (Q35 + ICH9, 2009)","pc-q35-8.2"]))
+    # type 3, length 0x16
+    s.append(structure(3, 0x0300,
+        u8(1)+u8(1)+u8(2)+u8(0)+u8(0)+u8(3)+u8(3)+u8(3)+u8(2)+u32(0)+u8(0)+u8(0)+u8(0)+u8(0)+u8(0),
+        ["QEMU","pc-q35-8.2"]))
+    # type 4, 2.6 layout, length 0x2A
+    s.append(structure(4, 0x0400,
+        u8(1)+u8(3)+u8(0x01)+u8(2)+u64(0x078BFBFF00A00F11)+u8(3)+u8(0)+u16(0)+u16(2000)+u16(2000)+u8(0x41)+u8(1)
+        +u16(0xFFFF)+u16(0xFFFF)+u16(0xFFFF)+u8(0)+u8(0)+u8(0)+u8(4)+u8(4)+u8(4)+u16(0x0002)+u16(0x0001),
+        ["CPU 0","QEMU","pc-q35-8.2"]))
+    # type 16, length 0x17
+    s.append(structure(16, 0x1000,
+        u8(1)+u8(3)+u8(6)+u32(8*1024*1024)+u16(0xFFFE)+u16(1)+u64(0)))
+    # type 17, 2.8 layout, length 0x28
+    s.append(structure(17, 0x1100,
+        u16(0x1000)+u16(0xFFFE)+u16(0xFFFF)+u16(0xFFFF)+u16(8192)+u8(9)+u8(0)+u8(1)+u8(0)+u8(7)+u16(0x0002)+u16(0)
+        +u8(2)+u8(0)+u8(0)+u8(0)+u8(0)+u32(0)+u16(0)+u16(0)+u16(0)+u16(0),
+        ["DIMM 0","QEMU"]))
+    # type 19, length 0x1F
+    s.append(structure(19, 0x1300,
+        u32(0)+u32(8*1024*1024-1)+u16(0x1000)+u8(1)+u64(0)+u64(0)))
+    # type 32, length 0x0B
+    s.append(structure(32, 0x2000, b'\0'*6 + u8(0)))
+    s.append(structure(127, 0x7F00, b''))
+    return s
+
+
+def desktop():
+    s = []
+    # type 0, 3.1 layout, length 0x1A
+    s.append(structure(0, 0x0000,
+        u8(1)+u8(2)+u16(0xF000)+u8(3)+u8(0xFF)+u64(0x7C0B9880)+u8(0x03)+u8(0x0D)+u8(5)+u8(24)+u8(0xFF)+u8(0xFF)+u16(0x0020),
+        ["Synthetic Firmware","1.24.0","04/17/2024"]))
+    # type 1, length 0x1B
+    s.append(structure(1, 0x0001,
+        u8(1)+u8(2)+u8(3)+u8(4)+uuid_bytes("8c2d5e71-4b09-4a3f-b6e8-1f97d0a2c45b")+u8(6)+u8(5)+u8(6),
+        ["Synthetic Systems","Desktop 7000","Rev 1.0","SYN7K-000123","SKU-7000-EU","Desktop"]))
+    # type 2 with two contained objects, length 0x13
+    s.append(structure(2, 0x0002,
+        u8(1)+u8(2)+u8(3)+u8(4)+u8(5)+u8(0x09)+u8(6)+u16(0x0003)+u8(0x0A)+u8(2)+u16(0x0004)+u16(0x0009),
+        ["Synthetic Systems","SYN7K-MB","A02","MB-0042","Not Specified","Default string"]))
+    # type 3, length 0x16
+    s.append(structure(3, 0x0003,
+        u8(1)+u8(0x83)+u8(2)+u8(3)+u8(4)+u8(3)+u8(3)+u8(3)+u8(3)+u32(0)+u8(4)+u8(1)+u8(0)+u8(0)+u8(5),
+        ["Synthetic Systems","1.0","CH-99812","Asset-0001","SKU-CH-7000"]))
+    # type 4, 3.0 layout, length 0x30.  The thread enabled count is 3.6, newer than the table
+    s.append(structure(4, 0x0004,
+        u8(1)+u8(3)+u8(0xFE)+u8(2)+u64(0xBFEBFBFF000906A3)+u8(3)+u8(0x80|0x0B)+u16(100)+u16(5200)+u16(3600)+u8(0x41)+u8(0x40)
+        +u16(0x0005)+u16(0x0006)+u16(0x0007)+u8(4)+u8(5)+u8(6)+u8(16)+u8(16)+u8(24)+u16(0x00FC)+u16(0x00CF)
+        +u16(16)+u16(16)+u16(24),
+        ["LGA1700","Intel(R) Corporation","12th Gen Intel(R) Core(TM) i9-12900","To Be Filled By O.E.M.","Unknown","Not Specified"]))
+    # types 7: L1, L2, L3, 3.1 layout, length 0x1B
+    for handle, name, cfg, size, ecc, ctype, assoc in [
+        (0x0005, "L1 Cache", 0x0180, 1280, 4, 5, 7),
+        (0x0006, "L2 Cache", 0x0181, 14336, 5, 5, 8),
+        (0x0007, "L3 Cache", 0x0182, 30720, 6, 5, 9)]:
+        s.append(structure(7, handle,
+            u8(1)+u16(cfg)+u16(size if size < 0x8000 else 0x8000|(size//64))+u16(size if size < 0x8000 else 0x8000|(size//64))
+            +u16(0x0020)+u16(0x0020)+u8(0)+u8(ecc)+u8(ctype)+u8(assoc)+u32(size)+u32(size),
+            [name]))
+    # type 9, 3.2 layout with no peer groups, length 0x13
+    s.append(structure(9, 0x0008,
+        u8(1)+u8(0xB6)+u8(0x0D)+u8(3)+u8(4)+u16(1)+u8(0x0C)+u8(0x01)+u16(0)+u8(0x01)+u8(0x00)+u8(0x0D)+u8(0),
+        ["PCIEX16_1"]))
+    # type 9, length 0x13
+    s.append(structure(9, 0x0009,
+        u8(1)+u8(0xBB)+u8(0x0A)+u8(4)+u8(3)+u16(2)+u8(0x0C)+u8(0x01)+u16(0)+u8(0x02)+u8(0x00)+u8(0x0B)+u8(0),
+        ["PCIEX4_1"]))
+    # type 16, length 0x17, extended capacity
+    s.append(structure(16, 0x000A,
+        u8(3)+u8(3)+u8(3)+u32(0x80000000)+u16(0xFFFE)+u16(2)+u64(128*1024*1024*1024)))
+    # types 17, 3.3 layout, length 0x5C
+    for handle, loc, bank, serial, size in [(0x000B,"DIMM_A1","BANK 0","0A1B2C3D",16384),(0x000C,"DIMM_B1","BANK 1","0A1B2C3E",16384)]:
+        s.append(structure(17, handle,
+            u16(0x000A)+u16(0xFFFE)+u16(64)+u16(64)+u16(size)+u8(0x09)+u8(0)+u8(1)+u8(2)+u8(0x22)+u16(0x0080)+u16(4800)
+            +u8(3)+u8(4)+u8(5)+u8(6)+u8(0x02)+u32(0)+u16(4800)+u16(1100)+u16(1100)+u16(1100)
+            +u8(3)+u16(0x0008)+u8(7)+u16(0x2C80)+u16(0)+u16(0)+u16(0)+u64(0)+u64(size*1024*1024)+u64(0)+u64(0)
+            +u32(0)+u32(0),
+            [loc,bank,"Micron Technology",serial,"Not Specified","MTC8C1084S1SC48BA1","Not Specified"]))
+    # type 19, length 0x1F
+    s.append(structure(19, 0x000D,
+        u32(0)+u32(32*1024*1024-1)+u16(0x000A)+u8(2)+u64(0)+u64(0)))
+    # type 32, length 0x0B
+    s.append(structure(32, 0x000E, b'\0'*6 + u8(0)))
+    # type 38, length 0x12
+    s.append(structure(38, 0x000F,
+        u8(1)+u8(0x20)+u8(0x20)+u8(0xFF)+u64(0xCA3)+u8(0x00)+u8(0)))
+    # types 41, length 0x0B
+    s.append(structure(41, 0x0010,
+        u8(1)+u8(0x85)+u8(1)+u16(0)+u8(0)+u8(0xFE),
+        ["Onboard LAN"]))
+    s.append(structure(41, 0x0011,
+        u8(1)+u8(0x87)+u8(1)+u16(0)+u8(0)+u8(0xF8),
+        ["Onboard Audio"]))
+    # an OEM structure, which has no decoder
+    s.append(structure(0xDD, 0x0012, bytes([0x05, 0x01, 0x02, 0x03]), ["OEM"]))
+    s.append(structure(127, 0x0013, b''))
+    return s
+
+
+def checksum(b):
+    return (-sum(b)) & 0xFF
+
+
+def ep2(structs, major, minor, addr):
+    table = b''.join(structs)
+    inter = bytearray(b'_DMI_' + u8(0) + u16(len(table)) + u32(addr) + u16(len(structs)) + u8((major<<4)|minor))
+    inter[5] = checksum(inter)
+    ep = bytearray(b'_SM_' + u8(0) + u8(0x1F) + u8(major) + u8(minor) + u16(max(len(x) for x in structs)) + u8(0) + b'\0'*5) + inter
+    ep[4] = checksum(ep)
+    assert len(ep) == 0x1F
+    return bytes(ep), table
+
+
+def ep3(structs, major, minor, docrev, addr):
+    table = b''.join(structs)
+    ep = bytearray(b'_SM3_' + u8(0) + u8(0x18) + u8(major) + u8(minor) + u8(docrev) + u8(1) + u8(0) + u32(len(table)) + u64(addr))
+    ep[5] = checksum(ep)
+    assert len(ep) == 0x18
+    return bytes(ep), table
+
+
+def dump_bin(ep, table):
+    # as dmidecode --dump-bin writes it: the entry point pointing at offset 32, then the table.
+    ep = bytearray(ep)
+    if ep.startswith(b'_SM3_'):
+        ep[0x10:0x18] = u64(32)
+        ep[5] = 0
+        ep[5] = checksum(ep[:ep[6]])
+    else:
+        ep[0x18:0x1C] = u32(32)
+        ep[0x15] = 0
+        ep[0x15] = checksum(ep[0x10:0x1F])
+        ep[4] = 0
+        ep[4] = checksum(ep[:ep[5]])
+    return bytes(ep).ljust(32, b'\0') + table
+
+
+out = os.path.dirname(os.path.abspath(__file__))
+for name, (ep, table) in {
+    'synthetic-qemu-2.8': ep2(qemu(), 2, 8, 0x000F5A90),
+    'synthetic-desktop-3.4': ep3(desktop(), 3, 4, 0, 0x7AE3F000),
+}.items():
+    d = os.path.join(out, name)
+    os.makedirs(d, exist_ok=True)
+    open(os.path.join(d, 'smbios_entry_point'), 'wb').write(ep)
+    open(os.path.join(d, 'DMI'), 'wb').write(table)
+    open(os.path.join(d, 'dump.bin'), 'wb').write(dump_bin(ep, table))
+    print(name, len(ep), len(table))
@@ -2,34 +2,122 @@
 // Copyright 2023 IROX Contributors
 //
 
-#![allow(clippy::print_stdout)]
+//!
+//! Dumps every SMBIOS structure the way `dmidecode` prints them, or as JSON.  Like `dmidecode`,
+//! the tables can be read from and written to a binary dump file, so reports from other
+//! machines can be reproduced anywhere.
+//!
+
+#![allow(clippy::print_stderr)]
 
-use irox::tools::hex::HexDump;
 use irox_safe_windows::error::Error;
-#[cfg(windows)]
-use irox_safe_windows::smbios::{read_next_table, read_raw_smbios_tables, SMBIOSHeader};
-use irox_structs::Struct;
+use irox_safe_windows::smbios::{read_dump_file, write_dump, SmbiosReport};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::process::ExitCode;
+
+const NAME: &str = "smbios";
+
+const USAGE: &str = "\
+Usage: smbios [OPTION]...
+Print the SMBIOS tables of this machine, or of a dump file.
+
+  -j, --json            print JSON rather than dmidecode-style text
+      --from-dump FILE  read the tables from a binary dump FILE rather than the firmware
+      --dump-bin FILE   write the tables to a binary dump FILE rather than printing them
+  -h, --help            print this help and exit
+";
+
+#[derive(Default)]
+struct Options {
+    json: bool,
+    from_dump: Option<String>,
+    dump_bin: Option<String>,
+}
 
-#[cfg(windows)]
-fn main() -> Result<(), Error> {
-    let mut tables = read_raw_smbios_tables();
-    let header = SMBIOSHeader::parse_from(&mut tables)?;
+enum Parsed {
+    Run(Options),
+    Help,
+}
+
+fn parse_args<I: Iterator<Item = String>>(mut args: I) -> Result<Parsed, String> {
+    let mut opts = Options::default();
+    while let Some(arg) = args.next() {
+        let (flag, inline) = match arg.split_once('=') {
+            Some((flag, value)) if arg.starts_with("--") => (flag, Some(value.to_string())),
+            _ => (arg.as_str(), None),
+        };
+        let mut value = |name: &str| -> Result<String, String> {
+            inline
+                .clone()
+                .or_else(|| args.next())
+                .ok_or_else(|| format!("option '{name}' requires an argument"))
+        };
+        match flag {
+            "-h" | "--help" => return Ok(Parsed::Help),
+            "-j" | "--json" => opts.json = true,
+            "--from-dump" => opts.from_dump = Some(value(flag)?),
+            "--dump-bin" => opts.dump_bin = Some(value(flag)?),
+            f => return Err(format!("unrecognized option '{f}'")),
+        }
+    }
+    Ok(Parsed::Run(opts))
+}
 
-    println!("Header: {header:#?}");
-    let first = read_next_table(&mut tables)?;
-    println!("first table: {first:#?}");
-    let second = read_next_table(&mut tables)?;
-    println!("second table: {second:#?}");
-    let third = read_next_table(&mut tables)?;
-    println!("second table: {third:#?}");
+#[cfg(any(windows, target_os = "linux"))]
+fn read_tables() -> Result<Vec<u8>, Error> {
+    irox_safe_windows::smbios::read_raw_smbios_tables()
+}
+
+#[cfg(not(any(windows, target_os = "linux")))]
+fn read_tables() -> Result<Vec<u8>, Error> {
+    Error::invalid_data("reading the firmware's tables is only supported on windows and linux")
+}
 
-    tables.hexdump();
+fn run(opts: &Options) -> Result<(), Error> {
+    let raw = match &opts.from_dump {
+        Some(path) => read_dump_file(path)?,
+        None => read_tables()?,
+    };
 
+    let mut out = BufWriter::new(std::io::stdout().lock());
+    if let Some(path) = &opts.dump_bin {
+        let mut dump = Vec::new();
+        write_dump(&raw, &mut dump)?;
+        writeln!(out, "# Writing {} bytes to {path}.", dump.len())?;
+        File::create(path)?.write_all(&dump)?;
+    } else {
+        let report = SmbiosReport::parse_from(&raw)?;
+        if opts.json {
+            report.write_json(&mut out)?;
+        } else {
+            report.write_text(&mut out)?;
+        }
+    }
+    out.flush()?;
     Ok(())
 }
 
-#[cfg(not(windows))]
-#[allow(clippy::print_stderr)]
-pub fn main() {
-    eprintln!("This example only supported on windows targets.");
+fn main() -> ExitCode {
+    let result = match parse_args(std::env::args().skip(1)) {
+        Ok(Parsed::Help) => {
+            let mut out = std::io::stdout().lock();
+            return match out.write_all(USAGE.as_bytes()) {
+                Ok(()) => ExitCode::SUCCESS,
+                Err(_) => ExitCode::FAILURE,
+            };
+        }
+        Ok(Parsed::Run(opts)) => run(&opts),
+        Err(e) => {
+            eprintln!("{NAME}: {e}\nTry '{NAME} --help' for more information.");
+            return ExitCode::FAILURE;
+        }
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{NAME}: {e}");
+            ExitCode::FAILURE
+        }
+    }
 }
@@ -0,0 +1,118 @@
+// SPDX-License-Identifier: MIT
+// Copyright 2025 IROX Contributors
+//
+
+//!
+//! Structure-aware fuzzing of the SMBIOS table parser.  The input is a list of structures, each
+//! with a type, a declared length that may not match its formatted area, and a string table,
+//! serialized the way the firmware lays them out.  The parser must return an error rather than
+//! panic, and must consume input on every structure it reads.  Every structure it decodes must
+//! be described and printed as text and JSON without panicking, and so must the whole table
+//! when it's parsed as [`SmbiosTables`](irox_safe_windows::smbios::SmbiosTables).
+
+#![no_main]
+
+use arbitrary::Arbitrary;
+use irox_safe_windows::smbios::{read_next_table, SmbiosReport, StructureReport};
+use libfuzzer_sys::fuzz_target;
+
+#[derive(Debug, Arbitrary)]
+enum StructureType {
+    Bios,
+    System,
+    Baseboard,
+    Chassis,
+    Processor,
+    Cache,
+    SystemSlot,
+    PhysicalMemoryArray,
+    MemoryDevice,
+    MemoryArrayMappedAddress,
+    SystemBoot,
+    IPMIDevice,
+    OnboardDeviceExtended,
+    EndOfTable,
+    Other(u8),
+}
+
+impl StructureType {
+    fn value(&self) -> u8 {
+        match self {
+            StructureType::Bios => 0,
+            StructureType::System => 1,
+            StructureType::Baseboard => 2,
+            StructureType::Chassis => 3,
+            StructureType::Processor => 4,
+            StructureType::Cache => 7,
+            StructureType::SystemSlot => 9,
+            StructureType::PhysicalMemoryArray => 16,
+            StructureType::MemoryDevice => 17,
+            StructureType::MemoryArrayMappedAddress => 19,
+            StructureType::SystemBoot => 32,
+            StructureType::IPMIDevice => 38,
+            StructureType::OnboardDeviceExtended => 41,
+            StructureType::EndOfTable => 127,
+            StructureType::Other(v) => *v,
+        }
+    }
+}
+
+#[derive(Debug, Arbitrary)]
+struct Structure<'a> {
+    smtype: StructureType,
+    /// the declared length, the actual length of the formatted area if `None`
+    length: Option<u8>,
+    handle: u16,
+    formatted: &'a [u8],
+    strings: Vec<&'a [u8]>,
+}
+
+impl Structure<'_> {
+    fn write_to(&self, out: &mut Vec<u8>) {
+        // the formatted area's length must fit in the length byte along with the header.
+        let formatted = self.formatted.get(..251).unwrap_or(self.formatted);
+        out.push(self.smtype.value());
+        out.push(self.length.unwrap_or(formatted.len() as u8 + 4));
+        out.extend_from_slice(&self.handle.to_le_bytes());
+        out.extend_from_slice(formatted);
+        for s in &self.strings {
+            out.extend(s.iter().filter(|v| **v != 0));
+            out.push(0);
+        }
+        if self.strings.is_empty() {
+            out.push(0);
+        }
+        out.push(0);
+    }
+}
+
+fuzz_target!(|structures: Vec<Structure<'_>>| {
+    let mut data = Vec::new();
+    for s in &structures {
+        s.write_to(&mut data);
+    }
+    // as windows lays it out, after a header for the latest version.
+    let mut raw = vec![0, 3, 8, 0];
+    raw.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    raw.extend_from_slice(&data);
+
+    while !data.is_empty() {
+        let remaining = data.len();
+        let Ok(table) = read_next_table(&mut data) else {
+            break;
+        };
+        assert!(data.len() < remaining);
+        assert!(usize::from(table.length()) <= remaining);
+
+        let report = StructureReport::describe(&table);
+        let mut out = Vec::new();
+        assert!(report.write_text(&mut out).is_ok());
+        assert!(report.write_json(&mut out, "").is_ok());
+    }
+
+    if let Ok(report) = SmbiosReport::parse_from(&raw) {
+        let mut out = Vec::new();
+        assert!(report.write_text(&mut out).is_ok());
+        assert!(report.write_json(&mut out).is_ok());
+    }
+});
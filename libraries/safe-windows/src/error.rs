@@ -36,6 +36,7 @@ pub enum ErrorType {
     NoConsole,
     Network,
     IOIncomplete,
+    InvalidData,
     #[default]
     Other,
 }
@@ -73,6 +74,12 @@ impl Error {
             err_type: ErrorType::NotFound,
         })
     }
+    pub fn invalid_data<T>(message: &str) -> Result<T, Error> {
+        Err(Error {
+            msg: format!("Invalid data: {message}"),
+            err_type: ErrorType::InvalidData,
+        })
+    }
     pub fn msg(&self) -> &str {
         &self.msg
     }
@@ -87,6 +94,10 @@ impl Error {
         self.err_type == ErrorType::IOIncomplete
     }
 
+    pub fn is_invalid_data(&self) -> bool {
+        self.err_type == ErrorType::InvalidData
+    }
+
     #[cfg(windows)]
     pub fn win32<T>(err: WIN32_ERROR) -> Result<T, Error> {
         use windows::core::PSTR;
@@ -21,7 +21,6 @@ pub mod net_if;
 pub mod priority;
 #[cfg(windows)]
 pub mod registry;
-#[cfg(windows)]
 pub mod smbios;
 #[cfg(windows)]
 pub mod system;
@@ -3,18 +3,131 @@
 //
 
 use crate::error::Error;
-use irox::irox_bits::{Bits, MutBits};
-use irox::irox_log::log::info;
+use irox::irox_bits::Bits;
 use irox::structs::Struct;
 use irox::tools::uuid::UUID;
-use std::io::Write;
+use std::fmt::{Display, Formatter};
+#[cfg(windows)]
 use windows::Win32::System::SystemInformation::{GetSystemFirmwareTable, RSMB};
 
-pub fn read_raw_smbios_tables() -> Vec<u8> {
-    let mut buf = [0u8; 1024 * 16];
+///
+/// An enumerated byte (or word) field, with the names `dmidecode` gives its values.  Values the
+/// specification doesn't define decode to `OutOfSpec`.
+macro_rules! smbios_enum {
+    (
+        $(#[$meta:meta])*
+        $name:ident($ty:ty) {
+            $($variant:ident = $value:literal => $text:literal,)*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+        pub enum $name {
+            $($variant,)*
+            OutOfSpec($ty),
+        }
+        impl From<$ty> for $name {
+            fn from(value: $ty) -> Self {
+                match value {
+                    $($value => $name::$variant,)*
+                    e => $name::OutOfSpec(e),
+                }
+            }
+        }
+        impl $name {
+            /// The raw value of the field
+            pub fn value(&self) -> $ty {
+                match self {
+                    $($name::$variant => $value,)*
+                    $name::OutOfSpec(e) => *e,
+                }
+            }
+            /// The name `dmidecode` gives the value
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $($name::$variant => $text,)*
+                    $name::OutOfSpec(_) => "<OUT OF SPEC>",
+                }
+            }
+        }
+        impl std::fmt::Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str(self.name())
+            }
+        }
+    };
+}
+
+///
+/// A bitfield, with a constant for each defined bit and the names `dmidecode` gives them.
+macro_rules! smbios_flags {
+    (
+        $(#[$meta:meta])*
+        $name:ident($ty:ty) {
+            $($flag:ident = $bit:literal => $text:literal,)*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+        pub struct $name(pub $ty);
+        impl $name {
+            $(pub const $flag: $ty = 1 << $bit;)*
+
+            /// Whether all of the bits in `flags` are set
+            pub fn contains(&self, flags: $ty) -> bool {
+                self.0 & flags == flags
+            }
+            /// The names `dmidecode` gives the set bits, in bit order
+            pub fn names(&self) -> Vec<&'static str> {
+                [$(($name::$flag, $text),)*]
+                    .into_iter()
+                    .filter_map(|(flag, text)| self.contains(flag).then_some(text))
+                    .collect()
+            }
+        }
+    };
+}
+
+mod boot;
+mod cache;
+mod chassis;
+mod entry_point;
+mod ipmi;
+mod memory;
+mod onboard;
+mod processor;
+mod raw;
+mod report;
+mod slots;
+mod tables;
+pub use boot::*;
+pub use cache::*;
+pub use chassis::*;
+pub use entry_point::*;
+pub use ipmi::*;
+pub use memory::*;
+pub use onboard::*;
+pub use processor::*;
+pub use raw::*;
+pub use report::*;
+pub use slots::*;
+pub use tables::*;
+
+///
+/// Reads the raw SMBIOS data from the firmware, laid out as windows' `RawSMBIOSData`: an
+/// [`SMBIOSHeader`] followed by the structure table.
+#[cfg(windows)]
+pub fn read_raw_smbios_tables() -> Result<Vec<u8>, Error> {
     let firmware_table_provider = RSMB;
     let firmware_table_id = 0x0000;
 
+    // the first call returns the size of the table.
+    let len = unsafe { GetSystemFirmwareTable(firmware_table_provider, firmware_table_id, None) }
+        as usize;
+    if len == 0 {
+        return Error::notfound();
+    }
+    let mut buf = vec![0u8; len];
     let p_firmware_table_buffer = Some(buf.as_mut_slice());
 
     let val = unsafe {
@@ -24,8 +137,42 @@ pub fn read_raw_smbios_tables() -> Vec<u8> {
             p_firmware_table_buffer,
         )
     } as usize;
+    if val == 0 || val > len {
+        return Error::notfound();
+    }
+    buf.truncate(val);
+    Ok(buf)
+}
+
+///
+/// Reads the raw SMBIOS data from [`SYSFS_DMI_TABLES`], laid out as windows' `RawSMBIOSData`:
+/// an [`SMBIOSHeader`] followed by the structure table.  Reading the table usually needs root.
+#[cfg(target_os = "linux")]
+pub fn read_raw_smbios_tables() -> Result<Vec<u8>, Error> {
+    read_sysfs_tables(SYSFS_DMI_TABLES)
+}
+
+///
+/// The version of the SMBIOS specification a table implements.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct SMBIOSVersion {
+    pub major: u8,
+    pub minor: u8,
+}
 
-    Vec::from(buf.get(0..val).unwrap_or_default())
+impl SMBIOSVersion {
+    /// The latest version the structures are decoded to
+    pub const LATEST: SMBIOSVersion = SMBIOSVersion::new(3, 8);
+
+    pub const fn new(major: u8, minor: u8) -> SMBIOSVersion {
+        SMBIOSVersion { major, minor }
+    }
+}
+
+impl Display for SMBIOSVersion {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
 }
 
 #[derive(Default, Debug, Struct)]
@@ -38,99 +185,278 @@ pub struct SMBIOSHeader {
     pub table_data_length: u32,
 }
 
-pub fn read_next_table<T: MutBits + Bits>(val: &mut T) -> Result<SMBiosTable, Error> {
-    let smtype = val.read_u8()?;
-    let len = val.read_u8()? - 2;
+impl SMBIOSHeader {
+    pub fn version(&self) -> SMBIOSVersion {
+        SMBIOSVersion::new(self.smbios_major_version, self.smbios_minor_version)
+    }
+}
+
+///
+/// Reads the next structure, decoding it as the [latest](SMBIOSVersion::LATEST) version of the
+/// specification lays it out.
+pub fn read_next_table<T: Bits>(val: &mut T) -> Result<SMBiosTable, Error> {
+    read_next_table_for(val, SMBIOSVersion::LATEST)
+}
 
-    match smtype {
-        0 => Ok(SMBiosTable::BiosInformation(BIOSInformation::parse_from(
-            val, len,
-        )?)),
-        1 => Ok(SMBiosTable::SystemInformation(
-            SystemInformation::parse_from(val)?,
-        )),
-        2 => Ok(SMBiosTable::BaseboardInformation(
-            BaseboardInformation::parse_from(val)?,
-        )),
+///
+/// Reads the next structure of a table implementing `version` of the specification, as its
+/// [`SMBIOSHeader`] gives it.  Fields past the end of a structure decode to `None`, and a
+/// structure too short for even the fields every version has is kept as
+/// [`SMBiosTable::Unknown`].  Only fails when the data ends before the structure does.
+pub fn read_next_table_for<T: Bits>(
+    val: &mut T,
+    version: SMBIOSVersion,
+) -> Result<SMBiosTable, Error> {
+    let (area, strings) = read_structure(val)?;
+    Ok(match decode_structure(&area, &strings, version) {
+        Ok(Some(structure)) => structure,
+        Ok(None) | Err(_) => SMBiosTable::Unknown(UnknownStructure::new(area, strings)),
+    })
+}
 
-        e => Ok(SMBiosTable::Unknown(e)),
+///
+/// Reads the next structure as its formatted area and strings, without decoding it.
+fn read_structure<T: Bits>(val: &mut T) -> Result<(FormattedArea, Vec<String>), Error> {
+    let smtype = val.read_u8()?;
+    let len = val.read_u8()?;
+    // the length covers the 4 byte header of type, length and handle.
+    if len < 4 {
+        return Error::invalid_data(&format!(
+            "SMBIOS structure type {smtype} has length {len}, shorter than its header"
+        ));
     }
+    let area = FormattedArea::read_from(val, smtype, len - 2)?;
+    let strings = read_str_table(val)?;
+    Ok((area, strings))
 }
 
-macro_rules! get_str_from_table {
-    ($table:ident,$index:ident) => {
-        ($index > 0).then(|| $table.get($index as usize - 1).cloned().unwrap_or_default())
-    };
+///
+/// Decodes a structure read as its formatted area and strings, none for the types without a
+/// decoder.  Fails when the structure is too short for its type.
+fn decode_structure(
+    area: &FormattedArea,
+    strings: &[String],
+    version: SMBIOSVersion,
+) -> Result<Option<SMBiosTable>, Error> {
+    Ok(Some(match area.smtype() {
+        0 => SMBiosTable::BiosInformation(BIOSInformation::decode(area, strings)?),
+        1 => SMBiosTable::SystemInformation(SystemInformation::decode(area, strings, version)?),
+        2 => SMBiosTable::BaseboardInformation(BaseboardInformation::decode(area, strings)?),
+        3 => SMBiosTable::ChassisInformation(ChassisInformation::decode(area, strings)?),
+        4 => SMBiosTable::ProcessorInformation(ProcessorInformation::decode(area, strings)?),
+        7 => SMBiosTable::CacheInformation(CacheInformation::decode(area, strings)?),
+        9 => SMBiosTable::SystemSlotInformation(SystemSlotInformation::decode(area, strings)?),
+        16 => SMBiosTable::PhysicalMemoryArray(PhysicalMemoryArray::decode(area, strings)?),
+        17 => SMBiosTable::MemoryDevice(MemoryDevice::decode(area, strings)?),
+        19 => {
+            SMBiosTable::MemoryArrayMappedAddress(MemoryArrayMappedAddress::decode(area, strings)?)
+        }
+        32 => SMBiosTable::SystemBootInformation(SystemBootInformation::decode(area, strings)?),
+        38 => SMBiosTable::IPMIDeviceInformation(IPMIDeviceInformation::decode(area, strings)?),
+        41 => SMBiosTable::OnboardDeviceExtended(OnboardDeviceExtended::decode(area, strings)?),
+        127 => SMBiosTable::EndOfTable(EndOfTable {
+            handle: area.handle(),
+            length: area.length(),
+        }),
+        _ => return Ok(None),
+    }))
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Eq, PartialEq)]
 pub enum SMBiosTable {
     BiosInformation(BIOSInformation),
     SystemInformation(SystemInformation),
     BaseboardInformation(BaseboardInformation),
-    Unknown(u8),
+    ChassisInformation(ChassisInformation),
+    ProcessorInformation(ProcessorInformation),
+    CacheInformation(CacheInformation),
+    SystemSlotInformation(SystemSlotInformation),
+    PhysicalMemoryArray(PhysicalMemoryArray),
+    MemoryDevice(MemoryDevice),
+    MemoryArrayMappedAddress(MemoryArrayMappedAddress),
+    SystemBootInformation(SystemBootInformation),
+    IPMIDeviceInformation(IPMIDeviceInformation),
+    OnboardDeviceExtended(OnboardDeviceExtended),
+    EndOfTable(EndOfTable),
+    Unknown(UnknownStructure),
 }
 
-#[derive(Debug)]
+impl SMBiosTable {
+    ///
+    /// The SMBIOS structure type
+    pub fn smtype(&self) -> u8 {
+        match self {
+            SMBiosTable::BiosInformation(_) => 0,
+            SMBiosTable::SystemInformation(_) => 1,
+            SMBiosTable::BaseboardInformation(_) => 2,
+            SMBiosTable::ChassisInformation(_) => 3,
+            SMBiosTable::ProcessorInformation(_) => 4,
+            SMBiosTable::CacheInformation(_) => 7,
+            SMBiosTable::SystemSlotInformation(_) => 9,
+            SMBiosTable::PhysicalMemoryArray(_) => 16,
+            SMBiosTable::MemoryDevice(_) => 17,
+            SMBiosTable::MemoryArrayMappedAddress(_) => 19,
+            SMBiosTable::SystemBootInformation(_) => 32,
+            SMBiosTable::IPMIDeviceInformation(_) => 38,
+            SMBiosTable::OnboardDeviceExtended(_) => 41,
+            SMBiosTable::EndOfTable(_) => 127,
+            SMBiosTable::Unknown(u) => u.smtype,
+        }
+    }
+
+    ///
+    /// The handle of the structure
+    pub fn handle(&self) -> u16 {
+        match self {
+            SMBiosTable::BiosInformation(t) => t.handle,
+            SMBiosTable::SystemInformation(t) => t.handle,
+            SMBiosTable::BaseboardInformation(t) => t.handle,
+            SMBiosTable::ChassisInformation(t) => t.handle,
+            SMBiosTable::ProcessorInformation(t) => t.handle,
+            SMBiosTable::CacheInformation(t) => t.handle,
+            SMBiosTable::SystemSlotInformation(t) => t.handle,
+            SMBiosTable::PhysicalMemoryArray(t) => t.handle,
+            SMBiosTable::MemoryDevice(t) => t.handle,
+            SMBiosTable::MemoryArrayMappedAddress(t) => t.handle,
+            SMBiosTable::SystemBootInformation(t) => t.handle,
+            SMBiosTable::IPMIDeviceInformation(t) => t.handle,
+            SMBiosTable::OnboardDeviceExtended(t) => t.handle,
+            SMBiosTable::EndOfTable(t) => t.handle,
+            SMBiosTable::Unknown(t) => t.handle,
+        }
+    }
+
+    ///
+    /// The length of the structure's formatted area, which tells the version of the
+    /// specification it was laid out for
+    pub fn length(&self) -> u8 {
+        match self {
+            SMBiosTable::BiosInformation(t) => t.length,
+            SMBiosTable::SystemInformation(t) => t.length,
+            SMBiosTable::BaseboardInformation(t) => t.length,
+            SMBiosTable::ChassisInformation(t) => t.length,
+            SMBiosTable::ProcessorInformation(t) => t.length,
+            SMBiosTable::CacheInformation(t) => t.length,
+            SMBiosTable::SystemSlotInformation(t) => t.length,
+            SMBiosTable::PhysicalMemoryArray(t) => t.length,
+            SMBiosTable::MemoryDevice(t) => t.length,
+            SMBiosTable::MemoryArrayMappedAddress(t) => t.length,
+            SMBiosTable::SystemBootInformation(t) => t.length,
+            SMBiosTable::IPMIDeviceInformation(t) => t.length,
+            SMBiosTable::OnboardDeviceExtended(t) => t.length,
+            SMBiosTable::EndOfTable(t) => t.length,
+            SMBiosTable::Unknown(t) => t.formatted.length(),
+        }
+    }
+}
+
+///
+/// Type 0, the BIOS information.  2.4 adds the second characteristics extension byte and the
+/// release numbers, and 3.1 the extended ROM size.
+#[derive(Debug, Clone, Eq, PartialEq)]
 pub struct BIOSInformation {
     pub handle: u16,
+    /// The length of the formatted area
+    pub length: u8,
     pub vendor_str: Option<String>,
     pub bios_version_str: Option<String>,
     pub bios_addr: u16,
     pub bios_date_str: Option<String>,
     pub bios_rom_size: u8,
-    pub bios_characteristics: u32,
-    pub bios_major_release: u8,
-    pub bios_minor_release: u8,
-    pub embfirm_major_release: u8,
-    pub embfirm_minor_release: u8,
-    pub extbios_rom_size: u16,
+    pub bios_characteristics: u64,
+    pub bios_characteristics_ext1: Option<u8>,
+    pub bios_characteristics_ext2: Option<u8>,
+    /// `0xFF` if the BIOS doesn't support the release numbers
+    pub bios_major_release: Option<u8>,
+    pub bios_minor_release: Option<u8>,
+    /// `0xFF` if there's no embedded controller firmware
+    pub embfirm_major_release: Option<u8>,
+    pub embfirm_minor_release: Option<u8>,
+    pub extbios_rom_size: Option<u16>,
 }
 impl BIOSInformation {
-    pub fn parse_from<T: Bits + MutBits>(val: &mut T, len: u8) -> Result<BIOSInformation, Error> {
-        let handle = val.read_le_u16()?;
-        let vendor_stridx = val.read_u8()?;
-        let bios_version_stridx = val.read_u8()?;
-        let bios_addr = val.read_le_u16()?;
-        let bios_date_stridx = val.read_u8()?;
-        let bios_rom_size = val.read_u8()?;
-        let bios_characteristics = val.read_le_u32()?;
-
-        let ext_bits = len.saturating_sub(18) as usize;
-        info!("ext bits: {ext_bits}");
-        val.advance(ext_bits)?;
-
-        let bios_major_release = val.read_u8()?;
-        let bios_minor_release = val.read_u8()?;
-        let embfirm_major_release = val.read_u8()?;
-        let embfirm_minor_release = val.read_u8()?;
-        let extbios_rom_size = val.read_le_u16()?;
-
-        let strs = read_str_table(val)?;
-        let vendor_str = get_str_from_table!(strs, vendor_stridx);
-        let bios_version_str = get_str_from_table!(strs, bios_version_stridx);
-        let bios_date_str = get_str_from_table!(strs, bios_date_stridx);
-
+    pub fn decode(area: &FormattedArea, strings: &[String]) -> Result<BIOSInformation, Error> {
         Ok(BIOSInformation {
-            handle,
-            vendor_str,
-            bios_version_str,
-            bios_addr,
-            bios_date_str,
-            bios_rom_size,
-            bios_characteristics,
-
-            bios_major_release,
-            bios_minor_release,
-            embfirm_major_release,
-            embfirm_minor_release,
-            extbios_rom_size,
+            handle: area.handle(),
+            length: area.length(),
+            vendor_str: area.read_string(0x04, strings)?,
+            bios_version_str: area.read_string(0x05, strings)?,
+            bios_addr: area.read_u16(0x06)?,
+            bios_date_str: area.read_string(0x08, strings)?,
+            bios_rom_size: area.read_u8(0x09)?,
+            bios_characteristics: area.read_u64(0x0A)?,
+            bios_characteristics_ext1: area.get_u8(0x12),
+            bios_characteristics_ext2: area.get_u8(0x13),
+            bios_major_release: area.get_u8(0x14),
+            bios_minor_release: area.get_u8(0x15),
+            embfirm_major_release: area.get_u8(0x16),
+            embfirm_minor_release: area.get_u8(0x17),
+            extbios_rom_size: area.get_u16(0x18),
         })
     }
 }
 
+smbios_flags!(
+    BIOSCharacteristics(u64) {
+        NOT_SUPPORTED = 3 => "BIOS characteristics not supported",
+        ISA = 4 => "ISA is supported",
+        MCA = 5 => "MCA is supported",
+        EISA = 6 => "EISA is supported",
+        PCI = 7 => "PCI is supported",
+        PC_CARD = 8 => "PC Card (PCMCIA) is supported",
+        PNP = 9 => "PNP is supported",
+        APM = 10 => "APM is supported",
+        UPGRADEABLE = 11 => "BIOS is upgradeable",
+        SHADOWING = 12 => "BIOS shadowing is allowed",
+        VLB = 13 => "VLB is supported",
+        ESCD = 14 => "ESCD support is available",
+        BOOT_FROM_CD = 15 => "Boot from CD is supported",
+        SELECTABLE_BOOT = 16 => "Selectable boot is supported",
+        ROM_SOCKETED = 17 => "BIOS ROM is socketed",
+        BOOT_FROM_PC_CARD = 18 => "Boot from PC Card (PCMCIA) is supported",
+        EDD = 19 => "EDD is supported",
+        FLOPPY_NEC_9800 = 20 => "Japanese floppy for NEC 9800 1.2 MB is supported (int 13h)",
+        FLOPPY_TOSHIBA = 21 => "Japanese floppy for Toshiba 1.2 MB is supported (int 13h)",
+        FLOPPY_525_360K = 22 => "5.25\"/360 kB floppy services are supported (int 13h)",
+        FLOPPY_525_1_2M = 23 => "5.25\"/1.2 MB floppy services are supported (int 13h)",
+        FLOPPY_35_720K = 24 => "3.5\"/720 kB floppy services are supported (int 13h)",
+        FLOPPY_35_2_88M = 25 => "3.5\"/2.88 MB floppy services are supported (int 13h)",
+        PRINT_SCREEN = 26 => "Print screen service is supported (int 5h)",
+        KEYBOARD_8042 = 27 => "8042 keyboard services are supported (int 9h)",
+        SERIAL = 28 => "Serial services are supported (int 14h)",
+        PRINTER = 29 => "Printer services are supported (int 17h)",
+        CGA_MONO_VIDEO = 30 => "CGA/mono video services are supported (int 10h)",
+        NEC_PC_98 = 31 => "NEC PC-98",
+    }
+);
+
+smbios_flags!(
+    BIOSCharacteristicsExt1(u8) {
+        ACPI = 0 => "ACPI is supported",
+        USB_LEGACY = 1 => "USB legacy is supported",
+        AGP = 2 => "AGP is supported",
+        I2O_BOOT = 3 => "I2O boot is supported",
+        LS120_BOOT = 4 => "LS-120 boot is supported",
+        ATAPI_ZIP_BOOT = 5 => "ATAPI Zip drive boot is supported",
+        IEEE1394_BOOT = 6 => "IEEE 1394 boot is supported",
+        SMART_BATTERY = 7 => "Smart battery is supported",
+    }
+);
+
+smbios_flags!(
+    BIOSCharacteristicsExt2(u8) {
+        BIOS_BOOT_SPECIFICATION = 0 => "BIOS boot specification is supported",
+        NETWORK_BOOT = 1 => "Function key-initiated network boot is supported",
+        TARGETED_CONTENT_DISTRIBUTION = 2 => "Targeted content distribution is supported",
+        UEFI = 3 => "UEFI is supported",
+        VIRTUAL_MACHINE = 4 => "System is a virtual machine",
+        MANUFACTURING_MODE_SUPPORTED = 5 => "Manufacturing mode is supported",
+        MANUFACTURING_MODE_ENABLED = 6 => "Manufacturing mode is enabled",
+    }
+);
+
 #[repr(u8)]
-#[derive(Debug)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub enum WakeUpType {
     Reserved = 0u8,
     Other = 1u8,
@@ -157,126 +483,157 @@ impl From<u8> for WakeUpType {
         }
     }
 }
+impl WakeUpType {
+    /// The name `dmidecode` gives the type
+    pub fn name(&self) -> &'static str {
+        match self {
+            WakeUpType::Reserved => "Reserved",
+            WakeUpType::Other => "Other",
+            WakeUpType::Unknown => "Unknown",
+            WakeUpType::APMTimer => "APM Timer",
+            WakeUpType::ModemRing => "Modem Ring",
+            WakeUpType::LANRemote => "LAN Remote",
+            WakeUpType::PowerSwitch => "Power Switch",
+            WakeUpType::PCIPME => "PCI PME#",
+            WakeUpType::ACPowerRestored => "AC Power Restored",
+        }
+    }
+}
+impl Display for WakeUpType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
 
-#[derive(Debug)]
+///
+/// Type 1, the system information.  2.1 adds the UUID and wake-up type, and 2.4 the SKU number
+/// and family.
+#[derive(Debug, Clone, Eq, PartialEq)]
 pub struct SystemInformation {
     pub handle: u16,
+    /// The length of the formatted area
+    pub length: u8,
     pub manufacturer: Option<String>,
     pub product_name: Option<String>,
     pub version: Option<String>,
     pub serial_number: Option<String>,
-    pub uuid: UUID,
-    pub wake_up_type: WakeUpType,
+    pub uuid: Option<UUID>,
+    pub wake_up_type: Option<WakeUpType>,
     pub sku_number: Option<String>,
     pub family: Option<String>,
 }
 
 impl SystemInformation {
-    pub fn parse_from<T: Bits + MutBits>(val: &mut T) -> Result<SystemInformation, Error> {
-        let handle = val.read_le_u16()?;
-        let manuf_stridx = val.read_u8()?;
-        let prodname_stridx = val.read_u8()?;
-        let version_stridx = val.read_u8()?;
-        let sernum_stridx = val.read_u8()?;
-        let uuid_time_low = val.read_le_u32()?;
-        let uuid_time_med = val.read_le_u16()?;
-        let uuid_time_high = val.read_le_u16()?;
-        let uuid_clk_hi = val.read_u8()?;
-        let uuid_clk_low = val.read_u8()?;
-        let node = val.read_exact::<6>()?;
-        let mut uuid = [0u8; 16];
-
-        let mut uuidwr = uuid.as_mut_slice();
-        uuidwr.write_be_u32(uuid_time_low)?;
-        uuidwr.write_be_u16(uuid_time_med)?;
-        uuidwr.write_be_u16(uuid_time_high)?;
-        uuidwr.write_u8(uuid_clk_hi)?;
-        uuidwr.write_u8(uuid_clk_low)?;
-        uuidwr.write_all(&node)?;
-
-        let uuid = UUID::from(uuid);
-        let wake_up_type: WakeUpType = val.read_u8()?.into();
-        let sku_num_stridx = val.read_u8()?;
-        let family_stridx = val.read_u8()?;
-
-        let table = read_str_table(val)?;
-        let manufacturer = get_str_from_table!(table, manuf_stridx);
-        let product_name = get_str_from_table!(table, prodname_stridx);
-        let version = get_str_from_table!(table, version_stridx);
-        let serial_number = get_str_from_table!(table, sernum_stridx);
-        let sku_number = get_str_from_table!(table, sku_num_stridx);
-        let family = get_str_from_table!(table, family_stridx);
+    pub fn decode(
+        area: &FormattedArea,
+        strings: &[String],
+        version: SMBIOSVersion,
+    ) -> Result<SystemInformation, Error> {
+        let uuid = area
+            .get_bytes(0x08, 16)
+            .and_then(|v| <[u8; 16]>::try_from(v).ok())
+            .map(|[t0, t1, t2, t3, m0, m1, h0, h1, node @ ..]| {
+                // 2.6 settled that the time fields are little endian, earlier versions are
+                // taken as the network order RFC 4122 gives.
+                let time = if version >= SMBIOSVersion::new(2, 6) {
+                    [t3, t2, t1, t0, m1, m0, h1, h0]
+                } else {
+                    [t0, t1, t2, t3, m0, m1, h0, h1]
+                };
+                let mut uuid = [0u8; 16];
+                for (out, v) in uuid.iter_mut().zip(time.into_iter().chain(node)) {
+                    *out = v;
+                }
+                UUID::from(uuid)
+            });
+
         Ok(SystemInformation {
-            handle,
-            manufacturer,
-            product_name,
-            version,
-            serial_number,
+            handle: area.handle(),
+            length: area.length(),
+            manufacturer: area.read_string(0x04, strings)?,
+            product_name: area.read_string(0x05, strings)?,
+            version: area.read_string(0x06, strings)?,
+            serial_number: area.read_string(0x07, strings)?,
             uuid,
-            wake_up_type,
-            sku_number,
-            family,
+            wake_up_type: area.get_u8(0x18).map(WakeUpType::from),
+            sku_number: area.get_string(0x19, strings),
+            family: area.get_string(0x1A, strings),
         })
     }
 }
 
-#[derive(Debug)]
+smbios_flags!(
+    BaseboardFeatures(u8) {
+        HOSTING_BOARD = 0 => "Board is a hosting board",
+        REQUIRES_DAUGHTER_BOARD = 1 => "Board requires at least one daughter board",
+        REMOVABLE = 2 => "Board is removable",
+        REPLACEABLE = 3 => "Board is replaceable",
+        HOT_SWAPPABLE = 4 => "Board is hot swappable",
+    }
+);
+
+smbios_enum!(
+    BoardType(u8) {
+        Unknown = 0x01 => "Unknown",
+        Other = 0x02 => "Other",
+        ServerBlade = 0x03 => "Server Blade",
+        ConnectivitySwitch = 0x04 => "Connectivity Switch",
+        SystemManagementModule = 0x05 => "System Management Module",
+        ProcessorModule = 0x06 => "Processor Module",
+        IOModule = 0x07 => "I/O Module",
+        MemoryModule = 0x08 => "Memory Module",
+        DaughterBoard = 0x09 => "Daughter Board",
+        Motherboard = 0x0A => "Motherboard",
+        ProcessorMemoryModule = 0x0B => "Processor+Memory Module",
+        ProcessorIOModule = 0x0C => "Processor+I/O Module",
+        InterconnectBoard = 0x0D => "Interconnect Board",
+    }
+);
+
+///
+/// Type 2, the baseboard information.  Only the strings up to the serial number are required,
+/// the rest are optional in every version.
+#[derive(Debug, Clone, Eq, PartialEq)]
 pub struct BaseboardInformation {
     pub handle: u16,
+    /// The length of the formatted area
+    pub length: u8,
     pub manufacturer: Option<String>,
     pub product: Option<String>,
     pub version: Option<String>,
     pub serial_number: Option<String>,
     pub asset_tag: Option<String>,
-    pub feature_flags: u8,
+    pub feature_flags: Option<u8>,
     pub location_in_chassis: Option<String>,
-    pub chassis_handle: u16,
-    pub board_type: u8,
+    pub chassis_handle: Option<u16>,
+    pub board_type: Option<u8>,
     pub object_handles: Vec<u16>,
 }
 impl BaseboardInformation {
-    pub fn parse_from<T: Bits + MutBits>(val: &mut T) -> Result<BaseboardInformation, Error> {
-        let handle = val.read_le_u16()?;
-        let manuf_stridx = val.read_u8()?;
-        let product_stridx = val.read_u8()?;
-        let version_stridx = val.read_u8()?;
-        let serno_stridx = val.read_u8()?;
-        let assettag_stridx = val.read_u8()?;
-        let feature_flags = val.read_u8()?;
-        let location_stridx = val.read_u8()?;
-        let chassis_handle = val.read_le_u16()?;
-        let board_type = val.read_u8()?;
-
-        let num_obj_handles = val.read_u8()?;
-        let mut object_handles = Vec::with_capacity(num_obj_handles as usize);
-        for _ in 0..num_obj_handles {
-            object_handles.push(val.read_le_u16()?);
-        }
-        let table = read_str_table(val)?;
-        let manufacturer = get_str_from_table!(table, manuf_stridx);
-        let product = get_str_from_table!(table, product_stridx);
-        let version = get_str_from_table!(table, version_stridx);
-        let serial_number = get_str_from_table!(table, serno_stridx);
-        let asset_tag = get_str_from_table!(table, assettag_stridx);
-        let location_in_chassis = get_str_from_table!(table, location_stridx);
+    pub fn decode(area: &FormattedArea, strings: &[String]) -> Result<BaseboardInformation, Error> {
+        let count = usize::from(area.get_u8(0x0E).unwrap_or_default());
+        let object_handles = (0..count)
+            .map_while(|i| area.get_u16(0x0F + i * 2))
+            .collect();
 
         Ok(BaseboardInformation {
-            handle,
-            manufacturer,
-            product,
-            version,
-            serial_number,
-            asset_tag,
-            feature_flags,
-            location_in_chassis,
-            chassis_handle,
-            board_type,
+            handle: area.handle(),
+            length: area.length(),
+            manufacturer: area.read_string(0x04, strings)?,
+            product: area.read_string(0x05, strings)?,
+            version: area.read_string(0x06, strings)?,
+            serial_number: area.read_string(0x07, strings)?,
+            asset_tag: area.get_string(0x08, strings),
+            feature_flags: area.get_u8(0x09),
+            location_in_chassis: area.get_string(0x0A, strings),
+            chassis_handle: area.get_u16(0x0B),
+            board_type: area.get_u8(0x0D),
             object_handles,
         })
     }
 }
 
-fn read_null_terminated_str<T: Bits + MutBits>(val: &mut T) -> Result<String, Error> {
+fn read_null_terminated_str<T: Bits>(val: &mut T) -> Result<String, Error> {
     let mut out = String::new();
     loop {
         let read = val.read_u8()?;
@@ -288,7 +645,7 @@ fn read_null_terminated_str<T: Bits + MutBits>(val: &mut T) -> Result<String, Er
     Ok(out)
 }
 
-fn read_str_table<T: Bits + MutBits>(val: &mut T) -> Result<Vec<String>, Error> {
+fn read_str_table<T: Bits>(val: &mut T) -> Result<Vec<String>, Error> {
     let mut strs = Vec::new();
     loop {
         let read_str = read_null_terminated_str(val)?;
@@ -297,5 +654,361 @@ fn read_str_table<T: Bits + MutBits>(val: &mut T) -> Result<Vec<String>, Error>
         }
         strs.push(read_str);
     }
+    if strs.is_empty() {
+        // a structure without strings still ends with two nulls.
+        val.read_u8()?;
+    }
     Ok(strs)
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::smbios::{
+        read_next_table, read_sysfs_tables, BootStatus, CPUStatus, ChassisType, IPMIInterfaceType,
+        MemoryDeviceSize, MemoryOperatingModeCapability, MemoryType, OnboardDeviceType,
+        ProcessorCharacteristics, ProcessorFamily, ProcessorUpgrade, ProcessorVoltage,
+        SMBIOSHeader, SMBiosTable, SlotType, SlotWidth, SystemCacheType,
+    };
+    use irox::structs::Struct;
+
+    const QEMU: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/doc/smbiostestdata/synthetic-qemu-2.8"
+    );
+    const DESKTOP: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/doc/smbiostestdata/synthetic-desktop-3.4"
+    );
+
+    ///
+    /// Reads the next structure, which must be the `$variant` one, returning from the test if not
+    macro_rules! next_table {
+        ($data:expr, $variant:ident) => {{
+            let table = read_next_table($data);
+            assert!(matches!(table, Ok(SMBiosTable::$variant(_))), "{table:?}");
+            let Ok(SMBiosTable::$variant(table)) = table else {
+                return;
+            };
+            table
+        }};
+    }
+
+    ///
+    /// Reads every structure in the table, returning their types
+    fn read_all(data: &mut Vec<u8>) -> Vec<u8> {
+        let mut types = Vec::new();
+        while !data.is_empty() {
+            let table = read_next_table(data).unwrap();
+            types.push(table.smtype());
+        }
+        types
+    }
+
+    #[test]
+    pub fn test_sysfs_smbios2() {
+        let mut data = read_sysfs_tables(QEMU).unwrap();
+        let header = SMBIOSHeader::parse_from(&mut data).unwrap();
+        assert_eq!(2, header.smbios_major_version);
+        assert_eq!(8, header.smbios_minor_version);
+        assert_eq!(381, header.table_data_length);
+        assert_eq!(381, data.len());
+
+        let mut tables = data.clone();
+        let bios = next_table!(&mut tables, BiosInformation);
+        assert_eq!(
+            Some("EFI Development Kit II / OVMF"),
+            bios.vendor_str.as_deref()
+        );
+        assert_eq!(Some("02/06/2015"), bios.bios_date_str.as_deref());
+        assert_eq!(Some(0), bios.bios_major_release);
+        assert_eq!(Some(0xFF), bios.embfirm_major_release);
+        // a 2.4 structure has no extended ROM size.
+        assert_eq!(None, bios.extbios_rom_size);
+        let system = next_table!(&mut tables, SystemInformation);
+        assert_eq!(Some("QEMU"), system.manufacturer.as_deref());
+        assert_eq!(
+            Some("Standard PC (Q35 + ICH9, 2009)"),
+            system.product_name.as_deref()
+        );
+
+        assert_eq!(vec![0, 1, 3, 4, 16, 17, 19, 32, 127], read_all(&mut data));
+    }
+
+    #[test]
+    pub fn test_sysfs_smbios3() {
+        let mut data = read_sysfs_tables(DESKTOP).unwrap();
+        let header = SMBIOSHeader::parse_from(&mut data).unwrap();
+        assert_eq!(3, header.smbios_major_version);
+        assert_eq!(4, header.smbios_minor_version);
+        assert_eq!(0, header.dmi_revision);
+        assert_eq!(1182, header.table_data_length);
+
+        let mut tables = data.clone();
+        let bios = next_table!(&mut tables, BiosInformation);
+        assert_eq!(Some("1.24.0"), bios.bios_version_str.as_deref());
+        assert_eq!(Some(5), bios.bios_major_release);
+        assert_eq!(Some(24), bios.bios_minor_release);
+        assert_eq!(Some(0x20), bios.extbios_rom_size);
+        let system = next_table!(&mut tables, SystemInformation);
+        assert_eq!(Some("SYN7K-000123"), system.serial_number.as_deref());
+        assert_eq!(Some("Desktop"), system.family.as_deref());
+        let board = next_table!(&mut tables, BaseboardInformation);
+        assert_eq!(Some("SYN7K-MB"), board.product.as_deref());
+        assert_eq!(Some(0x0003), board.chassis_handle);
+        assert_eq!(vec![0x0004, 0x0009], board.object_handles);
+
+        assert_eq!(
+            vec![0, 1, 2, 3, 4, 7, 7, 7, 9, 9, 16, 17, 17, 19, 32, 38, 41, 41, 0xDD, 127],
+            read_all(&mut data)
+        );
+    }
+
+    #[test]
+    pub fn test_decode_smbios2() {
+        let mut data = read_sysfs_tables(QEMU).unwrap();
+        SMBIOSHeader::parse_from(&mut data).unwrap();
+        next_table!(&mut data, BiosInformation);
+        next_table!(&mut data, SystemInformation);
+
+        let chassis = next_table!(&mut data, ChassisInformation);
+        assert_eq!(0x0300, chassis.handle);
+        assert_eq!(ChassisType::Other, chassis.chassis_type);
+        assert!(!chassis.lock_present);
+        assert_eq!(Some("pc-q35-8.2"), chassis.version.as_deref());
+        assert_eq!(None, chassis.serial_number);
+        assert!(chassis.contained_elements.is_empty());
+        assert_eq!(None, chassis.sku_number);
+
+        let cpu = next_table!(&mut data, ProcessorInformation);
+        assert_eq!(ProcessorFamily::Other, cpu.processor_family);
+        assert_eq!(Some("CPU 0"), cpu.socket_designation.as_deref());
+        assert_eq!(2000, cpu.max_speed);
+        assert_eq!(CPUStatus::Enabled, cpu.cpu_status);
+        assert_eq!(Some(0xFFFF), cpu.l1_cache_handle);
+        assert_eq!(Some(4), cpu.thread_count);
+        // a 2.6 processor has none of the 3.0 counts.
+        assert_eq!(None, cpu.thread_enabled);
+
+        let array = next_table!(&mut data, PhysicalMemoryArray);
+        assert_eq!(8 << 30, array.maximum_capacity);
+        assert_eq!(1, array.number_of_devices);
+
+        let dimm = next_table!(&mut data, MemoryDevice);
+        assert_eq!(0x1000, dimm.physical_memory_array_handle);
+        assert_eq!(None, dimm.total_width);
+        assert_eq!(MemoryDeviceSize::Bytes(8 << 30), dimm.size);
+        assert_eq!("8 GB", dimm.size.to_string());
+        assert_eq!(MemoryType::RAM, dimm.memory_type);
+        assert_eq!(Some("DIMM 0"), dimm.device_locator.as_deref());
+        assert_eq!(Some("QEMU"), dimm.manufacturer.as_deref());
+        assert_eq!(None, dimm.serial_number);
+        assert_eq!(Some(0), dimm.configured_voltage);
+        // a 2.8 device has none of the 3.2 fields.
+        assert_eq!(None, dimm.memory_technology);
+        assert_eq!(None, dimm.firmware_version);
+        assert_eq!(None, dimm.volatile_size);
+
+        let mapped = next_table!(&mut data, MemoryArrayMappedAddress);
+        assert_eq!(0, mapped.starting_address);
+        assert_eq!((8 << 30) - 1, mapped.ending_address);
+        assert_eq!(0x1000, mapped.memory_array_handle);
+
+        let boot = next_table!(&mut data, SystemBootInformation);
+        assert_eq!(BootStatus::NoErrors, boot.status);
+        assert!(boot.additional_data.is_empty());
+
+        let end = next_table!(&mut data, EndOfTable);
+        assert_eq!(0x7F00, end.handle);
+        assert!(data.is_empty());
+    }
+
+    #[test]
+    pub fn test_decode_smbios3() {
+        let mut data = read_sysfs_tables(DESKTOP).unwrap();
+        SMBIOSHeader::parse_from(&mut data).unwrap();
+        next_table!(&mut data, BiosInformation);
+        next_table!(&mut data, SystemInformation);
+        next_table!(&mut data, BaseboardInformation);
+
+        let chassis = next_table!(&mut data, ChassisInformation);
+        assert_eq!(ChassisType::Desktop, chassis.chassis_type);
+        assert!(chassis.lock_present);
+        assert_eq!(Some(4), chassis.height);
+        assert_eq!(Some("SKU-CH-7000"), chassis.sku_number.as_deref());
+
+        let cpu = next_table!(&mut data, ProcessorInformation);
+        assert_eq!(0x0004, cpu.handle);
+        assert_eq!(ProcessorFamily::CoreI9, cpu.processor_family);
+        assert_eq!("Core i9", cpu.processor_family.name());
+        assert_eq!(0xBFEB_FBFF_0009_06A3, cpu.processor_id);
+        assert_eq!(ProcessorVoltage::Current(11), cpu.voltage);
+        assert_eq!("1.1 V", cpu.voltage.to_string());
+        assert!(cpu.socket_populated);
+        assert_eq!(ProcessorUpgrade::SocketLGA1700, cpu.upgrade);
+        assert_eq!("Socket LGA1700", cpu.upgrade.name());
+        assert_eq!(
+            (Some(0x0005), Some(0x0006), Some(0x0007)),
+            (
+                cpu.l1_cache_handle,
+                cpu.l2_cache_handle,
+                cpu.l3_cache_handle
+            )
+        );
+        assert_eq!(Some(16), cpu.core_count);
+        assert_eq!(Some(24), cpu.thread_count);
+        // the thread enabled count is 3.6, and this is a 3.0 length structure.
+        assert_eq!(None, cpu.thread_enabled);
+        let chars = cpu.characteristics.unwrap_or_default();
+        assert!(chars.contains(ProcessorCharacteristics::CAPABLE_64BIT));
+        assert!(!chars.contains(ProcessorCharacteristics::CAPABLE_128BIT));
+
+        let mut caches = Vec::new();
+        for _ in 0..3 {
+            caches.push(next_table!(&mut data, CacheInformation));
+        }
+        let [l1, l2, l3] = caches.as_slice() else {
+            return;
+        };
+        assert_eq!(Some("L1 Cache"), l1.socket_designation.as_deref());
+        assert_eq!(1, l1.configuration.level());
+        assert!(l1.configuration.enabled());
+        assert_eq!(1280, l1.installed_size);
+        assert_eq!(2, l2.configuration.level());
+        assert_eq!(14336, l2.maximum_size);
+        assert_eq!(3, l3.configuration.level());
+        // over 32 MiB the size word has 64K granularity, the doubleword is exact.
+        assert_eq!(30720, l3.installed_size);
+        assert_eq!(Some(SystemCacheType::Unified), l3.system_cache_type);
+
+        let slot = next_table!(&mut data, SystemSlotInformation);
+        assert_eq!(Some("PCIEX16_1"), slot.designation.as_deref());
+        assert_eq!(SlotType::PCIExpress3X16, slot.slot_type);
+        assert_eq!(SlotWidth::X16, slot.data_bus_width);
+        assert_eq!(Some(1), slot.bus);
+        assert_eq!(Some(0), slot.device);
+        assert!(slot.peer_groups.is_empty());
+        assert_eq!(None, slot.physical_width);
+        let slot = next_table!(&mut data, SystemSlotInformation);
+        assert_eq!(SlotType::PCIExpress4X4, slot.slot_type);
+        assert_eq!(SlotWidth::X4, slot.data_bus_width);
+
+        let array = next_table!(&mut data, PhysicalMemoryArray);
+        assert_eq!(0x000A, array.handle);
+        assert_eq!(128 << 30, array.maximum_capacity);
+
+        for (handle, locator) in [(0x000B, "DIMM_A1"), (0x000C, "DIMM_B1")] {
+            let dimm = next_table!(&mut data, MemoryDevice);
+            assert_eq!(handle, dimm.handle);
+            assert_eq!(Some(locator), dimm.device_locator.as_deref());
+            assert_eq!(MemoryDeviceSize::Bytes(16 << 30), dimm.size);
+            assert_eq!(MemoryType::DDR5, dimm.memory_type);
+            assert_eq!(Some(4800), dimm.speed);
+            assert_eq!(Some(2), dimm.rank);
+            assert_eq!(Some("MTC8C1084S1SC48BA1"), dimm.part_number.as_deref());
+            let mode = dimm.operating_mode_capability.unwrap_or_default();
+            assert!(mode.contains(MemoryOperatingModeCapability::VOLATILE));
+            assert_eq!(vec!["Volatile memory"], mode.names());
+            assert_eq!(Some("Not Specified"), dimm.firmware_version.as_deref());
+            assert_eq!(Some(0x2C80), dimm.module_manufacturer_id);
+            assert_eq!(Some(16 << 30), dimm.volatile_size);
+        }
+
+        let mapped = next_table!(&mut data, MemoryArrayMappedAddress);
+        assert_eq!((32 << 30) - 1, mapped.ending_address);
+        assert_eq!(2, mapped.partition_width);
+
+        next_table!(&mut data, SystemBootInformation);
+
+        let ipmi = next_table!(&mut data, IPMIDeviceInformation);
+        assert_eq!(IPMIInterfaceType::KCS, ipmi.interface_type);
+        assert_eq!(0x20, ipmi.specification_revision);
+        assert_eq!(0x10, ipmi.i2c_target_address);
+        assert_eq!(0xCA2, ipmi.base_address);
+        assert!(ipmi.io_space);
+        assert_eq!(None, ipmi.interrupt);
+
+        let lan = next_table!(&mut data, OnboardDeviceExtended);
+        assert_eq!(OnboardDeviceType::Ethernet, lan.device_type);
+        assert!(lan.enabled);
+        assert_eq!((0, 0x1F, 6), (lan.bus, lan.device, lan.function));
+        let audio = next_table!(&mut data, OnboardDeviceExtended);
+        assert_eq!(OnboardDeviceType::Sound, audio.device_type);
+        assert_eq!((0x1F, 0), (audio.device, audio.function));
+
+        let oem = next_table!(&mut data, Unknown);
+        assert_eq!(0xDD, oem.smtype);
+        assert_eq!(0x0012, oem.handle);
+        assert_eq!(
+            &[0xDD, 0x08, 0x12, 0x00, 0x05, 0x01, 0x02, 0x03],
+            oem.formatted.as_bytes()
+        );
+        assert_eq!(Some(0x0201), oem.formatted.get_u16(0x05));
+        assert_eq!(None, oem.formatted.get_u16(0x07));
+        assert_eq!(vec!["OEM".to_string()], oem.strings);
+
+        next_table!(&mut data, EndOfTable);
+        assert!(data.is_empty());
+    }
+
+    #[test]
+    pub fn test_decode_too_short() {
+        // a chassis without its serial number and asset tag, which every version has.
+        let mut data = vec![3, 0x07, 0x03, 0x00, 1, 3, 0, 0, 0];
+        let res = read_next_table(&mut data);
+        assert!(
+            matches!(&res, Ok(SMBiosTable::Unknown(u)) if u.smtype == 3 && u.handle == 3),
+            "{res:?}"
+        );
+        assert!(data.is_empty());
+    }
+
+    #[test]
+    pub fn test_enum_names() {
+        assert_eq!("Mini Tower", ChassisType::from(0x06).to_string());
+        assert_eq!(ChassisType::OutOfSpec(0x7E), ChassisType::from(0x7E));
+        assert_eq!("<OUT OF SPEC>", ChassisType::from(0x7E).name());
+        assert_eq!(0x7E, ChassisType::from(0x7E).value());
+        assert_eq!(0xB6, SlotType::PCIExpress3X16.value());
+        assert_eq!(ProcessorFamily::Zen, ProcessorFamily::from(0x6B));
+        assert_eq!("Opteron A-Series", ProcessorFamily::from(0x69).name());
+        assert_eq!(BootStatus::OEM(0x90), BootStatus::from(0x90));
+        assert_eq!(BootStatus::ProductSpecific(0xC0), BootStatus::from(0xC0));
+        assert_eq!(BootStatus::OutOfSpec(0x20), BootStatus::from(0x20));
+        assert_eq!(
+            "System watchdog timer expired",
+            BootStatus::from(8).to_string()
+        );
+        assert_eq!("3.3 V 2.9 V", ProcessorVoltage::from(0x06).to_string());
+        assert_eq!("Unknown", ProcessorVoltage::from(0x00).to_string());
+        assert_eq!(
+            vec!["64-bit capable", "Multi-Core"],
+            ProcessorCharacteristics(0x000C).names()
+        );
+    }
+
+    #[test]
+    pub fn test_short_length() {
+        for len in 0..4 {
+            let mut data = vec![2, len, 0x34, 0x12, 0, 0, 0, 0];
+            let res = read_next_table(&mut data);
+            assert!(res.is_err_and(|e| e.is_invalid_data()), "{len}");
+        }
+    }
+
+    #[test]
+    pub fn test_baseboard() {
+        let mut data = vec![
+            2, 0x0F, 0x34, 0x12, 1, 2, 0, 0, 0, 0x09, 0, 0x03, 0x00, 0x0A, 0,
+        ];
+        data.extend_from_slice(b"Acme\0Board\0\0");
+        let board = next_table!(&mut data, BaseboardInformation);
+        assert_eq!(0x1234, board.handle);
+        assert_eq!(Some("Acme"), board.manufacturer.as_deref());
+        assert_eq!(Some("Board"), board.product.as_deref());
+        assert_eq!(None, board.version);
+        assert_eq!(Some(0x09), board.feature_flags);
+        assert_eq!(Some(0x0003), board.chassis_handle);
+        assert_eq!(Some(0x0A), board.board_type);
+        assert!(data.is_empty());
+    }
+}
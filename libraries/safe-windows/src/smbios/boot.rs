@@ -0,0 +1,119 @@
+// SPDX-License-Identifier: MIT
+// Copyright 2025 IROX Contributors
+//
+
+use crate::error::Error;
+use crate::smbios::FormattedArea;
+use std::fmt::{Display, Formatter};
+
+///
+/// The status of the last boot, the first byte of the boot status field
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum BootStatus {
+    NoErrors,
+    NoBootableMedia,
+    OSFailedToLoad,
+    FirmwareDetectedHardwareFailure,
+    OSDetectedHardwareFailure,
+    UserRequestedBoot,
+    SecurityViolation,
+    PreviouslyRequestedImage,
+    WatchdogTimerExpired,
+    /// 128 to 191
+    OEM(u8),
+    /// 192 to 255
+    ProductSpecific(u8),
+    OutOfSpec(u8),
+}
+
+impl From<u8> for BootStatus {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => BootStatus::NoErrors,
+            1 => BootStatus::NoBootableMedia,
+            2 => BootStatus::OSFailedToLoad,
+            3 => BootStatus::FirmwareDetectedHardwareFailure,
+            4 => BootStatus::OSDetectedHardwareFailure,
+            5 => BootStatus::UserRequestedBoot,
+            6 => BootStatus::SecurityViolation,
+            7 => BootStatus::PreviouslyRequestedImage,
+            8 => BootStatus::WatchdogTimerExpired,
+            128..=191 => BootStatus::OEM(value),
+            192..=255 => BootStatus::ProductSpecific(value),
+            e => BootStatus::OutOfSpec(e),
+        }
+    }
+}
+
+impl BootStatus {
+    /// The raw value of the status
+    pub fn value(&self) -> u8 {
+        match self {
+            BootStatus::NoErrors => 0,
+            BootStatus::NoBootableMedia => 1,
+            BootStatus::OSFailedToLoad => 2,
+            BootStatus::FirmwareDetectedHardwareFailure => 3,
+            BootStatus::OSDetectedHardwareFailure => 4,
+            BootStatus::UserRequestedBoot => 5,
+            BootStatus::SecurityViolation => 6,
+            BootStatus::PreviouslyRequestedImage => 7,
+            BootStatus::WatchdogTimerExpired => 8,
+            BootStatus::OEM(v) | BootStatus::ProductSpecific(v) | BootStatus::OutOfSpec(v) => *v,
+        }
+    }
+    /// The name `dmidecode` gives the status
+    pub fn name(&self) -> &'static str {
+        match self {
+            BootStatus::NoErrors => "No errors detected",
+            BootStatus::NoBootableMedia => "No bootable media",
+            BootStatus::OSFailedToLoad => "Operating system failed to load",
+            BootStatus::FirmwareDetectedHardwareFailure => "Firmware-detected hardware failure",
+            BootStatus::OSDetectedHardwareFailure => "Operating system-detected hardware failure",
+            BootStatus::UserRequestedBoot => "User-requested boot",
+            BootStatus::SecurityViolation => "System security violation",
+            BootStatus::PreviouslyRequestedImage => "Previously-requested image",
+            BootStatus::WatchdogTimerExpired => "System watchdog timer expired",
+            BootStatus::OEM(_) => "OEM-specific",
+            BootStatus::ProductSpecific(_) => "Product-specific",
+            BootStatus::OutOfSpec(_) => "<OUT OF SPEC>",
+        }
+    }
+}
+
+impl Display for BootStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+///
+/// Type 32, the system boot information.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct SystemBootInformation {
+    pub handle: u16,
+    /// The length of the formatted area
+    pub length: u8,
+    pub status: BootStatus,
+    /// The rest of the boot status field, vendor or product specific
+    pub additional_data: Vec<u8>,
+}
+
+impl SystemBootInformation {
+    pub fn decode(
+        area: &FormattedArea,
+        _strings: &[String],
+    ) -> Result<SystemBootInformation, Error> {
+        let status = area.read_u8(0x0A)?.into();
+        let additional_data = area
+            .as_bytes()
+            .get(0x0B..)
+            .map(<[u8]>::to_vec)
+            .unwrap_or_default();
+        Ok(SystemBootInformation {
+            handle: area.handle(),
+            length: area.length(),
+            status,
+            additional_data,
+        })
+    }
+}
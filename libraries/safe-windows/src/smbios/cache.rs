@@ -0,0 +1,176 @@
+// SPDX-License-Identifier: MIT
+// Copyright 2025 IROX Contributors
+//
+
+use crate::error::Error;
+use crate::smbios::FormattedArea;
+
+smbios_enum!(
+    /// Where the cache is, bits 5 and 6 of its configuration
+    CacheLocation(u8) {
+        Internal = 0x00 => "Internal",
+        External = 0x01 => "External",
+        Reserved = 0x02 => "Reserved",
+        Unknown = 0x03 => "Unknown",
+    }
+);
+
+smbios_enum!(
+    /// How the cache handles writes, bits 8 and 9 of its configuration
+    CacheOperationalMode(u8) {
+        WriteThrough = 0x00 => "Write Through",
+        WriteBack = 0x01 => "Write Back",
+        VariesWithMemoryAddress = 0x02 => "Varies With Memory Address",
+        Unknown = 0x03 => "Unknown",
+    }
+);
+
+smbios_flags!(
+    CacheSRAMType(u16) {
+        OTHER = 0 => "Other",
+        UNKNOWN = 1 => "Unknown",
+        NON_BURST = 2 => "Non-burst",
+        BURST = 3 => "Burst",
+        PIPELINE_BURST = 4 => "Pipeline Burst",
+        SYNCHRONOUS = 5 => "Synchronous",
+        ASYNCHRONOUS = 6 => "Asynchronous",
+    }
+);
+
+smbios_enum!(
+    /// The error correction of a cache or a memory array
+    ErrorCorrectionType(u8) {
+        Other = 0x01 => "Other",
+        Unknown = 0x02 => "Unknown",
+        None = 0x03 => "None",
+        Parity = 0x04 => "Parity",
+        SingleBitECC = 0x05 => "Single-bit ECC",
+        MultiBitECC = 0x06 => "Multi-bit ECC",
+        CRC = 0x07 => "CRC",
+    }
+);
+
+smbios_enum!(
+    SystemCacheType(u8) {
+        Other = 0x01 => "Other",
+        Unknown = 0x02 => "Unknown",
+        Instruction = 0x03 => "Instruction",
+        Data = 0x04 => "Data",
+        Unified = 0x05 => "Unified",
+    }
+);
+
+smbios_enum!(
+    CacheAssociativity(u8) {
+        Other = 0x01 => "Other",
+        Unknown = 0x02 => "Unknown",
+        DirectMapped = 0x03 => "Direct Mapped",
+        TwoWay = 0x04 => "2-way Set-associative",
+        FourWay = 0x05 => "4-way Set-associative",
+        FullyAssociative = 0x06 => "Fully Associative",
+        EightWay = 0x07 => "8-way Set-associative",
+        SixteenWay = 0x08 => "16-way Set-associative",
+        TwelveWay = 0x09 => "12-way Set-associative",
+        TwentyFourWay = 0x0A => "24-way Set-associative",
+        ThirtyTwoWay = 0x0B => "32-way Set-associative",
+        FortyEightWay = 0x0C => "48-way Set-associative",
+        SixtyFourWay = 0x0D => "64-way Set-associative",
+        TwentyWay = 0x0E => "20-way Set-associative",
+    }
+);
+
+///
+/// The cache's configuration word
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct CacheConfiguration(pub u16);
+
+impl CacheConfiguration {
+    /// The cache level, from one
+    pub fn level(&self) -> u8 {
+        (self.0 & 0x07) as u8 + 1
+    }
+    pub fn socketed(&self) -> bool {
+        self.0 & 0x08 != 0
+    }
+    pub fn location(&self) -> CacheLocation {
+        (((self.0 >> 5) & 0x03) as u8).into()
+    }
+    pub fn enabled(&self) -> bool {
+        self.0 & 0x80 != 0
+    }
+    pub fn operational_mode(&self) -> CacheOperationalMode {
+        (((self.0 >> 8) & 0x03) as u8).into()
+    }
+}
+
+///
+/// A cache size word in KiB: the top bit selects 64K granularity over 1K.
+fn size_kib(size: u16) -> u64 {
+    match size & 0x8000 {
+        0 => u64::from(size),
+        _ => u64::from(size & 0x7FFF) * 64,
+    }
+}
+
+///
+/// A 3.1 cache size doubleword in KiB, with the same granularity bit at the top
+fn size2_kib(size: u32) -> u64 {
+    match size & 0x8000_0000 {
+        0 => u64::from(size),
+        _ => u64::from(size & 0x7FFF_FFFF) * 64,
+    }
+}
+
+///
+/// The size word in KiB, or the 3.1 size doubleword when the word is saturated
+fn cache_size(area: &FormattedArea, offset: usize, offset2: usize) -> Result<u64, Error> {
+    let size = area.read_u16(offset)?;
+    if size == 0xFFFF {
+        if let Some(size2) = area.get_u32(offset2) {
+            return Ok(size2_kib(size2));
+        }
+    }
+    Ok(size_kib(size))
+}
+
+///
+/// Type 7, a cache.  2.1 adds the speed, error correction, type and associativity, and 3.1 the
+/// sizes over 2047 MiB.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct CacheInformation {
+    pub handle: u16,
+    /// The length of the formatted area
+    pub length: u8,
+    pub socket_designation: Option<String>,
+    pub configuration: CacheConfiguration,
+    /// In KiB
+    pub maximum_size: u64,
+    /// In KiB
+    pub installed_size: u64,
+    pub supported_sram_type: CacheSRAMType,
+    pub current_sram_type: CacheSRAMType,
+    /// In nanoseconds, zero if unknown
+    pub speed: Option<u8>,
+    pub error_correction_type: Option<ErrorCorrectionType>,
+    pub system_cache_type: Option<SystemCacheType>,
+    pub associativity: Option<CacheAssociativity>,
+}
+
+impl CacheInformation {
+    pub fn decode(area: &FormattedArea, strings: &[String]) -> Result<CacheInformation, Error> {
+        Ok(CacheInformation {
+            handle: area.handle(),
+            length: area.length(),
+            socket_designation: area.read_string(0x04, strings)?,
+            configuration: CacheConfiguration(area.read_u16(0x05)?),
+            maximum_size: cache_size(area, 0x07, 0x13)?,
+            installed_size: cache_size(area, 0x09, 0x17)?,
+            supported_sram_type: CacheSRAMType(area.read_u16(0x0B)?),
+            current_sram_type: CacheSRAMType(area.read_u16(0x0D)?),
+            speed: area.get_u8(0x0F),
+            error_correction_type: area.get_u8(0x10).map(ErrorCorrectionType::from),
+            system_cache_type: area.get_u8(0x11).map(SystemCacheType::from),
+            associativity: area.get_u8(0x12).map(CacheAssociativity::from),
+        })
+    }
+}
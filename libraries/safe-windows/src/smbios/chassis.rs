@@ -0,0 +1,169 @@
+// SPDX-License-Identifier: MIT
+// Copyright 2025 IROX Contributors
+//
+
+use crate::error::Error;
+use crate::smbios::FormattedArea;
+
+smbios_enum!(
+    /// The type of enclosure, the low seven bits of the chassis type byte
+    ChassisType(u8) {
+        Other = 0x01 => "Other",
+        Unknown = 0x02 => "Unknown",
+        Desktop = 0x03 => "Desktop",
+        LowProfileDesktop = 0x04 => "Low Profile Desktop",
+        PizzaBox = 0x05 => "Pizza Box",
+        MiniTower = 0x06 => "Mini Tower",
+        Tower = 0x07 => "Tower",
+        Portable = 0x08 => "Portable",
+        Laptop = 0x09 => "Laptop",
+        Notebook = 0x0A => "Notebook",
+        HandHeld = 0x0B => "Hand Held",
+        DockingStation = 0x0C => "Docking Station",
+        AllInOne = 0x0D => "All In One",
+        SubNotebook = 0x0E => "Sub Notebook",
+        SpaceSaving = 0x0F => "Space-saving",
+        LunchBox = 0x10 => "Lunch Box",
+        MainServerChassis = 0x11 => "Main Server Chassis",
+        ExpansionChassis = 0x12 => "Expansion Chassis",
+        SubChassis = 0x13 => "Sub Chassis",
+        BusExpansionChassis = 0x14 => "Bus Expansion Chassis",
+        PeripheralChassis = 0x15 => "Peripheral Chassis",
+        RAIDChassis = 0x16 => "RAID Chassis",
+        RackMountChassis = 0x17 => "Rack Mount Chassis",
+        SealedCasePC = 0x18 => "Sealed-case PC",
+        MultiSystem = 0x19 => "Multi-system",
+        CompactPCI = 0x1A => "CompactPCI",
+        AdvancedTCA = 0x1B => "AdvancedTCA",
+        Blade = 0x1C => "Blade",
+        BladeEnclosing = 0x1D => "Blade Enclosing",
+        Tablet = 0x1E => "Tablet",
+        Convertible = 0x1F => "Convertible",
+        Detachable = 0x20 => "Detachable",
+        IoTGateway = 0x21 => "IoT Gateway",
+        EmbeddedPC = 0x22 => "Embedded PC",
+        MiniPC = 0x23 => "Mini PC",
+        StickPC = 0x24 => "Stick PC",
+    }
+);
+
+smbios_enum!(
+    /// The boot-up, power supply and thermal states of the chassis
+    ChassisState(u8) {
+        Other = 0x01 => "Other",
+        Unknown = 0x02 => "Unknown",
+        Safe = 0x03 => "Safe",
+        Warning = 0x04 => "Warning",
+        Critical = 0x05 => "Critical",
+        NonRecoverable = 0x06 => "Non-recoverable",
+    }
+);
+
+smbios_enum!(
+    ChassisSecurityStatus(u8) {
+        Other = 0x01 => "Other",
+        Unknown = 0x02 => "Unknown",
+        None = 0x03 => "None",
+        ExternalInterfaceLockedOut = 0x04 => "External Interface Locked Out",
+        ExternalInterfaceEnabled = 0x05 => "External Interface Enabled",
+    }
+);
+
+///
+/// What a contained element record counts: boards of a baseboard type, or structures of an
+/// SMBIOS structure type.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum ChassisElementType {
+    BaseboardType(u8),
+    StructureType(u8),
+}
+
+impl From<u8> for ChassisElementType {
+    fn from(value: u8) -> Self {
+        if value & 0x80 == 0 {
+            ChassisElementType::BaseboardType(value)
+        } else {
+            ChassisElementType::StructureType(value & 0x7F)
+        }
+    }
+}
+
+///
+/// How many of an element the chassis can hold
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct ChassisElement {
+    pub element_type: ChassisElementType,
+    pub minimum: u8,
+    pub maximum: u8,
+}
+
+///
+/// Type 3, the system enclosure or chassis.  2.1 adds the states and security status, 2.3 the
+/// OEM data, size and contained elements, and 2.7 the SKU number.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ChassisInformation {
+    pub handle: u16,
+    /// The length of the formatted area
+    pub length: u8,
+    pub manufacturer: Option<String>,
+    pub chassis_type: ChassisType,
+    pub lock_present: bool,
+    pub version: Option<String>,
+    pub serial_number: Option<String>,
+    pub asset_tag: Option<String>,
+    pub boot_up_state: Option<ChassisState>,
+    pub power_supply_state: Option<ChassisState>,
+    pub thermal_state: Option<ChassisState>,
+    pub security_status: Option<ChassisSecurityStatus>,
+    pub oem_defined: Option<u32>,
+    /// In rack units, zero if unspecified
+    pub height: Option<u8>,
+    /// Zero if unspecified
+    pub number_of_power_cords: Option<u8>,
+    pub contained_elements: Vec<ChassisElement>,
+    pub sku_number: Option<String>,
+}
+
+impl ChassisInformation {
+    pub fn decode(area: &FormattedArea, strings: &[String]) -> Result<ChassisInformation, Error> {
+        let chassis_type = area.read_u8(0x05)?;
+        let count = usize::from(area.get_u8(0x13).unwrap_or_default());
+        let record_len = usize::from(area.get_u8(0x14).unwrap_or_default());
+        let mut contained_elements = Vec::with_capacity(count);
+        // a record is at least three bytes, any more are reserved.
+        if record_len >= 3 {
+            for i in 0..count {
+                let Some(rec) = area.get_bytes(0x15 + i * record_len, 3) else {
+                    break;
+                };
+                if let [element_type, minimum, maximum] = *rec {
+                    contained_elements.push(ChassisElement {
+                        element_type: element_type.into(),
+                        minimum,
+                        maximum,
+                    });
+                }
+            }
+        }
+
+        Ok(ChassisInformation {
+            handle: area.handle(),
+            length: area.length(),
+            manufacturer: area.read_string(0x04, strings)?,
+            chassis_type: ChassisType::from(chassis_type & 0x7F),
+            lock_present: chassis_type & 0x80 != 0,
+            version: area.read_string(0x06, strings)?,
+            serial_number: area.read_string(0x07, strings)?,
+            asset_tag: area.read_string(0x08, strings)?,
+            boot_up_state: area.get_u8(0x09).map(ChassisState::from),
+            power_supply_state: area.get_u8(0x0A).map(ChassisState::from),
+            thermal_state: area.get_u8(0x0B).map(ChassisState::from),
+            security_status: area.get_u8(0x0C).map(ChassisSecurityStatus::from),
+            oem_defined: area.get_u32(0x0D),
+            height: area.get_u8(0x11),
+            number_of_power_cords: area.get_u8(0x12),
+            contained_elements,
+            sku_number: area.get_string(0x15 + count * record_len, strings),
+        })
+    }
+}
@@ -0,0 +1,509 @@
+// SPDX-License-Identifier: MIT
+// Copyright 2025 IROX Contributors
+//
+
+use crate::error::Error;
+use crate::smbios::{read_next_table, SMBIOSHeader};
+use irox::structs::Struct;
+use std::io::Write;
+use std::path::Path;
+
+/// Where the linux kernel exports the firmware's entry point and structure table
+pub const SYSFS_DMI_TABLES: &str = "/sys/firmware/dmi/tables";
+
+const SMBIOS2_ANCHOR: &[u8] = b"_SM_";
+const SMBIOS2_INTERMEDIATE_ANCHOR: &[u8] = b"_DMI_";
+const SMBIOS3_ANCHOR: &[u8] = b"_SM3_";
+
+/// Where a `dmidecode --dump-bin` dump puts the structure table, after the entry point
+const DUMP_TABLE_ADDRESS: usize = 0x20;
+
+///
+/// The 32-bit entry point of SMBIOS 2.1 through 2.8, anchored by `_SM_`
+#[derive(Default, Debug, Clone, Eq, PartialEq)]
+pub struct SMBIOS2EntryPoint {
+    pub checksum: u8,
+    pub length: u8,
+    pub major_version: u8,
+    pub minor_version: u8,
+    pub max_structure_size: u16,
+    pub entry_point_revision: u8,
+    pub formatted_area: [u8; 5],
+    pub intermediate_checksum: u8,
+    pub table_length: u16,
+    pub table_address: u32,
+    pub num_structures: u16,
+    pub bcd_revision: u8,
+}
+
+///
+/// The 64-bit entry point of SMBIOS 3.0 and later, anchored by `_SM3_`
+#[derive(Default, Debug, Clone, Eq, PartialEq)]
+pub struct SMBIOS3EntryPoint {
+    pub checksum: u8,
+    pub length: u8,
+    pub major_version: u8,
+    pub minor_version: u8,
+    pub docrev: u8,
+    pub entry_point_revision: u8,
+    pub table_max_size: u32,
+    pub table_address: u64,
+}
+
+///
+/// The SMBIOS entry point, which locates the structure table and carries its version.  This is
+/// what the linux kernel exports as `/sys/firmware/dmi/tables/smbios_entry_point`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum SMBIOSEntryPoint {
+    SMBIOS2(SMBIOS2EntryPoint),
+    SMBIOS3(SMBIOS3EntryPoint),
+}
+
+fn read_u8(data: &[u8], offset: usize) -> Result<u8, Error> {
+    match data.get(offset) {
+        Some(v) => Ok(*v),
+        None => Error::invalid_data("SMBIOS entry point is truncated"),
+    }
+}
+
+fn read_bytes<const N: usize>(data: &[u8], offset: usize) -> Result<[u8; N], Error> {
+    let Some(Ok(v)) = data.get(offset..offset + N).map(<[u8; N]>::try_from) else {
+        return Error::invalid_data("SMBIOS entry point is truncated");
+    };
+    Ok(v)
+}
+
+///
+/// Checks that the bytes sum to zero, as the entry point checksums require
+fn verify_checksum(data: &[u8], name: &str) -> Result<(), Error> {
+    let sum = data.iter().fold(0u8, |a, b| a.wrapping_add(*b));
+    if sum != 0 {
+        return Error::invalid_data(&format!("{name} checksum does not match"));
+    }
+    Ok(())
+}
+
+///
+/// The checksum byte that makes the bytes sum to zero
+fn checksum(data: &[u8]) -> u8 {
+    0u8.wrapping_sub(data.iter().fold(0u8, |a, b| a.wrapping_add(*b)))
+}
+
+impl SMBIOS2EntryPoint {
+    pub fn parse_from(data: &[u8]) -> Result<SMBIOS2EntryPoint, Error> {
+        if !data.starts_with(SMBIOS2_ANCHOR) {
+            return Error::invalid_data("SMBIOS 2 entry point anchor not found");
+        }
+        let length = read_u8(data, 5)?;
+        let Some(ep) = data.get(..length as usize).filter(|ep| ep.len() >= 0x1F) else {
+            return Error::invalid_data(&format!("SMBIOS 2 entry point has length {length}"));
+        };
+        verify_checksum(ep, "SMBIOS 2 entry point")?;
+        let Some(intermediate) = ep.get(0x10..0x1F) else {
+            return Error::invalid_data("SMBIOS 2 entry point is truncated");
+        };
+        if !intermediate.starts_with(SMBIOS2_INTERMEDIATE_ANCHOR) {
+            return Error::invalid_data("SMBIOS 2 intermediate anchor not found");
+        }
+        verify_checksum(intermediate, "SMBIOS 2 intermediate")?;
+
+        Ok(SMBIOS2EntryPoint {
+            checksum: read_u8(ep, 4)?,
+            length,
+            major_version: read_u8(ep, 6)?,
+            minor_version: read_u8(ep, 7)?,
+            max_structure_size: u16::from_le_bytes(read_bytes(ep, 8)?),
+            entry_point_revision: read_u8(ep, 0x0A)?,
+            formatted_area: read_bytes(ep, 0x0B)?,
+            intermediate_checksum: read_u8(ep, 0x15)?,
+            table_length: u16::from_le_bytes(read_bytes(ep, 0x16)?),
+            table_address: u32::from_le_bytes(read_bytes(ep, 0x18)?),
+            num_structures: u16::from_le_bytes(read_bytes(ep, 0x1C)?),
+            bcd_revision: read_u8(ep, 0x1E)?,
+        })
+    }
+
+    ///
+    /// The entry point as the firmware lays it out, with both checksums computed rather than
+    /// taken from the fields.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut intermediate = Vec::with_capacity(0x0F);
+        intermediate.extend_from_slice(SMBIOS2_INTERMEDIATE_ANCHOR);
+        intermediate.push(0);
+        intermediate.extend_from_slice(&self.table_length.to_le_bytes());
+        intermediate.extend_from_slice(&self.table_address.to_le_bytes());
+        intermediate.extend_from_slice(&self.num_structures.to_le_bytes());
+        intermediate.push(self.bcd_revision);
+        let sum = checksum(&intermediate);
+        if let Some(v) = intermediate.get_mut(5) {
+            *v = sum;
+        }
+
+        let mut out = Vec::with_capacity(0x1F);
+        out.extend_from_slice(SMBIOS2_ANCHOR);
+        out.extend_from_slice(&[0, 0x1F, self.major_version, self.minor_version]);
+        out.extend_from_slice(&self.max_structure_size.to_le_bytes());
+        out.push(self.entry_point_revision);
+        out.extend_from_slice(&self.formatted_area);
+        out.append(&mut intermediate);
+        let sum = checksum(&out);
+        if let Some(v) = out.get_mut(4) {
+            *v = sum;
+        }
+        out
+    }
+}
+
+impl SMBIOS3EntryPoint {
+    pub fn parse_from(data: &[u8]) -> Result<SMBIOS3EntryPoint, Error> {
+        if !data.starts_with(SMBIOS3_ANCHOR) {
+            return Error::invalid_data("SMBIOS 3 entry point anchor not found");
+        }
+        let length = read_u8(data, 6)?;
+        let Some(ep) = data.get(..length as usize).filter(|ep| ep.len() >= 0x18) else {
+            return Error::invalid_data(&format!("SMBIOS 3 entry point has length {length}"));
+        };
+        verify_checksum(ep, "SMBIOS 3 entry point")?;
+
+        Ok(SMBIOS3EntryPoint {
+            checksum: read_u8(ep, 5)?,
+            length,
+            major_version: read_u8(ep, 7)?,
+            minor_version: read_u8(ep, 8)?,
+            docrev: read_u8(ep, 9)?,
+            entry_point_revision: read_u8(ep, 0x0A)?,
+            table_max_size: u32::from_le_bytes(read_bytes(ep, 0x0C)?),
+            table_address: u64::from_le_bytes(read_bytes(ep, 0x10)?),
+        })
+    }
+
+    ///
+    /// The entry point as the firmware lays it out, with the checksum computed rather than
+    /// taken from the field.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(0x18);
+        out.extend_from_slice(SMBIOS3_ANCHOR);
+        out.extend_from_slice(&[
+            0,
+            0x18,
+            self.major_version,
+            self.minor_version,
+            self.docrev,
+            self.entry_point_revision,
+            0,
+        ]);
+        out.extend_from_slice(&self.table_max_size.to_le_bytes());
+        out.extend_from_slice(&self.table_address.to_le_bytes());
+        let sum = checksum(&out);
+        if let Some(v) = out.get_mut(5) {
+            *v = sum;
+        }
+        out
+    }
+}
+
+impl SMBIOSEntryPoint {
+    ///
+    /// Decodes either entry point, checking its anchors and checksums
+    pub fn parse_from(data: &[u8]) -> Result<SMBIOSEntryPoint, Error> {
+        if data.starts_with(SMBIOS3_ANCHOR) {
+            Ok(SMBIOSEntryPoint::SMBIOS3(SMBIOS3EntryPoint::parse_from(
+                data,
+            )?))
+        } else if data.starts_with(SMBIOS2_ANCHOR) {
+            Ok(SMBIOSEntryPoint::SMBIOS2(SMBIOS2EntryPoint::parse_from(
+                data,
+            )?))
+        } else {
+            Error::invalid_data("no SMBIOS entry point anchor found")
+        }
+    }
+
+    ///
+    /// An entry point for the structure table at `table_address` that `header` describes, a 3.x
+    /// one for SMBIOS 3 and later.  A 2.x entry point also counts the structures and the size of
+    /// the largest, which the header doesn't give.
+    pub fn for_table(header: &SMBIOSHeader, table: &[u8], table_address: u64) -> SMBIOSEntryPoint {
+        let major_version = header.smbios_major_version;
+        let minor_version = header.smbios_minor_version;
+        if major_version >= 3 {
+            return SMBIOSEntryPoint::SMBIOS3(SMBIOS3EntryPoint {
+                checksum: 0,
+                length: 0x18,
+                major_version,
+                minor_version,
+                docrev: header.dmi_revision,
+                entry_point_revision: 1,
+                table_max_size: u32::try_from(table.len()).unwrap_or(u32::MAX),
+                table_address,
+            });
+        }
+
+        let mut num_structures = 0u16;
+        let mut max_structure_size = 0u16;
+        let mut rest = table;
+        while !rest.is_empty() {
+            let before = rest.len();
+            if read_next_table(&mut rest).is_err() {
+                break;
+            }
+            num_structures = num_structures.saturating_add(1);
+            let size = u16::try_from(before - rest.len()).unwrap_or(u16::MAX);
+            max_structure_size = max_structure_size.max(size);
+        }
+        SMBIOSEntryPoint::SMBIOS2(SMBIOS2EntryPoint {
+            checksum: 0,
+            length: 0x1F,
+            major_version,
+            minor_version,
+            max_structure_size,
+            entry_point_revision: 0,
+            formatted_area: [0; 5],
+            intermediate_checksum: 0,
+            table_length: u16::try_from(table.len()).unwrap_or(u16::MAX),
+            table_address: u32::try_from(table_address).unwrap_or(u32::MAX),
+            num_structures,
+            bcd_revision: (major_version << 4) | (minor_version & 0x0F),
+        })
+    }
+
+    ///
+    /// The entry point as the firmware lays it out, with its checksums computed
+    pub fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            SMBIOSEntryPoint::SMBIOS2(ep) => ep.to_bytes(),
+            SMBIOSEntryPoint::SMBIOS3(ep) => ep.to_bytes(),
+        }
+    }
+
+    pub fn major_version(&self) -> u8 {
+        match self {
+            SMBIOSEntryPoint::SMBIOS2(ep) => ep.major_version,
+            SMBIOSEntryPoint::SMBIOS3(ep) => ep.major_version,
+        }
+    }
+
+    pub fn minor_version(&self) -> u8 {
+        match self {
+            SMBIOSEntryPoint::SMBIOS2(ep) => ep.minor_version,
+            SMBIOSEntryPoint::SMBIOS3(ep) => ep.minor_version,
+        }
+    }
+
+    ///
+    /// The length of the structure table.  A 3.x entry point only gives its maximum.
+    pub fn table_length(&self) -> u32 {
+        match self {
+            SMBIOSEntryPoint::SMBIOS2(ep) => u32::from(ep.table_length),
+            SMBIOSEntryPoint::SMBIOS3(ep) => ep.table_max_size,
+        }
+    }
+
+    ///
+    /// The physical address of the structure table
+    pub fn table_address(&self) -> u64 {
+        match self {
+            SMBIOSEntryPoint::SMBIOS2(ep) => u64::from(ep.table_address),
+            SMBIOSEntryPoint::SMBIOS3(ep) => ep.table_address,
+        }
+    }
+
+    ///
+    /// The header windows puts in front of the structure table, for a table of
+    /// `table_data_length` bytes.  A 2.x entry point has no docrev, so `dmi_revision` is zero.
+    pub fn header(&self, table_data_length: u32) -> SMBIOSHeader {
+        SMBIOSHeader {
+            used_calling_method: 0,
+            smbios_major_version: self.major_version(),
+            smbios_minor_version: self.minor_version(),
+            dmi_revision: match self {
+                SMBIOSEntryPoint::SMBIOS2(_) => 0,
+                SMBIOSEntryPoint::SMBIOS3(ep) => ep.docrev,
+            },
+            table_data_length,
+        }
+    }
+}
+
+///
+/// Reads the `smbios_entry_point` and `DMI` files the linux kernel exports in `dir`, normally
+/// [`SYSFS_DMI_TABLES`], and returns them laid out as windows' `RawSMBIOSData`: an
+/// [`SMBIOSHeader`] followed by the structure table.  Reading `DMI` usually needs root.
+pub fn read_sysfs_tables<P: AsRef<Path>>(dir: P) -> Result<Vec<u8>, Error> {
+    let dir = dir.as_ref();
+    let entry_point =
+        SMBIOSEntryPoint::parse_from(&std::fs::read(dir.join("smbios_entry_point"))?)?;
+    let table = std::fs::read(dir.join("DMI"))?;
+    raw_tables(&entry_point, &table)
+}
+
+///
+/// Reads a binary dump, as `dmidecode --dump-bin` writes it, and returns it laid out as windows'
+/// `RawSMBIOSData`: an [`SMBIOSHeader`] followed by the structure table.  The dump starts with
+/// the entry point, and the structure table is at the address it gives.
+pub fn read_dump_file<P: AsRef<Path>>(path: P) -> Result<Vec<u8>, Error> {
+    let data = std::fs::read(path)?;
+    let entry_point = SMBIOSEntryPoint::parse_from(&data)?;
+    let address = usize::try_from(entry_point.table_address()).unwrap_or(usize::MAX);
+    let Some(table) = data.get(address..) else {
+        return Error::invalid_data("SMBIOS dump's table address is past its end");
+    };
+    raw_tables(&entry_point, table)
+}
+
+///
+/// Writes raw SMBIOS data, laid out as windows' `RawSMBIOSData`, as a binary dump the way
+/// `dmidecode --dump-bin` does, which [`read_dump_file`] and `dmidecode --from-dump` read.  The
+/// raw data doesn't carry the firmware's entry point, so it's rebuilt from the header.
+pub fn write_dump<W: Write>(raw: &[u8], out: &mut W) -> Result<(), Error> {
+    let mut data = raw;
+    let header = SMBIOSHeader::parse_from(&mut data)?;
+    let table = data
+        .get(..header.table_data_length as usize)
+        .unwrap_or(data);
+    let entry_point = SMBIOSEntryPoint::for_table(&header, table, DUMP_TABLE_ADDRESS as u64);
+
+    let mut start = entry_point.to_bytes();
+    start.resize(DUMP_TABLE_ADDRESS, 0);
+    out.write_all(&start)?;
+    out.write_all(table)?;
+    Ok(())
+}
+
+///
+/// The structure table behind an [`SMBIOSHeader`], cut to the length the entry point gives
+fn raw_tables(entry_point: &SMBIOSEntryPoint, table: &[u8]) -> Result<Vec<u8>, Error> {
+    let table = table
+        .get(..entry_point.table_length() as usize)
+        .unwrap_or(table);
+    let header = entry_point.header(table.len() as u32);
+
+    let mut out = Vec::with_capacity(table.len() + 8);
+    header.write_to(&mut out)?;
+    out.extend_from_slice(table);
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::smbios::{
+        read_dump_file, read_sysfs_tables, write_dump, SMBIOS2EntryPoint, SMBIOS3EntryPoint,
+        SMBIOSEntryPoint,
+    };
+
+    const QEMU: &[u8] =
+        include_bytes!("../../doc/smbiostestdata/synthetic-qemu-2.8/smbios_entry_point");
+    const DESKTOP: &[u8] =
+        include_bytes!("../../doc/smbiostestdata/synthetic-desktop-3.4/smbios_entry_point");
+    const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/doc/smbiostestdata");
+
+    #[test]
+    pub fn test_smbios2() {
+        let ep = SMBIOSEntryPoint::parse_from(QEMU);
+        assert!(matches!(ep, Ok(SMBIOSEntryPoint::SMBIOS2(_))), "{ep:?}");
+        let Ok(ep) = ep else {
+            return;
+        };
+        assert_eq!(2, ep.major_version());
+        assert_eq!(8, ep.minor_version());
+        assert_eq!(381, ep.table_length());
+        assert_eq!(0x000F_5A90, ep.table_address());
+        let SMBIOSEntryPoint::SMBIOS2(ep) = ep else {
+            return;
+        };
+        assert_eq!(0x1F, ep.length);
+        assert_eq!(9, ep.num_structures);
+        assert_eq!(0x28, ep.bcd_revision);
+        assert_eq!(75, ep.max_structure_size);
+    }
+
+    #[test]
+    pub fn test_smbios3() {
+        let ep = SMBIOSEntryPoint::parse_from(DESKTOP);
+        assert!(matches!(ep, Ok(SMBIOSEntryPoint::SMBIOS3(_))), "{ep:?}");
+        let Ok(ep) = ep else {
+            return;
+        };
+        assert_eq!(3, ep.major_version());
+        assert_eq!(4, ep.minor_version());
+        assert_eq!(1182, ep.table_length());
+        assert_eq!(0x7AE3_F000, ep.table_address());
+        let header = ep.header(1182);
+        assert_eq!(3, header.smbios_major_version);
+        assert_eq!(4, header.smbios_minor_version);
+        assert_eq!(1182, header.table_data_length);
+    }
+
+    #[test]
+    pub fn test_invalid() {
+        // the table address's low byte, 0x90, is covered by both checksums.
+        let mut bad = QEMU.to_vec();
+        if let Some(v) = bad.get_mut(0x18) {
+            *v += 1;
+        }
+        assert!(SMBIOS2EntryPoint::parse_from(&bad).is_err_and(|e| e.is_invalid_data()));
+        // fix up the outer checksum, so only the intermediate one is wrong.
+        if let Some(v) = bad.get_mut(4) {
+            *v = v.wrapping_sub(1);
+        }
+        assert!(SMBIOS2EntryPoint::parse_from(&bad).is_err_and(|e| e.is_invalid_data()));
+
+        let mut bad = DESKTOP.to_vec();
+        if let Some(v) = bad.get_mut(0x10) {
+            *v += 1;
+        }
+        assert!(SMBIOS3EntryPoint::parse_from(&bad).is_err_and(|e| e.is_invalid_data()));
+
+        for ep in [QEMU, DESKTOP] {
+            for len in 0..ep.len() {
+                let res = SMBIOSEntryPoint::parse_from(ep.get(..len).unwrap_or_default());
+                assert!(res.is_err_and(|e| e.is_invalid_data()), "{len}");
+            }
+        }
+        let ep = SMBIOSEntryPoint::parse_from(b"_DMI_ and nothing else");
+        assert!(ep.is_err_and(|e| e.is_invalid_data()));
+    }
+
+    #[test]
+    pub fn test_to_bytes() {
+        for ep in [QEMU, DESKTOP] {
+            let parsed = SMBIOSEntryPoint::parse_from(ep);
+            assert_eq!(Some(ep.to_vec()), parsed.ok().map(|p| p.to_bytes()));
+        }
+    }
+
+    #[test]
+    pub fn test_dump_files() {
+        for dir in ["synthetic-qemu-2.8", "synthetic-desktop-3.4"] {
+            let dir = std::path::Path::new(FIXTURES).join(dir);
+            let raw = read_sysfs_tables(&dir).unwrap();
+            let dump = dir.join("dump.bin");
+            assert_eq!(raw, read_dump_file(&dump).unwrap(), "{dir:?}");
+
+            // the entry point rebuilt from the header matches the one dmidecode writes.
+            let mut written = Vec::new();
+            write_dump(&raw, &mut written).unwrap();
+            assert_eq!(std::fs::read(&dump).unwrap(), written, "{dir:?}");
+        }
+    }
+
+    #[test]
+    pub fn test_dump_past_end() {
+        let path = std::env::temp_dir().join(format!("irox-smbios-dump-{}", std::process::id()));
+        let mut ep = SMBIOSEntryPoint::parse_from(DESKTOP).unwrap();
+        let mut read_at = |address| {
+            if let SMBIOSEntryPoint::SMBIOS3(ep) = &mut ep {
+                ep.table_address = address;
+            }
+            let mut dump = ep.to_bytes();
+            dump.resize(0x20, 0);
+            std::fs::write(&path, &dump).unwrap();
+            let res = read_dump_file(&path);
+            let _ = std::fs::remove_file(&path);
+            res
+        };
+        // an empty table at the end of the file is still in it.
+        assert_eq!(Some(8), read_at(0x20).ok().map(|raw| raw.len()));
+        assert!(read_at(0x40).is_err_and(|e| e.is_invalid_data()));
+    }
+}
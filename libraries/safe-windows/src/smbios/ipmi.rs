@@ -0,0 +1,90 @@
+// SPDX-License-Identifier: MIT
+// Copyright 2025 IROX Contributors
+//
+
+use crate::error::Error;
+use crate::smbios::FormattedArea;
+
+smbios_enum!(
+    IPMIInterfaceType(u8) {
+        Unknown = 0x00 => "Unknown",
+        KCS = 0x01 => "KCS (Keyboard Control Style)",
+        SMIC = 0x02 => "SMIC (Server Management Interface Chip)",
+        BT = 0x03 => "BT (Block Transfer)",
+        SSIF = 0x04 => "SSIF (SMBus System Interface)",
+    }
+);
+
+smbios_enum!(
+    /// The spacing of the interface registers, bits 6 and 7 of the base address modifier
+    IPMIRegisterSpacing(u8) {
+        Successive = 0x00 => "Successive Byte Boundaries",
+        Boundary32Bit = 0x01 => "32-bit Boundaries",
+        Boundary16Byte = 0x02 => "16-byte Boundaries",
+    }
+);
+
+///
+/// How the interface interrupts, from the base address modifier.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct IPMIInterrupt {
+    pub active_high: bool,
+    pub level_triggered: bool,
+}
+
+///
+/// Type 38, the IPMI device information.  The fields after the non-volatile storage address are
+/// optional for an SSIF interface.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct IPMIDeviceInformation {
+    pub handle: u16,
+    /// The length of the formatted area
+    pub length: u8,
+    pub interface_type: IPMIInterfaceType,
+    /// The IPMI revision as BCD, major in the high nibble
+    pub specification_revision: u8,
+    /// The 7-bit I2C address of the BMC
+    pub i2c_target_address: u8,
+    /// `0xFF` if there's no storage device
+    pub nv_storage_device_address: u8,
+    /// For SSIF, the SMBus address of the BMC
+    pub base_address: u64,
+    /// Whether the base address is in I/O space rather than memory
+    pub io_space: bool,
+    pub register_spacing: Option<IPMIRegisterSpacing>,
+    /// None if the interrupt information isn't specified
+    pub interrupt: Option<IPMIInterrupt>,
+    /// Zero if unspecified or unsupported
+    pub interrupt_number: Option<u8>,
+}
+
+impl IPMIDeviceInformation {
+    pub fn decode(
+        area: &FormattedArea,
+        _strings: &[String],
+    ) -> Result<IPMIDeviceInformation, Error> {
+        let address = area.read_u64(0x08)?;
+        let modifier = area.get_u8(0x10);
+        // bit 0 of the address selects I/O space, and bit 4 of the modifier holds the address'
+        // real least significant bit.
+        let lsb = modifier
+            .map(|m| u64::from((m >> 4) & 0x01))
+            .unwrap_or_default();
+        Ok(IPMIDeviceInformation {
+            handle: area.handle(),
+            length: area.length(),
+            interface_type: area.read_u8(0x04)?.into(),
+            specification_revision: area.read_u8(0x05)?,
+            i2c_target_address: area.read_u8(0x06)? >> 1,
+            nv_storage_device_address: area.read_u8(0x07)?,
+            base_address: (address & !0x01) | lsb,
+            io_space: address & 0x01 != 0,
+            register_spacing: modifier.map(|m| IPMIRegisterSpacing::from(m >> 6)),
+            interrupt: modifier.filter(|m| m & 0x08 != 0).map(|m| IPMIInterrupt {
+                active_high: m & 0x02 != 0,
+                level_triggered: m & 0x01 != 0,
+            }),
+            interrupt_number: area.get_u8(0x11),
+        })
+    }
+}
@@ -0,0 +1,367 @@
+// SPDX-License-Identifier: MIT
+// Copyright 2025 IROX Contributors
+//
+
+use crate::error::Error;
+use crate::smbios::{ErrorCorrectionType, FormattedArea};
+use std::fmt::{Display, Formatter};
+
+smbios_enum!(
+    MemoryArrayLocation(u8) {
+        Other = 0x01 => "Other",
+        Unknown = 0x02 => "Unknown",
+        SystemBoard = 0x03 => "System Board Or Motherboard",
+        ISAAddOnCard = 0x04 => "ISA Add-on Card",
+        EISAAddOnCard = 0x05 => "EISA Add-on Card",
+        PCIAddOnCard = 0x06 => "PCI Add-on Card",
+        MCAAddOnCard = 0x07 => "MCA Add-on Card",
+        PCMCIAAddOnCard = 0x08 => "PCMCIA Add-on Card",
+        ProprietaryAddOnCard = 0x09 => "Proprietary Add-on Card",
+        NuBus = 0x0A => "NuBus",
+        PC98C20 = 0xA0 => "PC-98/C20 Add-on Card",
+        PC98C24 = 0xA1 => "PC-98/C24 Add-on Card",
+        PC98E = 0xA2 => "PC-98/E Add-on Card",
+        PC98LocalBus = 0xA3 => "PC-98/Local Bus Add-on Card",
+        CXLFlexbus1 = 0xA4 => "CXL Flexbus 1.0",
+    }
+);
+
+smbios_enum!(
+    MemoryArrayUse(u8) {
+        Other = 0x01 => "Other",
+        Unknown = 0x02 => "Unknown",
+        SystemMemory = 0x03 => "System Memory",
+        VideoMemory = 0x04 => "Video Memory",
+        FlashMemory = 0x05 => "Flash Memory",
+        NonVolatileRAM = 0x06 => "Non-volatile RAM",
+        CacheMemory = 0x07 => "Cache Memory",
+    }
+);
+
+smbios_enum!(
+    MemoryFormFactor(u8) {
+        Other = 0x01 => "Other",
+        Unknown = 0x02 => "Unknown",
+        SIMM = 0x03 => "SIMM",
+        SIP = 0x04 => "SIP",
+        Chip = 0x05 => "Chip",
+        DIP = 0x06 => "DIP",
+        ZIP = 0x07 => "ZIP",
+        ProprietaryCard = 0x08 => "Proprietary Card",
+        DIMM = 0x09 => "DIMM",
+        TSOP = 0x0A => "TSOP",
+        RowOfChips = 0x0B => "Row Of Chips",
+        RIMM = 0x0C => "RIMM",
+        SODIMM = 0x0D => "SODIMM",
+        SRIMM = 0x0E => "SRIMM",
+        FBDIMM = 0x0F => "FB-DIMM",
+        Die = 0x10 => "Die",
+        CAMM = 0x11 => "CAMM",
+    }
+);
+
+smbios_enum!(
+    MemoryType(u8) {
+        Other = 0x01 => "Other",
+        Unknown = 0x02 => "Unknown",
+        DRAM = 0x03 => "DRAM",
+        EDRAM = 0x04 => "EDRAM",
+        VRAM = 0x05 => "VRAM",
+        SRAM = 0x06 => "SRAM",
+        RAM = 0x07 => "RAM",
+        ROM = 0x08 => "ROM",
+        Flash = 0x09 => "Flash",
+        EEPROM = 0x0A => "EEPROM",
+        FEPROM = 0x0B => "FEPROM",
+        EPROM = 0x0C => "EPROM",
+        CDRAM = 0x0D => "CDRAM",
+        RAM3D = 0x0E => "3DRAM",
+        SDRAM = 0x0F => "SDRAM",
+        SGRAM = 0x10 => "SGRAM",
+        RDRAM = 0x11 => "RDRAM",
+        DDR = 0x12 => "DDR",
+        DDR2 = 0x13 => "DDR2",
+        DDR2FBDIMM = 0x14 => "DDR2 FB-DIMM",
+        DDR3 = 0x18 => "DDR3",
+        FBD2 = 0x19 => "FBD2",
+        DDR4 = 0x1A => "DDR4",
+        LPDDR = 0x1B => "LPDDR",
+        LPDDR2 = 0x1C => "LPDDR2",
+        LPDDR3 = 0x1D => "LPDDR3",
+        LPDDR4 = 0x1E => "LPDDR4",
+        LogicalNonVolatileDevice = 0x1F => "Logical non-volatile device",
+        HBM = 0x20 => "HBM",
+        HBM2 = 0x21 => "HBM2",
+        DDR5 = 0x22 => "DDR5",
+        LPDDR5 = 0x23 => "LPDDR5",
+        HBM3 = 0x24 => "HBM3",
+    }
+);
+
+smbios_flags!(
+    MemoryTypeDetail(u16) {
+        OTHER = 1 => "Other",
+        UNKNOWN = 2 => "Unknown",
+        FAST_PAGED = 3 => "Fast-paged",
+        STATIC_COLUMN = 4 => "Static Column",
+        PSEUDO_STATIC = 5 => "Pseudo-static",
+        RAMBUS = 6 => "RAMBus",
+        SYNCHRONOUS = 7 => "Synchronous",
+        CMOS = 8 => "CMOS",
+        EDO = 9 => "EDO",
+        WINDOW_DRAM = 10 => "Window DRAM",
+        CACHE_DRAM = 11 => "Cache DRAM",
+        NON_VOLATILE = 12 => "Non-Volatile",
+        REGISTERED = 13 => "Registered (Buffered)",
+        UNBUFFERED = 14 => "Unbuffered (Unregistered)",
+        LRDIMM = 15 => "LRDIMM",
+    }
+);
+
+smbios_enum!(
+    MemoryTechnology(u8) {
+        Other = 0x01 => "Other",
+        Unknown = 0x02 => "Unknown",
+        DRAM = 0x03 => "DRAM",
+        NVDIMMN = 0x04 => "NVDIMM-N",
+        NVDIMMF = 0x05 => "NVDIMM-F",
+        NVDIMMP = 0x06 => "NVDIMM-P",
+        IntelOptane = 0x07 => "Intel Optane persistent memory",
+    }
+);
+
+smbios_flags!(
+    MemoryOperatingModeCapability(u16) {
+        OTHER = 1 => "Other",
+        UNKNOWN = 2 => "Unknown",
+        VOLATILE = 3 => "Volatile memory",
+        BYTE_ACCESSIBLE_PERSISTENT = 4 => "Byte-accessible persistent memory",
+        BLOCK_ACCESSIBLE_PERSISTENT = 5 => "Block-accessible persistent memory",
+    }
+);
+
+///
+/// The size of a memory device
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum MemoryDeviceSize {
+    NotInstalled,
+    Unknown,
+    /// In bytes
+    Bytes(u64),
+}
+
+impl Display for MemoryDeviceSize {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MemoryDeviceSize::NotInstalled => f.write_str("No Module Installed"),
+            MemoryDeviceSize::Unknown => f.write_str("Unknown"),
+            MemoryDeviceSize::Bytes(b) => f.write_str(&format_bytes(*b)),
+        }
+    }
+}
+
+///
+/// Formats a size the way `dmidecode` does, in the largest unit that divides it exactly
+pub(crate) fn format_bytes(bytes: u64) -> String {
+    let units = ["bytes", "kB", "MB", "GB", "TB", "PB", "EB"];
+    let mut value = bytes;
+    let mut unit = 0;
+    while value >= 1024 && value & 0x3FF == 0 && unit + 1 < units.len() {
+        value /= 1024;
+        unit += 1;
+    }
+    format!("{value} {}", units.get(unit).unwrap_or(&"bytes"))
+}
+
+///
+/// Type 16, a physical memory array.  2.7 adds the capacity over 2 TiB.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct PhysicalMemoryArray {
+    pub handle: u16,
+    /// The length of the formatted area
+    pub length: u8,
+    pub location: MemoryArrayLocation,
+    pub array_use: MemoryArrayUse,
+    pub error_correction: ErrorCorrectionType,
+    /// In bytes
+    pub maximum_capacity: u64,
+    /// `0xFFFE` if not provided, `0xFFFF` if there was no error
+    pub error_information_handle: u16,
+    pub number_of_devices: u16,
+}
+
+impl PhysicalMemoryArray {
+    pub fn decode(area: &FormattedArea, _strings: &[String]) -> Result<PhysicalMemoryArray, Error> {
+        let capacity = area.read_u32(0x07)?;
+        let maximum_capacity = match (capacity, area.get_u64(0x0F)) {
+            (0x8000_0000, Some(ext)) => ext,
+            (kib, _) => u64::from(kib) * 1024,
+        };
+        Ok(PhysicalMemoryArray {
+            handle: area.handle(),
+            length: area.length(),
+            location: area.read_u8(0x04)?.into(),
+            array_use: area.read_u8(0x05)?.into(),
+            error_correction: area.read_u8(0x06)?.into(),
+            maximum_capacity,
+            error_information_handle: area.read_u16(0x0B)?,
+            number_of_devices: area.read_u16(0x0D)?,
+        })
+    }
+}
+
+///
+/// Type 17, a memory device.  2.3 adds the speed, manufacturer, serial and part numbers, 2.6 the
+/// rank, 2.7 the configured speed and sizes over 32 GiB, 2.8 the voltages, 3.2 the persistent
+/// memory fields, and 3.3 the speeds over 65534 MT/s.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct MemoryDevice {
+    pub handle: u16,
+    /// The length of the formatted area
+    pub length: u8,
+    pub physical_memory_array_handle: u16,
+    /// `0xFFFE` if not provided, `0xFFFF` if there was no error
+    pub error_information_handle: u16,
+    /// In bits, none if unknown
+    pub total_width: Option<u16>,
+    /// In bits, none if unknown
+    pub data_width: Option<u16>,
+    pub size: MemoryDeviceSize,
+    pub form_factor: MemoryFormFactor,
+    /// Zero if the device isn't in a set, `0xFF` if unknown
+    pub device_set: u8,
+    pub device_locator: Option<String>,
+    pub bank_locator: Option<String>,
+    pub memory_type: MemoryType,
+    pub type_detail: MemoryTypeDetail,
+    /// In MT/s, zero if unknown
+    pub speed: Option<u32>,
+    pub manufacturer: Option<String>,
+    pub serial_number: Option<String>,
+    pub asset_tag: Option<String>,
+    pub part_number: Option<String>,
+    /// Zero if unknown
+    pub rank: Option<u8>,
+    /// In MT/s, zero if unknown
+    pub configured_memory_speed: Option<u32>,
+    /// In millivolts, zero if unknown
+    pub minimum_voltage: Option<u16>,
+    pub maximum_voltage: Option<u16>,
+    pub configured_voltage: Option<u16>,
+    pub memory_technology: Option<MemoryTechnology>,
+    pub operating_mode_capability: Option<MemoryOperatingModeCapability>,
+    pub firmware_version: Option<String>,
+    /// The JEDEC ID: the continuation count in the low byte and the manufacturer in the high
+    pub module_manufacturer_id: Option<u16>,
+    pub module_product_id: Option<u16>,
+    pub memory_subsystem_controller_manufacturer_id: Option<u16>,
+    pub memory_subsystem_controller_product_id: Option<u16>,
+    /// In bytes, `u64::MAX` if unknown
+    pub non_volatile_size: Option<u64>,
+    pub volatile_size: Option<u64>,
+    pub cache_size: Option<u64>,
+    pub logical_size: Option<u64>,
+}
+
+///
+/// A speed word, or the 3.3 speed doubleword when the word is saturated
+fn speed(area: &FormattedArea, offset: usize, offset2: usize) -> Option<u32> {
+    match area.get_u16(offset)? {
+        0xFFFF => area.get_u32(offset2).or(Some(0xFFFF)),
+        v => Some(u32::from(v)),
+    }
+}
+
+impl MemoryDevice {
+    pub fn decode(area: &FormattedArea, strings: &[String]) -> Result<MemoryDevice, Error> {
+        let size = match area.read_u16(0x0C)? {
+            0 => MemoryDeviceSize::NotInstalled,
+            0xFFFF => MemoryDeviceSize::Unknown,
+            0x7FFF => match area.get_u32(0x1C) {
+                Some(ext) => MemoryDeviceSize::Bytes(u64::from(ext & 0x7FFF_FFFF) << 20),
+                None => MemoryDeviceSize::Bytes(0x7FFF << 20),
+            },
+            v if v & 0x8000 != 0 => MemoryDeviceSize::Bytes(u64::from(v & 0x7FFF) << 10),
+            v => MemoryDeviceSize::Bytes(u64::from(v) << 20),
+        };
+        let width = |offset| -> Result<Option<u16>, Error> {
+            Ok(Some(area.read_u16(offset)?).filter(|w| *w != 0xFFFF))
+        };
+
+        Ok(MemoryDevice {
+            handle: area.handle(),
+            length: area.length(),
+            physical_memory_array_handle: area.read_u16(0x04)?,
+            error_information_handle: area.read_u16(0x06)?,
+            total_width: width(0x08)?,
+            data_width: width(0x0A)?,
+            size,
+            form_factor: area.read_u8(0x0E)?.into(),
+            device_set: area.read_u8(0x0F)?,
+            device_locator: area.read_string(0x10, strings)?,
+            bank_locator: area.read_string(0x11, strings)?,
+            memory_type: area.read_u8(0x12)?.into(),
+            type_detail: MemoryTypeDetail(area.read_u16(0x13)?),
+            speed: speed(area, 0x15, 0x54),
+            manufacturer: area.get_string(0x17, strings),
+            serial_number: area.get_string(0x18, strings),
+            asset_tag: area.get_string(0x19, strings),
+            part_number: area.get_string(0x1A, strings),
+            rank: area.get_u8(0x1B).map(|v| v & 0x0F),
+            configured_memory_speed: speed(area, 0x20, 0x58),
+            minimum_voltage: area.get_u16(0x22),
+            maximum_voltage: area.get_u16(0x24),
+            configured_voltage: area.get_u16(0x26),
+            memory_technology: area.get_u8(0x28).map(MemoryTechnology::from),
+            operating_mode_capability: area.get_u16(0x29).map(MemoryOperatingModeCapability),
+            firmware_version: area.get_string(0x2B, strings),
+            module_manufacturer_id: area.get_u16(0x2C),
+            module_product_id: area.get_u16(0x2E),
+            memory_subsystem_controller_manufacturer_id: area.get_u16(0x30),
+            memory_subsystem_controller_product_id: area.get_u16(0x32),
+            non_volatile_size: area.get_u64(0x34),
+            volatile_size: area.get_u64(0x3C),
+            cache_size: area.get_u64(0x44),
+            logical_size: area.get_u64(0x4C),
+        })
+    }
+}
+
+///
+/// Type 19, the range of physical addresses a memory array is mapped to.  2.7 adds the
+/// addresses over 4 TiB.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct MemoryArrayMappedAddress {
+    pub handle: u16,
+    /// The length of the formatted area
+    pub length: u8,
+    /// In bytes
+    pub starting_address: u64,
+    /// In bytes, the last byte in the range
+    pub ending_address: u64,
+    pub memory_array_handle: u16,
+    pub partition_width: u8,
+}
+
+impl MemoryArrayMappedAddress {
+    pub fn decode(
+        area: &FormattedArea,
+        _strings: &[String],
+    ) -> Result<MemoryArrayMappedAddress, Error> {
+        let start = area.read_u32(0x04)?;
+        let end = area.read_u32(0x08)?;
+        let (starting_address, ending_address) =
+            match (start, area.get_u64(0x0F), area.get_u64(0x17)) {
+                (0xFFFF_FFFF, Some(start), Some(end)) => (start, end),
+                _ => (u64::from(start) << 10, (u64::from(end) << 10) + 0x3FF),
+            };
+        Ok(MemoryArrayMappedAddress {
+            handle: area.handle(),
+            length: area.length(),
+            starting_address,
+            ending_address,
+            memory_array_handle: area.read_u16(0x0C)?,
+            partition_width: area.read_u8(0x0E)?,
+        })
+    }
+}
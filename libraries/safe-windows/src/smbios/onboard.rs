@@ -0,0 +1,67 @@
+// SPDX-License-Identifier: MIT
+// Copyright 2025 IROX Contributors
+//
+
+use crate::error::Error;
+use crate::smbios::FormattedArea;
+
+smbios_enum!(
+    /// The kind of device, the low seven bits of the device type byte
+    OnboardDeviceType(u8) {
+        Other = 0x01 => "Other",
+        Unknown = 0x02 => "Unknown",
+        Video = 0x03 => "Video",
+        SCSIController = 0x04 => "SCSI Controller",
+        Ethernet = 0x05 => "Ethernet",
+        TokenRing = 0x06 => "Token Ring",
+        Sound = 0x07 => "Sound",
+        PATAController = 0x08 => "PATA Controller",
+        SATAController = 0x09 => "SATA Controller",
+        SASController = 0x0A => "SAS Controller",
+        WirelessLAN = 0x0B => "Wireless LAN",
+        Bluetooth = 0x0C => "Bluetooth",
+        WWAN = 0x0D => "WWAN",
+        EMMC = 0x0E => "eMMC (embedded Multi-Media Controller)",
+        NVMeController = 0x0F => "NVMe Controller",
+        UFSController = 0x10 => "UFS Controller",
+    }
+);
+
+///
+/// Type 41, an onboard device with its PCI address.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct OnboardDeviceExtended {
+    pub handle: u16,
+    /// The length of the formatted area
+    pub length: u8,
+    pub reference_designation: Option<String>,
+    pub device_type: OnboardDeviceType,
+    pub enabled: bool,
+    pub device_type_instance: u8,
+    pub segment_group: u16,
+    pub bus: u8,
+    pub device: u8,
+    pub function: u8,
+}
+
+impl OnboardDeviceExtended {
+    pub fn decode(
+        area: &FormattedArea,
+        strings: &[String],
+    ) -> Result<OnboardDeviceExtended, Error> {
+        let device_type = area.read_u8(0x05)?;
+        let devfn = area.read_u8(0x0A)?;
+        Ok(OnboardDeviceExtended {
+            handle: area.handle(),
+            length: area.length(),
+            reference_designation: area.read_string(0x04, strings)?,
+            device_type: OnboardDeviceType::from(device_type & 0x7F),
+            enabled: device_type & 0x80 != 0,
+            device_type_instance: area.read_u8(0x06)?,
+            segment_group: area.read_u16(0x07)?,
+            bus: area.read_u8(0x09)?,
+            device: devfn >> 3,
+            function: devfn & 0x07,
+        })
+    }
+}
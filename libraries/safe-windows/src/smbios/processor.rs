@@ -0,0 +1,418 @@
+// SPDX-License-Identifier: MIT
+// Copyright 2025 IROX Contributors
+//
+
+use crate::error::Error;
+use crate::smbios::FormattedArea;
+use std::fmt::{Display, Formatter};
+
+smbios_enum!(
+    ProcessorType(u8) {
+        Other = 0x01 => "Other",
+        Unknown = 0x02 => "Unknown",
+        CentralProcessor = 0x03 => "Central Processor",
+        MathProcessor = 0x04 => "Math Processor",
+        DSPProcessor = 0x05 => "DSP Processor",
+        VideoProcessor = 0x06 => "Video Processor",
+    }
+);
+
+smbios_enum!(
+    /// The processor family, from the family byte, or from the family 2 word when the byte is
+    /// `0xFE`.
+    ProcessorFamily(u16) {
+        Other = 0x01 => "Other",
+        Unknown = 0x02 => "Unknown",
+        I8086 = 0x03 => "8086",
+        I80286 = 0x04 => "80286",
+        I80386 = 0x05 => "80386",
+        I80486 = 0x06 => "80486",
+        I8087 = 0x07 => "8087",
+        I80287 = 0x08 => "80287",
+        I80387 = 0x09 => "80387",
+        I80487 = 0x0A => "80487",
+        Pentium = 0x0B => "Pentium",
+        PentiumPro = 0x0C => "Pentium Pro",
+        PentiumII = 0x0D => "Pentium II",
+        PentiumMMX = 0x0E => "Pentium MMX",
+        Celeron = 0x0F => "Celeron",
+        PentiumIIXeon = 0x10 => "Pentium II Xeon",
+        PentiumIII = 0x11 => "Pentium III",
+        M1 = 0x12 => "M1",
+        M2 = 0x13 => "M2",
+        CeleronM = 0x14 => "Celeron M",
+        Pentium4HT = 0x15 => "Pentium 4 HT",
+        Intel = 0x16 => "Intel",
+        Duron = 0x18 => "Duron",
+        K5 = 0x19 => "K5",
+        K6 = 0x1A => "K6",
+        K6_2 = 0x1B => "K6-2",
+        K6_3 = 0x1C => "K6-3",
+        Athlon = 0x1D => "Athlon",
+        AMD29000 = 0x1E => "AMD29000",
+        K6_2Plus = 0x1F => "K6-2+",
+        PowerPC = 0x20 => "Power PC",
+        PowerPC601 = 0x21 => "Power PC 601",
+        PowerPC603 = 0x22 => "Power PC 603",
+        PowerPC603Plus = 0x23 => "Power PC 603+",
+        PowerPC604 = 0x24 => "Power PC 604",
+        PowerPC620 = 0x25 => "Power PC 620",
+        PowerPCX704 = 0x26 => "Power PC x704",
+        PowerPC750 = 0x27 => "Power PC 750",
+        CoreDuo = 0x28 => "Core Duo",
+        CoreDuoMobile = 0x29 => "Core Duo Mobile",
+        CoreSoloMobile = 0x2A => "Core Solo Mobile",
+        Atom = 0x2B => "Atom",
+        CoreM = 0x2C => "Core M",
+        CoreM3 = 0x2D => "Core m3",
+        CoreM5 = 0x2E => "Core m5",
+        CoreM7 = 0x2F => "Core m7",
+        Alpha = 0x30 => "Alpha",
+        MIPS = 0x40 => "MIPS",
+        SPARC = 0x50 => "SPARC",
+        M68040 = 0x60 => "68040",
+        M68xxx = 0x61 => "68xxx",
+        M68000 = 0x62 => "68000",
+        M68010 = 0x63 => "68010",
+        M68020 = 0x64 => "68020",
+        M68030 = 0x65 => "68030",
+        AthlonX4 = 0x66 => "Athlon X4",
+        OpteronX1000 = 0x67 => "Opteron X1000",
+        OpteronX2000 = 0x68 => "Opteron X2000",
+        OpteronASeries = 0x69 => "Opteron A-Series",
+        OpteronX3000 = 0x6A => "Opteron X3000",
+        Zen = 0x6B => "Zen",
+        Hobbit = 0x70 => "Hobbit",
+        CrusoeTM5000 = 0x78 => "Crusoe TM5000",
+        CrusoeTM3000 = 0x79 => "Crusoe TM3000",
+        EfficeonTM8000 = 0x7A => "Efficeon TM8000",
+        Weitek = 0x80 => "Weitek",
+        Itanium = 0x82 => "Itanium",
+        Athlon64 = 0x83 => "Athlon 64",
+        Opteron = 0x84 => "Opteron",
+        Sempron = 0x85 => "Sempron",
+        Turion64 = 0x86 => "Turion 64",
+        DualCoreOpteron = 0x87 => "Dual-Core Opteron",
+        Athlon64X2 = 0x88 => "Athlon 64 X2",
+        Turion64X2 = 0x89 => "Turion 64 X2",
+        QuadCoreOpteron = 0x8A => "Quad-Core Opteron",
+        ThirdGenerationOpteron = 0x8B => "Third-Generation Opteron",
+        PhenomFX = 0x8C => "Phenom FX",
+        PhenomX4 = 0x8D => "Phenom X4",
+        PhenomX2 = 0x8E => "Phenom X2",
+        AthlonX2 = 0x8F => "Athlon X2",
+        PARISC = 0x90 => "PA-RISC",
+        V30 = 0xA0 => "V30",
+        PentiumIIIXeon = 0xB0 => "Pentium III Xeon",
+        PentiumIIISpeedstep = 0xB1 => "Pentium III Speedstep",
+        Pentium4 = 0xB2 => "Pentium 4",
+        Xeon = 0xB3 => "Xeon",
+        AS400 = 0xB4 => "AS400",
+        XeonMP = 0xB5 => "Xeon MP",
+        AthlonXP = 0xB6 => "Athlon XP",
+        AthlonMP = 0xB7 => "Athlon MP",
+        Itanium2 = 0xB8 => "Itanium 2",
+        PentiumM = 0xB9 => "Pentium M",
+        CeleronD = 0xBA => "Celeron D",
+        PentiumD = 0xBB => "Pentium D",
+        PentiumEE = 0xBC => "Pentium EE",
+        CoreSolo = 0xBD => "Core Solo",
+        Core2OrK7 = 0xBE => "Core 2 or K7",
+        Core2Duo = 0xBF => "Core 2 Duo",
+        Core2Solo = 0xC0 => "Core 2 Solo",
+        Core2Extreme = 0xC1 => "Core 2 Extreme",
+        Core2Quad = 0xC2 => "Core 2 Quad",
+        Core2ExtremeMobile = 0xC3 => "Core 2 Extreme Mobile",
+        Core2DuoMobile = 0xC4 => "Core 2 Duo Mobile",
+        Core2SoloMobile = 0xC5 => "Core 2 Solo Mobile",
+        CoreI7 = 0xC6 => "Core i7",
+        DualCoreCeleron = 0xC7 => "Dual-Core Celeron",
+        IBM390 = 0xC8 => "IBM390",
+        G4 = 0xC9 => "G4",
+        G5 = 0xCA => "G5",
+        ESA390G6 = 0xCB => "ESA/390 G6",
+        ZArchitecture = 0xCC => "z/Architecture",
+        CoreI5 = 0xCD => "Core i5",
+        CoreI3 = 0xCE => "Core i3",
+        CoreI9 = 0xCF => "Core i9",
+        C7M = 0xD2 => "C7-M",
+        C7D = 0xD3 => "C7-D",
+        C7 = 0xD4 => "C7",
+        Eden = 0xD5 => "Eden",
+        MultiCoreXeon = 0xD6 => "Multi-Core Xeon",
+        DualCoreXeon3xxx = 0xD7 => "Dual-Core Xeon 3xxx",
+        QuadCoreXeon3xxx = 0xD8 => "Quad-Core Xeon 3xxx",
+        Nano = 0xD9 => "Nano",
+        DualCoreXeon5xxx = 0xDA => "Dual-Core Xeon 5xxx",
+        QuadCoreXeon5xxx = 0xDB => "Quad-Core Xeon 5xxx",
+        DualCoreXeon7xxx = 0xDD => "Dual-Core Xeon 7xxx",
+        QuadCoreXeon7xxx = 0xDE => "Quad-Core Xeon 7xxx",
+        MultiCoreXeon7xxx = 0xDF => "Multi-Core Xeon 7xxx",
+        MultiCoreXeon3400 = 0xE0 => "Multi-Core Xeon 3400",
+        Opteron3000 = 0xE4 => "Opteron 3000",
+        SempronII = 0xE5 => "Sempron II",
+        EmbeddedOpteronQuadCore = 0xE6 => "Embedded Opteron Quad-Core",
+        PhenomTripleCore = 0xE7 => "Phenom Triple-Core",
+        TurionUltraDualCoreMobile = 0xE8 => "Turion Ultra Dual-Core Mobile",
+        TurionDualCoreMobile = 0xE9 => "Turion Dual-Core Mobile",
+        AthlonDualCore = 0xEA => "Athlon Dual-Core",
+        SempronSI = 0xEB => "Sempron SI",
+        PhenomII = 0xEC => "Phenom II",
+        AthlonII = 0xED => "Athlon II",
+        SixCoreOpteron = 0xEE => "Six-Core Opteron",
+        SempronM = 0xEF => "Sempron M",
+        I860 = 0xFA => "i860",
+        I960 = 0xFB => "i960",
+        ARMv7 = 0x100 => "ARMv7",
+        ARMv8 = 0x101 => "ARMv8",
+        ARMv9 = 0x102 => "ARMv9",
+        SH3 = 0x104 => "SH-3",
+        SH4 = 0x105 => "SH-4",
+        ARM = 0x118 => "ARM",
+        StrongARM = 0x119 => "StrongARM",
+        Cyrix6x86 = 0x12C => "6x86",
+        MediaGX = 0x12D => "MediaGX",
+        MII = 0x12E => "MII",
+        WinChip = 0x140 => "WinChip",
+        DSP = 0x15E => "DSP",
+        VideoProcessor = 0x1F4 => "Video Processor",
+        RV32 = 0x200 => "RV32",
+        RV64 = 0x201 => "RV64",
+        RV128 = 0x202 => "RV128",
+    }
+);
+
+smbios_enum!(
+    /// The processor's socket or slot
+    ProcessorUpgrade(u8) {
+        Other = 0x01 => "Other",
+        Unknown = 0x02 => "Unknown",
+        DaughterBoard = 0x03 => "Daughter Board",
+        ZIFSocket = 0x04 => "ZIF Socket",
+        ReplaceablePiggyBack = 0x05 => "Replaceable Piggy Back",
+        None = 0x06 => "None",
+        LIFSocket = 0x07 => "LIF Socket",
+        Slot1 = 0x08 => "Slot 1",
+        Slot2 = 0x09 => "Slot 2",
+        Socket370 = 0x0A => "370-pin Socket",
+        SlotA = 0x0B => "Slot A",
+        SlotM = 0x0C => "Slot M",
+        Socket423 = 0x0D => "Socket 423",
+        SocketA = 0x0E => "Socket A (Socket 462)",
+        Socket478 = 0x0F => "Socket 478",
+        Socket754 = 0x10 => "Socket 754",
+        Socket940 = 0x11 => "Socket 940",
+        Socket939 = 0x12 => "Socket 939",
+        SocketMPGA604 = 0x13 => "Socket mPGA604",
+        SocketLGA771 = 0x14 => "Socket LGA771",
+        SocketLGA775 = 0x15 => "Socket LGA775",
+        SocketS1 = 0x16 => "Socket S1",
+        SocketAM2 = 0x17 => "Socket AM2",
+        SocketF = 0x18 => "Socket F (1207)",
+        SocketLGA1366 = 0x19 => "Socket LGA1366",
+        SocketG34 = 0x1A => "Socket G34",
+        SocketAM3 = 0x1B => "Socket AM3",
+        SocketC32 = 0x1C => "Socket C32",
+        SocketLGA1156 = 0x1D => "Socket LGA1156",
+        SocketLGA1567 = 0x1E => "Socket LGA1567",
+        SocketPGA988A = 0x1F => "Socket PGA988A",
+        SocketBGA1288 = 0x20 => "Socket BGA1288",
+        SocketRPGA988B = 0x21 => "Socket rPGA988B",
+        SocketBGA1023 = 0x22 => "Socket BGA1023",
+        SocketBGA1224 = 0x23 => "Socket BGA1224",
+        SocketLGA1155 = 0x24 => "Socket LGA1155",
+        SocketLGA1356 = 0x25 => "Socket LGA1356",
+        SocketLGA2011 = 0x26 => "Socket LGA2011",
+        SocketFS1 = 0x27 => "Socket FS1",
+        SocketFS2 = 0x28 => "Socket FS2",
+        SocketFM1 = 0x29 => "Socket FM1",
+        SocketFM2 = 0x2A => "Socket FM2",
+        SocketLGA2011_3 = 0x2B => "Socket LGA2011-3",
+        SocketLGA1356_3 = 0x2C => "Socket LGA1356-3",
+        SocketLGA1150 = 0x2D => "Socket LGA1150",
+        SocketBGA1168 = 0x2E => "Socket BGA1168",
+        SocketBGA1234 = 0x2F => "Socket BGA1234",
+        SocketBGA1364 = 0x30 => "Socket BGA1364",
+        SocketAM4 = 0x31 => "Socket AM4",
+        SocketLGA1151 = 0x32 => "Socket LGA1151",
+        SocketBGA1356 = 0x33 => "Socket BGA1356",
+        SocketBGA1440 = 0x34 => "Socket BGA1440",
+        SocketBGA1515 = 0x35 => "Socket BGA1515",
+        SocketLGA3647_1 = 0x36 => "Socket LGA3647-1",
+        SocketSP3 = 0x37 => "Socket SP3",
+        SocketSP3r2 = 0x38 => "Socket SP3r2",
+        SocketLGA2066 = 0x39 => "Socket LGA2066",
+        SocketBGA1392 = 0x3A => "Socket BGA1392",
+        SocketBGA1510 = 0x3B => "Socket BGA1510",
+        SocketBGA1528 = 0x3C => "Socket BGA1528",
+        SocketLGA4189 = 0x3D => "Socket LGA4189",
+        SocketLGA1200 = 0x3E => "Socket LGA1200",
+        SocketLGA4677 = 0x3F => "Socket LGA4677",
+        SocketLGA1700 = 0x40 => "Socket LGA1700",
+        SocketBGA1744 = 0x41 => "Socket BGA1744",
+        SocketBGA1781 = 0x42 => "Socket BGA1781",
+        SocketBGA1211 = 0x43 => "Socket BGA1211",
+        SocketBGA2422 = 0x44 => "Socket BGA2422",
+        SocketLGA1211 = 0x45 => "Socket LGA1211",
+        SocketLGA2422 = 0x46 => "Socket LGA2422",
+        SocketLGA5773 = 0x47 => "Socket LGA5773",
+        SocketBGA5773 = 0x48 => "Socket BGA5773",
+        SocketAM5 = 0x49 => "Socket AM5",
+        SocketSP5 = 0x4A => "Socket SP5",
+        SocketSP6 = 0x4B => "Socket SP6",
+        SocketBGA883 = 0x4C => "Socket BGA883",
+        SocketBGA1190 = 0x4D => "Socket BGA1190",
+        SocketBGA4129 = 0x4E => "Socket BGA4129",
+        SocketLGA4710 = 0x4F => "Socket LGA4710",
+        SocketLGA7529 = 0x50 => "Socket LGA7529",
+    }
+);
+
+smbios_enum!(
+    /// Whether the processor is in use, the low three bits of the status byte
+    CPUStatus(u8) {
+        Unknown = 0x00 => "Unknown",
+        Enabled = 0x01 => "Enabled",
+        DisabledByUser = 0x02 => "Disabled By User",
+        DisabledByBIOS = 0x03 => "Disabled By BIOS",
+        Idle = 0x04 => "Idle",
+        Other = 0x07 => "Other",
+    }
+);
+
+smbios_flags!(
+    ProcessorCharacteristics(u16) {
+        UNKNOWN = 1 => "Unknown",
+        CAPABLE_64BIT = 2 => "64-bit capable",
+        MULTI_CORE = 3 => "Multi-Core",
+        HARDWARE_THREAD = 4 => "Hardware Thread",
+        EXECUTE_PROTECTION = 5 => "Execute Protection",
+        ENHANCED_VIRTUALIZATION = 6 => "Enhanced Virtualization",
+        POWER_PERFORMANCE_CONTROL = 7 => "Power/Performance Control",
+        CAPABLE_128BIT = 8 => "128-bit Capable",
+        ARM64_SOC_ID = 9 => "Arm64 SoC ID",
+    }
+);
+
+///
+/// The processor's voltage: either its current voltage, or the legacy voltages it supports.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ProcessorVoltage {
+    /// In tenths of a volt
+    Current(u8),
+    /// Bit 0 is 5V, bit 1 3.3V and bit 2 2.9V
+    Legacy(u8),
+}
+
+impl From<u8> for ProcessorVoltage {
+    fn from(value: u8) -> Self {
+        if value & 0x80 != 0 {
+            ProcessorVoltage::Current(value & 0x7F)
+        } else {
+            ProcessorVoltage::Legacy(value & 0x07)
+        }
+    }
+}
+
+impl Display for ProcessorVoltage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProcessorVoltage::Current(v) => write!(f, "{}.{} V", v / 10, v % 10),
+            ProcessorVoltage::Legacy(0) => f.write_str("Unknown"),
+            ProcessorVoltage::Legacy(v) => {
+                let volts = [(1, "5.0 V"), (2, "3.3 V"), (4, "2.9 V")]
+                    .into_iter()
+                    .filter_map(|(bit, name)| (v & bit != 0).then_some(name))
+                    .collect::<Vec<_>>();
+                f.write_str(&volts.join(" "))
+            }
+        }
+    }
+}
+
+///
+/// Type 4, a processor.  2.1 adds the cache handles, 2.3 the serial number, asset tag and part
+/// number, 2.5 the core and thread counts and characteristics, 2.6 the family 2 word, 3.0 the
+/// counts over 255 and 3.6 the enabled thread count.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ProcessorInformation {
+    pub handle: u16,
+    /// The length of the formatted area
+    pub length: u8,
+    pub socket_designation: Option<String>,
+    pub processor_type: ProcessorType,
+    pub processor_family: ProcessorFamily,
+    pub manufacturer: Option<String>,
+    /// The raw ID, for x86 the EAX and EDX results of CPUID leaf 1
+    pub processor_id: u64,
+    pub version: Option<String>,
+    pub voltage: ProcessorVoltage,
+    /// In MHz, zero if unknown
+    pub external_clock: u16,
+    /// In MHz, zero if unknown
+    pub max_speed: u16,
+    /// In MHz, zero if unknown
+    pub current_speed: u16,
+    pub socket_populated: bool,
+    pub cpu_status: CPUStatus,
+    pub upgrade: ProcessorUpgrade,
+    /// `0xFFFF` if the processor has no cache at the level
+    pub l1_cache_handle: Option<u16>,
+    pub l2_cache_handle: Option<u16>,
+    pub l3_cache_handle: Option<u16>,
+    pub serial_number: Option<String>,
+    pub asset_tag: Option<String>,
+    pub part_number: Option<String>,
+    pub core_count: Option<u16>,
+    pub core_enabled: Option<u16>,
+    pub thread_count: Option<u16>,
+    pub characteristics: Option<ProcessorCharacteristics>,
+    pub thread_enabled: Option<u16>,
+}
+
+///
+/// A count that's a byte, or `0xFF` and a word at `offset2` when it's larger than 255
+fn count(area: &FormattedArea, offset: usize, offset2: usize) -> Option<u16> {
+    match area.get_u8(offset)? {
+        0xFF => area.get_u16(offset2).or(Some(0xFF)),
+        v => Some(u16::from(v)),
+    }
+}
+
+impl ProcessorInformation {
+    pub fn decode(area: &FormattedArea, strings: &[String]) -> Result<ProcessorInformation, Error> {
+        let family = match area.read_u8(0x06)? {
+            0xFE => area.get_u16(0x28).unwrap_or(0xFE),
+            v => u16::from(v),
+        };
+        let status = area.read_u8(0x18)?;
+        Ok(ProcessorInformation {
+            handle: area.handle(),
+            length: area.length(),
+            socket_designation: area.read_string(0x04, strings)?,
+            processor_type: area.read_u8(0x05)?.into(),
+            processor_family: family.into(),
+            manufacturer: area.read_string(0x07, strings)?,
+            processor_id: area.read_u64(0x08)?,
+            version: area.read_string(0x10, strings)?,
+            voltage: area.read_u8(0x11)?.into(),
+            external_clock: area.read_u16(0x12)?,
+            max_speed: area.read_u16(0x14)?,
+            current_speed: area.read_u16(0x16)?,
+            socket_populated: status & 0x40 != 0,
+            cpu_status: (status & 0x07).into(),
+            upgrade: area.read_u8(0x19)?.into(),
+            l1_cache_handle: area.get_u16(0x1A),
+            l2_cache_handle: area.get_u16(0x1C),
+            l3_cache_handle: area.get_u16(0x1E),
+            serial_number: area.get_string(0x20, strings),
+            asset_tag: area.get_string(0x21, strings),
+            part_number: area.get_string(0x22, strings),
+            core_count: count(area, 0x23, 0x2A),
+            core_enabled: count(area, 0x24, 0x2C),
+            thread_count: count(area, 0x25, 0x2E),
+            characteristics: area.get_u16(0x26).map(ProcessorCharacteristics),
+            thread_enabled: area.get_u16(0x30),
+        })
+    }
+}
@@ -0,0 +1,161 @@
+// SPDX-License-Identifier: MIT
+// Copyright 2025 IROX Contributors
+//
+
+use crate::error::Error;
+use irox::irox_bits::Bits;
+
+///
+/// The formatted area of a structure, its header included, read at the offsets the
+/// specification gives.  A structure is only as long as the version of the specification its
+/// firmware implements, so later fields read as `None`.
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub struct FormattedArea {
+    data: Vec<u8>,
+}
+
+impl FormattedArea {
+    ///
+    /// Reads the rest of the formatted area of a structure of type `smtype`, after its type and
+    /// length, where `len` is the length less those two bytes.
+    pub fn read_from<T: Bits>(val: &mut T, smtype: u8, len: u8) -> Result<FormattedArea, Error> {
+        let mut data = Vec::with_capacity(usize::from(len) + 2);
+        data.push(smtype);
+        data.push(len.saturating_add(2));
+        for _ in 0..len {
+            data.push(val.read_u8()?);
+        }
+        Ok(FormattedArea { data })
+    }
+
+    pub fn smtype(&self) -> u8 {
+        self.get_u8(0).unwrap_or_default()
+    }
+
+    pub fn length(&self) -> u8 {
+        self.get_u8(1).unwrap_or_default()
+    }
+
+    pub fn handle(&self) -> u16 {
+        self.get_u16(2).unwrap_or_default()
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.data
+    }
+
+    pub fn get_bytes(&self, offset: usize, len: usize) -> Option<&[u8]> {
+        self.data.get(offset..offset.checked_add(len)?)
+    }
+
+    fn get_array<const N: usize>(&self, offset: usize) -> Option<[u8; N]> {
+        self.get_bytes(offset, N)?.try_into().ok()
+    }
+
+    pub fn get_u8(&self, offset: usize) -> Option<u8> {
+        self.data.get(offset).copied()
+    }
+
+    pub fn get_u16(&self, offset: usize) -> Option<u16> {
+        self.get_array(offset).map(u16::from_le_bytes)
+    }
+
+    pub fn get_u32(&self, offset: usize) -> Option<u32> {
+        self.get_array(offset).map(u32::from_le_bytes)
+    }
+
+    pub fn get_u64(&self, offset: usize) -> Option<u64> {
+        self.get_array(offset).map(u64::from_le_bytes)
+    }
+
+    ///
+    /// The string the byte at `offset` refers to, if the structure has the byte and it refers to
+    /// a string
+    pub fn get_string(&self, offset: usize, strings: &[String]) -> Option<String> {
+        string_at(strings, self.get_u8(offset)?)
+    }
+
+    fn too_short<T>(&self, offset: usize) -> Result<T, Error> {
+        Error::invalid_data(&format!(
+            "SMBIOS structure type {} has length {}, too short for its field at {offset:#04X}",
+            self.smtype(),
+            self.length()
+        ))
+    }
+
+    ///
+    /// Reads a field every version of the structure has, failing if the structure is too short.
+    pub fn read_u8(&self, offset: usize) -> Result<u8, Error> {
+        match self.get_u8(offset) {
+            Some(v) => Ok(v),
+            None => self.too_short(offset),
+        }
+    }
+
+    pub fn read_u16(&self, offset: usize) -> Result<u16, Error> {
+        match self.get_u16(offset) {
+            Some(v) => Ok(v),
+            None => self.too_short(offset),
+        }
+    }
+
+    pub fn read_u32(&self, offset: usize) -> Result<u32, Error> {
+        match self.get_u32(offset) {
+            Some(v) => Ok(v),
+            None => self.too_short(offset),
+        }
+    }
+
+    pub fn read_u64(&self, offset: usize) -> Result<u64, Error> {
+        match self.get_u64(offset) {
+            Some(v) => Ok(v),
+            None => self.too_short(offset),
+        }
+    }
+
+    pub fn read_string(&self, offset: usize, strings: &[String]) -> Result<Option<String>, Error> {
+        Ok(string_at(strings, self.read_u8(offset)?))
+    }
+}
+
+///
+/// The string numbered `index` from one, none if it is zero
+pub(crate) fn string_at(strings: &[String], index: u8) -> Option<String> {
+    (index > 0).then(|| {
+        strings
+            .get(usize::from(index) - 1)
+            .cloned()
+            .unwrap_or_default()
+    })
+}
+
+///
+/// A structure type without a decoder, such as an OEM one, or a structure too short for its
+/// type's decoder, kept as read.
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub struct UnknownStructure {
+    pub smtype: u8,
+    pub handle: u16,
+    pub formatted: FormattedArea,
+    pub strings: Vec<String>,
+}
+
+impl UnknownStructure {
+    pub fn new(formatted: FormattedArea, strings: Vec<String>) -> UnknownStructure {
+        UnknownStructure {
+            smtype: formatted.smtype(),
+            handle: formatted.handle(),
+            formatted,
+            strings,
+        }
+    }
+}
+
+///
+/// Type 127, the end-of-table marker
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub struct EndOfTable {
+    pub handle: u16,
+    /// The length of the formatted area
+    pub length: u8,
+}
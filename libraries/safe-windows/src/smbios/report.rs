@@ -0,0 +1,1174 @@
+// SPDX-License-Identifier: MIT
+// Copyright 2025 IROX Contributors
+//
+
+use crate::error::Error;
+use crate::smbios::*;
+use std::fmt::Display;
+use std::io::Write;
+
+///
+/// A field's value, as `dmidecode` prints it
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum FieldValue {
+    Text(String),
+    /// One item to a line, under the field's name
+    List(Vec<String>),
+    /// One item to a line, under the field's name and the number of items
+    CountedList(Vec<String>),
+    /// A string the structure refers to none of, `Not Specified` in text and `null` in JSON
+    NotSpecified,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Field {
+    pub name: &'static str,
+    pub value: FieldValue,
+}
+
+///
+/// One structure, described with the names `dmidecode` gives its fields.  Fields the structure
+/// is too short for are left out.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct StructureReport {
+    pub handle: u16,
+    pub smtype: u8,
+    /// The length of the formatted area
+    pub length: u8,
+    pub title: String,
+    pub fields: Vec<Field>,
+}
+
+///
+/// Every structure in an SMBIOS table, described the way `dmidecode` describes them, to print
+/// as text or JSON.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct SmbiosReport {
+    pub version: SMBIOSVersion,
+    pub dmi_revision: u8,
+    pub table_length: u32,
+    pub structures: Vec<StructureReport>,
+}
+
+///
+/// The name `dmidecode` gives a structure type
+pub fn structure_type_name(smtype: u8) -> &'static str {
+    match smtype {
+        0 => "BIOS",
+        1 => "System",
+        2 => "Base Board",
+        3 => "Chassis",
+        4 => "Processor",
+        5 => "Memory Controller",
+        6 => "Memory Module",
+        7 => "Cache",
+        8 => "Port Connector",
+        9 => "System Slots",
+        10 => "On Board Devices",
+        11 => "OEM Strings",
+        12 => "System Configuration Options",
+        13 => "BIOS Language",
+        14 => "Group Associations",
+        15 => "System Event Log",
+        16 => "Physical Memory Array",
+        17 => "Memory Device",
+        18 => "32-bit Memory Error",
+        19 => "Memory Array Mapped Address",
+        20 => "Memory Device Mapped Address",
+        21 => "Built-in Pointing Device",
+        22 => "Portable Battery",
+        23 => "System Reset",
+        24 => "Hardware Security",
+        25 => "System Power Controls",
+        26 => "Voltage Probe",
+        27 => "Cooling Device",
+        28 => "Temperature Probe",
+        29 => "Electrical Current Probe",
+        30 => "Out-of-band Remote Access",
+        31 => "Boot Integrity Services",
+        32 => "System Boot",
+        33 => "64-bit Memory Error",
+        34 => "Management Device",
+        35 => "Management Device Component",
+        36 => "Management Device Threshold Data",
+        37 => "Memory Channel",
+        38 => "IPMI Device",
+        39 => "Power Supply",
+        40 => "Additional Information",
+        41 => "Onboard Device",
+        42 => "Management Controller Host Interface",
+        43 => "TPM Device",
+        44 => "Processor Additional Information",
+        45 => "Firmware Inventory Information",
+        46 => "String Property",
+        126 => "Inactive",
+        127 => "End Of Table",
+        128.. => "OEM-specific Type",
+        _ => "Unknown Type",
+    }
+}
+
+///
+/// Collects the fields of a structure
+struct Fields {
+    /// The length of the structure's formatted area
+    length: u8,
+    fields: Vec<Field>,
+}
+
+impl Fields {
+    fn push(&mut self, name: &'static str, value: FieldValue) {
+        self.fields.push(Field { name, value });
+    }
+
+    fn text<T: Display>(&mut self, name: &'static str, value: T) {
+        self.push(name, FieldValue::Text(value.to_string()));
+    }
+
+    fn opt<T: Display>(&mut self, name: &'static str, value: Option<T>) {
+        if let Some(value) = value {
+            self.text(name, value);
+        }
+    }
+
+    fn string(&mut self, name: &'static str, value: &Option<String>) {
+        match value {
+            Some(v) => self.text(name, v),
+            None => self.push(name, FieldValue::NotSpecified),
+        }
+    }
+
+    ///
+    /// A string a later version added at `offset`, which the structure may be too short for
+    fn later_string(&mut self, name: &'static str, value: &Option<String>, offset: u8) {
+        if self.length > offset {
+            self.string(name, value);
+        }
+    }
+
+    ///
+    /// Flag names as a list, or `None` when no flags are set
+    fn flags(&mut self, name: &'static str, names: Vec<&'static str>) {
+        if names.is_empty() {
+            self.text(name, "None");
+        } else {
+            let names = names.into_iter().map(String::from).collect();
+            self.push(name, FieldValue::List(names));
+        }
+    }
+}
+
+fn handle(handle: u16) -> String {
+    format!("{handle:#06X}")
+}
+
+///
+/// An error information handle, which has two special values
+fn error_handle(value: u16) -> String {
+    match value {
+        0xFFFE => "Not Provided".to_string(),
+        0xFFFF => "No Error".to_string(),
+        v => handle(v),
+    }
+}
+
+fn unknown_if_zero<T: Display + Default + PartialEq + Copy>(value: T, unit: &str) -> String {
+    if value == T::default() {
+        "Unknown".to_string()
+    } else {
+        format!("{value} {unit}")
+    }
+}
+
+fn bus_address(segment: u16, bus: u8, device: u8, function: u8) -> String {
+    format!("{segment:04x}:{bus:02x}:{device:02x}.{function:x}")
+}
+
+///
+/// A voltage in millivolts, in volts with the precision it was given to
+fn millivolts(value: u16) -> String {
+    if value == 0 {
+        return "Unknown".to_string();
+    }
+    let volts = format!("{}.{:03}", value / 1000, value % 1000);
+    let trimmed = volts.trim_end_matches('0');
+    match trimmed.strip_suffix('.') {
+        Some(whole) => format!("{whole}.0 V"),
+        None => format!("{trimmed} V"),
+    }
+}
+
+///
+/// A JEDEC manufacturer ID: the continuation code count in the low byte, with its parity bit,
+/// and the code in the high byte.
+fn jedec_id(value: u16) -> String {
+    match value {
+        0 => "Unknown".to_string(),
+        v => format!("Bank {}, Hex {:#04X}", (v & 0x7F) + 1, v >> 8),
+    }
+}
+
+fn product_id(value: u16) -> String {
+    match value {
+        0 => "Unknown".to_string(),
+        v => format!("{v:#06X}"),
+    }
+}
+
+fn memory_size(value: u64) -> String {
+    match value {
+        0 => "None".to_string(),
+        u64::MAX => "Unknown".to_string(),
+        v => format_bytes(v),
+    }
+}
+
+fn hex_bytes(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|b| format!("{b:02X}"))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn describe_bios(f: &mut Fields, bios: &BIOSInformation) {
+    f.string("Vendor", &bios.vendor_str);
+    f.string("Version", &bios.bios_version_str);
+    f.string("Release Date", &bios.bios_date_str);
+    if bios.bios_addr != 0 {
+        let address = u32::from(bios.bios_addr) << 4;
+        f.text("Address", format!("{address:#07X}"));
+        f.text("Runtime Size", format_bytes(u64::from(0x10_0000 - address)));
+    }
+    let rom_size = match (bios.bios_rom_size, bios.extbios_rom_size) {
+        (0xFF, Some(ext)) => match ext >> 14 {
+            0 => format_bytes(u64::from(ext & 0x3FFF) << 20),
+            1 => format_bytes(u64::from(ext & 0x3FFF) << 30),
+            _ => "<OUT OF SPEC>".to_string(),
+        },
+        (size, _) => format_bytes((u64::from(size) + 1) << 16),
+    };
+    f.text("ROM Size", rom_size);
+
+    let characteristics = BIOSCharacteristics(bios.bios_characteristics);
+    let mut names = if characteristics.contains(BIOSCharacteristics::NOT_SUPPORTED) {
+        vec!["BIOS characteristics not supported"]
+    } else {
+        characteristics.names()
+    };
+    if let Some(ext1) = bios.bios_characteristics_ext1 {
+        names.extend(BIOSCharacteristicsExt1(ext1).names());
+    }
+    if let Some(ext2) = bios.bios_characteristics_ext2 {
+        names.extend(BIOSCharacteristicsExt2(ext2).names());
+    }
+    f.flags("Characteristics", names);
+
+    let revision = |major: Option<u8>, minor: Option<u8>| match (major, minor) {
+        (Some(major), Some(minor)) if major != 0xFF => Some(format!("{major}.{minor}")),
+        _ => None,
+    };
+    f.opt(
+        "BIOS Revision",
+        revision(bios.bios_major_release, bios.bios_minor_release),
+    );
+    f.opt(
+        "Firmware Revision",
+        revision(bios.embfirm_major_release, bios.embfirm_minor_release),
+    );
+}
+
+fn describe_system(f: &mut Fields, system: &SystemInformation) {
+    f.string("Manufacturer", &system.manufacturer);
+    f.string("Product Name", &system.product_name);
+    f.string("Version", &system.version);
+    f.string("Serial Number", &system.serial_number);
+    f.opt("UUID", system.uuid.map(|u| u.to_string().to_uppercase()));
+    f.opt("Wake-up Type", system.wake_up_type);
+    f.later_string("SKU Number", &system.sku_number, 0x19);
+    f.later_string("Family", &system.family, 0x1A);
+}
+
+fn describe_baseboard(f: &mut Fields, board: &BaseboardInformation) {
+    f.string("Manufacturer", &board.manufacturer);
+    f.string("Product Name", &board.product);
+    f.string("Version", &board.version);
+    f.string("Serial Number", &board.serial_number);
+    f.later_string("Asset Tag", &board.asset_tag, 0x08);
+    if let Some(features) = board.feature_flags {
+        f.flags("Features", BaseboardFeatures(features).names());
+    }
+    f.later_string("Location In Chassis", &board.location_in_chassis, 0x0A);
+    f.opt("Chassis Handle", board.chassis_handle.map(handle));
+    f.opt("Type", board.board_type.map(BoardType::from));
+    if f.length > 0x0E {
+        let handles = board.object_handles.iter().map(|h| handle(*h)).collect();
+        f.push("Contained Object Handles", FieldValue::CountedList(handles));
+    }
+}
+
+fn describe_chassis(f: &mut Fields, chassis: &ChassisInformation) {
+    f.string("Manufacturer", &chassis.manufacturer);
+    f.text("Type", chassis.chassis_type);
+    f.text(
+        "Lock",
+        if chassis.lock_present {
+            "Present"
+        } else {
+            "Not Present"
+        },
+    );
+    f.string("Version", &chassis.version);
+    f.string("Serial Number", &chassis.serial_number);
+    f.string("Asset Tag", &chassis.asset_tag);
+    f.opt("Boot-up State", chassis.boot_up_state);
+    f.opt("Power Supply State", chassis.power_supply_state);
+    f.opt("Thermal State", chassis.thermal_state);
+    f.opt("Security Status", chassis.security_status);
+    f.opt(
+        "OEM Information",
+        chassis.oem_defined.map(|v| format!("{v:#010X}")),
+    );
+    f.opt(
+        "Height",
+        chassis.height.map(|h| match h {
+            0 => "Unspecified".to_string(),
+            h => format!("{h} U"),
+        }),
+    );
+    f.opt(
+        "Number Of Power Cords",
+        chassis.number_of_power_cords.map(|n| match n {
+            0 => "Unspecified".to_string(),
+            n => n.to_string(),
+        }),
+    );
+    if f.length > 0x13 {
+        let elements = chassis
+            .contained_elements
+            .iter()
+            .map(|e| {
+                let name = match e.element_type {
+                    ChassisElementType::BaseboardType(t) => BoardType::from(t).name(),
+                    ChassisElementType::StructureType(t) => structure_type_name(t),
+                };
+                if e.minimum == e.maximum {
+                    format!("{name} ({})", e.minimum)
+                } else {
+                    format!("{name} ({}-{})", e.minimum, e.maximum)
+                }
+            })
+            .collect();
+        f.push("Contained Elements", FieldValue::CountedList(elements));
+    }
+    f.later_string("SKU Number", &chassis.sku_number, 0x15);
+}
+
+fn describe_processor(f: &mut Fields, cpu: &ProcessorInformation) {
+    f.string("Socket Designation", &cpu.socket_designation);
+    f.text("Type", cpu.processor_type);
+    f.text("Family", cpu.processor_family);
+    f.string("Manufacturer", &cpu.manufacturer);
+    f.text("ID", hex_bytes(&cpu.processor_id.to_le_bytes()));
+    f.string("Version", &cpu.version);
+    f.text("Voltage", cpu.voltage);
+    f.text("External Clock", unknown_if_zero(cpu.external_clock, "MHz"));
+    f.text("Max Speed", unknown_if_zero(cpu.max_speed, "MHz"));
+    f.text("Current Speed", unknown_if_zero(cpu.current_speed, "MHz"));
+    if cpu.socket_populated {
+        f.text("Status", format!("Populated, {}", cpu.cpu_status));
+    } else {
+        f.text("Status", "Unpopulated");
+    }
+    f.text("Upgrade", cpu.upgrade);
+    let cache = |h: Option<u16>| {
+        h.map(|h| match h {
+            0xFFFF => "Not Provided".to_string(),
+            h => handle(h),
+        })
+    };
+    f.opt("L1 Cache Handle", cache(cpu.l1_cache_handle));
+    f.opt("L2 Cache Handle", cache(cpu.l2_cache_handle));
+    f.opt("L3 Cache Handle", cache(cpu.l3_cache_handle));
+    f.later_string("Serial Number", &cpu.serial_number, 0x20);
+    f.later_string("Asset Tag", &cpu.asset_tag, 0x21);
+    f.later_string("Part Number", &cpu.part_number, 0x22);
+    let count = |v: Option<u16>| v.map(|v| unknown_if_zero(v, "").trim_end().to_string());
+    f.opt("Core Count", count(cpu.core_count));
+    f.opt("Core Enabled", count(cpu.core_enabled));
+    f.opt("Thread Count", count(cpu.thread_count));
+    f.opt("Thread Enabled", count(cpu.thread_enabled));
+    if let Some(characteristics) = cpu.characteristics {
+        f.flags("Characteristics", characteristics.names());
+    }
+}
+
+fn describe_cache(f: &mut Fields, cache: &CacheInformation) {
+    let config = cache.configuration;
+    f.string("Socket Designation", &cache.socket_designation);
+    f.text(
+        "Configuration",
+        format!(
+            "{}, {}, Level {}",
+            if config.enabled() {
+                "Enabled"
+            } else {
+                "Disabled"
+            },
+            if config.socketed() {
+                "Socketed"
+            } else {
+                "Not Socketed"
+            },
+            config.level()
+        ),
+    );
+    f.text("Operational Mode", config.operational_mode());
+    f.text("Location", config.location());
+    f.text("Installed Size", memory_size(cache.installed_size << 10));
+    f.text("Maximum Size", memory_size(cache.maximum_size << 10));
+    f.flags("Supported SRAM Types", cache.supported_sram_type.names());
+    let installed = cache.current_sram_type.names();
+    f.text("Installed SRAM Type", installed.first().unwrap_or(&"None"));
+    f.opt("Speed", cache.speed.map(|s| unknown_if_zero(s, "ns")));
+    f.opt("Error Correction Type", cache.error_correction_type);
+    f.opt("System Type", cache.system_cache_type);
+    f.opt("Associativity", cache.associativity);
+}
+
+fn describe_slot(f: &mut Fields, slot: &SystemSlotInformation) {
+    f.string("Designation", &slot.designation);
+    f.text("Type", slot.slot_type);
+    f.text("Data Bus Width", slot.data_bus_width);
+    f.text("Current Usage", slot.current_usage);
+    f.text("Length", slot.slot_length);
+    f.text("ID", slot.slot_id);
+    let mut names = slot.characteristics1.names();
+    if let Some(characteristics2) = slot.characteristics2 {
+        names.extend(characteristics2.names());
+    }
+    f.flags("Characteristics", names);
+    if let (Some(segment), Some(bus), Some(device), Some(function)) =
+        (slot.segment_group, slot.bus, slot.device, slot.function)
+    {
+        f.text("Bus Address", bus_address(segment, bus, device, function));
+    }
+    if !slot.peer_groups.is_empty() {
+        let peers = slot
+            .peer_groups
+            .iter()
+            .map(|p| {
+                let address = bus_address(p.segment_group, p.bus, p.device, p.function);
+                format!("{address} (Width {})", p.data_bus_width)
+            })
+            .collect();
+        f.push("Peer Devices", FieldValue::CountedList(peers));
+    }
+    f.opt("Slot Physical Width", slot.physical_width);
+    f.opt(
+        "Pitch",
+        slot.pitch.map(|p| match p {
+            0 => "Unknown".to_string(),
+            p => format!("{}.{:02} mm", p / 100, p % 100),
+        }),
+    );
+    f.opt("Height", slot.height);
+}
+
+fn describe_memory_array(f: &mut Fields, array: &PhysicalMemoryArray) {
+    f.text("Location", array.location);
+    f.text("Use", array.array_use);
+    f.text("Error Correction Type", array.error_correction);
+    f.text("Maximum Capacity", format_bytes(array.maximum_capacity));
+    f.text(
+        "Error Information Handle",
+        error_handle(array.error_information_handle),
+    );
+    f.text("Number Of Devices", array.number_of_devices);
+}
+
+fn describe_memory_device(f: &mut Fields, dimm: &MemoryDevice) {
+    let width = |w: Option<u16>| w.map_or("Unknown".to_string(), |w| format!("{w} bits"));
+    let detail = |names: Vec<&str>| match names.is_empty() {
+        true => "None".to_string(),
+        false => names.join(" "),
+    };
+    f.text("Array Handle", handle(dimm.physical_memory_array_handle));
+    f.text(
+        "Error Information Handle",
+        error_handle(dimm.error_information_handle),
+    );
+    f.text("Total Width", width(dimm.total_width));
+    f.text("Data Width", width(dimm.data_width));
+    f.text("Size", dimm.size);
+    f.text("Form Factor", dimm.form_factor);
+    f.text(
+        "Set",
+        match dimm.device_set {
+            0 => "None".to_string(),
+            0xFF => "Unknown".to_string(),
+            s => s.to_string(),
+        },
+    );
+    f.string("Locator", &dimm.device_locator);
+    f.string("Bank Locator", &dimm.bank_locator);
+    f.text("Type", dimm.memory_type);
+    f.text("Type Detail", detail(dimm.type_detail.names()));
+    f.opt("Speed", dimm.speed.map(|s| unknown_if_zero(s, "MT/s")));
+    f.later_string("Manufacturer", &dimm.manufacturer, 0x17);
+    f.later_string("Serial Number", &dimm.serial_number, 0x18);
+    f.later_string("Asset Tag", &dimm.asset_tag, 0x19);
+    f.later_string("Part Number", &dimm.part_number, 0x1A);
+    f.opt(
+        "Rank",
+        dimm.rank
+            .map(|r| unknown_if_zero(r, "").trim_end().to_string()),
+    );
+    f.opt(
+        "Configured Memory Speed",
+        dimm.configured_memory_speed
+            .map(|s| unknown_if_zero(s, "MT/s")),
+    );
+    f.opt("Minimum Voltage", dimm.minimum_voltage.map(millivolts));
+    f.opt("Maximum Voltage", dimm.maximum_voltage.map(millivolts));
+    f.opt(
+        "Configured Voltage",
+        dimm.configured_voltage.map(millivolts),
+    );
+    f.opt("Memory Technology", dimm.memory_technology);
+    f.opt(
+        "Memory Operating Mode Capability",
+        dimm.operating_mode_capability.map(|m| detail(m.names())),
+    );
+    f.later_string("Firmware Version", &dimm.firmware_version, 0x2B);
+    f.opt(
+        "Module Manufacturer ID",
+        dimm.module_manufacturer_id.map(jedec_id),
+    );
+    f.opt("Module Product ID", dimm.module_product_id.map(product_id));
+    f.opt(
+        "Memory Subsystem Controller Manufacturer ID",
+        dimm.memory_subsystem_controller_manufacturer_id
+            .map(jedec_id),
+    );
+    f.opt(
+        "Memory Subsystem Controller Product ID",
+        dimm.memory_subsystem_controller_product_id.map(product_id),
+    );
+    f.opt("Non-Volatile Size", dimm.non_volatile_size.map(memory_size));
+    f.opt("Volatile Size", dimm.volatile_size.map(memory_size));
+    f.opt("Cache Size", dimm.cache_size.map(memory_size));
+    f.opt("Logical Size", dimm.logical_size.map(memory_size));
+}
+
+fn describe_mapped_address(f: &mut Fields, mapped: &MemoryArrayMappedAddress) {
+    f.text(
+        "Starting Address",
+        format!("{:#013X}", mapped.starting_address),
+    );
+    f.text("Ending Address", format!("{:#013X}", mapped.ending_address));
+    let size = mapped
+        .ending_address
+        .saturating_sub(mapped.starting_address)
+        .saturating_add(1);
+    f.text("Range Size", format_bytes(size));
+    f.text("Physical Array Handle", handle(mapped.memory_array_handle));
+    f.text("Partition Width", mapped.partition_width);
+}
+
+fn describe_ipmi(f: &mut Fields, ipmi: &IPMIDeviceInformation) {
+    let revision = ipmi.specification_revision;
+    f.text("Interface Type", ipmi.interface_type);
+    f.text(
+        "Specification Version",
+        format!("{}.{}", revision >> 4, revision & 0x0F),
+    );
+    f.text(
+        "I2C Slave Address",
+        format!("{:#04x}", ipmi.i2c_target_address),
+    );
+    match ipmi.nv_storage_device_address {
+        0xFF => f.text("NV Storage Device", "Not Present"),
+        v => f.text("NV Storage Device Address", v),
+    }
+    if ipmi.interface_type == IPMIInterfaceType::SSIF {
+        f.text(
+            "Base Address",
+            format!("{:#04X} (SMBus)", ipmi.base_address >> 1),
+        );
+        return;
+    }
+    let space = if ipmi.io_space {
+        "I/O"
+    } else {
+        "Memory-mapped"
+    };
+    f.text(
+        "Base Address",
+        format!("{:#018X} ({space})", ipmi.base_address),
+    );
+    f.opt("Register Spacing", ipmi.register_spacing);
+    if let Some(interrupt) = ipmi.interrupt {
+        f.text(
+            "Interrupt Polarity",
+            if interrupt.active_high {
+                "Active High"
+            } else {
+                "Active Low"
+            },
+        );
+        f.text(
+            "Interrupt Trigger Mode",
+            if interrupt.level_triggered {
+                "Level"
+            } else {
+                "Edge"
+            },
+        );
+    }
+    f.opt(
+        "Interrupt Number",
+        ipmi.interrupt_number.filter(|n| *n != 0),
+    );
+}
+
+fn describe_onboard(f: &mut Fields, device: &OnboardDeviceExtended) {
+    f.string("Reference Designation", &device.reference_designation);
+    f.text("Type", device.device_type);
+    f.text(
+        "Status",
+        if device.enabled {
+            "Enabled"
+        } else {
+            "Disabled"
+        },
+    );
+    f.text("Type Instance", device.device_type_instance);
+    f.text(
+        "Bus Address",
+        bus_address(
+            device.segment_group,
+            device.bus,
+            device.device,
+            device.function,
+        ),
+    );
+}
+
+fn describe_unknown(f: &mut Fields, unknown: &UnknownStructure) {
+    let data = unknown
+        .formatted
+        .as_bytes()
+        .chunks(16)
+        .map(hex_bytes)
+        .collect();
+    f.push("Header and Data", FieldValue::List(data));
+    if !unknown.strings.is_empty() {
+        f.push("Strings", FieldValue::List(unknown.strings.clone()));
+    }
+}
+
+impl StructureReport {
+    ///
+    /// Describes a structure, leaving out the fields its formatted area is too short for
+    pub fn describe(table: &SMBiosTable) -> StructureReport {
+        let length = table.length();
+        let mut f = Fields {
+            length,
+            fields: Vec::new(),
+        };
+        let title = match table {
+            SMBiosTable::BiosInformation(t) => {
+                describe_bios(&mut f, t);
+                "BIOS Information"
+            }
+            SMBiosTable::SystemInformation(t) => {
+                describe_system(&mut f, t);
+                "System Information"
+            }
+            SMBiosTable::BaseboardInformation(t) => {
+                describe_baseboard(&mut f, t);
+                "Base Board Information"
+            }
+            SMBiosTable::ChassisInformation(t) => {
+                describe_chassis(&mut f, t);
+                "Chassis Information"
+            }
+            SMBiosTable::ProcessorInformation(t) => {
+                describe_processor(&mut f, t);
+                "Processor Information"
+            }
+            SMBiosTable::CacheInformation(t) => {
+                describe_cache(&mut f, t);
+                "Cache Information"
+            }
+            SMBiosTable::SystemSlotInformation(t) => {
+                describe_slot(&mut f, t);
+                "System Slot Information"
+            }
+            SMBiosTable::PhysicalMemoryArray(t) => {
+                describe_memory_array(&mut f, t);
+                "Physical Memory Array"
+            }
+            SMBiosTable::MemoryDevice(t) => {
+                describe_memory_device(&mut f, t);
+                "Memory Device"
+            }
+            SMBiosTable::MemoryArrayMappedAddress(t) => {
+                describe_mapped_address(&mut f, t);
+                "Memory Array Mapped Address"
+            }
+            SMBiosTable::SystemBootInformation(t) => {
+                f.text("Status", t.status);
+                "System Boot Information"
+            }
+            SMBiosTable::IPMIDeviceInformation(t) => {
+                describe_ipmi(&mut f, t);
+                "IPMI Device Information"
+            }
+            SMBiosTable::OnboardDeviceExtended(t) => {
+                describe_onboard(&mut f, t);
+                "Onboard Device"
+            }
+            SMBiosTable::EndOfTable(_) => "End Of Table",
+            SMBiosTable::Unknown(t) => {
+                describe_unknown(&mut f, t);
+                structure_type_name(t.smtype)
+            }
+        };
+        StructureReport {
+            handle: table.handle(),
+            smtype: table.smtype(),
+            length,
+            title: title.to_string(),
+            fields: f.fields,
+        }
+    }
+
+    ///
+    /// Writes the structure the way `dmidecode` prints it, followed by a blank line
+    pub fn write_text<W: Write>(&self, out: &mut W) -> Result<(), Error> {
+        writeln!(
+            out,
+            "Handle {}, DMI type {}, {} bytes",
+            handle(self.handle),
+            self.smtype,
+            self.length
+        )?;
+        writeln!(out, "{}", self.title)?;
+        for field in &self.fields {
+            let name = field.name;
+            match &field.value {
+                FieldValue::Text(v) => writeln!(out, "\t{name}: {v}")?,
+                FieldValue::NotSpecified => writeln!(out, "\t{name}: Not Specified")?,
+                FieldValue::List(items) => {
+                    writeln!(out, "\t{name}:")?;
+                    for item in items {
+                        writeln!(out, "\t\t{item}")?;
+                    }
+                }
+                FieldValue::CountedList(items) => {
+                    writeln!(out, "\t{name}: {}", items.len())?;
+                    for item in items {
+                        writeln!(out, "\t\t{item}")?;
+                    }
+                }
+            }
+        }
+        writeln!(out)?;
+        Ok(())
+    }
+
+    ///
+    /// Writes the structure as a JSON object, its fields an object of strings, lists of strings
+    /// and nulls.
+    pub fn write_json<W: Write>(&self, out: &mut W, indent: &str) -> Result<(), Error> {
+        writeln!(out, "{{")?;
+        writeln!(out, "{indent}  \"handle\": {},", self.handle)?;
+        writeln!(out, "{indent}  \"type\": {},", self.smtype)?;
+        writeln!(out, "{indent}  \"length\": {},", self.length)?;
+        writeln!(out, "{indent}  \"name\": {},", json_string(&self.title))?;
+        write!(out, "{indent}  \"fields\": {{")?;
+        for (i, field) in self.fields.iter().enumerate() {
+            let separator = if i == 0 { "" } else { "," };
+            write!(
+                out,
+                "{separator}\n{indent}    {}: ",
+                json_string(field.name)
+            )?;
+            match &field.value {
+                FieldValue::Text(v) => write!(out, "{}", json_string(v))?,
+                FieldValue::NotSpecified => write!(out, "null")?,
+                FieldValue::List(items) | FieldValue::CountedList(items) => {
+                    let items = items.iter().map(|v| json_string(v)).collect::<Vec<_>>();
+                    write!(out, "[{}]", items.join(", "))?;
+                }
+            }
+        }
+        if !self.fields.is_empty() {
+            write!(out, "\n{indent}  ")?;
+        }
+        writeln!(out, "}}")?;
+        write!(out, "{indent}}}")?;
+        Ok(())
+    }
+}
+
+///
+/// A quoted JSON string
+fn json_string(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_ascii_control() => {
+                let code = u32::from(c);
+                out.push_str("\\u00");
+                out.extend(char::from_digit(code >> 4, 16));
+                out.extend(char::from_digit(code & 0xF, 16));
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+impl SmbiosReport {
+    ///
+    /// Describes raw SMBIOS data laid out as windows' `RawSMBIOSData`, as
+    /// [`SmbiosTables::parse_from`] parses it.
+    pub fn parse_from(raw: &[u8]) -> Result<SmbiosReport, Error> {
+        Ok(SmbiosReport::new(&SmbiosTables::parse_from(raw)?))
+    }
+
+    ///
+    /// Describes every structure of the tables
+    pub fn new(tables: &SmbiosTables) -> SmbiosReport {
+        let header = tables.header();
+        SmbiosReport {
+            version: header.version(),
+            dmi_revision: header.dmi_revision,
+            table_length: header.table_data_length,
+            structures: tables.iter().map(StructureReport::describe).collect(),
+        }
+    }
+
+    ///
+    /// Writes the report the way `dmidecode` prints it
+    pub fn write_text<W: Write>(&self, out: &mut W) -> Result<(), Error> {
+        if self.version.major >= 3 {
+            writeln!(
+                out,
+                "SMBIOS {}.{} present.",
+                self.version, self.dmi_revision
+            )?;
+        } else {
+            writeln!(out, "SMBIOS {} present.", self.version)?;
+        }
+        writeln!(
+            out,
+            "{} structures occupying {} bytes.",
+            self.structures.len(),
+            self.table_length
+        )?;
+        writeln!(out)?;
+        for structure in &self.structures {
+            structure.write_text(out)?;
+        }
+        Ok(())
+    }
+
+    ///
+    /// Writes the report as a JSON object
+    pub fn write_json<W: Write>(&self, out: &mut W) -> Result<(), Error> {
+        writeln!(out, "{{")?;
+        writeln!(
+            out,
+            "  \"version\": {},",
+            json_string(&self.version.to_string())
+        )?;
+        writeln!(out, "  \"dmi_revision\": {},", self.dmi_revision)?;
+        writeln!(out, "  \"table_length\": {},", self.table_length)?;
+        write!(out, "  \"structures\": [")?;
+        for (i, structure) in self.structures.iter().enumerate() {
+            let separator = if i == 0 { "" } else { "," };
+            write!(out, "{separator}\n    ")?;
+            structure.write_json(out, "    ")?;
+        }
+        if !self.structures.is_empty() {
+            write!(out, "\n  ")?;
+        }
+        writeln!(out, "]")?;
+        writeln!(out, "}}")?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::smbios::*;
+    use std::collections::HashMap;
+
+    const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/doc/smbiostestdata");
+
+    const QEMU: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/doc/smbiostestdata/synthetic-qemu-2.8"
+    );
+    const DESKTOP: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/doc/smbiostestdata/synthetic-desktop-3.4"
+    );
+
+    fn report(path: &str) -> Result<SmbiosReport, Error> {
+        SmbiosReport::parse_from(&read_dump_file(format!("{path}/dump.bin"))?)
+    }
+
+    fn text(report: &SmbiosReport) -> String {
+        let mut out = Vec::new();
+        assert!(report.write_text(&mut out).is_ok());
+        String::from_utf8(out).unwrap_or_default()
+    }
+
+    fn json(report: &SmbiosReport) -> String {
+        let mut out = Vec::new();
+        assert!(report.write_json(&mut out).is_ok());
+        String::from_utf8(out).unwrap_or_default()
+    }
+
+    ///
+    /// The structures in `dmidecode`'s text output, their lines keyed by the `Handle` line
+    fn dmidecode_blocks(text: &str) -> HashMap<&str, Vec<&str>> {
+        let mut blocks = HashMap::new();
+        for block in text.split("\n\n") {
+            let mut lines = block.lines().skip_while(|l| !l.starts_with("Handle "));
+            if let Some(header) = lines.next() {
+                blocks.insert(header, lines.collect());
+            }
+        }
+        blocks
+    }
+
+    ///
+    /// Checks the tables captured from real firmware by `capture.sh` against what `dmidecode`
+    /// printed for them: every structure, its length and, for the decoded types, its title and
+    /// every single-line field `dmidecode` also prints.  The synthetic fixtures have no
+    /// `dmidecode.txt` and are skipped.
+    #[test]
+    pub fn test_captures_match_dmidecode() {
+        let dirs = std::fs::read_dir(FIXTURES);
+        assert!(dirs.is_ok(), "{dirs:?}");
+        let Ok(dirs) = dirs else {
+            return;
+        };
+        for dir in dirs.flatten() {
+            let dir = dir.path();
+            let Ok(expected) = std::fs::read_to_string(dir.join("dmidecode.txt")) else {
+                continue;
+            };
+            let report = read_sysfs_tables(&dir).and_then(|raw| SmbiosReport::parse_from(&raw));
+            assert!(report.is_ok(), "{}: {report:?}", dir.display());
+            let Ok(report) = report else {
+                continue;
+            };
+            let blocks = dmidecode_blocks(&expected);
+            assert_eq!(blocks.len(), report.structures.len(), "{}", dir.display());
+            for structure in &report.structures {
+                let header = format!(
+                    "Handle {}, DMI type {}, {} bytes",
+                    super::handle(structure.handle),
+                    structure.smtype,
+                    structure.length
+                );
+                let block = blocks.get(header.as_str());
+                assert!(block.is_some(), "{}: missing {header:?}", dir.display());
+                let Some(block) = block else {
+                    continue;
+                };
+                if structure.fields.iter().any(|f| f.name == "Header and Data") {
+                    continue;
+                }
+                assert_eq!(Some(&structure.title.as_str()), block.first(), "{header}");
+                for field in &structure.fields {
+                    let line = match &field.value {
+                        FieldValue::Text(v) => format!("\t{}: {v}", field.name),
+                        FieldValue::NotSpecified => format!("\t{}: Not Specified", field.name),
+                        _ => continue,
+                    };
+                    let prefix = format!("\t{}:", field.name);
+                    let printed: Vec<&&str> =
+                        block.iter().filter(|l| l.starts_with(&prefix)).collect();
+                    assert!(
+                        printed.is_empty() || printed.iter().any(|l| **l == line),
+                        "{header}: {line:?} but dmidecode printed {printed:?}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    pub fn test_desktop_text() {
+        let report = report(DESKTOP);
+        assert!(report.is_ok(), "{report:?}");
+        let Ok(report) = report else {
+            return;
+        };
+        assert_eq!(20, report.structures.len());
+        let text = text(&report);
+        assert!(text.starts_with("SMBIOS 3.4.0 present.\n20 structures occupying 1182 bytes.\n\n"));
+        for expected in [
+            "Handle 0x0000, DMI type 0, 26 bytes\nBIOS Information\n\tVendor: Synthetic Firmware\n",
+            "\tROM Size: 32 MB\n",
+            "\t\tUEFI is supported\n\tBIOS Revision: 5.24\n\nHandle 0x0001",
+            "\tUUID: 8C2D5E71-4B09-4A3F-B6E8-1F97D0A2C45B\n\tWake-up Type: Power Switch\n",
+            "\tAsset Tag: Not Specified\n\tFeatures:\n\t\tBoard is a hosting board\n",
+            "\tContained Object Handles: 2\n\t\t0x0004\n\t\t0x0009\n",
+            "\tID: A3 06 09 00 FF FB EB BF\n",
+            "\tFamily: Core i9\n",
+            "\tStatus: Populated, Enabled\n\tUpgrade: Socket LGA1700\n",
+            "\tL3 Cache Handle: 0x0007\n",
+            "Cache Information\n\tSocket Designation: L2 Cache\n\tConfiguration: Enabled, Not Socketed, Level 2\n",
+            "\tInstalled Size: 14 MB\n",
+            "\tBus Address: 0000:01:00.0\n",
+            "\tDesignation: PCIEX4_1\n\tType: PCI Express 4 x4\n\tData Bus Width: x4\n",
+            "\tMaximum Capacity: 128 GB\n\tError Information Handle: Not Provided\n",
+            "\tSize: 16 GB\n\tForm Factor: DIMM\n\tSet: None\n\tLocator: DIMM_B1\n",
+            "\tSpeed: 4800 MT/s\n",
+            "\tConfigured Voltage: 1.1 V\n",
+            "\tModule Manufacturer ID: Bank 1, Hex 0x2C\n",
+            "\tEnding Address: 0x007FFFFFFFF\n\tRange Size: 32 GB\n",
+            "\tStatus: No errors detected\n",
+            "\tBase Address: 0x0000000000000CA2 (I/O)\n",
+            "Onboard Device\n\tReference Designation: Onboard LAN\n\tType: Ethernet\n",
+            "\tBus Address: 0000:00:1f.6\n",
+            "Handle 0x0012, DMI type 221, 8 bytes\nOEM-specific Type\n\tHeader and Data:\n\t\tDD 08 12 00 05 01 02 03\n\tStrings:\n\t\tOEM\n",
+        ] {
+            assert!(text.contains(expected), "missing {expected:?} from:\n{text}");
+        }
+        assert!(text.ends_with("Handle 0x0013, DMI type 127, 4 bytes\nEnd Of Table\n\n"));
+    }
+
+    #[test]
+    pub fn test_qemu_text() {
+        let report = report(QEMU);
+        assert!(report.is_ok(), "{report:?}");
+        let Ok(report) = report else {
+            return;
+        };
+        let text = text(&report);
+        assert!(text.starts_with("SMBIOS 2.8 present.\n9 structures occupying 381 bytes.\n\n"));
+        for expected in [
+            "\tRuntime Size: 96 kB\n\tROM Size: 64 kB\n\tCharacteristics:\n\t\tBIOS characteristics not supported\n",
+            "\tSerial Number: Not Specified\n",
+            "\tHeight: Unspecified\n",
+            "\tFamily: Other\n\tManufacturer: QEMU\n",
+            "\tL1 Cache Handle: Not Provided\n",
+            "\tThread Count: 4\n\tCharacteristics:\n\t\tUnknown\n",
+            "\tTotal Width: Unknown\n",
+        ] {
+            assert!(text.contains(expected), "missing {expected:?} from:\n{text}");
+        }
+
+        // fields added after 2.8 are left out rather than reported as unspecified
+        for absent in [
+            "Thread Enabled",
+            "Memory Technology",
+            "Firmware Version",
+            "Logical Size",
+        ] {
+            assert!(!text.contains(absent), "unexpected {absent:?} in:\n{text}");
+        }
+    }
+
+    #[test]
+    pub fn test_json() {
+        let report = report(DESKTOP);
+        assert!(report.is_ok(), "{report:?}");
+        let Ok(report) = report else {
+            return;
+        };
+        let json = json(&report);
+        assert!(json.starts_with(
+            "{\n  \"version\": \"3.4\",\n  \"dmi_revision\": 0,\n  \"table_length\": 1182,\n  \"structures\": [\n    {\n      \"handle\": 0,\n      \"type\": 0,\n      \"length\": 26,\n      \"name\": \"BIOS Information\",\n      \"fields\": {\n        \"Vendor\": \"Synthetic Firmware\",\n"
+        ));
+        for expected in [
+            "\"Contained Object Handles\": [\"0x0004\", \"0x0009\"]\n",
+            "\"Strings\": [\"OEM\"]\n      }\n    },",
+            "\"name\": \"End Of Table\",\n      \"fields\": {}\n    }\n  ]\n}\n",
+        ] {
+            assert!(
+                json.contains(expected),
+                "missing {expected:?} from:\n{json}"
+            );
+        }
+        assert_eq!(
+            json.matches('{').count(),
+            json.matches('}').count(),
+            "{json}"
+        );
+        assert_eq!(1 + 2 * 20, json.matches('{').count());
+    }
+
+    #[test]
+    pub fn test_json_not_specified() {
+        // strings the structure refers to none of are null
+        let qemu = report(QEMU);
+        assert!(qemu.is_ok(), "{qemu:?}");
+        let Ok(qemu) = qemu else {
+            return;
+        };
+        let json = json(&qemu);
+        assert!(
+            json.contains("\"Version\": \"pc-q35-8.2\",\n        \"Serial Number\": null,"),
+            "{json}"
+        );
+    }
+
+    #[test]
+    pub fn test_too_short() {
+        // a system information structure without its 2.0 strings, then the end of table
+        let structures = [
+            &[1, 5, 0x01, 0x00, 1][..],
+            b"Acme\0\0",
+            &[127, 4, 0x02, 0x00, 0, 0],
+        ];
+        let mut raw = vec![0, 3, 0, 0, 17, 0, 0, 0];
+        for s in structures {
+            raw.extend_from_slice(s);
+        }
+        let report = SmbiosReport::parse_from(&raw);
+        assert!(report.is_ok(), "{report:?}");
+        let Ok(report) = report else {
+            return;
+        };
+        assert_eq!(2, report.structures.len());
+        let text = text(&report);
+        assert!(
+            text.contains("Handle 0x0001, DMI type 1, 5 bytes\nSystem\n\tHeader and Data:\n\t\t01 05 01 00 01\n\tStrings:\n\t\tAcme\n"),
+            "{text}"
+        );
+    }
+
+    #[test]
+    pub fn test_json_string() {
+        assert_eq!("\"plain\"", super::json_string("plain"));
+        assert_eq!(
+            "\"a \\\"quoted\\\" \\\\ path\\t\\n\\u001f\"",
+            super::json_string("a \"quoted\" \\ path\t\n\u{1F}")
+        );
+    }
+
+    #[test]
+    pub fn test_type_names() {
+        assert_eq!("Port Connector", structure_type_name(8));
+        assert_eq!("Unknown Type", structure_type_name(50));
+        assert_eq!("OEM-specific Type", structure_type_name(0x80));
+    }
+}
@@ -0,0 +1,256 @@
+// SPDX-License-Identifier: MIT
+// Copyright 2025 IROX Contributors
+//
+
+use crate::error::Error;
+use crate::smbios::FormattedArea;
+
+smbios_enum!(
+    SlotType(u8) {
+        Other = 0x01 => "Other",
+        Unknown = 0x02 => "Unknown",
+        ISA = 0x03 => "ISA",
+        MCA = 0x04 => "MCA",
+        EISA = 0x05 => "EISA",
+        PCI = 0x06 => "PCI",
+        PCCard = 0x07 => "PC Card (PCMCIA)",
+        VLB = 0x08 => "VLB",
+        Proprietary = 0x09 => "Proprietary",
+        ProcessorCard = 0x0A => "Processor Card",
+        ProprietaryMemoryCard = 0x0B => "Proprietary Memory Card",
+        IORiserCard = 0x0C => "I/O Riser Card",
+        NuBus = 0x0D => "NuBus",
+        PCI66 = 0x0E => "PCI-66",
+        AGP = 0x0F => "AGP",
+        AGP2x = 0x10 => "AGP 2x",
+        AGP4x = 0x11 => "AGP 4x",
+        PCIX = 0x12 => "PCI-X",
+        AGP8x = 0x13 => "AGP 8x",
+        M2Socket1DP = 0x14 => "M.2 Socket 1-DP",
+        M2Socket1SD = 0x15 => "M.2 Socket 1-SD",
+        M2Socket2 = 0x16 => "M.2 Socket 2",
+        M2Socket3 = 0x17 => "M.2 Socket 3",
+        MXMTypeI = 0x18 => "MXM Type I",
+        MXMTypeII = 0x19 => "MXM Type II",
+        MXMTypeIII = 0x1A => "MXM Type III",
+        MXMTypeIIIHE = 0x1B => "MXM Type III-HE",
+        MXMTypeIV = 0x1C => "MXM Type IV",
+        MXM3TypeA = 0x1D => "MXM 3.0 Type A",
+        MXM3TypeB = 0x1E => "MXM 3.0 Type B",
+        PCIExpress2SFF8639 = 0x1F => "PCI Express 2 SFF-8639 (U.2)",
+        PCIExpress3SFF8639 = 0x20 => "PCI Express 3 SFF-8639 (U.2)",
+        PCIExpressMini52WithKeepOuts = 0x21 => "PCI Express Mini 52-pin with bottom-side keep-outs",
+        PCIExpressMini52WithoutKeepOuts = 0x22 => "PCI Express Mini 52-pin without bottom-side keep-outs",
+        PCIExpressMini76 = 0x23 => "PCI Express Mini 76-pin",
+        PCIExpress4SFF8639 = 0x24 => "PCI Express 4 SFF-8639 (U.2)",
+        PCIExpress5SFF8639 = 0x25 => "PCI Express 5 SFF-8639 (U.2)",
+        OCPNIC3SFF = 0x26 => "OCP NIC 3.0 Small Form Factor (SFF)",
+        OCPNIC3LFF = 0x27 => "OCP NIC 3.0 Large Form Factor (LFF)",
+        OCPNICPrior3 = 0x28 => "OCP NIC Prior to 3.0",
+        CXLFlexbus1 = 0x30 => "CXL FLexbus 1.0",
+        PC98C20 = 0xA0 => "PC-98/C20",
+        PC98C24 = 0xA1 => "PC-98/C24",
+        PC98E = 0xA2 => "PC-98/E",
+        PC98LocalBus = 0xA3 => "PC-98/Local Bus",
+        PC98Card = 0xA4 => "PC-98/Card",
+        PCIExpress = 0xA5 => "PCI Express",
+        PCIExpressX1 = 0xA6 => "PCI Express x1",
+        PCIExpressX2 = 0xA7 => "PCI Express x2",
+        PCIExpressX4 = 0xA8 => "PCI Express x4",
+        PCIExpressX8 = 0xA9 => "PCI Express x8",
+        PCIExpressX16 = 0xAA => "PCI Express x16",
+        PCIExpress2 = 0xAB => "PCI Express 2",
+        PCIExpress2X1 = 0xAC => "PCI Express 2 x1",
+        PCIExpress2X2 = 0xAD => "PCI Express 2 x2",
+        PCIExpress2X4 = 0xAE => "PCI Express 2 x4",
+        PCIExpress2X8 = 0xAF => "PCI Express 2 x8",
+        PCIExpress2X16 = 0xB0 => "PCI Express 2 x16",
+        PCIExpress3 = 0xB1 => "PCI Express 3",
+        PCIExpress3X1 = 0xB2 => "PCI Express 3 x1",
+        PCIExpress3X2 = 0xB3 => "PCI Express 3 x2",
+        PCIExpress3X4 = 0xB4 => "PCI Express 3 x4",
+        PCIExpress3X8 = 0xB5 => "PCI Express 3 x8",
+        PCIExpress3X16 = 0xB6 => "PCI Express 3 x16",
+        PCIExpress4 = 0xB8 => "PCI Express 4",
+        PCIExpress4X1 = 0xB9 => "PCI Express 4 x1",
+        PCIExpress4X2 = 0xBA => "PCI Express 4 x2",
+        PCIExpress4X4 = 0xBB => "PCI Express 4 x4",
+        PCIExpress4X8 = 0xBC => "PCI Express 4 x8",
+        PCIExpress4X16 = 0xBD => "PCI Express 4 x16",
+        PCIExpress5 = 0xBE => "PCI Express 5",
+        PCIExpress5X1 = 0xBF => "PCI Express 5 x1",
+        PCIExpress5X2 = 0xC0 => "PCI Express 5 x2",
+        PCIExpress5X4 = 0xC1 => "PCI Express 5 x4",
+        PCIExpress5X8 = 0xC2 => "PCI Express 5 x8",
+        PCIExpress5X16 = 0xC3 => "PCI Express 5 x16",
+        PCIExpress6Plus = 0xC4 => "PCI Express 6+",
+        EDSFFE1 = 0xC5 => "EDSFF E1",
+        EDSFFE3 = 0xC6 => "EDSFF E3",
+    }
+);
+
+smbios_enum!(
+    /// The width of a slot's data bus, or of the slot itself
+    SlotWidth(u8) {
+        Other = 0x01 => "Other",
+        Unknown = 0x02 => "Unknown",
+        Bit8 = 0x03 => "8-bit",
+        Bit16 = 0x04 => "16-bit",
+        Bit32 = 0x05 => "32-bit",
+        Bit64 = 0x06 => "64-bit",
+        Bit128 = 0x07 => "128-bit",
+        X1 = 0x08 => "x1",
+        X2 = 0x09 => "x2",
+        X4 = 0x0A => "x4",
+        X8 = 0x0B => "x8",
+        X12 = 0x0C => "x12",
+        X16 = 0x0D => "x16",
+        X32 = 0x0E => "x32",
+    }
+);
+
+smbios_enum!(
+    SlotUsage(u8) {
+        Other = 0x01 => "Other",
+        Unknown = 0x02 => "Unknown",
+        Available = 0x03 => "Available",
+        InUse = 0x04 => "In Use",
+        Unavailable = 0x05 => "Unavailable",
+    }
+);
+
+smbios_enum!(
+    SlotLength(u8) {
+        Other = 0x01 => "Other",
+        Unknown = 0x02 => "Unknown",
+        Short = 0x03 => "Short",
+        Long = 0x04 => "Long",
+        DriveFormFactor2_5 = 0x05 => "2.5\" drive form factor",
+        DriveFormFactor3_5 = 0x06 => "3.5\" drive form factor",
+    }
+);
+
+smbios_enum!(
+    SlotHeight(u8) {
+        NotApplicable = 0x00 => "Not applicable",
+        Other = 0x01 => "Other",
+        Unknown = 0x02 => "Unknown",
+        FullHeight = 0x03 => "Full height",
+        LowProfile = 0x04 => "Low-profile",
+    }
+);
+
+smbios_flags!(
+    SlotCharacteristics1(u8) {
+        UNKNOWN = 0 => "Unknown",
+        PROVIDES_5V = 1 => "5.0 V is provided",
+        PROVIDES_3_3V = 2 => "3.3 V is provided",
+        SHARED = 3 => "Opening is shared",
+        PC_CARD_16 = 4 => "PC Card-16 is supported",
+        CARDBUS = 5 => "Cardbus is supported",
+        ZOOM_VIDEO = 6 => "Zoom Video is supported",
+        MODEM_RING_RESUME = 7 => "Modem ring resume is supported",
+    }
+);
+
+smbios_flags!(
+    SlotCharacteristics2(u8) {
+        PME = 0 => "PME signal is supported",
+        HOT_PLUG = 1 => "Hot-plug devices are supported",
+        SMBUS = 2 => "SMBus signal is supported",
+        BIFURCATION = 3 => "PCIe slot bifurcation is supported",
+        SURPRISE_REMOVAL = 4 => "Async/surprise removal is supported",
+        CXL1 = 5 => "Flexbus slot, CXL 1.0 capable",
+        CXL2 = 6 => "Flexbus slot, CXL 2.0 capable",
+        CXL3 = 7 => "Flexbus slot, CXL 3.0 capable",
+    }
+);
+
+///
+/// A PCI function in the same slot, a 3.2 peer group
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct SlotPeerGroup {
+    pub segment_group: u16,
+    pub bus: u8,
+    pub device: u8,
+    pub function: u8,
+    pub data_bus_width: u8,
+}
+
+///
+/// Type 9, a system slot.  2.1 adds the second characteristics byte, 2.6 the PCI address, 3.2
+/// the peer groups, 3.4 the slot information, physical width and pitch, and 3.5 the height.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct SystemSlotInformation {
+    pub handle: u16,
+    /// The length of the formatted area
+    pub length: u8,
+    pub designation: Option<String>,
+    pub slot_type: SlotType,
+    pub data_bus_width: SlotWidth,
+    pub current_usage: SlotUsage,
+    pub slot_length: SlotLength,
+    pub slot_id: u16,
+    pub characteristics1: SlotCharacteristics1,
+    pub characteristics2: Option<SlotCharacteristics2>,
+    pub segment_group: Option<u16>,
+    pub bus: Option<u8>,
+    pub device: Option<u8>,
+    pub function: Option<u8>,
+    pub data_bus_width_base: Option<u8>,
+    pub peer_groups: Vec<SlotPeerGroup>,
+    pub slot_information: Option<u8>,
+    pub physical_width: Option<SlotWidth>,
+    /// In hundredths of a millimeter, zero if unknown
+    pub pitch: Option<u16>,
+    pub height: Option<SlotHeight>,
+}
+
+impl SystemSlotInformation {
+    pub fn decode(
+        area: &FormattedArea,
+        strings: &[String],
+    ) -> Result<SystemSlotInformation, Error> {
+        let devfn = area.get_u8(0x10);
+        let groups = usize::from(area.get_u8(0x12).unwrap_or_default());
+        let mut peer_groups = Vec::with_capacity(groups);
+        for i in 0..groups {
+            let Some(group) = area.get_bytes(0x13 + i * 5, 5) else {
+                break;
+            };
+            if let [seg0, seg1, bus, devfn, data_bus_width] = *group {
+                peer_groups.push(SlotPeerGroup {
+                    segment_group: u16::from_le_bytes([seg0, seg1]),
+                    bus,
+                    device: devfn >> 3,
+                    function: devfn & 0x07,
+                    data_bus_width,
+                });
+            }
+        }
+        let after_groups = 0x13 + groups * 5;
+
+        Ok(SystemSlotInformation {
+            handle: area.handle(),
+            length: area.length(),
+            designation: area.read_string(0x04, strings)?,
+            slot_type: area.read_u8(0x05)?.into(),
+            data_bus_width: area.read_u8(0x06)?.into(),
+            current_usage: area.read_u8(0x07)?.into(),
+            slot_length: area.read_u8(0x08)?.into(),
+            slot_id: area.read_u16(0x09)?,
+            characteristics1: SlotCharacteristics1(area.read_u8(0x0B)?),
+            characteristics2: area.get_u8(0x0C).map(SlotCharacteristics2),
+            segment_group: area.get_u16(0x0D),
+            bus: area.get_u8(0x0F),
+            device: devfn.map(|v| v >> 3),
+            function: devfn.map(|v| v & 0x07),
+            data_bus_width_base: area.get_u8(0x11),
+            peer_groups,
+            slot_information: area.get_u8(after_groups),
+            physical_width: area.get_u8(after_groups + 1).map(SlotWidth::from),
+            pitch: area.get_u16(after_groups + 2),
+            height: area.get_u8(after_groups + 4).map(SlotHeight::from),
+        })
+    }
+}
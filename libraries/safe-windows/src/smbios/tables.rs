@@ -0,0 +1,474 @@
+// SPDX-License-Identifier: MIT
+// Copyright 2025 IROX Contributors
+//
+
+use crate::error::Error;
+use crate::smbios::*;
+use irox::structs::Struct;
+use std::collections::HashMap;
+
+///
+/// A decoded structure type, to look structures up by.
+pub trait SMBiosStructure: 'static {
+    /// The SMBIOS structure type
+    const SMTYPE: u8;
+
+    /// The structure, if the table is one of this type
+    fn from_table(table: &SMBiosTable) -> Option<&Self>;
+}
+
+macro_rules! impl_structure {
+    ($($variant:ident($ty:ident) = $smtype:literal,)*) => {
+        $(
+            impl SMBiosStructure for $ty {
+                const SMTYPE: u8 = $smtype;
+
+                fn from_table(table: &SMBiosTable) -> Option<&Self> {
+                    match table {
+                        SMBiosTable::$variant(v) => Some(v),
+                        _ => None,
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_structure!(
+    BiosInformation(BIOSInformation) = 0,
+    SystemInformation(SystemInformation) = 1,
+    BaseboardInformation(BaseboardInformation) = 2,
+    ChassisInformation(ChassisInformation) = 3,
+    ProcessorInformation(ProcessorInformation) = 4,
+    CacheInformation(CacheInformation) = 7,
+    SystemSlotInformation(SystemSlotInformation) = 9,
+    PhysicalMemoryArray(PhysicalMemoryArray) = 16,
+    MemoryDevice(MemoryDevice) = 17,
+    MemoryArrayMappedAddress(MemoryArrayMappedAddress) = 19,
+    SystemBootInformation(SystemBootInformation) = 32,
+    IPMIDeviceInformation(IPMIDeviceInformation) = 38,
+    OnboardDeviceExtended(OnboardDeviceExtended) = 41,
+    EndOfTable(EndOfTable) = 127,
+);
+
+///
+/// The caches a processor refers to, none if it has no cache at a level or the handle doesn't
+/// resolve
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub struct ProcessorCaches<'a> {
+    pub l1: Option<&'a CacheInformation>,
+    pub l2: Option<&'a CacheInformation>,
+    pub l3: Option<&'a CacheInformation>,
+}
+
+///
+/// Every structure in an SMBIOS table, decoded for the version its header gives, and indexed
+/// by handle so the references between structures can be followed.
+#[derive(Debug, Default)]
+pub struct SmbiosTables {
+    header: SMBIOSHeader,
+    structures: Vec<SMBiosTable>,
+    handles: HashMap<u16, usize>,
+}
+
+impl SmbiosTables {
+    ///
+    /// Parses raw SMBIOS data laid out as windows' `RawSMBIOSData`, an [`SMBIOSHeader`]
+    /// followed by the structure table, as [`read_raw_smbios_tables`] returns it.  Parsing stops
+    /// at the end of the table or its end-of-table structure, whichever comes first.  A
+    /// structure too short for its type is kept as [`SMBiosTable::Unknown`], only a table that
+    /// ends partway through a structure fails.
+    pub fn parse_from(mut data: &[u8]) -> Result<SmbiosTables, Error> {
+        let header = SMBIOSHeader::parse_from(&mut data)?;
+        let version = header.version();
+        let mut table = data
+            .get(..header.table_data_length as usize)
+            .unwrap_or(data);
+
+        let mut structures = Vec::new();
+        let mut handles = HashMap::new();
+        while !table.is_empty() {
+            let structure = read_next_table_for(&mut table, version)?;
+            // firmware shouldn't reuse a handle, but if it does the first structure wins.
+            handles
+                .entry(structure.handle())
+                .or_insert(structures.len());
+            let end = matches!(structure, SMBiosTable::EndOfTable(_));
+            structures.push(structure);
+            if end {
+                break;
+            }
+        }
+        Ok(SmbiosTables {
+            header,
+            structures,
+            handles,
+        })
+    }
+
+    ///
+    /// Reads and parses the firmware's SMBIOS tables.
+    #[cfg(any(windows, target_os = "linux"))]
+    pub fn read() -> Result<SmbiosTables, Error> {
+        SmbiosTables::parse_from(&read_raw_smbios_tables()?)
+    }
+
+    pub fn header(&self) -> &SMBIOSHeader {
+        &self.header
+    }
+
+    pub fn version(&self) -> SMBIOSVersion {
+        self.header.version()
+    }
+
+    pub fn len(&self) -> usize {
+        self.structures.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.structures.is_empty()
+    }
+
+    ///
+    /// The structures, in the order of the table
+    pub fn iter(&self) -> std::slice::Iter<'_, SMBiosTable> {
+        self.structures.iter()
+    }
+
+    ///
+    /// The structure with the handle
+    pub fn get(&self, handle: u16) -> Option<&SMBiosTable> {
+        self.structures.get(*self.handles.get(&handle)?)
+    }
+
+    ///
+    /// The structure with the handle, if it's a `T`
+    pub fn get_as<T: SMBiosStructure>(&self, handle: u16) -> Option<&T> {
+        T::from_table(self.get(handle)?)
+    }
+
+    ///
+    /// The structures of SMBIOS type `smtype`, including any without a decoder
+    pub fn of_type(&self, smtype: u8) -> impl Iterator<Item = &SMBiosTable> {
+        self.iter().filter(move |t| t.smtype() == smtype)
+    }
+
+    ///
+    /// The structures of type `T`
+    pub fn iter_as<T: SMBiosStructure>(&self) -> impl Iterator<Item = &T> {
+        self.iter().filter_map(T::from_table)
+    }
+
+    ///
+    /// The first structure of type `T`, for the types a table only has one of
+    pub fn first<T: SMBiosStructure>(&self) -> Option<&T> {
+        self.iter_as().next()
+    }
+
+    ///
+    /// The chassis the baseboard is in
+    pub fn chassis(&self, board: &BaseboardInformation) -> Option<&ChassisInformation> {
+        self.get_as(board.chassis_handle?)
+    }
+
+    ///
+    /// The structures the baseboard contains, such as its processors and slots.  Handles that
+    /// don't resolve are skipped.
+    pub fn contained_objects<'a>(
+        &'a self,
+        board: &'a BaseboardInformation,
+    ) -> impl Iterator<Item = &'a SMBiosTable> {
+        board.object_handles.iter().filter_map(|h| self.get(*h))
+    }
+
+    ///
+    /// The caches of the processor
+    pub fn caches(&self, processor: &ProcessorInformation) -> ProcessorCaches<'_> {
+        let cache = |handle: Option<u16>| self.get_as(handle?);
+        ProcessorCaches {
+            l1: cache(processor.l1_cache_handle),
+            l2: cache(processor.l2_cache_handle),
+            l3: cache(processor.l3_cache_handle),
+        }
+    }
+
+    ///
+    /// The memory array the device is in
+    pub fn memory_array(&self, device: &MemoryDevice) -> Option<&PhysicalMemoryArray> {
+        self.get_as(device.physical_memory_array_handle)
+    }
+
+    ///
+    /// The memory devices in the array
+    pub fn memory_devices<'a>(
+        &'a self,
+        array: &'a PhysicalMemoryArray,
+    ) -> impl Iterator<Item = &'a MemoryDevice> {
+        self.iter_as::<MemoryDevice>()
+            .filter(|d| d.physical_memory_array_handle == array.handle)
+    }
+}
+
+impl<'a> IntoIterator for &'a SmbiosTables {
+    type Item = &'a SMBiosTable;
+    type IntoIter = std::slice::Iter<'a, SMBiosTable>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl IntoIterator for SmbiosTables {
+    type Item = SMBiosTable;
+    type IntoIter = std::vec::IntoIter<SMBiosTable>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.structures.into_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::smbios::*;
+
+    const QEMU: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/doc/smbiostestdata/synthetic-qemu-2.8"
+    );
+    const DESKTOP: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/doc/smbiostestdata/synthetic-desktop-3.4"
+    );
+
+    #[test]
+    pub fn test_desktop_links() {
+        let tables = SmbiosTables::parse_from(&read_sysfs_tables(DESKTOP).unwrap());
+        assert!(tables.is_ok(), "{tables:?}");
+        let Ok(tables) = tables else {
+            return;
+        };
+        assert_eq!(SMBIOSVersion::new(3, 4), tables.version());
+        assert_eq!("3.4", tables.version().to_string());
+        assert_eq!(20, tables.len());
+        assert_eq!(
+            (0..20).collect::<Vec<u16>>(),
+            tables.iter().map(SMBiosTable::handle).collect::<Vec<_>>()
+        );
+
+        let board = tables.first::<BaseboardInformation>();
+        assert!(board.is_some());
+        let Some(board) = board else {
+            return;
+        };
+        let chassis = tables.chassis(board);
+        assert_eq!(Some(0x0003), chassis.map(|c| c.handle));
+        assert_eq!(
+            vec![4, 9],
+            tables
+                .contained_objects(board)
+                .map(SMBiosTable::smtype)
+                .collect::<Vec<_>>()
+        );
+
+        let cpu = tables.get_as::<ProcessorInformation>(0x0004);
+        assert!(cpu.is_some());
+        let Some(cpu) = cpu else {
+            return;
+        };
+        let caches = tables.caches(cpu);
+        assert_eq!(
+            (Some(1), Some(2), Some(3)),
+            (
+                caches.l1.map(|c| c.configuration.level()),
+                caches.l2.map(|c| c.configuration.level()),
+                caches.l3.map(|c| c.configuration.level())
+            )
+        );
+        // a handle of the wrong type doesn't resolve.
+        assert!(tables.get_as::<CacheInformation>(0x0004).is_none());
+        assert!(tables.get(0x0004).is_some());
+
+        let array = tables.first::<PhysicalMemoryArray>();
+        assert!(array.is_some());
+        let Some(array) = array else {
+            return;
+        };
+        let dimms = tables.memory_devices(array).collect::<Vec<_>>();
+        assert_eq!(2, dimms.len());
+        for dimm in dimms {
+            assert_eq!(Some(array), tables.memory_array(dimm));
+        }
+
+        assert_eq!(3, tables.iter_as::<CacheInformation>().count());
+        assert_eq!(2, tables.of_type(41).count());
+        let oem = tables.of_type(0xDD).next();
+        assert!(matches!(oem, Some(SMBiosTable::Unknown(u)) if u.handle == 0x0012));
+        assert!(tables.get(0x0014).is_none());
+    }
+
+    #[test]
+    pub fn test_qemu_links() {
+        let tables = SmbiosTables::parse_from(&read_sysfs_tables(QEMU).unwrap());
+        assert!(tables.is_ok(), "{tables:?}");
+        let Ok(tables) = tables else {
+            return;
+        };
+        assert_eq!(SMBIOSVersion::new(2, 8), tables.version());
+        assert_eq!(9, tables.len());
+        assert!(tables.first::<BaseboardInformation>().is_none());
+
+        let cpu = tables.first::<ProcessorInformation>();
+        assert!(cpu.is_some());
+        let Some(cpu) = cpu else {
+            return;
+        };
+        // qemu's processor has no caches, its handles are all 0xFFFF.
+        assert_eq!(ProcessorCaches::default(), tables.caches(cpu));
+
+        let mapped = tables.first::<MemoryArrayMappedAddress>();
+        assert_eq!(
+            Some(0x1000),
+            mapped
+                .and_then(|m| tables.get_as::<PhysicalMemoryArray>(m.memory_array_handle))
+                .map(|a| a.handle)
+        );
+        let types = tables.into_iter().map(|t| t.smtype()).collect::<Vec<_>>();
+        assert_eq!(vec![0, 1, 3, 4, 16, 17, 19, 32, 127], types);
+    }
+
+    ///
+    /// A table with a header for `version`, a system information structure and the end of table
+    fn system_table(version: SMBIOSVersion, length: u8) -> Vec<u8> {
+        let mut system = vec![1, length, 0x01, 0x00, 1, 0, 0, 0];
+        system.extend_from_slice(&[
+            0x33, 0x22, 0x11, 0x00, 0x55, 0x44, 0x77, 0x66, 0x88, 0x99, 0xAA, 0xBB, 0xCC, 0xDD,
+            0xEE, 0xFF, 0x06, 0x00, 0x00,
+        ]);
+        system.truncate(usize::from(length));
+        system.extend_from_slice(b"Acme\0\0");
+        system.extend_from_slice(&[127, 4, 0x02, 0x00, 0, 0]);
+        // and some padding after the end of the table
+        system.extend_from_slice(&[0, 0, 0, 0]);
+
+        let mut data = vec![0, version.major, version.minor, 0];
+        data.extend_from_slice(&(system.len() as u32).to_le_bytes());
+        data.extend_from_slice(&system);
+        data
+    }
+
+    #[test]
+    pub fn test_version_aware() {
+        let uuid = |version, length| -> Option<Option<UUID>> {
+            let tables = SmbiosTables::parse_from(&system_table(version, length)).ok()?;
+            assert_eq!(2, tables.len());
+            Some(tables.first::<SystemInformation>()?.uuid)
+        };
+        let expected = |bytes: [u8; 16]| Some(Some(UUID::from(bytes)));
+        assert_eq!(
+            expected([
+                0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88, 0x99, 0xAA, 0xBB, 0xCC, 0xDD,
+                0xEE, 0xFF
+            ]),
+            uuid(SMBIOSVersion::new(2, 6), 0x1B)
+        );
+        // before 2.6 the UUID is in network order.
+        assert_eq!(
+            expected([
+                0x33, 0x22, 0x11, 0x00, 0x55, 0x44, 0x77, 0x66, 0x88, 0x99, 0xAA, 0xBB, 0xCC, 0xDD,
+                0xEE, 0xFF
+            ]),
+            uuid(SMBIOSVersion::new(2, 5), 0x1B)
+        );
+        // a 2.0 structure has no UUID.
+        assert_eq!(Some(None), uuid(SMBIOSVersion::new(2, 0), 0x08));
+
+        let tables = SmbiosTables::parse_from(&system_table(SMBIOSVersion::new(2, 4), 0x19));
+        let system = tables
+            .as_ref()
+            .ok()
+            .and_then(|t| t.first::<SystemInformation>());
+        assert_eq!(
+            Some(Some(WakeUpType::PowerSwitch)),
+            system.map(|s| s.wake_up_type)
+        );
+        assert_eq!(Some(None), system.map(|s| s.sku_number.as_deref()));
+        assert_eq!(
+            Some(Some("Acme")),
+            system.map(|s| s.manufacturer.as_deref())
+        );
+    }
+
+    #[test]
+    pub fn test_truncated() {
+        let mut data = system_table(SMBIOSVersion::new(3, 0), 0x1B);
+        // the table length cuts the end of table structure short.
+        if let Some(len) = data.get_mut(4) {
+            *len -= 7;
+        }
+        let tables = SmbiosTables::parse_from(&data);
+        assert!(tables.is_err());
+    }
+
+    #[test]
+    pub fn test_too_short_kept() {
+        // a system information structure needs at least its four 2.0 strings.
+        let tables = SmbiosTables::parse_from(&system_table(SMBIOSVersion::new(3, 0), 0x05));
+        assert!(tables.is_ok(), "{tables:?}");
+        let Ok(tables) = tables else {
+            return;
+        };
+        assert_eq!(
+            vec![1, 127],
+            tables.iter().map(SMBiosTable::smtype).collect::<Vec<_>>()
+        );
+        assert_eq!(None, tables.first::<SystemInformation>());
+        let system = tables.get(0x0001);
+        assert!(
+            matches!(system, Some(SMBiosTable::Unknown(_))),
+            "{system:?}"
+        );
+        let Some(SMBiosTable::Unknown(system)) = system else {
+            return;
+        };
+        assert_eq!(1, system.smtype);
+        assert_eq!(&[1, 0x05, 0x01, 0x00, 1], system.formatted.as_bytes());
+        assert_eq!(vec!["Acme".to_string()], system.strings);
+        assert!(tables.get(0x0002).is_some());
+    }
+
+    #[test]
+    pub fn test_too_short_processor() {
+        // a processor with its socket, type, family and manufacturer but not its ID.
+        let mut structures = vec![4, 0x08, 0x04, 0x00, 1, 3, 0x01, 0];
+        structures.extend_from_slice(b"CPU 0\0\0");
+        structures.extend_from_slice(&[127, 4, 0x05, 0x00, 0, 0]);
+
+        let mut data = structures.clone();
+        let direct = read_next_table_for(&mut data, SMBIOSVersion::new(3, 0));
+        assert!(
+            matches!(&direct, Ok(SMBiosTable::Unknown(u)) if u.smtype == 4),
+            "{direct:?}"
+        );
+        let Ok(direct) = direct else {
+            return;
+        };
+        assert_eq!(0x0004, direct.handle());
+        assert_eq!(0x08, direct.length());
+        assert!(matches!(
+            read_next_table_for(&mut data, SMBIOSVersion::new(3, 0)),
+            Ok(SMBiosTable::EndOfTable(_))
+        ));
+
+        let mut raw = vec![0, 3, 0, 0];
+        raw.extend_from_slice(&(structures.len() as u32).to_le_bytes());
+        raw.extend_from_slice(&structures);
+        let tables = SmbiosTables::parse_from(&raw);
+        assert!(tables.is_ok(), "{tables:?}");
+        let Ok(tables) = tables else {
+            return;
+        };
+        assert_eq!(2, tables.len());
+        assert_eq!(Some(&direct), tables.get(0x0004));
+        assert_eq!(None, tables.first::<ProcessorInformation>());
+    }
+}
@@ -5,7 +5,7 @@
 use criterion::{criterion_group, criterion_main, Criterion, Throughput};
 use irox_tools::buf::ZeroedBuffer;
 use irox_tools::hash::murmur3::{Murmur3_128, Murmur3_32};
-use irox_tools::hash::{BLAKE2b512, BLAKE2s256, MD5, SHA256, SHA512};
+use irox_tools::hash::{BLAKE2b512, BLAKE2s256, MD5, SHA1, SHA256, SHA512};
 use irox_tools::hex::to_hex_str_upper;
 use std::io::Read;
 
@@ -13,6 +13,11 @@ struct Hasher {
     iter: [u8; 4096],
     b256: BLAKE2s256,
     b256simd: irox_simd::blake2::BLAKE2s256,
+    b512: BLAKE2b512,
+    b512simd: irox_simd::blake2::BLAKE2b512,
+    sp256: irox_simd::blake2::BLAKE2sp256,
+    bp512: irox_simd::blake2::BLAKE2bp512,
+    b3: irox_simd::blake3::BLAKE3,
 }
 impl Default for Hasher {
     fn default() -> Self {
@@ -20,6 +25,11 @@ impl Default for Hasher {
             iter: [0; 4096],
             b256: BLAKE2s256::default(),
             b256simd: irox_simd::blake2::BLAKE2s256::default(),
+            b512: BLAKE2b512::default(),
+            b512simd: irox_simd::blake2::BLAKE2b512::default(),
+            sp256: irox_simd::blake2::BLAKE2sp256::default(),
+            bp512: irox_simd::blake2::BLAKE2bp512::default(),
+            b3: irox_simd::blake3::BLAKE3::default(),
         }
     }
 }
@@ -34,15 +44,32 @@ impl Hasher {
         let _ = hash.hash(&self.iter);
         self.iter[0] += 1;
     }
-    // pub fn hash_murmur_32_simd(&mut self) {
-    //     let hash = irox_simd::murmur3::Murmur3_32::new();
-    //     let _ = hash.hash(&self.iter);
-    //     self.iter[0] += 1;
-    // }
+    pub fn hash_murmur_3_simd(&mut self) {
+        let hash = irox_simd::murmur3::Murmur3_128::new();
+        let _ = hash.hash(&self.iter);
+        self.iter[0] += 1;
+    }
+    pub fn hash_murmur_32_simd(&mut self) {
+        let hash = irox_simd::murmur3::Murmur3_32::new();
+        let _ = hash.hash(&self.iter);
+        self.iter[0] += 1;
+    }
     pub fn hash_sha356(&mut self) {
         let _hash = SHA256::new().hash(&self.iter);
         self.iter[0] += 1;
     }
+    pub fn hash_sha256_simd(&mut self) {
+        let _hash = irox_simd::sha2::SHA256::new().hash(&self.iter);
+        self.iter[0] += 1;
+    }
+    pub fn hash_sha1(&mut self) {
+        let _hash = SHA1::new().hash(&self.iter);
+        self.iter[0] += 1;
+    }
+    pub fn hash_sha1_simd(&mut self) {
+        let _hash = irox_simd::sha1::SHA1::new().hash(&self.iter);
+        self.iter[0] += 1;
+    }
     pub fn hash_sha512(&mut self) {
         let _hash = SHA512::new().hash(&self.iter);
         self.iter[0] += 1;
@@ -56,7 +83,47 @@ impl Hasher {
         self.iter[0] += 1;
     }
     pub fn hash_blake2b(&mut self) {
-        let _hash = BLAKE2b512::default().hash(&self.iter);
+        self.b512.write(&self.iter);
+        self.iter[0] += 1;
+    }
+    pub fn hash_blake2b_simd(&mut self) {
+        self.b512simd.write(&self.iter);
+        self.iter[0] += 1;
+    }
+    pub fn hash_blake2sp(&mut self) {
+        self.sp256.write(&self.iter);
+        self.iter[0] += 1;
+    }
+    pub fn hash_blake2bp(&mut self) {
+        self.bp512.write(&self.iter);
+        self.iter[0] += 1;
+    }
+    pub fn hash_blake3_simd(&mut self) {
+        self.b3.write(&self.iter);
+        self.iter[0] += 1;
+    }
+    pub fn hash_crc32(&mut self) {
+        let _ = irox_simd::crc::Crc32::new().hash(&self.iter);
+        self.iter[0] += 1;
+    }
+    pub fn hash_crc32c(&mut self) {
+        let _ = irox_simd::crc::Crc32c::new().hash(&self.iter);
+        self.iter[0] += 1;
+    }
+    pub fn hash_crc64(&mut self) {
+        let _ = irox_simd::crc::Crc64Nvme::new().hash(&self.iter);
+        self.iter[0] += 1;
+    }
+    pub fn hash_xxh64(&mut self) {
+        let _ = irox_simd::xxhash::XXH64::new().hash(&self.iter);
+        self.iter[0] += 1;
+    }
+    pub fn hash_xxh3_64(&mut self) {
+        let _ = irox_simd::xxhash::XXH3_64::new().hash(&self.iter);
+        self.iter[0] += 1;
+    }
+    pub fn hash_xxh3_128(&mut self) {
+        let _ = irox_simd::xxhash::XXH3_128::new().hash(&self.iter);
         self.iter[0] += 1;
     }
     pub fn hash_md5(&mut self) {
@@ -99,6 +166,24 @@ pub fn criterion_benchmark(c: &mut Criterion) {
             hasher.hash_sha356();
         })
     });
+    grp.bench_function("hash_sha256_simd", |b| {
+        b.iter(|| {
+            hasher.hash_sha256_simd();
+        })
+    });
+    grp.finish();
+    let mut grp = c.benchmark_group("sha1");
+    grp.throughput(Throughput::Bytes(4096));
+    grp.bench_function("hash_sha1", |b| {
+        b.iter(|| {
+            hasher.hash_sha1();
+        })
+    });
+    grp.bench_function("hash_sha1_simd", |b| {
+        b.iter(|| {
+            hasher.hash_sha1_simd();
+        })
+    });
     grp.finish();
     // std::thread::sleep(Duration::from_secs(20));
     let mut grp = c.benchmark_group("murmur3_128");
@@ -108,6 +193,11 @@ pub fn criterion_benchmark(c: &mut Criterion) {
             hasher.hash_murmur_3();
         })
     });
+    grp.bench_function("hash_murmur_3_simd", |b| {
+        b.iter(|| {
+            hasher.hash_murmur_3_simd();
+        })
+    });
     grp.finish();
     // std::thread::sleep(Duration::from_secs(20));
     let mut grp = c.benchmark_group("murmur3_32");
@@ -117,11 +207,11 @@ pub fn criterion_benchmark(c: &mut Criterion) {
             hasher.hash_murmur_32();
         })
     });
-    // grp.bench_function("hash_murmur_32_simd", |b| {
-    //     b.iter(|| {
-    //         hasher.hash_murmur_32_simd();
-    //     })
-    // });
+    grp.bench_function("hash_murmur_32_simd", |b| {
+        b.iter(|| {
+            hasher.hash_murmur_32_simd();
+        })
+    });
     grp.finish();
     // std::thread::sleep(Duration::from_secs(20));
     let mut grp = c.benchmark_group("file_blake2s256");
@@ -145,6 +235,11 @@ pub fn criterion_benchmark(c: &mut Criterion) {
             hasher.hash_blake2s_simd();
         })
     });
+    grp.bench_function("hash_blake2sp256_simd", |b| {
+        b.iter(|| {
+            hasher.hash_blake2sp();
+        })
+    });
     grp.finish();
     // std::thread::sleep(Duration::from_secs(20));
     let mut grp = c.benchmark_group("blake2b512");
@@ -154,6 +249,60 @@ pub fn criterion_benchmark(c: &mut Criterion) {
             hasher.hash_blake2b();
         })
     });
+    grp.bench_function("hash_blake2b512_simd", |b| {
+        b.iter(|| {
+            hasher.hash_blake2b_simd();
+        })
+    });
+    grp.bench_function("hash_blake2bp512_simd", |b| {
+        b.iter(|| {
+            hasher.hash_blake2bp();
+        })
+    });
+    grp.finish();
+    let mut grp = c.benchmark_group("blake3");
+    grp.throughput(Throughput::Bytes(4096));
+    grp.bench_function("hash_blake3_simd", |b| {
+        b.iter(|| {
+            hasher.hash_blake3_simd();
+        })
+    });
+    grp.finish();
+    let mut grp = c.benchmark_group("crc");
+    grp.throughput(Throughput::Bytes(4096));
+    grp.bench_function("hash_crc32", |b| {
+        b.iter(|| {
+            hasher.hash_crc32();
+        })
+    });
+    grp.bench_function("hash_crc32c", |b| {
+        b.iter(|| {
+            hasher.hash_crc32c();
+        })
+    });
+    grp.bench_function("hash_crc64", |b| {
+        b.iter(|| {
+            hasher.hash_crc64();
+        })
+    });
+    grp.finish();
+    let mut grp = c.benchmark_group("xxhash");
+    grp.throughput(Throughput::Bytes(4096));
+    grp.bench_function("hash_xxh64", |b| {
+        b.iter(|| {
+            hasher.hash_xxh64();
+        })
+    });
+    grp.bench_function("hash_xxh3_64", |b| {
+        b.iter(|| {
+            hasher.hash_xxh3_64();
+        })
+    });
+    grp.bench_function("hash_xxh3_128", |b| {
+        b.iter(|| {
+            hasher.hash_xxh3_128();
+        })
+    });
     grp.finish();
     let mut grp = c.benchmark_group("md5");
     grp.throughput(Throughput::Bytes(4096));
@@ -0,0 +1,188 @@
+// SPDX-License-Identifier: MIT
+// Copyright 2025 IROX Contributors
+//
+
+//!
+//! Differential fuzzing of the streaming hashers.  The input is split into writes at arbitrary
+//! points and hashed by the SIMD hasher, then compared against the irox-tools scalar hasher
+//! where there is one.  The hashers without one are compared against a single-write hash of the
+//! whole input computed under [`force_level`]`(Some(Level::Scalar))`, so the portable kernels
+//! check the vector ones.  Run the target at each `IROX_SIMD_LEVEL` to cover every kernel.
+
+#![no_main]
+
+use arbitrary::Arbitrary;
+use irox_simd::blake2::{BLAKE2b, BLAKE2bp, BLAKE2s, BLAKE2sp};
+use irox_simd::blake3::BLAKE3;
+use irox_simd::crc::{Crc32, Crc32c, Crc64Ecma, Crc64Nvme};
+use irox_simd::dispatch::{force_level, Level};
+use irox_simd::murmur3::{Murmur3_128, Murmur3_32};
+use irox_simd::sha1::SHA1;
+use irox_simd::sha2::SHA256;
+use irox_simd::xxhash::{XXH3_128, XXH3_64, XXH64};
+use libfuzzer_sys::fuzz_target;
+
+#[derive(Debug, Arbitrary)]
+enum Hasher {
+    Blake2s,
+    Blake2b,
+    Blake2sp,
+    Blake2bp,
+    Blake3,
+    Sha1,
+    Sha256,
+    Murmur3,
+    XxHash,
+    Crc,
+}
+
+#[derive(Debug, Arbitrary)]
+struct Input<'a> {
+    hasher: Hasher,
+    /// up to the hasher's maximum is used
+    key: &'a [u8],
+    seed: u64,
+    /// where to split `data` into writes, taken modulo its length
+    cuts: Vec<u16>,
+    data: &'a [u8],
+}
+
+fn split<'a>(data: &'a [u8], cuts: &[u16]) -> Vec<&'a [u8]> {
+    let mut cuts: Vec<usize> = cuts
+        .iter()
+        .map(|c| usize::from(*c) % (data.len() + 1))
+        .collect();
+    cuts.sort_unstable();
+    let mut out = Vec::with_capacity(cuts.len() + 1);
+    let mut rest = data;
+    let mut at = 0;
+    for cut in cuts {
+        let (a, b) = rest.split_at(cut - at);
+        out.push(a);
+        rest = b;
+        at = cut;
+    }
+    out.push(rest);
+    out
+}
+
+fn key(key: &[u8], max: usize) -> &[u8] {
+    key.get(..max).unwrap_or(key)
+}
+
+///
+/// Writes each chunk to the hasher, then finishes it
+macro_rules! streamed {
+    ($hasher:expr, $chunks:expr) => {{
+        let mut h = $hasher;
+        for c in $chunks {
+            h.write(c);
+        }
+        h.finish()
+    }};
+}
+
+///
+/// Runs `f` on the portable kernels for the reference result, then goes back to the detected level
+fn scalar<T>(f: impl FnOnce() -> T) -> T {
+    force_level(Some(Level::Scalar));
+    let out = f();
+    force_level(None);
+    out
+}
+
+fn crc_bitwise(poly: u64, mask: u64, data: &[u8]) -> u64 {
+    let mut crc = mask;
+    for v in data {
+        crc ^= u64::from(*v);
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ poly
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    crc ^ mask
+}
+
+fuzz_target!(|input: Input<'_>| {
+    let Input {
+        hasher,
+        key: k,
+        seed,
+        cuts,
+        data,
+    } = input;
+    let chunks = split(data, &cuts);
+    match hasher {
+        Hasher::Blake2s => {
+            let k = key(k, 32);
+            let exp = irox_tools::hash::BLAKE2s::<32>::new(k).hash(data);
+            assert_eq!(exp, streamed!(BLAKE2s::<32>::new(k), &chunks));
+            let exp = irox_tools::hash::BLAKE2s::<20>::new(k).hash(data);
+            assert_eq!(exp, streamed!(BLAKE2s::<20>::new(k), &chunks));
+        }
+        Hasher::Blake2b => {
+            let k = key(k, 64);
+            let exp = irox_tools::hash::BLAKE2b::<64>::new(k).hash(data);
+            assert_eq!(exp, streamed!(BLAKE2b::<64>::new(k), &chunks));
+            let exp = irox_tools::hash::BLAKE2b::<32>::new(k).hash(data);
+            assert_eq!(exp, streamed!(BLAKE2b::<32>::new(k), &chunks));
+        }
+        Hasher::Blake2sp => {
+            let k = key(k, 32);
+            let exp = scalar(|| BLAKE2sp::<32>::new(k).hash(data));
+            assert_eq!(exp, streamed!(BLAKE2sp::<32>::new(k), &chunks));
+            assert_eq!(exp, BLAKE2sp::<32>::hash_multithreaded(k, data));
+        }
+        Hasher::Blake2bp => {
+            let k = key(k, 64);
+            let exp = scalar(|| BLAKE2bp::<64>::new(k).hash(data));
+            assert_eq!(exp, streamed!(BLAKE2bp::<64>::new(k), &chunks));
+            assert_eq!(exp, BLAKE2bp::<64>::hash_multithreaded(k, data));
+        }
+        Hasher::Blake3 => {
+            let exp = scalar(|| BLAKE3::new().hash(data));
+            assert_eq!(exp, streamed!(BLAKE3::new(), &chunks));
+            let mut full = [0u8; 32];
+            for (f, k) in full.iter_mut().zip(k) {
+                *f = *k;
+            }
+            let exp = scalar(|| BLAKE3::new_keyed(&full).hash(data));
+            assert_eq!(exp, streamed!(BLAKE3::new_keyed(&full), &chunks));
+        }
+        Hasher::Sha1 => {
+            let exp = irox_tools::hash::SHA1::new().hash(data);
+            assert_eq!(exp, streamed!(SHA1::new(), &chunks));
+        }
+        Hasher::Sha256 => {
+            let exp = irox_tools::hash::SHA256::new().hash(data);
+            assert_eq!(exp, streamed!(SHA256::new(), &chunks));
+        }
+        Hasher::Murmur3 => {
+            let exp = irox_tools::hash::murmur3::Murmur3_32::new_seeded(seed as u32).hash(data);
+            assert_eq!(exp, streamed!(Murmur3_32::new_seeded(seed as u32), &chunks));
+            let exp = irox_tools::hash::murmur3::Murmur3_128::new_seeded(seed).hash(data);
+            assert_eq!(exp, streamed!(Murmur3_128::new_seeded(seed), &chunks));
+        }
+        Hasher::XxHash => {
+            let exp = scalar(|| XXH64::new_seeded(seed).hash(data));
+            assert_eq!(exp, streamed!(XXH64::new_seeded(seed), &chunks));
+            let exp = scalar(|| XXH3_64::new_seeded(seed).hash(data));
+            assert_eq!(exp, streamed!(XXH3_64::new_seeded(seed), &chunks));
+            let exp = scalar(|| XXH3_128::new_seeded(seed).hash(data));
+            assert_eq!(exp, streamed!(XXH3_128::new_seeded(seed), &chunks));
+        }
+        Hasher::Crc => {
+            let exp = crc_bitwise(0xEDB8_8320, 0xFFFF_FFFF, data);
+            assert_eq!(exp, u64::from(streamed!(Crc32::new(), &chunks)));
+            let exp = crc_bitwise(0x82F6_3B78, 0xFFFF_FFFF, data);
+            assert_eq!(exp, u64::from(streamed!(Crc32c::new(), &chunks)));
+            let exp = crc_bitwise(0xC96C_5795_D787_0F42, u64::MAX, data);
+            assert_eq!(exp, streamed!(Crc64Ecma::new(), &chunks));
+            let exp = crc_bitwise(0x9A6C_9329_AC4B_C9B5, u64::MAX, data);
+            assert_eq!(exp, streamed!(Crc64Nvme::new(), &chunks));
+        }
+    }
+});
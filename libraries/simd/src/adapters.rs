@@ -0,0 +1,321 @@
+// SPDX-License-Identifier: MIT
+// Copyright 2025 IROX Contributors
+//
+
+//!
+//! Macros implementing the standard library and RustCrypto traits for the hashers in this crate.
+//!
+//! * [`std::io::Write`] for every streaming hasher, so `std::io::copy` can feed one, plus a
+//!   `hash_reader` method for the fixed-output ones (needs `std`)
+//! * [`core::hash::Hasher`] for the non-cryptographic hashes, with a seeded
+//!   [`core::hash::BuildHasher`] alongside each one
+//! * the [`digest`](https://docs.rs/digest/0.10) traits for the cryptographic hashes (needs
+//!   `digest`)
+//!
+
+///
+/// Buffer size used by `hash_reader`.  Big enough that BLAKE3 and BLAKE2sp/bp are handed whole
+/// batches of chunks for their multi-lane kernels on each write, small enough to stay in L2.
+#[cfg(feature = "std")]
+pub(crate) const READ_BUFFER_SIZE: usize = 64 * 1024;
+
+///
+/// Reads `reader` to the end, passing each block read to `write`.  Reads that are interrupted
+/// are retried.
+#[cfg(feature = "std")]
+pub(crate) fn read_into<R: std::io::Read, F: FnMut(&[u8])>(
+    mut reader: R,
+    mut write: F,
+) -> std::io::Result<()> {
+    let mut buf = alloc::vec![0u8; READ_BUFFER_SIZE];
+    loop {
+        match reader.read(&mut buf) {
+            Ok(0) => return Ok(()),
+            Ok(read) => {
+                if let Some(v) = buf.get(..read) {
+                    write(v);
+                }
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+///
+/// Implements [`std::io::Write`] for a hasher with an inherent `write(&mut self, &[u8])`.  With
+/// `=> Output`, also adds `hash_reader`, which needs an inherent `finish(self) -> Output`.  A
+/// leading `const N: usize,` makes the impls generic over the hasher's output length.
+macro_rules! impl_io_write {
+    ($(const $n:ident: usize,)? $ty:ty => $out:ty) => {
+        impl_io_write!($(const $n: usize,)? $ty);
+
+        #[cfg(feature = "std")]
+        impl$(<const $n: usize>)? $ty {
+            ///
+            /// Hashes everything `reader` produces, in blocks sized for this crate's kernels, and
+            /// returns the finished hash.
+            pub fn hash_reader<R: std::io::Read>(mut self, reader: R) -> std::io::Result<$out> {
+                $crate::adapters::read_into(reader, |v| <$ty>::write(&mut self, v))?;
+                Ok(self.finish())
+            }
+        }
+    };
+    ($(const $n:ident: usize,)? $ty:ty) => {
+        #[cfg(feature = "std")]
+        impl$(<const $n: usize>)? std::io::Write for $ty {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                <$ty>::write(self, buf);
+                Ok(buf.len())
+            }
+
+            fn write_all(&mut self, buf: &[u8]) -> std::io::Result<()> {
+                <$ty>::write(self, buf);
+                Ok(())
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+    };
+}
+
+///
+/// Implements [`core::hash::Hasher`] for a non-cryptographic hasher with inherent `write` and
+/// `finish` methods, `finish` keeping the low 64 bits of the hash.  Also declares a
+/// [`core::hash::BuildHasher`] that creates the hasher with `new_seeded`.
+macro_rules! impl_hasher {
+    ($(#[$meta:meta])* $build:ident, $ty:ident, $seed:ty) => {
+        impl core::hash::Hasher for $ty {
+            #[allow(clippy::unnecessary_cast)] // the hash may already be a u64
+            fn finish(&self) -> u64 {
+                $ty::finish(self.clone()) as u64
+            }
+
+            fn write(&mut self, bytes: &[u8]) {
+                $ty::write(self, bytes)
+            }
+        }
+
+        $(#[$meta])*
+        #[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Hash)]
+        pub struct $build {
+            seed: $seed,
+        }
+        impl $build {
+            #[must_use]
+            pub const fn new(seed: $seed) -> Self {
+                Self { seed }
+            }
+
+            #[must_use]
+            pub const fn seed(&self) -> $seed {
+                self.seed
+            }
+        }
+        impl core::hash::BuildHasher for $build {
+            type Hasher = $ty;
+
+            fn build_hasher(&self) -> $ty {
+                $ty::new_seeded(self.seed)
+            }
+        }
+    };
+}
+
+///
+/// Implements the `digest` 0.10 fixed-output traits, and so `digest::Digest`, for a hasher with
+/// inherent `write` and `finish(self) -> [u8; N]` methods.  `$size` is the `typenum` for `N`.
+macro_rules! impl_digest {
+    ($ty:ty, $size:ident) => {
+        #[cfg(feature = "digest")]
+        impl digest::HashMarker for $ty {}
+
+        #[cfg(feature = "digest")]
+        impl digest::OutputSizeUser for $ty {
+            type OutputSize = digest::consts::$size;
+        }
+
+        #[cfg(feature = "digest")]
+        impl digest::Update for $ty {
+            fn update(&mut self, data: &[u8]) {
+                <$ty>::write(self, data)
+            }
+        }
+
+        #[cfg(feature = "digest")]
+        impl digest::FixedOutput for $ty {
+            fn finalize_into(self, out: &mut digest::Output<Self>) {
+                out.copy_from_slice(&<$ty>::finish(self));
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::blake2::{BLAKE2b512, BLAKE2s256, BLAKE2sp256};
+    use crate::blake3::BLAKE3;
+    use crate::crc::Crc32c;
+    use crate::murmur3::{BuildMurmur3_32, Murmur3_32};
+    use crate::sha2::SHA256;
+    use crate::xxhash::tests::sanity_buffer;
+    use crate::xxhash::{BuildXXH3_64, BuildXXH64, XXH3_128, XXH3_64, XXH64};
+    use std::collections::HashMap;
+    use std::hash::{BuildHasher, Hasher};
+    use std::io::{ErrorKind, Read, Write};
+
+    ///
+    /// Hands out at most `step` bytes per read, failing every other read with `Interrupted`
+    struct Trickle<'a> {
+        data: &'a [u8],
+        step: usize,
+        interrupt: bool,
+    }
+    impl Read for Trickle<'_> {
+        #[allow(clippy::indexing_slicing)]
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            self.interrupt = !self.interrupt;
+            if self.interrupt {
+                return Err(ErrorKind::Interrupted.into());
+            }
+            let n = self.step.min(buf.len()).min(self.data.len());
+            let (a, b) = self.data.split_at(n);
+            buf[..n].copy_from_slice(a);
+            self.data = b;
+            Ok(n)
+        }
+    }
+
+    fn input(len: usize) -> Vec<u8> {
+        let mut v = sanity_buffer();
+        while v.len() < len {
+            v.extend_from_within(..);
+        }
+        v.truncate(len);
+        v
+    }
+
+    #[test]
+    pub fn test_hash_reader() {
+        for len in [0, 1, 63, 64, 1025, 8192, 70_000, 200_001] {
+            let data = input(len);
+            for step in [1usize, 7, 4096, usize::MAX] {
+                if step == 1 && len > 8192 {
+                    continue;
+                }
+                let reader = || Trickle {
+                    data: &data,
+                    step,
+                    interrupt: false,
+                };
+                assert_eq!(
+                    Ok(BLAKE3::new().hash(&data)),
+                    BLAKE3::new().hash_reader(reader()).map_err(|e| e.kind())
+                );
+                assert_eq!(
+                    Ok(BLAKE2sp256::default().hash(&data)),
+                    BLAKE2sp256::default()
+                        .hash_reader(reader())
+                        .map_err(|e| e.kind())
+                );
+                assert_eq!(
+                    Ok(SHA256::new().hash(&data)),
+                    SHA256::new().hash_reader(reader()).map_err(|e| e.kind())
+                );
+                assert_eq!(
+                    Ok(XXH3_128::new().hash(&data)),
+                    XXH3_128::new().hash_reader(reader()).map_err(|e| e.kind())
+                );
+            }
+        }
+    }
+
+    #[test]
+    pub fn test_io_write() {
+        let data = input(100_000);
+        let mut h = BLAKE2s256::default();
+        assert_eq!(
+            Ok(100_000),
+            std::io::copy(&mut data.as_slice(), &mut h).map_err(|e| e.kind())
+        );
+        assert_eq!(BLAKE2s256::default().hash(&data), h.finish());
+
+        let mut h = BLAKE2b512::default();
+        for c in data.chunks(333) {
+            assert!(h.write_all(c).is_ok());
+        }
+        assert!(h.flush().is_ok());
+        assert_eq!(BLAKE2b512::default().hash(&data), h.finish());
+
+        let mut h = Crc32c::new();
+        assert!(write!(h, "123456789").is_ok());
+        assert_eq!(0xE3069283, h.finish());
+    }
+
+    #[test]
+    #[allow(clippy::indexing_slicing)]
+    pub fn test_hasher() {
+        let data = input(1000);
+        let mut h = XXH64::new_seeded(7);
+        Hasher::write(&mut h, &data[..500]);
+        let partial = Hasher::finish(&h);
+        assert_eq!(XXH64::new_seeded(7).hash(&data[..500]), partial);
+        Hasher::write(&mut h, &data[500..]);
+        assert_eq!(XXH64::new_seeded(7).hash(&data), Hasher::finish(&h));
+
+        let h = BuildXXH3_64::new(99).hash_one(&data[..]);
+        let mut expected = XXH3_64::new_seeded(99);
+        std::hash::Hash::hash(&data[..], &mut expected);
+        assert_eq!(expected.finish(), h);
+
+        let h = XXH3_128::new().hash(&data);
+        let mut hasher = XXH3_128::new();
+        Hasher::write(&mut hasher, &data);
+        assert_eq!(h as u64, Hasher::finish(&hasher));
+
+        let h = Murmur3_32::new_seeded(3).hash(&data);
+        let mut hasher = BuildMurmur3_32::new(3).build_hasher();
+        Hasher::write(&mut hasher, &data);
+        assert_eq!(u64::from(h), Hasher::finish(&hasher));
+    }
+
+    #[test]
+    pub fn test_hashmap() {
+        let mut map: HashMap<String, usize, BuildXXH64> = HashMap::with_hasher(BuildXXH64::new(1));
+        for i in 0..1000 {
+            map.insert(format!("key {i}"), i);
+        }
+        for i in 0..1000 {
+            assert_eq!(Some(&i), map.get(&format!("key {i}")));
+        }
+        assert_eq!(BuildXXH64::default(), BuildXXH64::new(0));
+    }
+
+    #[cfg(feature = "digest")]
+    #[test]
+    #[allow(clippy::indexing_slicing)]
+    pub fn test_digest() {
+        use digest::{Digest, ExtendableOutput, Update, XofReader};
+        let data = input(5000);
+        assert_eq!(
+            SHA256::new().hash(&data),
+            <SHA256 as Digest>::digest(&data).as_slice()
+        );
+        let mut h = <BLAKE2s256 as Digest>::new();
+        Digest::update(&mut h, &data[..10]);
+        Digest::update(&mut h, &data[10..]);
+        assert_eq!(BLAKE2s256::default().hash(&data), h.finalize().as_slice());
+
+        let mut expected = [0u8; 100];
+        let mut x = BLAKE3::new();
+        x.write(&data);
+        x.finish_xof().fill(&mut expected);
+        let mut h = BLAKE3::new();
+        Update::update(&mut h, &data);
+        let mut out = [0u8; 100];
+        h.finalize_xof().read(&mut out);
+        assert_eq!(expected, out);
+    }
+}
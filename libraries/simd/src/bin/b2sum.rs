@@ -0,0 +1,637 @@
+// SPDX-License-Identifier: MIT
+// Copyright 2025 IROX Contributors
+//
+
+//!
+//! `irox-b2sum`, a drop-in for the reference and GNU `b2sum` tools using this crate's BLAKE2
+//! hashers.  Output and check files are interchangeable with both, including GNU's escaping of
+//! file names holding a backslash or newline.  Large regular files are hashed from a memory
+//! mapping on linux x86-64, everything else is read in blocks.
+//!
+
+#![allow(clippy::print_stderr)]
+
+use irox_simd::blake2::{BLAKE2b, BLAKE2bp, BLAKE2s, BLAKE2sp};
+use irox_simd::codec::hex;
+use std::ffi::OsString;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Read, Write};
+use std::process::ExitCode;
+
+const NAME: &str = "irox-b2sum";
+
+const USAGE: &str = "\
+Usage: irox-b2sum [OPTION]... [FILE]...
+Print or check BLAKE2 checksums.  With no FILE, or when FILE is -, read standard input.
+
+  -a, --algorithm ALG  hash with ALG: blake2b (default), blake2s, blake2bp or blake2sp
+  -l, --length BITS    digest length in bits, a multiple of 8 up to the algorithm's maximum
+                       (512 for blake2b/bp, 256 for blake2s/sp), 0 for the maximum
+      --tag            print BSD-style checksums
+  -c, --check          read checksums from the FILEs and check them
+      --quiet          when checking, don't print OK for each verified file
+      --status         when checking, print nothing, the exit status shows success
+  -h, --help           print this help and exit
+";
+
+///
+/// Files at least this big are mapped rather than read, below it the mapping costs more than
+/// the copies it saves.
+#[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+const MMAP_THRESHOLD: u64 = 1024 * 1024;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Algorithm {
+    Blake2b,
+    Blake2s,
+    Blake2bp,
+    Blake2sp,
+}
+
+impl Algorithm {
+    const ALL: [Algorithm; 4] = [
+        Algorithm::Blake2b,
+        Algorithm::Blake2s,
+        Algorithm::Blake2bp,
+        Algorithm::Blake2sp,
+    ];
+
+    ///
+    /// The name used in BSD-style lines, which is also accepted case-insensitively by `-a`
+    fn tag(self) -> &'static str {
+        match self {
+            Algorithm::Blake2b => "BLAKE2b",
+            Algorithm::Blake2s => "BLAKE2s",
+            Algorithm::Blake2bp => "BLAKE2bp",
+            Algorithm::Blake2sp => "BLAKE2sp",
+        }
+    }
+
+    fn max_bytes(self) -> usize {
+        match self {
+            Algorithm::Blake2b | Algorithm::Blake2bp => 64,
+            Algorithm::Blake2s | Algorithm::Blake2sp => 32,
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|a| a.tag().eq_ignore_ascii_case(name))
+    }
+
+    fn from_tag(tag: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|a| a.tag() == tag)
+    }
+}
+
+///
+/// An algorithm and digest length in bytes
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+struct Digest {
+    algorithm: Algorithm,
+    len: usize,
+}
+
+enum Hasher {
+    B(BLAKE2b<64>),
+    S(BLAKE2s<32>),
+    Bp(BLAKE2bp<64>),
+    Sp(BLAKE2sp<32>),
+}
+
+impl Digest {
+    fn hasher(self) -> Hasher {
+        match self.algorithm {
+            Algorithm::Blake2b => Hasher::B(BLAKE2b::new_with_length(&[], self.len)),
+            Algorithm::Blake2s => Hasher::S(BLAKE2s::new_with_length(&[], self.len)),
+            Algorithm::Blake2bp => Hasher::Bp(BLAKE2bp::new_with_length(&[], self.len)),
+            Algorithm::Blake2sp => Hasher::Sp(BLAKE2sp::new_with_length(&[], self.len)),
+        }
+    }
+
+    #[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+    fn hash(self, data: &[u8]) -> Vec<u8> {
+        let mut out = match self.hasher() {
+            Hasher::B(h) => h.hash(data).to_vec(),
+            Hasher::S(h) => h.hash(data).to_vec(),
+            Hasher::Bp(h) => h.hash(data).to_vec(),
+            Hasher::Sp(h) => h.hash(data).to_vec(),
+        };
+        out.truncate(self.len);
+        out
+    }
+
+    fn hash_reader<R: Read>(self, reader: R) -> std::io::Result<Vec<u8>> {
+        let mut out = match self.hasher() {
+            Hasher::B(h) => h.hash_reader(reader)?.to_vec(),
+            Hasher::S(h) => h.hash_reader(reader)?.to_vec(),
+            Hasher::Bp(h) => h.hash_reader(reader)?.to_vec(),
+            Hasher::Sp(h) => h.hash_reader(reader)?.to_vec(),
+        };
+        out.truncate(self.len);
+        Ok(out)
+    }
+
+    ///
+    /// Hashes the named file, or standard input for `-`
+    fn hash_file(self, name: &OsString) -> std::io::Result<Vec<u8>> {
+        if name == "-" {
+            return self.hash_reader(std::io::stdin().lock());
+        }
+        let file = File::open(name)?;
+        #[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+        if let Some(map) = map_file(&file) {
+            return Ok(self.hash(map.as_slice()));
+        }
+        self.hash_reader(file)
+    }
+
+    ///
+    /// The `BLAKE2b-256` style name in BSD lines, the length is left off at the maximum
+    fn tag(self) -> String {
+        if self.len == self.algorithm.max_bytes() {
+            self.algorithm.tag().to_string()
+        } else {
+            format!("{}-{}", self.algorithm.tag(), self.len * 8)
+        }
+    }
+}
+
+///
+/// Maps `file` if it's a regular file big enough to be worth it, `None` to read it instead.
+#[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+fn map_file(file: &File) -> Option<irox_safe_linux::mman::MappedFile> {
+    use std::os::fd::AsRawFd;
+    let meta = file.metadata().ok()?;
+    if !meta.is_file() || meta.len() < MMAP_THRESHOLD {
+        return None;
+    }
+    let len = usize::try_from(meta.len()).ok()?;
+    // SAFETY: like every other checksum tool, this assumes nothing modifies the file while it's
+    // being hashed.
+    unsafe { irox_safe_linux::mman::MappedFile::map_readonly(file.as_raw_fd(), len) }.ok()
+}
+
+///
+/// GNU's escaping of file names in checksum lines: a name with a backslash, newline or carriage
+/// return has them escaped, and the whole line is prefixed with a backslash.
+fn escape(name: &str) -> (bool, String) {
+    if !name.contains(['\\', '\n', '\r']) {
+        return (false, name.to_string());
+    }
+    let mut out = String::with_capacity(name.len() + 2);
+    for c in name.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            c => out.push(c),
+        }
+    }
+    (true, out)
+}
+
+fn unescape(name: &str) -> Option<String> {
+    let mut out = String::with_capacity(name.len());
+    let mut chars = name.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next()? {
+            '\\' => out.push('\\'),
+            'n' => out.push('\n'),
+            'r' => out.push('\r'),
+            _ => return None,
+        }
+    }
+    Some(out)
+}
+
+///
+/// Formats the checksum line for `name`, without the trailing newline
+fn format_line(digest: Digest, hash: &[u8], name: &str, bsd: bool) -> String {
+    let (escaped, name) = escape(name);
+    let prefix = if escaped { "\\" } else { "" };
+    let hash = hex::encode_lower(hash);
+    if bsd {
+        format!("{prefix}{} ({name}) = {hash}", digest.tag())
+    } else {
+        format!("{prefix}{hash}  {name}")
+    }
+}
+
+///
+/// One parsed line of a check file
+#[derive(Debug, Clone, Eq, PartialEq)]
+struct CheckLine {
+    digest: Digest,
+    expected: Vec<u8>,
+    name: String,
+}
+
+///
+/// Parses a line in either the BSD or the default format.  BSD lines name their algorithm and
+/// length, default lines use `algorithm` and take the length from `len`, or from the line itself
+/// when that's `None`.  Returns `None` if the line is improperly formatted.
+fn parse_check_line(line: &str, algorithm: Algorithm, len: Option<usize>) -> Option<CheckLine> {
+    let line = line.strip_suffix('\r').unwrap_or(line).trim_start();
+    let (escaped, line) = match line.strip_prefix('\\') {
+        Some(line) => (true, line),
+        None => (false, line),
+    };
+    let (digest, hash, name) = if line.starts_with("BLAKE2") {
+        let (tag, rest) = line.split_once(" (")?;
+        let (name, hash) = rest.rsplit_once(") = ")?;
+        let (tag, bits) = match tag.split_once('-') {
+            Some((tag, bits)) => (tag, Some(bits)),
+            None => (tag, None),
+        };
+        let algorithm = Algorithm::from_tag(tag)?;
+        let len = match bits {
+            Some(bits) => parse_bits(bits, algorithm)?,
+            None => algorithm.max_bytes(),
+        };
+        (Digest { algorithm, len }, hash, name)
+    } else {
+        let (hash, rest) = line.split_once(' ')?;
+        let name = rest.strip_prefix([' ', '*'])?;
+        let len = len.unwrap_or(hash.len() / 2);
+        (Digest { algorithm, len }, hash, name)
+    };
+    if digest.len == 0 || digest.len > digest.algorithm.max_bytes() || hash.len() != digest.len * 2
+    {
+        return None;
+    }
+    let expected = hex::decode(hash.as_bytes()).ok()?;
+    let name = if escaped {
+        unescape(name)?
+    } else {
+        name.to_string()
+    };
+    Some(CheckLine {
+        digest,
+        expected,
+        name,
+    })
+}
+
+///
+/// A digest length in bits from `-l` or a BSD line, as bytes.  0 means the maximum.
+fn parse_bits(bits: &str, algorithm: Algorithm) -> Option<usize> {
+    let bits: usize = bits.parse().ok()?;
+    if bits % 8 != 0 || bits > algorithm.max_bytes() * 8 {
+        return None;
+    }
+    if bits == 0 {
+        return Some(algorithm.max_bytes());
+    }
+    Some(bits / 8)
+}
+
+#[derive(Debug, Default)]
+struct Options {
+    algorithm: Option<Algorithm>,
+    bits: Option<String>,
+    bsd: bool,
+    check: bool,
+    quiet: bool,
+    status: bool,
+    files: Vec<OsString>,
+}
+
+enum Parsed {
+    Run(Options),
+    Help,
+}
+
+fn parse_args<I: Iterator<Item = OsString>>(mut args: I) -> Result<Parsed, String> {
+    let mut opts = Options::default();
+    let mut only_files = false;
+    while let Some(arg) = args.next() {
+        let text = arg.to_string_lossy();
+        if only_files || text == "-" || !text.starts_with('-') {
+            opts.files.push(arg);
+            continue;
+        }
+        let (flag, inline) = match text.split_once('=') {
+            Some((flag, value)) if text.starts_with("--") => (flag, Some(value.to_string())),
+            _ => (text.as_ref(), None),
+        };
+        let mut value = |name: &str| -> Result<String, String> {
+            if let Some(v) = &inline {
+                return Ok(v.clone());
+            }
+            if let Some(v) = flag
+                .strip_prefix(name)
+                .filter(|v| !v.is_empty() && name.len() == 2)
+            {
+                return Ok(v.to_string());
+            }
+            args.next()
+                .map(|v| v.to_string_lossy().into_owned())
+                .ok_or_else(|| format!("option requires an argument -- '{name}'"))
+        };
+        match flag {
+            "--" => only_files = true,
+            "-h" | "--help" => return Ok(Parsed::Help),
+            "--tag" => opts.bsd = true,
+            "-c" | "--check" => opts.check = true,
+            "--quiet" => opts.quiet = true,
+            "--status" => opts.status = true,
+            f if f.starts_with("-a") && !f.starts_with("--") || f == "--algorithm" => {
+                let name = value(if f == "--algorithm" { f } else { "-a" })?;
+                let algorithm = Algorithm::from_name(&name)
+                    .ok_or_else(|| format!("invalid algorithm: '{name}'"))?;
+                opts.algorithm = Some(algorithm);
+            }
+            f if f.starts_with("-l") && !f.starts_with("--") || f == "--length" => {
+                opts.bits = Some(value(if f == "--length" { f } else { "-l" })?);
+            }
+            f => return Err(format!("unrecognized option '{f}'")),
+        }
+    }
+    Ok(Parsed::Run(opts))
+}
+
+///
+/// Prints the checksum of each file, returns whether they could all be read
+fn print_sums<W: Write>(out: &mut W, digest: Digest, opts: &Options) -> std::io::Result<bool> {
+    let mut ok = true;
+    for name in &opts.files {
+        let display = name.to_string_lossy();
+        match digest.hash_file(name) {
+            Ok(hash) => writeln!(out, "{}", format_line(digest, &hash, &display, opts.bsd))?,
+            Err(e) => {
+                out.flush()?;
+                eprintln!("{NAME}: {display}: {e}");
+                ok = false;
+            }
+        }
+    }
+    Ok(ok)
+}
+
+///
+/// Verifies the checksums listed in each file, returns whether they all matched
+fn check_sums<W: Write>(
+    out: &mut W,
+    algorithm: Algorithm,
+    len: Option<usize>,
+    opts: &Options,
+) -> std::io::Result<bool> {
+    let mut ok = true;
+    for list in &opts.files {
+        let display = list.to_string_lossy();
+        let reader: Box<dyn BufRead> = if list == "-" {
+            Box::new(std::io::stdin().lock())
+        } else {
+            match File::open(list) {
+                Ok(f) => Box::new(BufReader::new(f)),
+                Err(e) => {
+                    eprintln!("{NAME}: {display}: {e}");
+                    ok = false;
+                    continue;
+                }
+            }
+        };
+        let (mut lines, mut improper, mut mismatched, mut unreadable) = (0, 0, 0, 0);
+        for line in reader.split(b'\n') {
+            let line = line?;
+            let line = String::from_utf8_lossy(&line);
+            let Some(check) = parse_check_line(&line, algorithm, len) else {
+                improper += 1;
+                continue;
+            };
+            lines += 1;
+            let (escaped, shown) = escape(&check.name);
+            let prefix = if escaped { "\\" } else { "" };
+            match check.digest.hash_file(&OsString::from(&check.name)) {
+                Ok(hash) if hash == check.expected => {
+                    if !opts.quiet && !opts.status {
+                        writeln!(out, "{prefix}{shown}: OK")?;
+                    }
+                }
+                Ok(_) => {
+                    mismatched += 1;
+                    if !opts.status {
+                        writeln!(out, "{prefix}{shown}: FAILED")?;
+                    }
+                }
+                Err(e) => {
+                    unreadable += 1;
+                    if !opts.status {
+                        out.flush()?;
+                        eprintln!("{NAME}: {}: {e}", check.name);
+                        writeln!(out, "{prefix}{shown}: FAILED open or read")?;
+                    }
+                }
+            }
+        }
+        out.flush()?;
+        if lines == 0 {
+            eprintln!("{NAME}: {display}: no properly formatted checksum lines found");
+            ok = false;
+            continue;
+        }
+        if !opts.status {
+            let plural = |n: usize, one: &'static str, many: &'static str| match n {
+                1 => one,
+                _ => many,
+            };
+            if improper > 0 {
+                eprintln!(
+                    "{NAME}: WARNING: {improper} {} improperly formatted",
+                    plural(improper, "line is", "lines are")
+                );
+            }
+            if unreadable > 0 {
+                eprintln!(
+                    "{NAME}: WARNING: {unreadable} listed {} could not be read",
+                    plural(unreadable, "file", "files")
+                );
+            }
+            if mismatched > 0 {
+                eprintln!(
+                    "{NAME}: WARNING: {mismatched} computed {} did NOT match",
+                    plural(mismatched, "checksum", "checksums")
+                );
+            }
+        }
+        ok &= mismatched == 0 && unreadable == 0;
+    }
+    Ok(ok)
+}
+
+fn run(mut opts: Options) -> Result<bool, String> {
+    let algorithm = opts.algorithm.unwrap_or(Algorithm::Blake2b);
+    let len = match &opts.bits {
+        Some(bits) => Some(parse_bits(bits, algorithm).ok_or_else(|| {
+            format!(
+                "invalid length: '{bits}', must be a multiple of 8 up to {}",
+                algorithm.max_bytes() * 8
+            )
+        })?),
+        None => None,
+    };
+    if opts.check && opts.bsd {
+        return Err("the --tag option is meaningless when verifying checksums".to_string());
+    }
+    if !opts.check && (opts.quiet || opts.status) {
+        return Err(
+            "the --quiet and --status options are only meaningful when verifying checksums"
+                .to_string(),
+        );
+    }
+    if opts.files.is_empty() {
+        opts.files.push(OsString::from("-"));
+    }
+    let mut out = BufWriter::new(std::io::stdout().lock());
+    let result = if opts.check {
+        check_sums(&mut out, algorithm, len, &opts)
+    } else {
+        let digest = Digest {
+            algorithm,
+            len: len.unwrap_or(algorithm.max_bytes()),
+        };
+        print_sums(&mut out, digest, &opts)
+    };
+    result
+        .and_then(|ok| out.flush().map(|()| ok))
+        .map_err(|e| format!("write error: {e}"))
+}
+
+fn main() -> ExitCode {
+    let result = match parse_args(std::env::args_os().skip(1)) {
+        Ok(Parsed::Help) => {
+            let mut out = std::io::stdout().lock();
+            return match out.write_all(USAGE.as_bytes()) {
+                Ok(()) => ExitCode::SUCCESS,
+                Err(_) => ExitCode::FAILURE,
+            };
+        }
+        Ok(Parsed::Run(opts)) => run(opts),
+        Err(e) => {
+            eprintln!("{NAME}: {e}\nTry '{NAME} --help' for more information.");
+            return ExitCode::FAILURE;
+        }
+    };
+    match result {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(e) => {
+            eprintln!("{NAME}: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn test_escape_roundtrip() {
+        for name in [
+            "plain",
+            "with space",
+            "back\\slash",
+            "new\nline",
+            "cr\rlf\\n",
+        ] {
+            let (escaped, text) = escape(name);
+            assert_eq!(name.contains(['\\', '\n', '\r']), escaped);
+            assert!(!text.contains(['\n', '\r']));
+            if escaped {
+                assert_eq!(Some(name.to_string()), unescape(&text));
+            }
+        }
+        assert_eq!(None, unescape("bad\\t"));
+        assert_eq!(None, unescape("trailing\\"));
+    }
+
+    #[test]
+    pub fn test_parse_lines() {
+        let b = Algorithm::Blake2b;
+        let hash = "ab".repeat(32);
+        let line = parse_check_line(&format!("{hash}  some file"), b, None);
+        assert_eq!(
+            Some(CheckLine {
+                digest: Digest {
+                    algorithm: b,
+                    len: 32
+                },
+                expected: vec![0xAB; 32],
+                name: "some file".to_string(),
+            }),
+            line
+        );
+        let line = parse_check_line(&format!("{hash} *bin"), b, None);
+        assert_eq!(Some("bin"), line.as_ref().map(|l| l.name.as_str()));
+        assert_eq!(None, parse_check_line(&format!("{hash}  f"), b, Some(64)));
+        assert_eq!(None, parse_check_line(&format!("{hash} f"), b, None));
+        assert_eq!(None, parse_check_line("abc  f", b, None));
+
+        let line = parse_check_line(&format!("BLAKE2s-256 (a) = b) = {hash}"), b, None);
+        assert_eq!(
+            Some(Digest {
+                algorithm: Algorithm::Blake2s,
+                len: 32
+            }),
+            line.as_ref().map(|l| l.digest)
+        );
+        assert_eq!(Some("a) = b"), line.as_ref().map(|l| l.name.as_str()));
+        let line = parse_check_line(&format!("\\BLAKE2sp (x\\ny) = {hash}\r"), b, None);
+        assert_eq!(Some("x\ny"), line.as_ref().map(|l| l.name.as_str()));
+        assert_eq!(
+            None,
+            parse_check_line(&format!("BLAKE2b (f) = {hash}"), b, None)
+        );
+        assert_eq!(
+            None,
+            parse_check_line(&format!("BLAKE2b-250 (f) = {hash}"), b, None)
+        );
+        assert_eq!(
+            None,
+            parse_check_line(&format!("BLAKE2x (f) = {hash}"), b, None)
+        );
+    }
+
+    #[test]
+    pub fn test_parse_bits() {
+        assert_eq!(Some(64), parse_bits("0", Algorithm::Blake2b));
+        assert_eq!(Some(32), parse_bits("0", Algorithm::Blake2sp));
+        assert_eq!(Some(1), parse_bits("8", Algorithm::Blake2s));
+        assert_eq!(Some(64), parse_bits("512", Algorithm::Blake2bp));
+        assert_eq!(None, parse_bits("512", Algorithm::Blake2s));
+        assert_eq!(None, parse_bits("12", Algorithm::Blake2b));
+        assert_eq!(None, parse_bits("-8", Algorithm::Blake2b));
+    }
+
+    #[test]
+    pub fn test_format() {
+        let digest = Digest {
+            algorithm: Algorithm::Blake2b,
+            len: 2,
+        };
+        assert_eq!("abcd  f", format_line(digest, &[0xAB, 0xCD], "f", false));
+        assert_eq!(
+            "BLAKE2b-16 (f) = abcd",
+            format_line(digest, &[0xAB, 0xCD], "f", true)
+        );
+        assert_eq!(
+            "\\abcd  a\\\\b\\nc",
+            format_line(digest, &[0xAB, 0xCD], "a\\b\nc", false)
+        );
+        let digest = Digest {
+            algorithm: Algorithm::Blake2sp,
+            len: 32,
+        };
+        assert_eq!(
+            format!("BLAKE2sp (f) = {}", "00".repeat(32)),
+            format_line(digest, &[0; 32], "f", true)
+        );
+    }
+}
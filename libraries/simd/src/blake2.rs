@@ -2,31 +2,66 @@
 // Copyright 2025 IROX Contributors
 //
 
+//!
+//! BLAKE2s and BLAKE2b (RFC 7693), their parallel tree modes BLAKE2sp and BLAKE2bp, and the
+//! BLAKE2X extendable-output variants.
+//!
+//! Every hasher here picks its compression function at runtime through [`crate::dispatch`], so
+//! there is no separate dispatching type: [`BLAKE2s`] uses SSE4.1 on x86-64, then NEON on
+//! aarch64, then the portable scalar form, and BLAKE2b and the tree modes use AVX2 or the scalar
+//! form.
+//!
+
 #![allow(clippy::integer_division_remainder_used)]
 
-use core::ops::BitXorAssign;
-use irox_bits::{MutBits, WriteToLEBits};
-use irox_tools::buf::Buffer;
-use irox_tools::buf::FixedU8Buf;
-use irox_tools::hash::HashDigest;
-use std::arch::x86_64::{
+#[cfg(any(
+    all(target_arch = "x86_64", target_feature = "sse2"),
+    target_arch = "aarch64"
+))]
+use crate::dispatch::{self, Level};
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+use core::arch::x86_64::{
     __m128i, _mm_add_epi32, _mm_alignr_epi8, _mm_blend_epi16, _mm_castps_si128, _mm_castsi128_ps,
     _mm_loadu_si128, _mm_set_epi8, _mm_setzero_si128, _mm_shuffle_epi32, _mm_shuffle_epi8,
     _mm_shuffle_ps, _mm_shufflehi_epi16, _mm_slli_epi32, _mm_slli_si128, _mm_srli_epi32,
     _mm_srli_si128, _mm_storeu_si128, _mm_unpackhi_epi32, _mm_unpackhi_epi64, _mm_unpacklo_epi32,
     _mm_unpacklo_epi64, _mm_xor_si128,
 };
+use core::ops::BitXorAssign;
+use irox_bits::{MutBits, WriteToLEBits};
+use irox_tools::buf::Buffer;
+use irox_tools::buf::FixedU8Buf;
+use irox_tools::hash::HashDigest;
+
+#[macro_use]
+mod blake2b;
+mod blake2bp;
+#[macro_use]
+mod blake2sp;
+mod blake2x;
+#[cfg(feature = "alloc")]
+mod multibuf;
+#[cfg(target_arch = "aarch64")]
+pub(crate) mod neon;
+mod scalar;
+pub use blake2b::*;
+pub use blake2bp::*;
+pub use blake2sp::*;
+pub use blake2x::*;
 
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
 macro_rules! tof {
     ($reg:expr) => {
         _mm_castsi128_ps($reg)
     };
 }
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
 macro_rules! toi {
     ($reg:expr) => {
         _mm_castps_si128($reg)
     };
 }
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
 macro_rules! g1 {
     ($a:expr, $b:expr, $c:expr, $d:expr, $x:expr, $r1:literal, $r2:literal) => {
         *$a = _mm_add_epi32(*$b, _mm_add_epi32(*$a, *$x));
@@ -37,6 +72,7 @@ macro_rules! g1 {
         *$b = _mm_roti_epi32!(*$b, $r2);
     };
 }
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
 macro_rules! diag {
     ($a:expr, $b:expr, $c:expr, $d:expr) => {
         *$a = _mm_shuffle_epi32(*$a, _mm_shuffle(2, 1, 0, 3));
@@ -44,6 +80,7 @@ macro_rules! diag {
         *$c = _mm_shuffle_epi32(*$c, _mm_shuffle(0, 3, 2, 1));
     };
 }
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
 macro_rules! undiag {
     ($a:expr, $b:expr, $c:expr, $d:expr) => {
         *$a = _mm_shuffle_epi32(*$a, _mm_shuffle(0, 3, 2, 1));
@@ -51,6 +88,7 @@ macro_rules! undiag {
         *$c = _mm_shuffle_epi32(*$c, _mm_shuffle(2, 1, 0, 3));
     };
 }
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
 macro_rules! round {
     ($a:expr, $b:expr, $c:expr, $d:expr, $buf1:expr, $x:expr, $y:expr, $z:expr,$n:expr) => {{
         *$buf1 = { $x };
@@ -65,20 +103,24 @@ macro_rules! round {
         undiag!($a, $b, $c, $d);
     }};
 }
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
 #[inline]
 pub(crate) const fn _mm_shuffle(a: u8, b: u8, c: u8, d: u8) -> i32 {
     ((a as i32) << 6) | ((b as i32) << 4) | ((c as i32) << 2) | (d as i32)
 }
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
 macro_rules! r8 {
     () => {
         _mm_set_epi8(12, 15, 14, 13, 8, 11, 10, 9, 4, 7, 6, 5, 0, 3, 2, 1)
     };
 }
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
 macro_rules! r16 {
     () => {
         _mm_set_epi8(13, 12, 15, 14, 9, 8, 11, 10, 5, 4, 7, 6, 1, 0, 3, 2)
     };
 }
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
 macro_rules! _mm_roti_epi32 {
     ($r: expr, $c: expr) => {{
         if $c == 8 {
@@ -97,12 +139,176 @@ macro_rules! _mm_roti_epi32 {
 static BLAKE2S_IV: &[u32; 8] = &[
     0x6A09E667, 0xBB67AE85, 0x3C6EF372, 0xA54FF53A, 0x510E527F, 0x9B05688C, 0x1F83D9AB, 0x5BE0CD19,
 ];
+static SIGMA: &[[usize; 16]; 12] = &[
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+    [14, 10, 4, 8, 9, 15, 13, 6, 1, 12, 0, 2, 11, 7, 5, 3],
+    [11, 8, 12, 0, 5, 2, 15, 13, 10, 14, 3, 6, 7, 1, 9, 4],
+    [7, 9, 3, 1, 13, 12, 11, 14, 2, 6, 5, 10, 4, 0, 15, 8],
+    [9, 0, 5, 7, 2, 4, 10, 15, 14, 1, 11, 12, 6, 8, 3, 13],
+    [2, 12, 6, 10, 0, 11, 8, 3, 4, 13, 7, 5, 15, 14, 1, 9],
+    [12, 5, 1, 15, 14, 13, 4, 10, 0, 7, 6, 3, 9, 2, 8, 11],
+    [13, 11, 7, 14, 12, 1, 3, 9, 5, 0, 15, 4, 8, 6, 2, 10],
+    [6, 15, 14, 9, 11, 3, 0, 8, 12, 2, 13, 7, 1, 4, 10, 5],
+    [10, 2, 8, 4, 7, 6, 1, 5, 15, 11, 9, 14, 3, 12, 13, 0],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+    [14, 10, 4, 8, 9, 15, 13, 6, 1, 12, 0, 2, 11, 7, 5, 3],
+];
+///
+/// The BLAKE2s parameter block (RFC 7693 section 2.5, and section 2.8 of the BLAKE2 paper).  The
+/// digest and key lengths are taken from the hasher itself, everything else is set here.  The
+/// defaults (fanout 1, depth 1, everything else zero) are the regular sequential mode.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Blake2sParams {
+    fanout: u8,
+    depth: u8,
+    leaf_length: u32,
+    node_offset: u64,
+    xof_length: u16,
+    node_depth: u8,
+    inner_length: u8,
+    salt: [u8; 8],
+    personal: [u8; 8],
+    last_node: bool,
+}
+impl Default for Blake2sParams {
+    fn default() -> Self {
+        Self {
+            fanout: 1,
+            depth: 1,
+            leaf_length: 0,
+            node_offset: 0,
+            xof_length: 0,
+            node_depth: 0,
+            inner_length: 0,
+            salt: [0; 8],
+            personal: [0; 8],
+            last_node: false,
+        }
+    }
+}
+impl Blake2sParams {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Salt value, up to 8 bytes.  Longer values are truncated, shorter are zero-padded.
+    #[must_use]
+    pub fn with_salt(mut self, salt: &[u8]) -> Self {
+        self.salt = pad(salt);
+        self
+    }
+    /// Personalization string, up to 8 bytes.  Longer values are truncated, shorter are
+    /// zero-padded.
+    #[must_use]
+    pub fn with_personal(mut self, personal: &[u8]) -> Self {
+        self.personal = pad(personal);
+        self
+    }
+    /// Tree fanout, 0 for unlimited, 1 for sequential mode
+    #[must_use]
+    pub fn with_fanout(mut self, fanout: u8) -> Self {
+        self.fanout = fanout;
+        self
+    }
+    /// Maximal tree depth, 255 for unlimited, 1 for sequential mode
+    #[must_use]
+    pub fn with_depth(mut self, depth: u8) -> Self {
+        self.depth = depth;
+        self
+    }
+    /// Maximal leaf length in bytes, 0 for unlimited or sequential mode
+    #[must_use]
+    pub fn with_leaf_length(mut self, leaf_length: u32) -> Self {
+        self.leaf_length = leaf_length;
+        self
+    }
+    /// Node offset, only the lower 48 bits are used.  0 for the first/leftmost leaf, or
+    /// sequential mode.
+    #[must_use]
+    pub fn with_node_offset(mut self, node_offset: u64) -> Self {
+        self.node_offset = node_offset & 0xFFFF_FFFF_FFFF;
+        self
+    }
+    /// BLAKE2Xs output length in bytes, 0 for a regular hash.  BLAKE2X reuses the upper 16 bits
+    /// of the node offset for this, so node offsets must then fit in 32 bits.
+    #[must_use]
+    pub fn with_xof_length(mut self, xof_length: u16) -> Self {
+        self.xof_length = xof_length;
+        self
+    }
+    /// Node depth, 0 for the leaves or sequential mode
+    #[must_use]
+    pub fn with_node_depth(mut self, node_depth: u8) -> Self {
+        self.node_depth = node_depth;
+        self
+    }
+    /// Inner hash length in bytes, 0 for sequential mode
+    #[must_use]
+    pub fn with_inner_length(mut self, inner_length: u8) -> Self {
+        self.inner_length = inner_length;
+        self
+    }
+    /// Marks this as the last (rightmost) node at its depth in tree mode, sets `flags[1]` on
+    /// the final block.
+    #[must_use]
+    pub fn with_last_node(mut self, last_node: bool) -> Self {
+        self.last_node = last_node;
+        self
+    }
+
+    ///
+    /// Encodes this parameter block for a hash with output length `nn` and key length `kk`
+    pub fn to_words(&self, nn: u8, kk: u8) -> [u32; 8] {
+        let offset = self.node_offset | (u64::from(self.xof_length) << 32);
+        let [o0, o1, o2, o3, o4, o5, _, _] = offset.to_le_bytes();
+        let [s0, s1, s2, s3, s4, s5, s6, s7] = self.salt;
+        let [p0, p1, p2, p3, p4, p5, p6, p7] = self.personal;
+        [
+            u32::from_le_bytes([nn, kk, self.fanout, self.depth]),
+            self.leaf_length,
+            u32::from_le_bytes([o0, o1, o2, o3]),
+            u32::from_le_bytes([o4, o5, self.node_depth, self.inner_length]),
+            u32::from_le_bytes([s0, s1, s2, s3]),
+            u32::from_le_bytes([s4, s5, s6, s7]),
+            u32::from_le_bytes([p0, p1, p2, p3]),
+            u32::from_le_bytes([p4, p5, p6, p7]),
+        ]
+    }
+}
+fn pad<const N: usize>(v: &[u8]) -> [u8; N] {
+    let mut out = [0u8; N];
+    for (o, i) in out.iter_mut().zip(v) {
+        *o = *i;
+    }
+    out
+}
+
+///
+/// Compares the two slices without short-circuiting on the first difference, so the time taken
+/// doesn't leak how much of a MAC matched.
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut diff = 0u8;
+    for (a, b) in a.iter().zip(b) {
+        diff |= core::hint::black_box(a ^ b);
+    }
+    diff == 0
+}
+
+///
+/// BLAKE2s with an `NN`-byte digest.  Each block is compressed with SSE4.1 when the CPU has
+/// it, otherwise with NEON on aarch64, otherwise with the scalar compression function, chosen
+/// per block from [`crate::dispatch::level`].
 #[repr(C)]
+#[derive(Clone)]
 pub struct BLAKE2s<const NN: usize> {
     h: [u32; 8],
     written: u64,
     flags: [u32; 2],
     buf: FixedU8Buf<64>,
+    last_node: bool,
 }
 impl<const NN: usize> Default for BLAKE2s<NN> {
     fn default() -> Self {
@@ -110,18 +316,57 @@ impl<const NN: usize> Default for BLAKE2s<NN> {
     }
 }
 impl<const NN: usize> BLAKE2s<NN> {
+    ///
+    /// Creates a new hasher with the specified key (up to 32 bytes, empty for an unkeyed hash).
+    ///
+    /// # Panics
+    /// If the key is longer than 32 bytes, the parameter block can't encode it.
     pub fn new(key: &[u8]) -> Self {
+        Self::new_with_params(key, &Blake2sParams::default())
+    }
+
+    ///
+    /// Creates a new hasher with the specified key (up to 32 bytes) and parameter block.  Panics
+    /// like [`Self::new`] if the key is longer.
+    pub fn new_with_params(key: &[u8], params: &Blake2sParams) -> Self {
+        let words = params.to_words(NN as u8, key.len() as u8);
+        Self::new_with_words(key, words, params.last_node)
+    }
+
+    ///
+    /// Creates a new hasher for a `length` byte digest (1 to `NN`), for when that's only known at
+    /// runtime.  The length is part of the parameter block so the digest isn't a truncated `NN`
+    /// byte hash, but it is the first `length` bytes of what [`Self::finish`] returns.
+    pub fn new_with_length(key: &[u8], length: usize) -> Self {
+        let words = Blake2sParams::default().to_words(length.clamp(1, NN) as u8, key.len() as u8);
+        Self::new_with_words(key, words, false)
+    }
+
+    ///
+    /// Rejects what the parameter block can't encode: a digest length outside 1 to 32 bytes
+    /// fails to compile, a key longer than 32 bytes panics.  Otherwise the `as u8` casts
+    /// would silently wrap and hash under the wrong parameters.
+    pub(crate) fn check_params(key: &[u8]) {
+        const { assert!(NN >= 1 && NN <= 32, "BLAKE2s digests are 1 to 32 bytes") };
+        assert!(key.len() <= 32, "BLAKE2s keys are at most 32 bytes");
+    }
+
+    ///
+    /// Creates a new hasher from an already encoded parameter block.  The tree modes need this
+    /// when the encoded digest length differs from `NN` or the key length from `key`.
+    pub(crate) fn new_with_words(key: &[u8], words: [u32; 8], last_node: bool) -> Self {
+        Self::check_params(key);
         let mut out = Self {
             h: *BLAKE2S_IV,
             written: 0,
             flags: [0, 0],
             buf: Default::default(),
+            last_node,
         };
-        let nn = (NN as u32) & 0xFF;
-        let kk = ((key.len() as u32) & 0xFF) << 8;
-        let p = (0x01010000u32) | nn | kk;
-        out.h[0].bitxor_assign(p);
-        if kk > 0 {
+        for (h, p) in out.h.iter_mut().zip(words) {
+            h.bitxor_assign(p);
+        }
+        if !key.is_empty() {
             let _ = out.buf.write_all_bytes(key);
             let _ = out.buf.update_length(64);
             out.written += 64;
@@ -130,15 +375,33 @@ impl<const NN: usize> BLAKE2s<NN> {
         out
     }
 
-    unsafe fn chomp(&mut self, last: bool) {
+    fn chomp(&mut self, last: bool) {
         let m = self.buf.as_buf_default();
-        let mp: *const u32 = core::mem::transmute(m.as_ptr());
-        self.chomp_exact(mp, last);
+        self.chomp_exact(&m, last);
+    }
+    fn chomp_exact(&mut self, block: &[u8; 64], last: bool) {
+        if last {
+            self.flags[0] = u32::MAX;
+            if self.last_node {
+                self.flags[1] = u32::MAX;
+            }
+        }
+        #[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+        if dispatch::level() >= Level::Sse41 {
+            unsafe { self.chomp_sse41(block.as_ptr() as *const u32) };
+            return;
+        }
+        let t = [self.written as u32, (self.written >> 32) as u32];
+        #[cfg(target_arch = "aarch64")]
+        if dispatch::level() >= Level::Neon {
+            unsafe { neon::compress_s(&mut self.h, block, t, self.flags) };
+            return;
+        }
+        scalar::compress_s(&mut self.h, &scalar::words_s(block), t, self.flags);
     }
-    unsafe fn chomp_exact(&mut self, mp: *const u32, last: bool) {
-        // if self.buf.is_empty() && self.written > 0 && !last {
-        //     return;
-        // }
+    #[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+    #[target_feature(enable = "sse2,ssse3,sse4.1")]
+    unsafe fn chomp_sse41(&mut self, mp: *const u32) {
         let m0 = _mm_loadu_si128(mp as *const _);
         let m1 = _mm_loadu_si128(mp.offset(4) as *const _);
         let m2 = _mm_loadu_si128(mp.offset(8) as *const _);
@@ -152,9 +415,6 @@ impl<const NN: usize> BLAKE2s<NN> {
 
         let ivp = BLAKE2S_IV.as_ptr();
         let mut row3 = _mm_loadu_si128(ivp as *const _);
-        if last {
-            self.flags[0] = u32::MAX;
-        }
         let a = _mm_loadu_si128(ivp.offset(4) as *const _);
         let b = _mm_loadu_si128(&raw const self.written as *const _);
         let mut row4 = _mm_xor_si128(a, b);
@@ -475,57 +735,45 @@ impl<const NN: usize> BLAKE2s<NN> {
         if v.is_empty() {
             return;
         }
-        if self.buf.is_full() {
-            unsafe {
-                self.chomp(false);
-            }
-        }
-        let rem = 64 - self.buf.len();
-        if !self.buf.is_empty() && v.len() > rem {
-            let (a, b) = v.split_at(rem);
+        if !self.buf.is_empty() && !self.buf.is_full() {
+            let rem = 64 - self.buf.len();
+            let (a, b) = v.split_at(rem.min(v.len()));
             v = b;
             let _ = self.buf.write_all_bytes(a);
-            if self.buf.is_full() {
-                unsafe {
-                    self.chomp(false);
-                }
-            }
             self.written += a.len() as u64;
-        }
-
-        let mut chunks = v.chunks_exact(64);
-        for c in chunks.by_ref() {
-            if self.buf.is_full() {
-                unsafe {
-                    self.chomp(false);
-                }
-            } else {
-                unsafe {
-                    let mp: *const u32 = core::mem::transmute(c.as_ptr());
-                    self.chomp_exact(mp, false);
-                }
+            if v.is_empty() {
+                return;
             }
-            self.written += 64;
+        }
+        // the final block must be held back until finish() so it can be flagged as last, so only
+        // compress what's buffered once we know more data follows it.
+        if self.buf.is_full() {
+            self.chomp(false);
         }
 
-        for val in chunks.remainder() {
-            if self.buf.is_full() {
-                unsafe {
-                    self.chomp(false);
-                }
+        while v.len() > 64 {
+            let (c, b) = v.split_at(64);
+            v = b;
+            self.written += 64;
+            if let Ok(c) = c.try_into() {
+                self.chomp_exact(c, false);
             }
-            let _ = self.buf.write_u8(*val);
-            self.written += 1;
         }
+
+        let _ = self.buf.write_all_bytes(v);
+        self.written += v.len() as u64;
     }
     pub fn hash(mut self, v: &[u8]) -> [u8; NN] {
         self.write(v);
         self.finish()
     }
+    ///
+    /// Finishes the MAC and compares it against the expected value in constant time.
+    pub fn verify_mac(self, expected: &[u8]) -> bool {
+        constant_time_eq(&self.finish(), expected)
+    }
     pub fn finish(mut self) -> [u8; NN] {
-        unsafe {
-            self.chomp(true);
-        }
+        self.chomp(true);
 
         // let out: [u8; NN] = unsafe { self.h.align_to::<u8>().1 }.copy_subset();
         let mut out: FixedU8Buf<NN> = FixedU8Buf::default();
@@ -536,17 +784,22 @@ impl<const NN: usize> BLAKE2s<NN> {
         out.take()
     }
 }
-impl<const NN: usize> HashDigest<32, NN> for BLAKE2s<NN> {
+impl<const NN: usize> HashDigest<64, NN> for BLAKE2s<NN> {
     fn finish(self) -> [u8; NN] {
-        todo!()
+        BLAKE2s::finish(self)
     }
-    fn hash(self, _: &[u8]) -> [u8; NN] {
-        todo!()
+    fn hash(self, v: &[u8]) -> [u8; NN] {
+        BLAKE2s::hash(self, v)
     }
-    fn write(&mut self, _: &[u8]) {
-        todo!()
+    fn write(&mut self, v: &[u8]) {
+        BLAKE2s::write(self, v)
     }
 }
+impl_io_write!(const NN: usize, BLAKE2s<NN> => [u8; NN]);
+impl_digest!(BLAKE2s<16>, U16);
+impl_digest!(BLAKE2s<20>, U20);
+impl_digest!(BLAKE2s<28>, U28);
+impl_digest!(BLAKE2s<32>, U32);
 
 pub type BLAKE2s128 = BLAKE2s<16>;
 pub type BLAKE2s160 = BLAKE2s<20>;
@@ -555,9 +808,18 @@ pub type BLAKE2s256 = BLAKE2s<32>;
 
 #[cfg(test)]
 mod tests {
-    use crate::blake2::{BLAKE2s224, BLAKE2s256};
+    use crate::blake2::{BLAKE2s, BLAKE2s160, BLAKE2s224, BLAKE2s256, Blake2sParams};
+    use irox_tools::hash::HashDigest;
     use irox_tools::{assert_eq_hex_slice, hex};
 
+    fn digest<const NN: usize, T: HashDigest<64, NN>>(chunks: &[&[u8]]) -> [u8; NN] {
+        let mut h = T::default();
+        for c in chunks {
+            h.write(c);
+        }
+        h.finish()
+    }
+
     #[test]
     pub fn test0() {
         let h = BLAKE2s224::default().hash(b"");
@@ -577,12 +839,148 @@ mod tests {
 
     #[test]
     pub fn test_long() {
-        let _exp = hex!("508C5E8C327C14E2E1A72BA34EEB452F37458B209ED63A294D999B4C86675982");
+        let exp = hex!("315395b1a387c97f0d165dd35803167c4716f84dbb80ac388d9215749412d0ee");
         let mut inp = [0u8; 128];
         let mut v = BLAKE2s256::default();
         for i in 0..100000 {
             v.write(&inp);
             inp[0] = inp[0].wrapping_add(i as u8);
         }
+        assert_eq_hex_slice!(exp, v.finish());
+    }
+
+    #[test]
+    pub fn test_block_boundaries() {
+        let mut inp = [0u8; 260];
+        for (i, v) in inp.iter_mut().enumerate() {
+            *v = i as u8;
+        }
+        for len in 0..inp.len() {
+            let (msg, _) = inp.split_at(len);
+            let exp = irox_tools::hash::BLAKE2s256::default().hash(msg);
+            assert_eq_hex_slice!(exp, BLAKE2s256::default().hash(msg));
+            for step in [1, 7, 64, 65] {
+                let chunks: Vec<&[u8]> = msg.chunks(step).collect();
+                assert_eq_hex_slice!(exp, digest::<32, BLAKE2s256>(&chunks));
+            }
+        }
+    }
+
+    #[test]
+    pub fn test_keyed() {
+        let key = hex!("000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f");
+        let exp = hex!("48a8997da407876b3d79c0d92325ad3b89cbb754d86ab71aee047ad345fd2c49");
+        assert_eq_hex_slice!(exp, BLAKE2s256::new(&key).finish());
+        let scalar = irox_tools::hash::BLAKE2s::<32>::new(&key);
+        assert_eq_hex_slice!(exp, scalar.finish());
+    }
+
+    #[test]
+    #[should_panic(expected = "BLAKE2s keys are at most 32 bytes")]
+    pub fn test_key_too_long() {
+        let _ = BLAKE2s256::new(&[0u8; 33]);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    #[should_panic(expected = "BLAKE2s keys are at most 32 bytes")]
+    pub fn test_hash_many_key_too_long() {
+        let _ = BLAKE2s256::hash_many_keyed(&[0u8; 33], &[b"abc"]);
+    }
+
+    fn selftest_seq(out: &mut [u8], seed: u32) {
+        let mut a = 0xDEAD4BADu32.wrapping_mul(seed);
+        let mut b = 1u32;
+        for v in out {
+            let t = a.wrapping_add(b);
+            a = b;
+            b = t;
+            *v = (t >> 24) as u8;
+        }
+    }
+    fn selftest_md<const NN: usize>(ctx: &mut BLAKE2s256, inlen: usize) {
+        let mut inp = [0u8; 1024];
+        let mut key = [0u8; NN];
+        let (inp, _) = inp.split_at_mut(inlen);
+        selftest_seq(inp, inlen as u32);
+        ctx.write(&BLAKE2s::<NN>::default().hash(inp));
+        selftest_seq(&mut key, NN as u32);
+        ctx.write(&BLAKE2s::<NN>::new(&key).hash(inp));
+    }
+
+    /// RFC 7693 Appendix E self-test
+    #[test]
+    pub fn test_rfc7693_selftest() {
+        let exp = hex!("6A411F08CE25ADCDFB02ABA641451CEC53C598B24F4FC787FBDC88797F4C1DFE");
+        let mut ctx = BLAKE2s256::default();
+        let inlens = [0, 3, 64, 65, 255, 1024];
+        for inlen in inlens {
+            selftest_md::<16>(&mut ctx, inlen);
+        }
+        for inlen in inlens {
+            selftest_md::<20>(&mut ctx, inlen);
+        }
+        for inlen in inlens {
+            selftest_md::<28>(&mut ctx, inlen);
+        }
+        for inlen in inlens {
+            selftest_md::<32>(&mut ctx, inlen);
+        }
+        assert_eq_hex_slice!(exp, ctx.finish());
+    }
+
+    #[test]
+    pub fn test_params() {
+        let params = Blake2sParams::new()
+            .with_salt(b"saltsalt")
+            .with_personal(b"personal");
+        let exp = hex!("c53092d1e407e687ca47e8a6662ec0b00fa2c1179476bac8f0f147740ef20278");
+        assert_eq_hex_slice!(exp, BLAKE2s256::new_with_params(&[], &params).hash(b"abc"));
+
+        let params = Blake2sParams::new()
+            .with_fanout(2)
+            .with_depth(3)
+            .with_leaf_length(0x1234)
+            .with_node_offset(0x0102_0304)
+            .with_node_depth(1)
+            .with_inner_length(32)
+            .with_last_node(true);
+        let exp = hex!("ed8d23194108958d933cadfea926f8e37612d4fdef7fe1045ad5f280adb70bd6");
+        assert_eq_hex_slice!(exp, BLAKE2s256::new_with_params(&[], &params).hash(b"abc"));
+
+        let params = Blake2sParams::new()
+            .with_fanout(0)
+            .with_depth(255)
+            .with_node_offset(u64::MAX)
+            .with_last_node(true)
+            .with_salt(&[0, 1, 2, 3, 4, 5, 6, 7])
+            .with_personal(&[8, 9, 10, 11, 12, 13, 14, 15]);
+        let exp = hex!("329bea6666b81bcc92a490ec88ea1abcc75df45f");
+        assert_eq_hex_slice!(
+            exp,
+            BLAKE2s160::new_with_params(b"secret", &params).hash(b"abc")
+        );
+
+        let mut inp = [0u8; 768];
+        for (i, v) in inp.iter_mut().enumerate() {
+            *v = i as u8;
+        }
+        let params = Blake2sParams::new().with_last_node(true);
+        let mut h = BLAKE2s256::new_with_params(&[], &params);
+        for c in inp.chunks(100) {
+            h.write(c);
+        }
+        let exp = hex!("55c69cf7a318d7558eab6498b12b222171f5cf4f5728593f7740b79dfd0327d3");
+        assert_eq_hex_slice!(exp, h.finish());
+    }
+
+    #[test]
+    pub fn test_verify_mac() {
+        let key = hex!("000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f");
+        let mut mac = hex!("48a8997da407876b3d79c0d92325ad3b89cbb754d86ab71aee047ad345fd2c49");
+        assert!(BLAKE2s256::new(&key).verify_mac(&mac));
+        assert!(!BLAKE2s256::new(&key).verify_mac(&mac[..31]));
+        mac[31] ^= 0x01;
+        assert!(!BLAKE2s256::new(&key).verify_mac(&mac));
     }
 }
@@ -0,0 +1,502 @@
+// SPDX-License-Identifier: MIT
+// Copyright 2025 IROX Contributors
+//
+
+//!
+//! AVX2 implementation of BLAKE2b (RFC 7693), with a scalar fallback
+//!
+
+#![allow(clippy::indexing_slicing)]
+
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+use super::{_mm_shuffle, SIGMA};
+use super::{constant_time_eq, pad, scalar};
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+use crate::dispatch::{self, Level};
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+use core::arch::x86_64::{
+    __m256i, _mm256_add_epi64, _mm256_loadu_si256, _mm256_or_si256, _mm256_permute4x64_epi64,
+    _mm256_set_epi64x, _mm256_setr_epi8, _mm256_shuffle_epi32, _mm256_shuffle_epi8,
+    _mm256_srli_epi64, _mm256_storeu_si256, _mm256_xor_si256,
+};
+use core::ops::BitXorAssign;
+use irox_bits::MutBits;
+use irox_tools::buf::Buffer;
+use irox_tools::buf::FixedU8Buf;
+use irox_tools::hash::HashDigest;
+
+pub const BLAKE2B_BLOCK_SIZE: usize = 128;
+
+pub(crate) static BLAKE2B_IV: &[u64; 8] = &[
+    0x6A09E667F3BCC908,
+    0xBB67AE8584CAA73B,
+    0x3C6EF372FE94F82B,
+    0xA54FF53A5F1D36F1,
+    0x510E527FADE682D1,
+    0x9B05688C2B3E6C1F,
+    0x1F83D9ABFB41BD6B,
+    0x5BE0CD19137E2179,
+];
+
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+macro_rules! r24 {
+    () => {
+        _mm256_setr_epi8(
+            3, 4, 5, 6, 7, 0, 1, 2, 11, 12, 13, 14, 15, 8, 9, 10, 3, 4, 5, 6, 7, 0, 1, 2, 11, 12,
+            13, 14, 15, 8, 9, 10,
+        )
+    };
+}
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+macro_rules! r16 {
+    () => {
+        _mm256_setr_epi8(
+            2, 3, 4, 5, 6, 7, 0, 1, 10, 11, 12, 13, 14, 15, 8, 9, 2, 3, 4, 5, 6, 7, 0, 1, 10, 11,
+            12, 13, 14, 15, 8, 9,
+        )
+    };
+}
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+macro_rules! _mm256_rotri_epi64 {
+    ($r: expr, 32) => {
+        _mm256_shuffle_epi32::<{ _mm_shuffle(2, 3, 0, 1) }>($r)
+    };
+    ($r: expr, 24) => {
+        _mm256_shuffle_epi8($r, r24!())
+    };
+    ($r: expr, 16) => {
+        _mm256_shuffle_epi8($r, r16!())
+    };
+    ($r: expr, 63) => {
+        _mm256_or_si256(_mm256_srli_epi64::<63>($r), _mm256_add_epi64($r, $r))
+    };
+}
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+macro_rules! g {
+    ($a:ident, $b:ident, $c:ident, $d:ident, $x:expr, $r1:tt, $r2:tt) => {
+        $a = _mm256_add_epi64(_mm256_add_epi64($a, $b), $x);
+        $d = _mm256_rotri_epi64!(_mm256_xor_si256($d, $a), $r1);
+        $c = _mm256_add_epi64($c, $d);
+        $b = _mm256_rotri_epi64!(_mm256_xor_si256($b, $c), $r2);
+    };
+}
+/// Loads the four message words at `SIGMA` positions `$s[$i]`, `$s[$i+2]`, `$s[$i+4]`,
+/// `$s[$i+6]` into lanes 0-3
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+macro_rules! msg {
+    ($m:ident, $s:ident, $i:literal) => {
+        _mm256_set_epi64x(
+            $m[$s[$i + 6]] as i64,
+            $m[$s[$i + 4]] as i64,
+            $m[$s[$i + 2]] as i64,
+            $m[$s[$i]] as i64,
+        )
+    };
+}
+
+///
+/// The BLAKE2b parameter block (RFC 7693 section 2.5, and section 2.8 of the BLAKE2 paper).  The
+/// digest and key lengths are taken from the hasher itself, everything else is set here.  The
+/// defaults (fanout 1, depth 1, everything else zero) are the regular sequential mode.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Blake2bParams {
+    fanout: u8,
+    depth: u8,
+    leaf_length: u32,
+    node_offset: u64,
+    xof_length: u32,
+    node_depth: u8,
+    inner_length: u8,
+    salt: [u8; 16],
+    personal: [u8; 16],
+    last_node: bool,
+}
+impl Default for Blake2bParams {
+    fn default() -> Self {
+        Self {
+            fanout: 1,
+            depth: 1,
+            leaf_length: 0,
+            node_offset: 0,
+            xof_length: 0,
+            node_depth: 0,
+            inner_length: 0,
+            salt: [0; 16],
+            personal: [0; 16],
+            last_node: false,
+        }
+    }
+}
+impl Blake2bParams {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Salt value, up to 16 bytes.  Longer values are truncated, shorter are zero-padded.
+    #[must_use]
+    pub fn with_salt(mut self, salt: &[u8]) -> Self {
+        self.salt = pad(salt);
+        self
+    }
+    /// Personalization string, up to 16 bytes.  Longer values are truncated, shorter are
+    /// zero-padded.
+    #[must_use]
+    pub fn with_personal(mut self, personal: &[u8]) -> Self {
+        self.personal = pad(personal);
+        self
+    }
+    /// Tree fanout, 0 for unlimited, 1 for sequential mode
+    #[must_use]
+    pub fn with_fanout(mut self, fanout: u8) -> Self {
+        self.fanout = fanout;
+        self
+    }
+    /// Maximal tree depth, 255 for unlimited, 1 for sequential mode
+    #[must_use]
+    pub fn with_depth(mut self, depth: u8) -> Self {
+        self.depth = depth;
+        self
+    }
+    /// Maximal leaf length in bytes, 0 for unlimited or sequential mode
+    #[must_use]
+    pub fn with_leaf_length(mut self, leaf_length: u32) -> Self {
+        self.leaf_length = leaf_length;
+        self
+    }
+    /// Node offset, 0 for the first/leftmost leaf, or sequential mode.
+    #[must_use]
+    pub fn with_node_offset(mut self, node_offset: u64) -> Self {
+        self.node_offset = node_offset;
+        self
+    }
+    /// BLAKE2Xb output length in bytes, 0 for a regular hash.  BLAKE2X reuses the upper 32 bits
+    /// of the node offset for this, so node offsets must then fit in 32 bits.
+    #[must_use]
+    pub fn with_xof_length(mut self, xof_length: u32) -> Self {
+        self.xof_length = xof_length;
+        self
+    }
+    /// Node depth, 0 for the leaves or sequential mode
+    #[must_use]
+    pub fn with_node_depth(mut self, node_depth: u8) -> Self {
+        self.node_depth = node_depth;
+        self
+    }
+    /// Inner hash length in bytes, 0 for sequential mode
+    #[must_use]
+    pub fn with_inner_length(mut self, inner_length: u8) -> Self {
+        self.inner_length = inner_length;
+        self
+    }
+    /// Marks this as the last (rightmost) node at its depth in tree mode, sets `flags[1]` on
+    /// the final block.
+    #[must_use]
+    pub fn with_last_node(mut self, last_node: bool) -> Self {
+        self.last_node = last_node;
+        self
+    }
+
+    ///
+    /// Encodes this parameter block for a hash with output length `nn` and key length `kk`
+    pub fn to_words(&self, nn: u8, kk: u8) -> [u64; 8] {
+        let [l0, l1, l2, l3] = self.leaf_length.to_le_bytes();
+        let [s0, s1, s2, s3, s4, s5, s6, s7, s8, s9, s10, s11, s12, s13, s14, s15] = self.salt;
+        let [p0, p1, p2, p3, p4, p5, p6, p7, p8, p9, p10, p11, p12, p13, p14, p15] = self.personal;
+        [
+            u64::from_le_bytes([nn, kk, self.fanout, self.depth, l0, l1, l2, l3]),
+            self.node_offset | (u64::from(self.xof_length) << 32),
+            u64::from_le_bytes([self.node_depth, self.inner_length, 0, 0, 0, 0, 0, 0]),
+            0,
+            u64::from_le_bytes([s0, s1, s2, s3, s4, s5, s6, s7]),
+            u64::from_le_bytes([s8, s9, s10, s11, s12, s13, s14, s15]),
+            u64::from_le_bytes([p0, p1, p2, p3, p4, p5, p6, p7]),
+            u64::from_le_bytes([p8, p9, p10, p11, p12, p13, p14, p15]),
+        ]
+    }
+}
+
+#[repr(C)]
+#[derive(Clone)]
+pub struct BLAKE2b<const NN: usize> {
+    h: [u64; 8],
+    written: u128,
+    flags: [u64; 2],
+    buf: FixedU8Buf<BLAKE2B_BLOCK_SIZE>,
+    last_node: bool,
+}
+impl<const NN: usize> Default for BLAKE2b<NN> {
+    fn default() -> Self {
+        Self::new(&[])
+    }
+}
+impl<const NN: usize> BLAKE2b<NN> {
+    ///
+    /// Creates a new hasher with the specified key (up to 64 bytes, empty for an unkeyed hash).
+    ///
+    /// # Panics
+    /// If the key is longer than 64 bytes, the parameter block can't encode it.
+    pub fn new(key: &[u8]) -> Self {
+        Self::new_with_params(key, &Blake2bParams::default())
+    }
+
+    ///
+    /// Creates a new hasher with the specified key (up to 64 bytes) and parameter block.  Panics
+    /// like [`Self::new`] if the key is longer.
+    pub fn new_with_params(key: &[u8], params: &Blake2bParams) -> Self {
+        let words = params.to_words(NN as u8, key.len() as u8);
+        Self::new_with_words(key, words, params.last_node)
+    }
+
+    ///
+    /// Creates a new hasher for a `length` byte digest (1 to `NN`), as in
+    /// [`BLAKE2s::new_with_length`](super::BLAKE2s::new_with_length).
+    pub fn new_with_length(key: &[u8], length: usize) -> Self {
+        let words = Blake2bParams::default().to_words(length.clamp(1, NN) as u8, key.len() as u8);
+        Self::new_with_words(key, words, false)
+    }
+
+    ///
+    /// Rejects what the parameter block can't encode: a digest length outside 1 to 64 bytes
+    /// fails to compile, a key longer than 64 bytes panics.  Otherwise the `as u8` casts
+    /// would silently wrap and hash under the wrong parameters.
+    pub(crate) fn check_params(key: &[u8]) {
+        const { assert!(NN >= 1 && NN <= 64, "BLAKE2b digests are 1 to 64 bytes") };
+        assert!(key.len() <= 64, "BLAKE2b keys are at most 64 bytes");
+    }
+
+    ///
+    /// Creates a new hasher from an already encoded parameter block.  The tree modes need this
+    /// when the encoded digest length differs from `NN` or the key length from `key`.
+    pub(crate) fn new_with_words(key: &[u8], words: [u64; 8], last_node: bool) -> Self {
+        Self::check_params(key);
+        let mut out = Self {
+            h: *BLAKE2B_IV,
+            written: 0,
+            flags: [0, 0],
+            buf: Default::default(),
+            last_node,
+        };
+        for (h, p) in out.h.iter_mut().zip(words) {
+            h.bitxor_assign(p);
+        }
+        if !key.is_empty() {
+            let _ = out.buf.write_all_bytes(key);
+            let _ = out.buf.update_length(BLAKE2B_BLOCK_SIZE);
+            out.written += BLAKE2B_BLOCK_SIZE as u128;
+        }
+
+        out
+    }
+
+    fn chomp(&mut self, last: bool) {
+        let m = self.buf.as_buf_default();
+        self.chomp_exact(&m, last);
+    }
+
+    fn chomp_exact(&mut self, block: &[u8; BLAKE2B_BLOCK_SIZE], last: bool) {
+        if last {
+            self.flags[0] = u64::MAX;
+            if self.last_node {
+                self.flags[1] = u64::MAX;
+            }
+        }
+        #[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+        if dispatch::level() >= Level::Avx2 {
+            unsafe { self.chomp_avx2(block.as_ptr()) };
+            return;
+        }
+        let t = [self.written as u64, (self.written >> 64) as u64];
+        scalar::compress_b(&mut self.h, &scalar::words_b(block), t, self.flags);
+    }
+
+    #[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+    #[target_feature(enable = "avx2")]
+    unsafe fn chomp_avx2(&mut self, mp: *const u8) {
+        let m: [u64; 16] = core::ptr::read_unaligned(mp as *const [u64; 16]);
+
+        let hp = self.h.as_ptr();
+        let mut a = _mm256_loadu_si256(hp as *const _);
+        let mut b = _mm256_loadu_si256(hp.offset(4) as *const _);
+        let ff0 = a;
+        let ff1 = b;
+
+        let ivp = BLAKE2B_IV.as_ptr();
+        let mut c = _mm256_loadu_si256(ivp as *const _);
+        let mut d = _mm256_xor_si256(
+            _mm256_loadu_si256(ivp.offset(4) as *const _),
+            _mm256_loadu_si256(&raw const self.written as *const _),
+        );
+
+        for s in SIGMA {
+            let x: __m256i = msg!(m, s, 0);
+            g!(a, b, c, d, x, 32, 24);
+            let x: __m256i = msg!(m, s, 1);
+            g!(a, b, c, d, x, 16, 63);
+
+            b = _mm256_permute4x64_epi64::<{ _mm_shuffle(0, 3, 2, 1) }>(b);
+            c = _mm256_permute4x64_epi64::<{ _mm_shuffle(1, 0, 3, 2) }>(c);
+            d = _mm256_permute4x64_epi64::<{ _mm_shuffle(2, 1, 0, 3) }>(d);
+
+            let x: __m256i = msg!(m, s, 8);
+            g!(a, b, c, d, x, 32, 24);
+            let x: __m256i = msg!(m, s, 9);
+            g!(a, b, c, d, x, 16, 63);
+
+            b = _mm256_permute4x64_epi64::<{ _mm_shuffle(2, 1, 0, 3) }>(b);
+            c = _mm256_permute4x64_epi64::<{ _mm_shuffle(1, 0, 3, 2) }>(c);
+            d = _mm256_permute4x64_epi64::<{ _mm_shuffle(0, 3, 2, 1) }>(d);
+        }
+
+        let hp = self.h.as_mut_ptr();
+        _mm256_storeu_si256(hp as *mut _, _mm256_xor_si256(ff0, _mm256_xor_si256(a, c)));
+        _mm256_storeu_si256(
+            hp.offset(4) as *mut _,
+            _mm256_xor_si256(ff1, _mm256_xor_si256(b, d)),
+        );
+    }
+
+    pub fn write(&mut self, mut v: &[u8]) {
+        if v.is_empty() {
+            return;
+        }
+        if !self.buf.is_empty() && !self.buf.is_full() {
+            let rem = BLAKE2B_BLOCK_SIZE - self.buf.len();
+            let (a, b) = v.split_at(rem.min(v.len()));
+            v = b;
+            let _ = self.buf.write_all_bytes(a);
+            self.written += a.len() as u128;
+            if v.is_empty() {
+                return;
+            }
+        }
+        // the final block must be held back until finish() so it can be flagged as last, so only
+        // compress what's buffered once we know more data follows it.
+        if self.buf.is_full() {
+            self.chomp(false);
+        }
+
+        while v.len() > BLAKE2B_BLOCK_SIZE {
+            let (c, b) = v.split_at(BLAKE2B_BLOCK_SIZE);
+            v = b;
+            self.written += BLAKE2B_BLOCK_SIZE as u128;
+            if let Ok(c) = c.try_into() {
+                self.chomp_exact(c, false);
+            }
+        }
+
+        let _ = self.buf.write_all_bytes(v);
+        self.written += v.len() as u128;
+    }
+    pub fn hash(mut self, v: &[u8]) -> [u8; NN] {
+        self.write(v);
+        self.finish()
+    }
+    ///
+    /// Finishes the MAC and compares it against the expected value in constant time.
+    pub fn verify_mac(self, expected: &[u8]) -> bool {
+        constant_time_eq(&self.finish(), expected)
+    }
+    pub fn finish(mut self) -> [u8; NN] {
+        self.chomp(true);
+
+        // NN needn't be a multiple of the word size (BLAKE2b-160), so truncate bytewise.
+        let mut out = [0u8; NN];
+        for (o, v) in out
+            .iter_mut()
+            .zip(self.h.iter().flat_map(|v| v.to_le_bytes()))
+        {
+            *o = v;
+        }
+        out
+    }
+}
+impl<const NN: usize> HashDigest<BLAKE2B_BLOCK_SIZE, NN> for BLAKE2b<NN> {
+    fn finish(self) -> [u8; NN] {
+        BLAKE2b::finish(self)
+    }
+    fn hash(self, v: &[u8]) -> [u8; NN] {
+        BLAKE2b::hash(self, v)
+    }
+    fn write(&mut self, v: &[u8]) {
+        BLAKE2b::write(self, v)
+    }
+}
+impl_io_write!(const NN: usize, BLAKE2b<NN> => [u8; NN]);
+impl_digest!(BLAKE2b<20>, U20);
+impl_digest!(BLAKE2b<32>, U32);
+impl_digest!(BLAKE2b<48>, U48);
+impl_digest!(BLAKE2b<64>, U64);
+
+pub type BLAKE2b160 = BLAKE2b<20>;
+pub type BLAKE2b256 = BLAKE2b<32>;
+pub type BLAKE2b384 = BLAKE2b<48>;
+pub type BLAKE2b512 = BLAKE2b<64>;
+
+#[cfg(test)]
+mod tests {
+    use crate::blake2::{BLAKE2b160, BLAKE2b256, BLAKE2b384, BLAKE2b512, Blake2bParams};
+    use irox_tools::{assert_eq_hex_slice, hex};
+
+    #[test]
+    pub fn test0() {
+        let exp = hex!("3345524abf6bbe1809449224b5972c41790b6cf2");
+        assert_eq_hex_slice!(exp, BLAKE2b160::default().hash(b""));
+        let exp = hex!("b32811423377f52d7862286ee1a72ee540524380fda1724a6f25d7978c6fd3244a6caf0498812673c5e05ef583825100");
+        assert_eq_hex_slice!(exp, BLAKE2b384::default().hash(b""));
+    }
+
+    #[test]
+    pub fn test_abc() {
+        let exp = hex!("ba80a53f981c4d0d6a2797b69f12f6e94c212f14685ac4b74b12bb6fdbffa2d17d87c5392aab792dc252d5de4533cc9518d38aa8dbf1925ab92386edd4009923");
+        assert_eq_hex_slice!(exp, BLAKE2b512::default().hash(b"abc"));
+    }
+
+    #[test]
+    pub fn test_block_boundaries() {
+        let mut inp = [0u8; 520];
+        for (i, v) in inp.iter_mut().enumerate() {
+            *v = i as u8;
+        }
+        for len in 0..inp.len() {
+            let (msg, _) = inp.split_at(len);
+            let exp = irox_tools::hash::BLAKE2b512::default().hash(msg);
+            assert_eq_hex_slice!(exp, BLAKE2b512::default().hash(msg));
+            for step in [1, 7, 128, 129] {
+                let mut h = BLAKE2b512::default();
+                for c in msg.chunks(step) {
+                    h.write(c);
+                }
+                assert_eq_hex_slice!(exp, h.finish());
+            }
+        }
+    }
+
+    #[test]
+    pub fn test_params() {
+        let params = Blake2bParams::new()
+            .with_salt(b"saltsaltsaltsalt")
+            .with_personal(b"personalpersonal");
+        let exp = hex!("2ebf4b31a484f93aaac87539b751dfb0ad447b683e1f7eed0a243cc1cb53d5e975ef8254264580aac968e88b714a3dd223e4c61feca65578b2b5ecbf4c4e5e36");
+        assert_eq_hex_slice!(exp, BLAKE2b512::new_with_params(&[], &params).hash(b"abc"));
+
+        let params = Blake2bParams::new()
+            .with_fanout(0)
+            .with_depth(255)
+            .with_leaf_length(0x1234)
+            .with_node_offset(0x0102030405060708)
+            .with_node_depth(2)
+            .with_inner_length(64)
+            .with_last_node(true);
+        let exp = hex!("c456621bec7b7401b046211a3e13755cbd13d5cbfa679bddc7e62c158f75a5da");
+        assert_eq_hex_slice!(
+            exp,
+            BLAKE2b256::new_with_params(b"secret", &params).hash(b"abc")
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "BLAKE2b keys are at most 64 bytes")]
+    pub fn test_key_too_long() {
+        let _ = BLAKE2b512::new(&[0u8; 65]);
+    }
+}
@@ -0,0 +1,416 @@
+// SPDX-License-Identifier: MIT
+// Copyright 2025 IROX Contributors
+//
+
+//!
+//! BLAKE2bp, the 4-way parallel tree mode of BLAKE2b.  The 4 leaves are transposed across the
+//! 64-bit lanes of AVX2 registers so a full stripe (one block per leaf) is compressed at once.
+//!
+
+#![allow(clippy::indexing_slicing)]
+
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+use super::{_mm_shuffle, SIGMA};
+use super::{scalar, BLAKE2b, Blake2bParams, BLAKE2B_IV};
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+use crate::dispatch::{self, Level};
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+use core::arch::x86_64::{
+    __m256i, _mm256_add_epi64, _mm256_loadu_si256, _mm256_or_si256, _mm256_permute2x128_si256,
+    _mm256_set1_epi64x, _mm256_setr_epi8, _mm256_shuffle_epi32, _mm256_shuffle_epi8,
+    _mm256_srli_epi64, _mm256_storeu_si256, _mm256_unpackhi_epi64, _mm256_unpacklo_epi64,
+    _mm256_xor_si256,
+};
+use irox_tools::hash::HashDigest;
+
+const LANES: usize = 4;
+const BLOCK: usize = 128;
+const STRIPE: usize = LANES * BLOCK;
+/// A stripe can only be compressed once the last leaf is known to have data after it.
+const HOLDBACK: usize = STRIPE + (LANES - 1) * BLOCK;
+
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+macro_rules! load {
+    ($v:expr) => {
+        _mm256_loadu_si256($v.as_ptr() as *const _)
+    };
+}
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+macro_rules! g4 {
+    ($v:ident, $m:ident, $s:ident, $a:literal, $b:literal, $c:literal, $d:literal, $x:literal) => {
+        $v[$a] = _mm256_add_epi64(_mm256_add_epi64($v[$a], $v[$b]), $m[$s[$x]]);
+        $v[$d] = _mm256_rotri_epi64!(_mm256_xor_si256($v[$d], $v[$a]), 32);
+        $v[$c] = _mm256_add_epi64($v[$c], $v[$d]);
+        $v[$b] = _mm256_rotri_epi64!(_mm256_xor_si256($v[$b], $v[$c]), 24);
+        $v[$a] = _mm256_add_epi64(_mm256_add_epi64($v[$a], $v[$b]), $m[$s[$x + 1]]);
+        $v[$d] = _mm256_rotri_epi64!(_mm256_xor_si256($v[$d], $v[$a]), 16);
+        $v[$c] = _mm256_add_epi64($v[$c], $v[$d]);
+        $v[$b] = _mm256_rotri_epi64!(_mm256_xor_si256($v[$b], $v[$c]), 63);
+    };
+}
+
+///
+/// Transposes a 4x4 matrix of u64s, row `i` of the input becomes lane `i` of each output.
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+#[target_feature(enable = "avx2")]
+unsafe fn transpose4(r: [__m256i; 4]) -> [__m256i; 4] {
+    let [r0, r1, r2, r3] = r;
+    let t0 = _mm256_unpacklo_epi64(r0, r1);
+    let t1 = _mm256_unpackhi_epi64(r0, r1);
+    let t2 = _mm256_unpacklo_epi64(r2, r3);
+    let t3 = _mm256_unpackhi_epi64(r2, r3);
+    [
+        _mm256_permute2x128_si256::<0x20>(t0, t2),
+        _mm256_permute2x128_si256::<0x20>(t1, t3),
+        _mm256_permute2x128_si256::<0x31>(t0, t2),
+        _mm256_permute2x128_si256::<0x31>(t1, t3),
+    ]
+}
+
+///
+/// Compresses one stripe (4 consecutive 128-byte blocks, one per leaf) into the transposed leaf
+/// states `h`, where `h[word][leaf]`.  Counters and finalization flags are per-leaf.
+fn compress4(
+    h: &mut [[u64; LANES]; 8],
+    stripe: &[u8; STRIPE],
+    t: &[u64; LANES],
+    f0: &[u64; LANES],
+    f1: &[u64; LANES],
+) {
+    #[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+    if dispatch::level() >= Level::Avx2 {
+        unsafe { compress4_avx2(h, stripe, t, f0, f1) };
+        return;
+    }
+    for (leaf, block) in stripe.chunks_exact(BLOCK).enumerate() {
+        let mut state = h.map(|w| w[leaf]);
+        let m = scalar::words_b(block);
+        scalar::compress_b(&mut state, &m, [t[leaf], 0], [f0[leaf], f1[leaf]]);
+        for (w, v) in h.iter_mut().zip(state) {
+            w[leaf] = v;
+        }
+    }
+}
+
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+#[target_feature(enable = "avx2")]
+unsafe fn compress4_avx2(
+    h: &mut [[u64; LANES]; 8],
+    stripe: &[u8; STRIPE],
+    t: &[u64; LANES],
+    f0: &[u64; LANES],
+    f1: &[u64; LANES],
+) {
+    let sp = stripe.as_ptr();
+    let mut m = [_mm256_set1_epi64x(0); 16];
+    for q in 0..4 {
+        let mut rows = [_mm256_set1_epi64x(0); LANES];
+        for (i, r) in rows.iter_mut().enumerate() {
+            *r = _mm256_loadu_si256(sp.add(i * BLOCK + q * 32) as *const _);
+        }
+        let [a, b, c, d] = transpose4(rows);
+        m[q * 4] = a;
+        m[q * 4 + 1] = b;
+        m[q * 4 + 2] = c;
+        m[q * 4 + 3] = d;
+    }
+
+    let mut v = [_mm256_set1_epi64x(0); 16];
+    for i in 0..8 {
+        v[i] = load!(&h[i]);
+        v[i + 8] = _mm256_set1_epi64x(BLAKE2B_IV[i] as i64);
+    }
+    // the counter is 128 bits, but a leaf would need 2^64 bytes to carry into the high word
+    v[12] = _mm256_xor_si256(v[12], load!(t));
+    v[14] = _mm256_xor_si256(v[14], load!(f0));
+    v[15] = _mm256_xor_si256(v[15], load!(f1));
+
+    for s in SIGMA {
+        g4!(v, m, s, 0, 4, 8, 12, 0);
+        g4!(v, m, s, 1, 5, 9, 13, 2);
+        g4!(v, m, s, 2, 6, 10, 14, 4);
+        g4!(v, m, s, 3, 7, 11, 15, 6);
+        g4!(v, m, s, 0, 5, 10, 15, 8);
+        g4!(v, m, s, 1, 6, 11, 12, 10);
+        g4!(v, m, s, 2, 7, 8, 13, 12);
+        g4!(v, m, s, 3, 4, 9, 14, 14);
+    }
+
+    for i in 0..8 {
+        let out = _mm256_xor_si256(load!(&h[i]), _mm256_xor_si256(v[i], v[i + 8]));
+        _mm256_storeu_si256(h[i].as_mut_ptr() as *mut _, out);
+    }
+}
+
+fn root_params() -> Blake2bParams {
+    Blake2bParams::new()
+        .with_fanout(LANES as u8)
+        .with_depth(2)
+        .with_node_depth(1)
+        .with_inner_length(64)
+        .with_last_node(true)
+}
+fn leaf_words(nn: u8, kk: u8, leaf: usize) -> [u64; 8] {
+    Blake2bParams::new()
+        .with_fanout(LANES as u8)
+        .with_depth(2)
+        .with_node_offset(leaf as u64)
+        .with_inner_length(64)
+        .to_words(nn, kk)
+}
+
+///
+/// Streaming BLAKE2bp hasher.  Produces the same output as the reference `blake2bp`, which
+/// differs from plain [`BLAKE2b`].
+#[derive(Clone)]
+pub struct BLAKE2bp<const NN: usize> {
+    /// transposed leaf states, `h[word][leaf]`
+    h: [[u64; LANES]; 8],
+    /// number of stripes compressed so far
+    stripes: u64,
+    nn: u8,
+    kk: u8,
+    buf: [u8; STRIPE * 2],
+    buflen: usize,
+}
+impl<const NN: usize> Default for BLAKE2bp<NN> {
+    fn default() -> Self {
+        Self::new(&[])
+    }
+}
+impl<const NN: usize> BLAKE2bp<NN> {
+    ///
+    /// Creates a new hasher with the specified key, which panics like [`BLAKE2b::new`] if it's too long.
+    pub fn new(key: &[u8]) -> Self {
+        Self::new_with_length(key, NN)
+    }
+
+    ///
+    /// Like [`BLAKE2b::new_with_length`], the `length` goes into every leaf's and the root's
+    /// parameter block.
+    pub fn new_with_length(key: &[u8], length: usize) -> Self {
+        BLAKE2b::<NN>::check_params(key);
+        let nn = length.clamp(1, NN) as u8;
+        let kk = key.len() as u8;
+        let mut h = [[0u64; LANES]; 8];
+        for leaf in 0..LANES {
+            for (i, w) in leaf_words(nn, kk, leaf).into_iter().enumerate() {
+                h[i][leaf] = BLAKE2B_IV[i] ^ w;
+            }
+        }
+        let mut out = Self {
+            h,
+            stripes: 0,
+            nn,
+            kk,
+            buf: [0; STRIPE * 2],
+            buflen: 0,
+        };
+        if !key.is_empty() {
+            // every leaf starts with its own copy of the padded key block, which is exactly one
+            // stripe of 4 identical blocks.
+            for block in out.buf.chunks_exact_mut(BLOCK).take(LANES) {
+                for (b, k) in block.iter_mut().zip(key) {
+                    *b = *k;
+                }
+            }
+            out.buflen = STRIPE;
+        }
+        out
+    }
+
+    fn compress_stripe(&mut self, stripe: &[u8; STRIPE]) {
+        self.stripes += 1;
+        let t = [self.stripes * BLOCK as u64; LANES];
+        compress4(&mut self.h, stripe, &t, &[0; LANES], &[0; LANES]);
+    }
+
+    pub fn write(&mut self, mut v: &[u8]) {
+        while !v.is_empty() {
+            if self.buflen == 0 {
+                while v.len() > HOLDBACK {
+                    let (a, b) = v.split_at(STRIPE);
+                    v = b;
+                    if let Ok(stripe) = a.try_into() {
+                        self.compress_stripe(stripe);
+                    }
+                }
+            }
+            let take = (self.buf.len() - self.buflen).min(v.len());
+            let (a, b) = v.split_at(take);
+            v = b;
+            if let Some(dst) = self.buf.get_mut(self.buflen..self.buflen + take) {
+                dst.copy_from_slice(a);
+            }
+            self.buflen += take;
+            if self.buflen > HOLDBACK {
+                let mut stripe = [0u8; STRIPE];
+                stripe.copy_from_slice(&self.buf[..STRIPE]);
+                self.compress_stripe(&stripe);
+                self.buf.copy_within(STRIPE..self.buflen, 0);
+                self.buflen -= STRIPE;
+            }
+        }
+    }
+
+    pub fn hash(mut self, v: &[u8]) -> [u8; NN] {
+        self.write(v);
+        self.finish()
+    }
+
+    ///
+    /// Finalizes the 4 leaves and returns their 64-byte digests
+    fn finish_leaves(mut self) -> [[u8; 64]; LANES] {
+        // At most two stripes remain.  A leaf's block in the second stripe, if it has one, is its
+        // last - otherwise its block in the first (possibly empty) stripe is.
+        let n = self.buflen;
+        let mut first = [0u8; STRIPE];
+        let mut second = [0u8; STRIPE];
+        first[..n.min(STRIPE)].copy_from_slice(&self.buf[..n.min(STRIPE)]);
+        if n > STRIPE {
+            second[..n - STRIPE].copy_from_slice(&self.buf[STRIPE..n]);
+        }
+        let mut t = [0u64; LANES];
+        let mut f0 = [0u64; LANES];
+        let mut f1 = [0u64; LANES];
+        let mut in_second = [false; LANES];
+        let base = self.stripes * BLOCK as u64;
+        for leaf in 0..LANES {
+            let start = leaf * BLOCK;
+            in_second[leaf] = n > STRIPE + start;
+            if in_second[leaf] {
+                t[leaf] = base + BLOCK as u64;
+            } else {
+                t[leaf] = base + n.saturating_sub(start).min(BLOCK) as u64;
+                f0[leaf] = u64::MAX;
+                if leaf == LANES - 1 {
+                    f1[leaf] = u64::MAX;
+                }
+            }
+        }
+        compress4(&mut self.h, &first, &t, &f0, &f1);
+
+        if in_second.iter().any(|v| *v) {
+            let saved = self.h;
+            for leaf in 0..LANES {
+                let start = leaf * BLOCK;
+                t[leaf] = base + BLOCK as u64 + n.saturating_sub(STRIPE + start).min(BLOCK) as u64;
+                f0[leaf] = u64::MAX;
+                if leaf == LANES - 1 {
+                    f1[leaf] = u64::MAX;
+                }
+            }
+            compress4(&mut self.h, &second, &t, &f0, &f1);
+            for (leaf, second) in in_second.iter().enumerate() {
+                if !second {
+                    for (h, saved) in self.h.iter_mut().zip(&saved) {
+                        h[leaf] = saved[leaf];
+                    }
+                }
+            }
+        }
+
+        let mut out = [[0u8; 64]; LANES];
+        for (leaf, o) in out.iter_mut().enumerate() {
+            for (i, b) in o.chunks_exact_mut(8).enumerate() {
+                b.copy_from_slice(&self.h[i][leaf].to_le_bytes());
+            }
+        }
+        out
+    }
+
+    pub fn finish(self) -> [u8; NN] {
+        let (nn, kk) = (self.nn, self.kk);
+        let leaves = self.finish_leaves();
+        let mut root = BLAKE2b::<NN>::new_with_words(&[], root_params().to_words(nn, kk), true);
+        for leaf in &leaves {
+            root.write(leaf);
+        }
+        root.finish()
+    }
+
+    ///
+    /// One-shot hash that processes each leaf on its own thread with the single-stream SIMD
+    /// [`BLAKE2b`], rather than all leaves in lockstep on one core.  Worthwhile for large inputs.
+    #[cfg(feature = "std")]
+    pub fn hash_multithreaded(key: &[u8], data: &[u8]) -> [u8; NN] {
+        BLAKE2b::<NN>::check_params(key);
+        let kk = key.len() as u8;
+        let leaves: Vec<[u8; 64]> = std::thread::scope(|s| {
+            let handles: Vec<_> = (0..LANES)
+                .map(|leaf| {
+                    s.spawn(move || {
+                        let words = leaf_words(NN as u8, kk, leaf);
+                        let mut h = BLAKE2b::<64>::new_with_words(key, words, leaf == LANES - 1);
+                        for block in data.chunks(BLOCK).skip(leaf).step_by(LANES) {
+                            h.write(block);
+                        }
+                        h.finish()
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|h| h.join().unwrap_or_else(|e| std::panic::resume_unwind(e)))
+                .collect()
+        });
+        let mut root =
+            BLAKE2b::<NN>::new_with_words(&[], root_params().to_words(NN as u8, kk), true);
+        for leaf in &leaves {
+            root.write(leaf);
+        }
+        root.finish()
+    }
+}
+impl<const NN: usize> HashDigest<128, NN> for BLAKE2bp<NN> {
+    fn finish(self) -> [u8; NN] {
+        BLAKE2bp::finish(self)
+    }
+    fn hash(self, v: &[u8]) -> [u8; NN] {
+        BLAKE2bp::hash(self, v)
+    }
+    fn write(&mut self, v: &[u8]) {
+        BLAKE2bp::write(self, v)
+    }
+}
+impl_io_write!(const NN: usize, BLAKE2bp<NN> => [u8; NN]);
+impl_digest!(BLAKE2bp<20>, U20);
+impl_digest!(BLAKE2bp<32>, U32);
+impl_digest!(BLAKE2bp<48>, U48);
+impl_digest!(BLAKE2bp<64>, U64);
+
+pub type BLAKE2bp160 = BLAKE2bp<20>;
+pub type BLAKE2bp256 = BLAKE2bp<32>;
+pub type BLAKE2bp384 = BLAKE2bp<48>;
+pub type BLAKE2bp512 = BLAKE2bp<64>;
+
+#[cfg(test)]
+mod tests {
+    use crate::blake2::{BLAKE2bp, BLAKE2bp512};
+    use irox_tools::assert_eq_hex_slice;
+
+    #[test]
+    pub fn test_streaming_vs_threaded() {
+        let mut inp = vec![0u8; 3000];
+        for (i, v) in inp.iter_mut().enumerate() {
+            *v = (i as u8).wrapping_mul(13).wrapping_add(1);
+        }
+        for key in [&b""[..], b"0123456789abcdef"] {
+            for len in (0..inp.len())
+                .step_by(37)
+                .chain([511, 512, 513, 896, 897, 1024])
+            {
+                let msg = &inp[..len];
+                let exp = BLAKE2bp::<64>::hash_multithreaded(key, msg);
+                assert_eq_hex_slice!(exp, BLAKE2bp512::new(key).hash(msg));
+                for step in [1, 127, 128, 500, 512] {
+                    let mut h = BLAKE2bp512::new(key);
+                    for c in msg.chunks(step) {
+                        h.write(c);
+                    }
+                    assert_eq_hex_slice!(exp, h.finish());
+                }
+            }
+        }
+    }
+}
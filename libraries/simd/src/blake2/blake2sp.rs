@@ -0,0 +1,470 @@
+// SPDX-License-Identifier: MIT
+// Copyright 2025 IROX Contributors
+//
+
+//!
+//! BLAKE2sp, the 8-way parallel tree mode of BLAKE2s.  The 8 leaves are transposed across the
+//! 32-bit lanes of AVX2 registers so a full stripe (one block per leaf) is compressed at once.
+//!
+
+#![allow(clippy::indexing_slicing)]
+
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+use super::SIGMA;
+use super::{scalar, BLAKE2s, Blake2sParams, BLAKE2S_IV};
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+use crate::dispatch::{self, Level};
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+use core::arch::x86_64::{
+    __m256i, _mm256_add_epi32, _mm256_blendv_epi8, _mm256_loadu_si256, _mm256_or_si256,
+    _mm256_permute2x128_si256, _mm256_set1_epi32, _mm256_setr_epi8, _mm256_shuffle_epi8,
+    _mm256_slli_epi32, _mm256_srli_epi32, _mm256_storeu_si256, _mm256_unpackhi_epi32,
+    _mm256_unpackhi_epi64, _mm256_unpacklo_epi32, _mm256_unpacklo_epi64, _mm256_xor_si256,
+};
+use irox_tools::hash::HashDigest;
+
+const LANES: usize = 8;
+const BLOCK: usize = 64;
+const STRIPE: usize = LANES * BLOCK;
+/// A stripe can only be compressed once the last leaf is known to have data after it.
+const HOLDBACK: usize = STRIPE + (LANES - 1) * BLOCK;
+const ALL: [u32; LANES] = [u32::MAX; LANES];
+
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+macro_rules! rotr8x32 {
+    ($r:expr, 16) => {
+        _mm256_shuffle_epi8(
+            $r,
+            _mm256_setr_epi8(
+                2, 3, 0, 1, 6, 7, 4, 5, 10, 11, 8, 9, 14, 15, 12, 13, 2, 3, 0, 1, 6, 7, 4, 5, 10,
+                11, 8, 9, 14, 15, 12, 13,
+            ),
+        )
+    };
+    ($r:expr, 8) => {
+        _mm256_shuffle_epi8(
+            $r,
+            _mm256_setr_epi8(
+                1, 2, 3, 0, 5, 6, 7, 4, 9, 10, 11, 8, 13, 14, 15, 12, 1, 2, 3, 0, 5, 6, 7, 4, 9,
+                10, 11, 8, 13, 14, 15, 12,
+            ),
+        )
+    };
+    ($r:expr, $c:literal) => {
+        _mm256_or_si256(
+            _mm256_srli_epi32::<$c>($r),
+            _mm256_slli_epi32::<{ 32 - $c }>($r),
+        )
+    };
+}
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+macro_rules! load {
+    ($v:expr) => {
+        _mm256_loadu_si256($v.as_ptr() as *const _)
+    };
+}
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+macro_rules! g8 {
+    ($v:ident, $m:ident, $s:ident, $a:literal, $b:literal, $c:literal, $d:literal, $x:literal) => {
+        $v[$a] = _mm256_add_epi32(_mm256_add_epi32($v[$a], $v[$b]), $m[$s[$x]]);
+        $v[$d] = rotr8x32!(_mm256_xor_si256($v[$d], $v[$a]), 16);
+        $v[$c] = _mm256_add_epi32($v[$c], $v[$d]);
+        $v[$b] = rotr8x32!(_mm256_xor_si256($v[$b], $v[$c]), 12);
+        $v[$a] = _mm256_add_epi32(_mm256_add_epi32($v[$a], $v[$b]), $m[$s[$x + 1]]);
+        $v[$d] = rotr8x32!(_mm256_xor_si256($v[$d], $v[$a]), 8);
+        $v[$c] = _mm256_add_epi32($v[$c], $v[$d]);
+        $v[$b] = rotr8x32!(_mm256_xor_si256($v[$b], $v[$c]), 7);
+    };
+}
+
+///
+/// Transposes an 8x8 matrix of u32s, row `i` of the input becomes lane `i` of each output.
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+#[target_feature(enable = "avx2")]
+pub(crate) unsafe fn transpose8(r: [__m256i; 8]) -> [__m256i; 8] {
+    let [r0, r1, r2, r3, r4, r5, r6, r7] = r;
+    let t0 = _mm256_unpacklo_epi32(r0, r1);
+    let t1 = _mm256_unpackhi_epi32(r0, r1);
+    let t2 = _mm256_unpacklo_epi32(r2, r3);
+    let t3 = _mm256_unpackhi_epi32(r2, r3);
+    let t4 = _mm256_unpacklo_epi32(r4, r5);
+    let t5 = _mm256_unpackhi_epi32(r4, r5);
+    let t6 = _mm256_unpacklo_epi32(r6, r7);
+    let t7 = _mm256_unpackhi_epi32(r6, r7);
+    let u0 = _mm256_unpacklo_epi64(t0, t2);
+    let u1 = _mm256_unpackhi_epi64(t0, t2);
+    let u2 = _mm256_unpacklo_epi64(t1, t3);
+    let u3 = _mm256_unpackhi_epi64(t1, t3);
+    let u4 = _mm256_unpacklo_epi64(t4, t6);
+    let u5 = _mm256_unpackhi_epi64(t4, t6);
+    let u6 = _mm256_unpacklo_epi64(t5, t7);
+    let u7 = _mm256_unpackhi_epi64(t5, t7);
+    [
+        _mm256_permute2x128_si256::<0x20>(u0, u4),
+        _mm256_permute2x128_si256::<0x20>(u1, u5),
+        _mm256_permute2x128_si256::<0x20>(u2, u6),
+        _mm256_permute2x128_si256::<0x20>(u3, u7),
+        _mm256_permute2x128_si256::<0x31>(u0, u4),
+        _mm256_permute2x128_si256::<0x31>(u1, u5),
+        _mm256_permute2x128_si256::<0x31>(u2, u6),
+        _mm256_permute2x128_si256::<0x31>(u3, u7),
+    ]
+}
+
+///
+/// Compresses one stripe (8 consecutive 64-byte blocks, one per leaf) into the transposed leaf
+/// states `h`, where `h[word][leaf]`.  Counters and finalization flags are per-leaf, and leaves
+/// whose `active` mask is zero are left unchanged.
+pub(crate) fn compress8(
+    h: &mut [[u32; LANES]; 8],
+    stripe: &[u8; STRIPE],
+    t: &[u64; LANES],
+    f0: &[u32; LANES],
+    f1: &[u32; LANES],
+    active: &[u32; LANES],
+) {
+    #[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+    if dispatch::level() >= Level::Avx2 {
+        unsafe { compress8_avx2(h, stripe, t, f0, f1, active) };
+        return;
+    }
+    for (leaf, block) in stripe.chunks_exact(BLOCK).enumerate() {
+        if active[leaf] == 0 {
+            continue;
+        }
+        let mut state = h.map(|w| w[leaf]);
+        let tt = [t[leaf] as u32, (t[leaf] >> 32) as u32];
+        let m = scalar::words_s(block);
+        scalar::compress_s(&mut state, &m, tt, [f0[leaf], f1[leaf]]);
+        for (w, v) in h.iter_mut().zip(state) {
+            w[leaf] = v;
+        }
+    }
+}
+
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+#[target_feature(enable = "avx2")]
+unsafe fn compress8_avx2(
+    h: &mut [[u32; LANES]; 8],
+    stripe: &[u8; STRIPE],
+    t: &[u64; LANES],
+    f0: &[u32; LANES],
+    f1: &[u32; LANES],
+    active: &[u32; LANES],
+) {
+    let sp = stripe.as_ptr();
+    let mut lo = [_mm256_set1_epi32(0); 8];
+    let mut hi = [_mm256_set1_epi32(0); 8];
+    for i in 0..LANES {
+        lo[i] = _mm256_loadu_si256(sp.add(i * BLOCK) as *const _);
+        hi[i] = _mm256_loadu_si256(sp.add(i * BLOCK + 32) as *const _);
+    }
+    let [m0, m1, m2, m3, m4, m5, m6, m7] = transpose8(lo);
+    let [m8, m9, m10, m11, m12, m13, m14, m15] = transpose8(hi);
+    let m = [
+        m0, m1, m2, m3, m4, m5, m6, m7, m8, m9, m10, m11, m12, m13, m14, m15,
+    ];
+
+    let t0 = t.map(|t| t as u32);
+    let t1 = t.map(|t| (t >> 32) as u32);
+    let mut v = [_mm256_set1_epi32(0); 16];
+    for i in 0..8 {
+        v[i] = load!(&h[i]);
+        v[i + 8] = _mm256_set1_epi32(BLAKE2S_IV[i] as i32);
+    }
+    v[12] = _mm256_xor_si256(v[12], load!(&t0));
+    v[13] = _mm256_xor_si256(v[13], load!(&t1));
+    v[14] = _mm256_xor_si256(v[14], load!(f0));
+    v[15] = _mm256_xor_si256(v[15], load!(f1));
+
+    for s in SIGMA.iter().take(10) {
+        g8!(v, m, s, 0, 4, 8, 12, 0);
+        g8!(v, m, s, 1, 5, 9, 13, 2);
+        g8!(v, m, s, 2, 6, 10, 14, 4);
+        g8!(v, m, s, 3, 7, 11, 15, 6);
+        g8!(v, m, s, 0, 5, 10, 15, 8);
+        g8!(v, m, s, 1, 6, 11, 12, 10);
+        g8!(v, m, s, 2, 7, 8, 13, 12);
+        g8!(v, m, s, 3, 4, 9, 14, 14);
+    }
+
+    let active = load!(active);
+    for i in 0..8 {
+        let prev = load!(&h[i]);
+        let out = _mm256_xor_si256(prev, _mm256_xor_si256(v[i], v[i + 8]));
+        let out = _mm256_blendv_epi8(prev, out, active);
+        _mm256_storeu_si256(h[i].as_mut_ptr() as *mut _, out);
+    }
+}
+
+fn root_params() -> Blake2sParams {
+    Blake2sParams::new()
+        .with_fanout(LANES as u8)
+        .with_depth(2)
+        .with_node_depth(1)
+        .with_inner_length(32)
+        .with_last_node(true)
+}
+fn leaf_words(nn: u8, kk: u8, leaf: usize) -> [u32; 8] {
+    Blake2sParams::new()
+        .with_fanout(LANES as u8)
+        .with_depth(2)
+        .with_node_offset(leaf as u64)
+        .with_inner_length(32)
+        .to_words(nn, kk)
+}
+
+///
+/// Streaming BLAKE2sp hasher.  Produces the same output as the reference `blake2sp`, which
+/// differs from plain [`BLAKE2s`].
+#[derive(Clone)]
+pub struct BLAKE2sp<const NN: usize> {
+    /// transposed leaf states, `h[word][leaf]`
+    h: [[u32; LANES]; 8],
+    /// number of stripes compressed so far
+    stripes: u64,
+    nn: u8,
+    kk: u8,
+    buf: [u8; STRIPE * 2],
+    buflen: usize,
+}
+impl<const NN: usize> Default for BLAKE2sp<NN> {
+    fn default() -> Self {
+        Self::new(&[])
+    }
+}
+impl<const NN: usize> BLAKE2sp<NN> {
+    ///
+    /// Creates a new hasher with the specified key, which panics like [`BLAKE2s::new`] if it's too long.
+    pub fn new(key: &[u8]) -> Self {
+        Self::new_with_length(key, NN)
+    }
+
+    ///
+    /// Like [`BLAKE2s::new_with_length`], the `length` goes into every leaf's and the root's
+    /// parameter block.
+    pub fn new_with_length(key: &[u8], length: usize) -> Self {
+        BLAKE2s::<NN>::check_params(key);
+        let nn = length.clamp(1, NN) as u8;
+        let kk = key.len() as u8;
+        let mut h = [[0u32; LANES]; 8];
+        for leaf in 0..LANES {
+            for (i, w) in leaf_words(nn, kk, leaf).into_iter().enumerate() {
+                h[i][leaf] = BLAKE2S_IV[i] ^ w;
+            }
+        }
+        let mut out = Self {
+            h,
+            stripes: 0,
+            nn,
+            kk,
+            buf: [0; STRIPE * 2],
+            buflen: 0,
+        };
+        if !key.is_empty() {
+            // every leaf starts with its own copy of the padded key block, which is exactly one
+            // stripe of 8 identical blocks.
+            for block in out.buf.chunks_exact_mut(BLOCK).take(LANES) {
+                for (b, k) in block.iter_mut().zip(key) {
+                    *b = *k;
+                }
+            }
+            out.buflen = STRIPE;
+        }
+        out
+    }
+
+    fn compress_stripe(&mut self, stripe: &[u8; STRIPE]) {
+        self.stripes += 1;
+        let t = [self.stripes * BLOCK as u64; LANES];
+        compress8(&mut self.h, stripe, &t, &[0; LANES], &[0; LANES], &ALL);
+    }
+
+    pub fn write(&mut self, mut v: &[u8]) {
+        while !v.is_empty() {
+            if self.buflen == 0 {
+                while v.len() > HOLDBACK {
+                    let (a, b) = v.split_at(STRIPE);
+                    v = b;
+                    if let Ok(stripe) = a.try_into() {
+                        self.compress_stripe(stripe);
+                    }
+                }
+            }
+            let take = (self.buf.len() - self.buflen).min(v.len());
+            let (a, b) = v.split_at(take);
+            v = b;
+            if let Some(dst) = self.buf.get_mut(self.buflen..self.buflen + take) {
+                dst.copy_from_slice(a);
+            }
+            self.buflen += take;
+            if self.buflen > HOLDBACK {
+                let mut stripe = [0u8; STRIPE];
+                stripe.copy_from_slice(&self.buf[..STRIPE]);
+                self.compress_stripe(&stripe);
+                self.buf.copy_within(STRIPE..self.buflen, 0);
+                self.buflen -= STRIPE;
+            }
+        }
+    }
+
+    pub fn hash(mut self, v: &[u8]) -> [u8; NN] {
+        self.write(v);
+        self.finish()
+    }
+
+    ///
+    /// Finalizes the 8 leaves and returns their 32-byte digests
+    fn finish_leaves(mut self) -> [[u8; 32]; LANES] {
+        // At most two stripes remain.  A leaf's block in the second stripe, if it has one, is its
+        // last - otherwise its block in the first (possibly empty) stripe is.
+        let n = self.buflen;
+        let mut first = [0u8; STRIPE];
+        let mut second = [0u8; STRIPE];
+        first[..n.min(STRIPE)].copy_from_slice(&self.buf[..n.min(STRIPE)]);
+        if n > STRIPE {
+            second[..n - STRIPE].copy_from_slice(&self.buf[STRIPE..n]);
+        }
+        let mut t = [0u64; LANES];
+        let mut f0 = [0u32; LANES];
+        let mut f1 = [0u32; LANES];
+        let mut in_second = [false; LANES];
+        let base = self.stripes * BLOCK as u64;
+        for leaf in 0..LANES {
+            let start = leaf * BLOCK;
+            in_second[leaf] = n > STRIPE + start;
+            if in_second[leaf] {
+                t[leaf] = base + BLOCK as u64;
+            } else {
+                t[leaf] = base + n.saturating_sub(start).min(BLOCK) as u64;
+                f0[leaf] = u32::MAX;
+                if leaf == LANES - 1 {
+                    f1[leaf] = u32::MAX;
+                }
+            }
+        }
+        compress8(&mut self.h, &first, &t, &f0, &f1, &ALL);
+
+        if in_second.iter().any(|v| *v) {
+            for leaf in 0..LANES {
+                let start = leaf * BLOCK;
+                t[leaf] = base + BLOCK as u64 + n.saturating_sub(STRIPE + start).min(BLOCK) as u64;
+                f0[leaf] = u32::MAX;
+                if leaf == LANES - 1 {
+                    f1[leaf] = u32::MAX;
+                }
+            }
+            let active = in_second.map(|v| if v { u32::MAX } else { 0 });
+            compress8(&mut self.h, &second, &t, &f0, &f1, &active);
+        }
+
+        let mut out = [[0u8; 32]; LANES];
+        for (leaf, o) in out.iter_mut().enumerate() {
+            for (i, b) in o.chunks_exact_mut(4).enumerate() {
+                b.copy_from_slice(&self.h[i][leaf].to_le_bytes());
+            }
+        }
+        out
+    }
+
+    pub fn finish(self) -> [u8; NN] {
+        let (nn, kk) = (self.nn, self.kk);
+        let leaves = self.finish_leaves();
+        let mut root = BLAKE2s::<NN>::new_with_words(&[], root_params().to_words(nn, kk), true);
+        for leaf in &leaves {
+            root.write(leaf);
+        }
+        root.finish()
+    }
+
+    ///
+    /// One-shot hash that processes each leaf on its own thread with the single-stream SIMD
+    /// [`BLAKE2s`], rather than all leaves in lockstep on one core.  Worthwhile for large inputs.
+    #[cfg(feature = "std")]
+    pub fn hash_multithreaded(key: &[u8], data: &[u8]) -> [u8; NN] {
+        BLAKE2s::<NN>::check_params(key);
+        let kk = key.len() as u8;
+        let leaves: Vec<[u8; 32]> = std::thread::scope(|s| {
+            let handles: Vec<_> = (0..LANES)
+                .map(|leaf| {
+                    s.spawn(move || {
+                        let words = leaf_words(NN as u8, kk, leaf);
+                        let mut h = BLAKE2s::<32>::new_with_words(key, words, leaf == LANES - 1);
+                        for block in data.chunks(BLOCK).skip(leaf).step_by(LANES) {
+                            h.write(block);
+                        }
+                        h.finish()
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|h| h.join().unwrap_or_else(|e| std::panic::resume_unwind(e)))
+                .collect()
+        });
+        let mut root =
+            BLAKE2s::<NN>::new_with_words(&[], root_params().to_words(NN as u8, kk), true);
+        for leaf in &leaves {
+            root.write(leaf);
+        }
+        root.finish()
+    }
+}
+impl<const NN: usize> HashDigest<64, NN> for BLAKE2sp<NN> {
+    fn finish(self) -> [u8; NN] {
+        BLAKE2sp::finish(self)
+    }
+    fn hash(self, v: &[u8]) -> [u8; NN] {
+        BLAKE2sp::hash(self, v)
+    }
+    fn write(&mut self, v: &[u8]) {
+        BLAKE2sp::write(self, v)
+    }
+}
+impl_io_write!(const NN: usize, BLAKE2sp<NN> => [u8; NN]);
+impl_digest!(BLAKE2sp<16>, U16);
+impl_digest!(BLAKE2sp<20>, U20);
+impl_digest!(BLAKE2sp<28>, U28);
+impl_digest!(BLAKE2sp<32>, U32);
+
+pub type BLAKE2sp128 = BLAKE2sp<16>;
+pub type BLAKE2sp160 = BLAKE2sp<20>;
+pub type BLAKE2sp224 = BLAKE2sp<28>;
+pub type BLAKE2sp256 = BLAKE2sp<32>;
+
+#[cfg(test)]
+mod tests {
+    use crate::blake2::{BLAKE2sp, BLAKE2sp256};
+    use irox_tools::assert_eq_hex_slice;
+
+    #[test]
+    pub fn test_streaming_vs_threaded() {
+        let mut inp = vec![0u8; 3000];
+        for (i, v) in inp.iter_mut().enumerate() {
+            *v = (i as u8).wrapping_mul(13).wrapping_add(1);
+        }
+        for key in [&b""[..], b"0123456789abcdef"] {
+            for len in (0..inp.len())
+                .step_by(37)
+                .chain([511, 512, 513, 960, 961, 1024])
+            {
+                let msg = &inp[..len];
+                let exp = BLAKE2sp::<32>::hash_multithreaded(key, msg);
+                assert_eq_hex_slice!(exp, BLAKE2sp256::new(key).hash(msg));
+                for step in [1, 63, 64, 500, 512] {
+                    let mut h = BLAKE2sp256::new(key);
+                    for c in msg.chunks(step) {
+                        h.write(c);
+                    }
+                    assert_eq_hex_slice!(exp, h.finish());
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "BLAKE2s keys are at most 32 bytes")]
+    pub fn test_key_too_long() {
+        let _ = BLAKE2sp256::new(&[0u8; 33]);
+    }
+}
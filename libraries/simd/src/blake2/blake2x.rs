@@ -0,0 +1,275 @@
+// SPDX-License-Identifier: MIT
+// Copyright 2025 IROX Contributors
+//
+
+//!
+//! BLAKE2X, the extendable-output variants BLAKE2Xs and BLAKE2Xb.  The input is hashed once into
+//! a root digest `H0` with the output length in the parameter block, then each block of output
+//! is an independent hash of `H0` with the block's index as the node offset.
+//!
+
+use super::{BLAKE2b, BLAKE2s, Blake2bParams, Blake2sParams};
+
+/// Output length for a BLAKE2Xs whose length isn't known up front, up to 2^32 blocks of 32 bytes
+pub const BLAKE2XS_UNKNOWN_LENGTH: u16 = u16::MAX;
+/// Output length for a BLAKE2Xb whose length isn't known up front, up to 2^32 blocks of 64 bytes
+pub const BLAKE2XB_UNKNOWN_LENGTH: u32 = u32::MAX;
+
+///
+/// Streaming BLAKE2Xs hasher, producing up to 65534 bytes of output, or an effectively unlimited
+/// amount with [`BLAKE2XS_UNKNOWN_LENGTH`].
+#[derive(Clone)]
+pub struct BLAKE2Xs {
+    root: BLAKE2s<32>,
+    params: Blake2sParams,
+    xof_length: u16,
+}
+impl BLAKE2Xs {
+    pub fn new(key: &[u8], xof_length: u16) -> Self {
+        Self::new_with_params(key, xof_length, &Blake2sParams::default())
+    }
+
+    ///
+    /// Creates a new hasher with the specified key (up to 32 bytes).  The salt and
+    /// personalization are taken from `params`, the rest of the parameter block is set by BLAKE2X.
+    pub fn new_with_params(key: &[u8], xof_length: u16, params: &Blake2sParams) -> Self {
+        let params = params.with_xof_length(xof_length);
+        Self {
+            root: BLAKE2s::new_with_params(key, &params),
+            params,
+            xof_length,
+        }
+    }
+
+    pub fn write(&mut self, v: &[u8]) {
+        self.root.write(v);
+    }
+
+    ///
+    /// Finishes the root hash and returns a reader for the output.
+    #[must_use]
+    pub fn finish_xof(self) -> XofReader {
+        let mut h0 = [0u8; 64];
+        h0[..32].copy_from_slice(&self.root.finish());
+        let length = if self.xof_length == BLAKE2XS_UNKNOWN_LENGTH {
+            None
+        } else {
+            Some(u64::from(self.xof_length))
+        };
+        XofReader::new(XofParams::S(self.params), h0, length)
+    }
+}
+impl_io_write!(BLAKE2Xs);
+
+///
+/// Streaming BLAKE2Xb hasher, producing up to 2^32-2 bytes of output, or an effectively unlimited
+/// amount with [`BLAKE2XB_UNKNOWN_LENGTH`].
+#[derive(Clone)]
+pub struct BLAKE2Xb {
+    root: BLAKE2b<64>,
+    params: Blake2bParams,
+    xof_length: u32,
+}
+impl BLAKE2Xb {
+    pub fn new(key: &[u8], xof_length: u32) -> Self {
+        Self::new_with_params(key, xof_length, &Blake2bParams::default())
+    }
+
+    ///
+    /// Creates a new hasher with the specified key (up to 64 bytes).  The salt and
+    /// personalization are taken from `params`, the rest of the parameter block is set by BLAKE2X.
+    pub fn new_with_params(key: &[u8], xof_length: u32, params: &Blake2bParams) -> Self {
+        let params = params.with_xof_length(xof_length);
+        Self {
+            root: BLAKE2b::new_with_params(key, &params),
+            params,
+            xof_length,
+        }
+    }
+
+    pub fn write(&mut self, v: &[u8]) {
+        self.root.write(v);
+    }
+
+    ///
+    /// Finishes the root hash and returns a reader for the output.
+    #[must_use]
+    pub fn finish_xof(self) -> XofReader {
+        let h0 = self.root.finish();
+        let length = if self.xof_length == BLAKE2XB_UNKNOWN_LENGTH {
+            None
+        } else {
+            Some(u64::from(self.xof_length))
+        };
+        XofReader::new(XofParams::B(self.params), h0, length)
+    }
+}
+impl_io_write!(BLAKE2Xb);
+
+#[derive(Clone)]
+enum XofParams {
+    S(Blake2sParams),
+    B(Blake2bParams),
+}
+
+///
+/// Streams the output of a finished [`BLAKE2Xs`] or [`BLAKE2Xb`], one output block at a time.
+#[derive(Clone)]
+pub struct XofReader {
+    params: XofParams,
+    /// the root digest, only the first 32 bytes for BLAKE2Xs
+    h0: [u8; 64],
+    /// the digest length of the underlying hash, and so of each output block
+    hn: usize,
+    /// total bytes of output
+    length: u64,
+    known_length: bool,
+    position: u64,
+    block: [u8; 64],
+    block_pos: usize,
+    block_len: usize,
+}
+impl XofReader {
+    fn new(params: XofParams, h0: [u8; 64], length: Option<u64>) -> Self {
+        let hn = match params {
+            XofParams::S(_) => 32,
+            XofParams::B(_) => 64,
+        };
+        Self {
+            params,
+            h0,
+            hn,
+            length: length.unwrap_or(hn as u64 * (1 << 32)),
+            known_length: length.is_some(),
+            position: 0,
+            block: [0; 64],
+            block_pos: 0,
+            block_len: 0,
+        }
+    }
+
+    ///
+    /// Hashes output block `node`, `nn` is the digest length written into its parameter block.
+    fn output_block(&self, node: u32, nn: usize) -> [u8; 64] {
+        let mut out = [0u8; 64];
+        match &self.params {
+            XofParams::S(p) => {
+                let words = p
+                    .with_fanout(0)
+                    .with_depth(0)
+                    .with_leaf_length(32)
+                    .with_node_offset(u64::from(node))
+                    .with_node_depth(0)
+                    .with_inner_length(32)
+                    .with_last_node(false)
+                    .to_words(nn as u8, 0);
+                let mut h = BLAKE2s::<32>::new_with_words(&[], words, false);
+                h.write(&self.h0[..32]);
+                out[..32].copy_from_slice(&h.finish());
+            }
+            XofParams::B(p) => {
+                let words = p
+                    .with_fanout(0)
+                    .with_depth(0)
+                    .with_leaf_length(64)
+                    .with_node_offset(u64::from(node))
+                    .with_node_depth(0)
+                    .with_inner_length(64)
+                    .with_last_node(false)
+                    .to_words(nn as u8, 0);
+                let mut h = BLAKE2b::<64>::new_with_words(&[], words, false);
+                h.write(&self.h0);
+                out = h.finish();
+            }
+        }
+        out
+    }
+
+    ///
+    /// Fills `out` with the next bytes of output, returning how many were written.  This is less
+    /// than `out.len()` only once the end of a known-length output is reached.
+    pub fn fill(&mut self, out: &mut [u8]) -> usize {
+        let mut written = 0;
+        while written < out.len() && self.position < self.length {
+            if self.block_pos == self.block_len {
+                let node = self.position / self.hn as u64;
+                let nn = if self.known_length {
+                    (self.length - self.position).min(self.hn as u64) as usize
+                } else {
+                    self.hn
+                };
+                self.block = self.output_block(node as u32, nn);
+                self.block_pos = 0;
+                self.block_len = nn;
+            }
+            let take = (self.block_len - self.block_pos).min(out.len() - written);
+            if let (Some(dst), Some(src)) = (
+                out.get_mut(written..written + take),
+                self.block.get(self.block_pos..self.block_pos + take),
+            ) {
+                dst.copy_from_slice(src);
+            }
+            self.block_pos += take;
+            self.position += take as u64;
+            written += take;
+        }
+        written
+    }
+
+    ///
+    /// The number of bytes of output produced so far
+    #[must_use]
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::blake2::{
+        BLAKE2Xb, BLAKE2Xs, Blake2sParams, BLAKE2XB_UNKNOWN_LENGTH, BLAKE2XS_UNKNOWN_LENGTH,
+    };
+    use irox_tools::{assert_eq_hex_slice, hex};
+
+    #[test]
+    pub fn test_unknown_length() {
+        let mut h = BLAKE2Xs::new(&[], BLAKE2XS_UNKNOWN_LENGTH);
+        h.write(b"abc");
+        let mut out = [0u8; 100];
+        assert_eq!(100, h.finish_xof().fill(&mut out));
+        assert_eq_hex_slice!(hex!("bf5c4f309fde8a62195bc8364ceea81e84eb9330579270c5737b9300085b61495576fef12a5cfa717343bff2bb2461d733fc71c0c51a60392e4d2f84218b1351e28d85cc8981eeffb4c8b952f91563f50ff8a4927a771832fe94208d09520bd6b6b3fd31"), out);
+
+        let mut h = BLAKE2Xb::new(&[], BLAKE2XB_UNKNOWN_LENGTH);
+        h.write(b"abc");
+        assert_eq!(100, h.finish_xof().fill(&mut out));
+        assert_eq_hex_slice!(hex!("ae080c1efbcf7f60ed52a04161d02b7ee63bed362534f0661da02c6e40cd208946d066b86b3dff620e57acea9cd72d3056cf6cb0c18341452a17ce2cced67b702669bf0bed358c1b708e97de2533b294cdd5e9e229678be36399b5b28d6541c4bc4e3079"), out);
+    }
+
+    #[test]
+    pub fn test_known_length_reads() {
+        let mut h = BLAKE2Xs::new(&[], 100);
+        h.write(b"abc");
+        let mut rdr = h.finish_xof();
+        let mut out = [0u8; 120];
+        let mut n = 0;
+        for c in out.chunks_mut(7) {
+            n += rdr.fill(c);
+        }
+        assert_eq!(100, n);
+        assert_eq!(100, rdr.position());
+        assert_eq_hex_slice!(hex!("afaabbf8422df9e7ccc56388e509db4dc68ee81a7c74a49d87cfd6a7aeac1fab1349239e468af27d468ef68ba1ac35221b66a9675a994408ab826a67a4e5d90dd7a7ab030cd52dc38fb6e4c0c5676a7f4931ef35c6bee442a5eafcf234e7f3cd9cea7a0d"), out[..100]);
+        assert_eq!([0u8; 20], out[100..]);
+    }
+
+    #[test]
+    pub fn test_salt_changes_output() {
+        let params = Blake2sParams::new().with_salt(b"saltsalt");
+        let mut a = [0u8; 40];
+        let mut b = [0u8; 40];
+        BLAKE2Xs::new(&[], 40).finish_xof().fill(&mut a);
+        BLAKE2Xs::new_with_params(&[], 40, &params)
+            .finish_xof()
+            .fill(&mut b);
+        assert_ne!(a, b);
+    }
+}
@@ -0,0 +1,128 @@
+// SPDX-License-Identifier: MIT
+// Copyright 2025 IROX Contributors
+//
+
+//!
+//! Multi-buffer BLAKE2s: hashes 8 independent messages per pass, one per 32-bit AVX2 lane, for
+//! workloads with many small inputs that a single stream can't spread across the lanes.
+//!
+
+#![allow(clippy::indexing_slicing)]
+
+use super::blake2sp::compress8;
+use super::{BLAKE2s, Blake2sParams, BLAKE2S_IV};
+use alloc::vec::Vec;
+
+const LANES: usize = 8;
+const BLOCK: usize = 64;
+
+impl<const NN: usize> BLAKE2s<NN> {
+    ///
+    /// Hashes each of `inputs` independently, identical to calling [`BLAKE2s::hash`] on each.
+    pub fn hash_many(inputs: &[&[u8]]) -> Vec<[u8; NN]> {
+        Self::hash_many_keyed(&[], inputs)
+    }
+
+    ///
+    /// Keyed (MAC) variant of [`BLAKE2s::hash_many`], every input is hashed with the same key.
+    pub fn hash_many_keyed(key: &[u8], inputs: &[&[u8]]) -> Vec<[u8; NN]> {
+        Self::check_params(key);
+        let words = Blake2sParams::default().to_words(NN as u8, key.len() as u8);
+        let mut out = Vec::with_capacity(inputs.len());
+        for group in inputs.chunks(LANES) {
+            let mut lanes: [&[u8]; LANES] = [&[]; LANES];
+            for (l, g) in lanes.iter_mut().zip(group) {
+                *l = g;
+            }
+            let digests = hash8::<NN>(&words, key, &lanes);
+            out.extend(digests.into_iter().take(group.len()));
+        }
+        out
+    }
+}
+
+///
+/// Hashes the 8 messages in lockstep.  Each lane runs for as many blocks as its own message
+/// needs, lanes that have already finished are masked out of the remaining compressions.
+fn hash8<const NN: usize>(
+    words: &[u32; 8],
+    key: &[u8],
+    lanes: &[&[u8]; LANES],
+) -> [[u8; NN]; LANES] {
+    let key_blocks = usize::from(!key.is_empty());
+    let total = lanes.map(|l| (key_blocks * BLOCK + l.len()) as u64);
+    let nblocks = total.map(|t| (t as usize).div_ceil(BLOCK).max(1));
+    let max_blocks = nblocks.iter().copied().max().unwrap_or_default();
+
+    let mut h = [[0u32; LANES]; 8];
+    for (h, (iv, p)) in h.iter_mut().zip(BLAKE2S_IV.iter().zip(words)) {
+        *h = [iv ^ p; LANES];
+    }
+
+    let mut stripe = [0u8; LANES * BLOCK];
+    let mut t = [0u64; LANES];
+    let mut f0 = [0u32; LANES];
+    let mut active = [0u32; LANES];
+    for blk in 0..max_blocks {
+        for (lane, msg) in lanes.iter().enumerate() {
+            let dst = &mut stripe[lane * BLOCK..(lane + 1) * BLOCK];
+            dst.fill(0);
+            let src = if blk < key_blocks {
+                key
+            } else {
+                let start = ((blk - key_blocks) * BLOCK).min(msg.len());
+                &msg[start..msg.len().min(start + BLOCK)]
+            };
+            dst[..src.len()].copy_from_slice(src);
+
+            t[lane] = ((blk as u64 + 1) * BLOCK as u64).min(total[lane]);
+            f0[lane] = if blk + 1 == nblocks[lane] {
+                u32::MAX
+            } else {
+                0
+            };
+            active[lane] = if blk < nblocks[lane] { u32::MAX } else { 0 };
+        }
+        compress8(&mut h, &stripe, &t, &f0, &[0; LANES], &active);
+    }
+
+    let mut out = [[0u8; NN]; LANES];
+    for (lane, o) in out.iter_mut().enumerate() {
+        let mut full = [0u8; 32];
+        for (b, w) in full.chunks_exact_mut(4).zip(&h) {
+            b.copy_from_slice(&w[lane].to_le_bytes());
+        }
+        o.copy_from_slice(&full[..NN]);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::blake2::{BLAKE2s, BLAKE2s128, BLAKE2s256};
+    use irox_tools::assert_eq_hex_slice;
+
+    #[test]
+    pub fn test_unequal_lengths() {
+        let mut inp = vec![0u8; 1000];
+        for (i, v) in inp.iter_mut().enumerate() {
+            *v = (i as u8).wrapping_mul(13).wrapping_add(1);
+        }
+        // every count covers a partial last group, and the lengths straddle block boundaries
+        let lens = [
+            0, 1, 63, 64, 65, 128, 129, 500, 999, 3, 64, 0, 200, 1000, 7, 8, 9, 640, 641,
+        ];
+        for count in 0..=lens.len() {
+            let msgs: Vec<&[u8]> = lens[..count].iter().map(|l| &inp[..*l]).collect();
+            let res = BLAKE2s256::hash_many(&msgs);
+            assert_eq!(msgs.len(), res.len());
+            for (m, r) in msgs.iter().zip(&res) {
+                assert_eq_hex_slice!(BLAKE2s256::new(&[]).hash(m), r);
+            }
+            let res = BLAKE2s128::hash_many_keyed(b"0123456789", &msgs);
+            for (m, r) in msgs.iter().zip(&res) {
+                assert_eq_hex_slice!(BLAKE2s::<16>::new(b"0123456789").hash(m), r);
+            }
+        }
+    }
+}
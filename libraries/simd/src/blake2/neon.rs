@@ -0,0 +1,90 @@
+// SPDX-License-Identifier: MIT
+// Copyright 2025 IROX Contributors
+//
+
+//!
+//! NEON row-wise G function for aarch64, shared by BLAKE2s and BLAKE3.  The 4x4 state is held
+//! as four `uint32x4_t` rows: each round mixes the columns, rotates rows 1-3 so the diagonals
+//! line up as columns, mixes those, and rotates the rows back.
+//!
+
+#![allow(clippy::indexing_slicing)]
+
+use super::{scalar, BLAKE2S_IV, SIGMA};
+use core::arch::aarch64::{
+    uint32x4_t, vaddq_u32, veorq_u32, vextq_u32, vld1q_u32, vshlq_n_u32, vsriq_n_u32, vst1q_u32,
+};
+
+macro_rules! rotr {
+    ($v:expr, $n:literal) => {{
+        let v = $v;
+        vsriq_n_u32::<$n>(vshlq_n_u32::<{ 32 - $n }>(v), v)
+    }};
+}
+macro_rules! g4 {
+    ($r:ident, $x:expr, $y:expr) => {
+        $r[0] = vaddq_u32(vaddq_u32($r[0], $r[1]), $x);
+        $r[3] = rotr!(veorq_u32($r[3], $r[0]), 16);
+        $r[2] = vaddq_u32($r[2], $r[3]);
+        $r[1] = rotr!(veorq_u32($r[1], $r[2]), 12);
+        $r[0] = vaddq_u32(vaddq_u32($r[0], $r[1]), $y);
+        $r[3] = rotr!(veorq_u32($r[3], $r[0]), 8);
+        $r[2] = vaddq_u32($r[2], $r[3]);
+        $r[1] = rotr!(veorq_u32($r[1], $r[2]), 7);
+    };
+}
+
+///
+/// Message words `s[first]`, `s[first + 2]`, `s[first + 4]` and `s[first + 6]` as one vector
+#[target_feature(enable = "neon")]
+#[inline]
+unsafe fn gather(m: &[u32; 16], s: &[usize; 16], first: usize) -> uint32x4_t {
+    let w = [
+        m[s[first]],
+        m[s[first + 2]],
+        m[s[first + 4]],
+        m[s[first + 6]],
+    ];
+    vld1q_u32(w.as_ptr())
+}
+
+///
+/// Runs one round over the rows `r` for each message word order in `schedule`
+#[target_feature(enable = "neon")]
+pub(crate) unsafe fn rounds(r: &mut [uint32x4_t; 4], m: &[u32; 16], schedule: &[[usize; 16]]) {
+    for s in schedule {
+        g4!(r, gather(m, s, 0), gather(m, s, 1));
+        r[1] = vextq_u32::<1>(r[1], r[1]);
+        r[2] = vextq_u32::<2>(r[2], r[2]);
+        r[3] = vextq_u32::<3>(r[3], r[3]);
+        g4!(r, gather(m, s, 8), gather(m, s, 9));
+        r[1] = vextq_u32::<3>(r[1], r[1]);
+        r[2] = vextq_u32::<2>(r[2], r[2]);
+        r[3] = vextq_u32::<1>(r[3], r[3]);
+    }
+}
+
+///
+/// The BLAKE2s compression function, with the same arguments as [`scalar::compress_s`] but the
+/// message block still in bytes.
+#[target_feature(enable = "neon")]
+pub(crate) unsafe fn compress_s(h: &mut [u32; 8], block: &[u8; 64], t: [u32; 2], f: [u32; 2]) {
+    let m = scalar::words_s(block);
+    let h0 = vld1q_u32(h.as_ptr());
+    let h1 = vld1q_u32(h.as_ptr().add(4));
+    let d = [
+        BLAKE2S_IV[4] ^ t[0],
+        BLAKE2S_IV[5] ^ t[1],
+        BLAKE2S_IV[6] ^ f[0],
+        BLAKE2S_IV[7] ^ f[1],
+    ];
+    let mut r = [
+        h0,
+        h1,
+        vld1q_u32(BLAKE2S_IV.as_ptr()),
+        vld1q_u32(d.as_ptr()),
+    ];
+    rounds(&mut r, &m, &SIGMA[..10]);
+    vst1q_u32(h.as_mut_ptr(), veorq_u32(h0, veorq_u32(r[0], r[2])));
+    vst1q_u32(h.as_mut_ptr().add(4), veorq_u32(h1, veorq_u32(r[1], r[3])));
+}
@@ -0,0 +1,70 @@
+// SPDX-License-Identifier: MIT
+// Copyright 2025 IROX Contributors
+//
+
+//!
+//! Portable BLAKE2s and BLAKE2b compression functions (RFC 7693 section 3.2), for CPUs without
+//! the instructions the vector implementations need.  The tree modes call these once per leaf.
+//!
+
+#![allow(clippy::indexing_slicing)]
+
+use super::blake2b::BLAKE2B_IV;
+use super::{BLAKE2S_IV, SIGMA};
+
+macro_rules! compress {
+    ($name:ident, $words:ident, $t:ty, $iv:expr, $rounds:literal, $r1:literal, $r2:literal, $r3:literal, $r4:literal) => {
+        ///
+        /// Compresses the message words `m` into the state `h`, with counter `t` and
+        /// finalization flags `f` in the low word first.
+        pub(crate) fn $name(h: &mut [$t; 8], m: &[$t; 16], t: [$t; 2], f: [$t; 2]) {
+            let mut v = [0 as $t; 16];
+            v[..8].copy_from_slice(h);
+            v[8..].copy_from_slice($iv);
+            v[12] ^= t[0];
+            v[13] ^= t[1];
+            v[14] ^= f[0];
+            v[15] ^= f[1];
+
+            let mut g = |a: usize, b: usize, c: usize, d: usize, x: $t, y: $t| {
+                v[a] = v[a].wrapping_add(v[b]).wrapping_add(x);
+                v[d] = (v[d] ^ v[a]).rotate_right($r1);
+                v[c] = v[c].wrapping_add(v[d]);
+                v[b] = (v[b] ^ v[c]).rotate_right($r2);
+                v[a] = v[a].wrapping_add(v[b]).wrapping_add(y);
+                v[d] = (v[d] ^ v[a]).rotate_right($r3);
+                v[c] = v[c].wrapping_add(v[d]);
+                v[b] = (v[b] ^ v[c]).rotate_right($r4);
+            };
+            for s in SIGMA.iter().take($rounds) {
+                g(0, 4, 8, 12, m[s[0]], m[s[1]]);
+                g(1, 5, 9, 13, m[s[2]], m[s[3]]);
+                g(2, 6, 10, 14, m[s[4]], m[s[5]]);
+                g(3, 7, 11, 15, m[s[6]], m[s[7]]);
+                g(0, 5, 10, 15, m[s[8]], m[s[9]]);
+                g(1, 6, 11, 12, m[s[10]], m[s[11]]);
+                g(2, 7, 8, 13, m[s[12]], m[s[13]]);
+                g(3, 4, 9, 14, m[s[14]], m[s[15]]);
+            }
+
+            for (i, h) in h.iter_mut().enumerate() {
+                *h ^= v[i] ^ v[i + 8];
+            }
+        }
+
+        ///
+        /// Reads a block as 16 little-endian message words
+        pub(crate) fn $words(block: &[u8]) -> [$t; 16] {
+            let mut m = [0 as $t; 16];
+            for (m, b) in m.iter_mut().zip(block.chunks_exact(size_of::<$t>())) {
+                let mut le = [0u8; size_of::<$t>()];
+                le.copy_from_slice(b);
+                *m = <$t>::from_le_bytes(le);
+            }
+            m
+        }
+    };
+}
+
+compress!(compress_s, words_s, u32, BLAKE2S_IV, 10, 16, 12, 8, 7);
+compress!(compress_b, words_b, u64, BLAKE2B_IV, 12, 32, 24, 16, 63);
@@ -0,0 +1,820 @@
+// SPDX-License-Identifier: MIT
+// Copyright 2025 IROX Contributors
+//
+
+//!
+//! BLAKE3, in the regular hash, keyed hash and key derivation modes, with extendable output.
+//!
+//! Single blocks (partial chunks, parent nodes, and the root/XOF output) use the same SSE4.1
+//! row-wise G function as [`crate::blake2::BLAKE2s`].  Runs of whole chunks are compressed 8 at
+//! a time with AVX2, or 4 at a time with SSE4.1, by transposing the chunks across the vector
+//! lanes so each lane carries one chunk's chaining value.  Below [`Level::Sse41`] everything goes
+//! through a scalar compression function, one block at a time, and on aarch64 single blocks use
+//! the NEON form of the row-wise G function.
+//!
+
+#![allow(clippy::indexing_slicing)]
+
+#[cfg(target_arch = "aarch64")]
+use crate::blake2::neon;
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+use crate::blake2::{_mm_shuffle, transpose8};
+#[cfg(any(
+    all(target_arch = "x86_64", target_feature = "sse2"),
+    target_arch = "aarch64"
+))]
+use crate::dispatch::{self, Level};
+#[cfg(target_arch = "aarch64")]
+use core::arch::aarch64::{veorq_u32, vld1q_u32, vst1q_u32};
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+use core::arch::x86_64::{
+    __m128i, _mm256_add_epi32, _mm256_loadu_si256, _mm256_or_si256, _mm256_set1_epi32,
+    _mm256_setr_epi8, _mm256_shuffle_epi8, _mm256_slli_epi32, _mm256_srli_epi32,
+    _mm256_storeu_si256, _mm256_xor_si256, _mm_add_epi32, _mm_loadu_si128, _mm_set1_epi32,
+    _mm_set_epi32, _mm_set_epi8, _mm_setzero_si128, _mm_shuffle_epi32, _mm_shuffle_epi8,
+    _mm_slli_epi32, _mm_srli_epi32, _mm_storeu_si128, _mm_unpackhi_epi32, _mm_unpackhi_epi64,
+    _mm_unpacklo_epi32, _mm_unpacklo_epi64, _mm_xor_si128,
+};
+use irox_tools::hash::HashDigest;
+
+pub const BLAKE3_BLOCK_SIZE: usize = 64;
+pub const BLAKE3_CHUNK_SIZE: usize = 1024;
+pub const BLAKE3_KEY_SIZE: usize = 32;
+pub const BLAKE3_OUTPUT_SIZE: usize = 32;
+
+const BLOCK: usize = BLAKE3_BLOCK_SIZE;
+const CHUNK: usize = BLAKE3_CHUNK_SIZE;
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+const BLOCKS_PER_CHUNK: usize = CHUNK / BLOCK;
+/// Enough chaining values for 2^64 bytes of input.
+const MAX_DEPTH: usize = 54;
+
+const CHUNK_START: u32 = 1 << 0;
+const CHUNK_END: u32 = 1 << 1;
+const PARENT: u32 = 1 << 2;
+const ROOT: u32 = 1 << 3;
+const KEYED_HASH: u32 = 1 << 4;
+const DERIVE_KEY_CONTEXT: u32 = 1 << 5;
+const DERIVE_KEY_MATERIAL: u32 = 1 << 6;
+
+static IV: &[u32; 8] = &[
+    0x6A09E667, 0xBB67AE85, 0x3C6EF372, 0xA54FF53A, 0x510E527F, 0x9B05688C, 0x1F83D9AB, 0x5BE0CD19,
+];
+/// The message word order for each of the 7 rounds, the fixed permutation applied repeatedly.
+static MSG_SCHEDULE: &[[usize; 16]; 7] = &[
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+    [2, 6, 3, 10, 7, 0, 4, 13, 1, 11, 12, 5, 9, 14, 15, 8],
+    [3, 4, 10, 12, 13, 2, 7, 14, 6, 5, 9, 0, 11, 15, 8, 1],
+    [10, 7, 12, 9, 14, 3, 13, 15, 4, 0, 11, 2, 5, 8, 1, 6],
+    [12, 13, 9, 11, 15, 10, 14, 8, 7, 2, 5, 3, 0, 1, 6, 4],
+    [9, 14, 11, 5, 8, 12, 15, 1, 13, 3, 0, 10, 2, 6, 4, 7],
+    [11, 15, 5, 0, 1, 9, 8, 6, 14, 10, 2, 12, 3, 4, 7, 13],
+];
+
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+macro_rules! g4x32 {
+    ($v:ident, $m:ident, $s:ident, $a:literal, $b:literal, $c:literal, $d:literal, $x:literal) => {
+        $v[$a] = _mm_add_epi32(_mm_add_epi32($v[$a], $v[$b]), $m[$s[$x]]);
+        $v[$d] = _mm_roti_epi32!(_mm_xor_si128($v[$d], $v[$a]), 16);
+        $v[$c] = _mm_add_epi32($v[$c], $v[$d]);
+        $v[$b] = _mm_roti_epi32!(_mm_xor_si128($v[$b], $v[$c]), 12);
+        $v[$a] = _mm_add_epi32(_mm_add_epi32($v[$a], $v[$b]), $m[$s[$x + 1]]);
+        $v[$d] = _mm_roti_epi32!(_mm_xor_si128($v[$d], $v[$a]), 8);
+        $v[$c] = _mm_add_epi32($v[$c], $v[$d]);
+        $v[$b] = _mm_roti_epi32!(_mm_xor_si128($v[$b], $v[$c]), 7);
+    };
+}
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+macro_rules! rounds {
+    ($g:ident, $v:ident, $m:ident) => {
+        for s in MSG_SCHEDULE {
+            $g!($v, $m, s, 0, 4, 8, 12, 0);
+            $g!($v, $m, s, 1, 5, 9, 13, 2);
+            $g!($v, $m, s, 2, 6, 10, 14, 4);
+            $g!($v, $m, s, 3, 7, 11, 15, 6);
+            $g!($v, $m, s, 0, 5, 10, 15, 8);
+            $g!($v, $m, s, 1, 6, 11, 12, 10);
+            $g!($v, $m, s, 2, 7, 8, 13, 12);
+            $g!($v, $m, s, 3, 4, 9, 14, 14);
+        }
+    };
+}
+
+fn words_from_le_bytes<const N: usize>(v: &[u8]) -> [u32; N] {
+    let mut out = [0u32; N];
+    for (w, b) in out.iter_mut().zip(v.chunks_exact(4)) {
+        *w = u32::from_le_bytes([b[0], b[1], b[2], b[3]]);
+    }
+    out
+}
+
+///
+/// The BLAKE3 compression function over a single block, returning all 16 output words - the
+/// first 8 are the new chaining value, all 16 are used for root/XOF output.
+fn compress(
+    cv: &[u32; 8],
+    block: &[u8; BLOCK],
+    counter: u64,
+    block_len: u32,
+    flags: u32,
+) -> [u32; 16] {
+    #[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+    if dispatch::level() >= Level::Sse41 {
+        return unsafe { compress_sse41(cv, block, counter, block_len, flags) };
+    }
+    #[cfg(target_arch = "aarch64")]
+    if dispatch::level() >= Level::Neon {
+        return unsafe { compress_neon(cv, block, counter, block_len, flags) };
+    }
+    compress_scalar(cv, block, counter, block_len, flags)
+}
+
+fn compress_scalar(
+    cv: &[u32; 8],
+    block: &[u8; BLOCK],
+    counter: u64,
+    block_len: u32,
+    flags: u32,
+) -> [u32; 16] {
+    let m: [u32; 16] = words_from_le_bytes(block);
+    let mut v = [0u32; 16];
+    v[..8].copy_from_slice(cv);
+    v[8..12].copy_from_slice(&IV[..4]);
+    v[12] = counter as u32;
+    v[13] = (counter >> 32) as u32;
+    v[14] = block_len;
+    v[15] = flags;
+
+    let mut g = |a: usize, b: usize, c: usize, d: usize, x: u32, y: u32| {
+        v[a] = v[a].wrapping_add(v[b]).wrapping_add(x);
+        v[d] = (v[d] ^ v[a]).rotate_right(16);
+        v[c] = v[c].wrapping_add(v[d]);
+        v[b] = (v[b] ^ v[c]).rotate_right(12);
+        v[a] = v[a].wrapping_add(v[b]).wrapping_add(y);
+        v[d] = (v[d] ^ v[a]).rotate_right(8);
+        v[c] = v[c].wrapping_add(v[d]);
+        v[b] = (v[b] ^ v[c]).rotate_right(7);
+    };
+    for s in MSG_SCHEDULE {
+        g(0, 4, 8, 12, m[s[0]], m[s[1]]);
+        g(1, 5, 9, 13, m[s[2]], m[s[3]]);
+        g(2, 6, 10, 14, m[s[4]], m[s[5]]);
+        g(3, 7, 11, 15, m[s[6]], m[s[7]]);
+        g(0, 5, 10, 15, m[s[8]], m[s[9]]);
+        g(1, 6, 11, 12, m[s[10]], m[s[11]]);
+        g(2, 7, 8, 13, m[s[12]], m[s[13]]);
+        g(3, 4, 9, 14, m[s[14]], m[s[15]]);
+    }
+
+    core::array::from_fn(|i| {
+        if i < 8 {
+            v[i] ^ v[i + 8]
+        } else {
+            v[i] ^ cv[i - 8]
+        }
+    })
+}
+
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+#[target_feature(enable = "sse2,ssse3,sse4.1")]
+unsafe fn compress_sse41(
+    cv: &[u32; 8],
+    block: &[u8; BLOCK],
+    counter: u64,
+    block_len: u32,
+    flags: u32,
+) -> [u32; 16] {
+    let m: [u32; 16] = words_from_le_bytes(block);
+    let cv0 = _mm_loadu_si128(cv.as_ptr() as *const _);
+    let cv1 = _mm_loadu_si128(cv.as_ptr().add(4) as *const _);
+    let mut a = cv0;
+    let mut b = cv1;
+    let mut c = _mm_loadu_si128(IV.as_ptr() as *const _);
+    let mut d = _mm_set_epi32(
+        flags as i32,
+        block_len as i32,
+        (counter >> 32) as i32,
+        counter as i32,
+    );
+    let mut buf = _mm_setzero_si128();
+    let (a_, b_, c_, d_, buf_) = (&mut a, &mut b, &mut c, &mut d, &mut buf);
+    for s in MSG_SCHEDULE {
+        // after diagonalization lane 0 holds the 4th diagonal G, hence the rotated z/n words
+        round!(
+            a_,
+            b_,
+            c_,
+            d_,
+            buf_,
+            _mm_set_epi32(
+                m[s[6]] as i32,
+                m[s[4]] as i32,
+                m[s[2]] as i32,
+                m[s[0]] as i32
+            ),
+            _mm_set_epi32(
+                m[s[7]] as i32,
+                m[s[5]] as i32,
+                m[s[3]] as i32,
+                m[s[1]] as i32
+            ),
+            _mm_set_epi32(
+                m[s[12]] as i32,
+                m[s[10]] as i32,
+                m[s[8]] as i32,
+                m[s[14]] as i32
+            ),
+            _mm_set_epi32(
+                m[s[13]] as i32,
+                m[s[11]] as i32,
+                m[s[9]] as i32,
+                m[s[15]] as i32
+            )
+        );
+    }
+    let out = [
+        _mm_xor_si128(a, c),
+        _mm_xor_si128(b, d),
+        _mm_xor_si128(c, cv0),
+        _mm_xor_si128(d, cv1),
+    ];
+    let mut res = [0u32; 16];
+    for (i, o) in out.into_iter().enumerate() {
+        _mm_storeu_si128(res.as_mut_ptr().add(i * 4) as *mut _, o);
+    }
+    res
+}
+
+#[cfg(target_arch = "aarch64")]
+#[target_feature(enable = "neon")]
+unsafe fn compress_neon(
+    cv: &[u32; 8],
+    block: &[u8; BLOCK],
+    counter: u64,
+    block_len: u32,
+    flags: u32,
+) -> [u32; 16] {
+    let m: [u32; 16] = words_from_le_bytes(block);
+    let cv0 = vld1q_u32(cv.as_ptr());
+    let cv1 = vld1q_u32(cv.as_ptr().add(4));
+    let d = [counter as u32, (counter >> 32) as u32, block_len, flags];
+    let mut r = [cv0, cv1, vld1q_u32(IV.as_ptr()), vld1q_u32(d.as_ptr())];
+    neon::rounds(&mut r, &m, MSG_SCHEDULE);
+    let out = [
+        veorq_u32(r[0], r[2]),
+        veorq_u32(r[1], r[3]),
+        veorq_u32(r[2], cv0),
+        veorq_u32(r[3], cv1),
+    ];
+    let mut res = [0u32; 16];
+    for (o, v) in res.chunks_exact_mut(4).zip(out) {
+        vst1q_u32(o.as_mut_ptr(), v);
+    }
+    res
+}
+
+///
+/// Transposes a 4x4 matrix of u32s, row `i` of the input becomes lane `i` of each output.
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+#[target_feature(enable = "sse2")]
+unsafe fn transpose4(r: [__m128i; 4]) -> [__m128i; 4] {
+    let [r0, r1, r2, r3] = r;
+    let t0 = _mm_unpacklo_epi32(r0, r1);
+    let t1 = _mm_unpackhi_epi32(r0, r1);
+    let t2 = _mm_unpacklo_epi32(r2, r3);
+    let t3 = _mm_unpackhi_epi32(r2, r3);
+    [
+        _mm_unpacklo_epi64(t0, t2),
+        _mm_unpackhi_epi64(t0, t2),
+        _mm_unpacklo_epi64(t1, t3),
+        _mm_unpackhi_epi64(t1, t3),
+    ]
+}
+
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+fn block_flags(flags: u32, block: usize) -> u32 {
+    let mut flags = flags;
+    if block == 0 {
+        flags |= CHUNK_START;
+    }
+    if block == BLOCKS_PER_CHUNK - 1 {
+        flags |= CHUNK_END;
+    }
+    flags
+}
+
+///
+/// Hashes 4 consecutive whole chunks starting at chunk `counter`, one per 32-bit lane, and
+/// returns each chunk's chaining value.
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+#[target_feature(enable = "sse2,ssse3,sse4.1")]
+unsafe fn hash4_chunks(
+    chunks: &[u8; 4 * CHUNK],
+    key: &[u32; 8],
+    counter: u64,
+    flags: u32,
+) -> [[u32; 8]; 4] {
+    let ctr: [u64; 4] = core::array::from_fn(|i| counter + i as u64);
+    let t0 = ctr.map(|t| t as u32);
+    let t1 = ctr.map(|t| (t >> 32) as u32);
+    let mut h = key.map(|k| _mm_set1_epi32(k as i32));
+    let cp = chunks.as_ptr();
+    for blk in 0..BLOCKS_PER_CHUNK {
+        let mut m = [_mm_setzero_si128(); 16];
+        for q in 0..4 {
+            let mut rows = [_mm_setzero_si128(); 4];
+            for (i, r) in rows.iter_mut().enumerate() {
+                *r = _mm_loadu_si128(cp.add(i * CHUNK + blk * BLOCK + q * 16) as *const _);
+            }
+            let t = transpose4(rows);
+            m[q * 4..q * 4 + 4].copy_from_slice(&t);
+        }
+        let mut v = [
+            h[0],
+            h[1],
+            h[2],
+            h[3],
+            h[4],
+            h[5],
+            h[6],
+            h[7],
+            _mm_set1_epi32(IV[0] as i32),
+            _mm_set1_epi32(IV[1] as i32),
+            _mm_set1_epi32(IV[2] as i32),
+            _mm_set1_epi32(IV[3] as i32),
+            _mm_loadu_si128(t0.as_ptr() as *const _),
+            _mm_loadu_si128(t1.as_ptr() as *const _),
+            _mm_set1_epi32(BLOCK as i32),
+            _mm_set1_epi32(block_flags(flags, blk) as i32),
+        ];
+        rounds!(g4x32, v, m);
+        for i in 0..8 {
+            h[i] = _mm_xor_si128(v[i], v[i + 8]);
+        }
+    }
+    let lo = transpose4([h[0], h[1], h[2], h[3]]);
+    let hi = transpose4([h[4], h[5], h[6], h[7]]);
+    let mut out = [[0u32; 8]; 4];
+    for (o, (lo, hi)) in out.iter_mut().zip(lo.into_iter().zip(hi)) {
+        _mm_storeu_si128(o.as_mut_ptr() as *mut _, lo);
+        _mm_storeu_si128(o.as_mut_ptr().add(4) as *mut _, hi);
+    }
+    out
+}
+
+///
+/// Hashes 8 consecutive whole chunks starting at chunk `counter`, one per 32-bit lane, and
+/// returns each chunk's chaining value.
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+#[target_feature(enable = "avx2")]
+unsafe fn hash8_chunks(
+    chunks: &[u8; 8 * CHUNK],
+    key: &[u32; 8],
+    counter: u64,
+    flags: u32,
+) -> [[u32; 8]; 8] {
+    let ctr: [u64; 8] = core::array::from_fn(|i| counter + i as u64);
+    let t0 = ctr.map(|t| t as u32);
+    let t1 = ctr.map(|t| (t >> 32) as u32);
+    let mut h = key.map(|k| _mm256_set1_epi32(k as i32));
+    let cp = chunks.as_ptr();
+    for blk in 0..BLOCKS_PER_CHUNK {
+        let mut lo = [_mm256_set1_epi32(0); 8];
+        let mut hi = [_mm256_set1_epi32(0); 8];
+        for i in 0..8 {
+            lo[i] = _mm256_loadu_si256(cp.add(i * CHUNK + blk * BLOCK) as *const _);
+            hi[i] = _mm256_loadu_si256(cp.add(i * CHUNK + blk * BLOCK + 32) as *const _);
+        }
+        let [m0, m1, m2, m3, m4, m5, m6, m7] = transpose8(lo);
+        let [m8, m9, m10, m11, m12, m13, m14, m15] = transpose8(hi);
+        let m = [
+            m0, m1, m2, m3, m4, m5, m6, m7, m8, m9, m10, m11, m12, m13, m14, m15,
+        ];
+        let mut v = [
+            h[0],
+            h[1],
+            h[2],
+            h[3],
+            h[4],
+            h[5],
+            h[6],
+            h[7],
+            _mm256_set1_epi32(IV[0] as i32),
+            _mm256_set1_epi32(IV[1] as i32),
+            _mm256_set1_epi32(IV[2] as i32),
+            _mm256_set1_epi32(IV[3] as i32),
+            _mm256_loadu_si256(t0.as_ptr() as *const _),
+            _mm256_loadu_si256(t1.as_ptr() as *const _),
+            _mm256_set1_epi32(BLOCK as i32),
+            _mm256_set1_epi32(block_flags(flags, blk) as i32),
+        ];
+        rounds!(g8, v, m);
+        for i in 0..8 {
+            h[i] = _mm256_xor_si256(v[i], v[i + 8]);
+        }
+    }
+    let mut out = [[0u32; 8]; 8];
+    for (o, h) in out.iter_mut().zip(transpose8(h)) {
+        _mm256_storeu_si256(o.as_mut_ptr() as *mut _, h);
+    }
+    out
+}
+
+///
+/// How many whole chunks the parallel kernels hash at once at `level`, 0 if there's no kernel
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+fn chunk_lanes(level: Level) -> usize {
+    match level {
+        Level::Scalar | Level::Neon => 0,
+        Level::Sse41 => 4,
+        Level::Avx2 | Level::Avx512 => 8,
+    }
+}
+
+///
+/// The inputs to a final compression, held back until it's known whether the node is the root.
+#[derive(Clone)]
+struct Output {
+    cv: [u32; 8],
+    block: [u8; BLOCK],
+    counter: u64,
+    block_len: u32,
+    flags: u32,
+}
+impl Output {
+    fn parent(left: &[u32; 8], right: &[u32; 8], key: &[u32; 8], flags: u32) -> Self {
+        let mut block = [0u8; BLOCK];
+        for (b, w) in block.chunks_exact_mut(4).zip(left.iter().chain(right)) {
+            b.copy_from_slice(&w.to_le_bytes());
+        }
+        Output {
+            cv: *key,
+            block,
+            counter: 0,
+            block_len: BLOCK as u32,
+            flags: flags | PARENT,
+        }
+    }
+
+    fn chaining_value(&self) -> [u32; 8] {
+        let out = compress(
+            &self.cv,
+            &self.block,
+            self.counter,
+            self.block_len,
+            self.flags,
+        );
+        let mut cv = [0u32; 8];
+        cv.copy_from_slice(&out[..8]);
+        cv
+    }
+
+    fn root_block(&self, counter: u64) -> [u8; BLOCK] {
+        let out = compress(
+            &self.cv,
+            &self.block,
+            counter,
+            self.block_len,
+            self.flags | ROOT,
+        );
+        let mut block = [0u8; BLOCK];
+        for (b, w) in block.chunks_exact_mut(4).zip(out) {
+            b.copy_from_slice(&w.to_le_bytes());
+        }
+        block
+    }
+}
+
+#[derive(Clone)]
+struct ChunkState {
+    cv: [u32; 8],
+    counter: u64,
+    buf: [u8; BLOCK],
+    buflen: usize,
+    blocks_compressed: usize,
+    flags: u32,
+}
+impl ChunkState {
+    fn new(key: &[u32; 8], counter: u64, flags: u32) -> Self {
+        Self {
+            cv: *key,
+            counter,
+            buf: [0; BLOCK],
+            buflen: 0,
+            blocks_compressed: 0,
+            flags,
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.blocks_compressed * BLOCK + self.buflen
+    }
+
+    fn start_flag(&self) -> u32 {
+        if self.blocks_compressed == 0 {
+            CHUNK_START
+        } else {
+            0
+        }
+    }
+
+    fn write(&mut self, mut v: &[u8]) {
+        while !v.is_empty() {
+            // the last block of the chunk is held until finalization
+            if self.buflen == BLOCK {
+                let flags = self.flags | self.start_flag();
+                let out = compress(&self.cv, &self.buf, self.counter, BLOCK as u32, flags);
+                self.cv.copy_from_slice(&out[..8]);
+                self.blocks_compressed += 1;
+                self.buflen = 0;
+            }
+            let take = (BLOCK - self.buflen).min(v.len());
+            let (a, b) = v.split_at(take);
+            self.buf[self.buflen..self.buflen + take].copy_from_slice(a);
+            self.buflen += take;
+            v = b;
+        }
+    }
+
+    fn output(&self) -> Output {
+        let mut block = [0u8; BLOCK];
+        block[..self.buflen].copy_from_slice(&self.buf[..self.buflen]);
+        Output {
+            cv: self.cv,
+            block,
+            counter: self.counter,
+            block_len: self.buflen as u32,
+            flags: self.flags | self.start_flag() | CHUNK_END,
+        }
+    }
+}
+
+///
+/// Streaming BLAKE3 hasher.  Use [`BLAKE3::new`] for the regular hash, [`BLAKE3::new_keyed`] for
+/// a keyed hash (MAC), or [`BLAKE3::new_derive_key`] for key derivation.
+#[derive(Clone)]
+pub struct BLAKE3 {
+    key: [u32; 8],
+    flags: u32,
+    chunk: ChunkState,
+    cv_stack: [[u32; 8]; MAX_DEPTH],
+    cv_len: usize,
+    /// the most whole chunks compressed at once, lowered further by the [`dispatch::level`]
+    #[cfg_attr(
+        not(all(target_arch = "x86_64", target_feature = "sse2")),
+        allow(dead_code)
+    )]
+    lanes: usize,
+}
+impl Default for BLAKE3 {
+    fn default() -> Self {
+        Self::with_key_flags(*IV, 0)
+    }
+}
+impl BLAKE3 {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    ///
+    /// Keyed hash mode, the output is a MAC of the input under `key`
+    #[must_use]
+    pub fn new_keyed(key: &[u8; BLAKE3_KEY_SIZE]) -> Self {
+        Self::with_key_flags(words_from_le_bytes(key), KEYED_HASH)
+    }
+
+    ///
+    /// Key derivation mode.  The `context` should be a hardcoded, globally unique and
+    /// application-specific string, the key material is then written as regular input.
+    #[must_use]
+    pub fn new_derive_key(context: &str) -> Self {
+        let mut ctx = Self::with_key_flags(*IV, DERIVE_KEY_CONTEXT);
+        ctx.write(context.as_bytes());
+        let key = ctx.finish();
+        Self::with_key_flags(words_from_le_bytes(&key), DERIVE_KEY_MATERIAL)
+    }
+
+    fn with_key_flags(key: [u32; 8], flags: u32) -> Self {
+        Self {
+            key,
+            flags,
+            chunk: ChunkState::new(&key, 0, flags),
+            cv_stack: [[0; 8]; MAX_DEPTH],
+            cv_len: 0,
+            lanes: 8,
+        }
+    }
+
+    ///
+    /// Pushes the chaining value of chunk number `total_chunks - 1`, first merging every subtree
+    /// that it completes.  Merging is lazy so the root is never finalized as a parent.
+    fn push_cv(&mut self, mut cv: [u32; 8], mut total_chunks: u64) {
+        while total_chunks & 1 == 0 {
+            self.cv_len -= 1;
+            cv = Output::parent(&self.cv_stack[self.cv_len], &cv, &self.key, self.flags)
+                .chaining_value();
+            total_chunks >>= 1;
+        }
+        self.cv_stack[self.cv_len] = cv;
+        self.cv_len += 1;
+    }
+
+    pub fn write(&mut self, mut v: &[u8]) {
+        while !v.is_empty() {
+            if self.chunk.len() == CHUNK {
+                let cv = self.chunk.output().chaining_value();
+                let total = self.chunk.counter + 1;
+                self.push_cv(cv, total);
+                self.chunk = ChunkState::new(&self.key, total, self.flags);
+            }
+            #[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+            if self.chunk.len() == 0 {
+                // whole chunks with more input after them are never the root, so hash them in
+                // parallel.
+                let lanes = self.lanes.min(chunk_lanes(dispatch::level()));
+                while lanes > 0 && v.len() > lanes * CHUNK {
+                    let (a, b) = v.split_at(lanes * CHUNK);
+                    v = b;
+                    let counter = self.chunk.counter;
+                    if lanes == 8 {
+                        if let Ok(a) = a.try_into() {
+                            let cvs = unsafe { hash8_chunks(a, &self.key, counter, self.flags) };
+                            for (i, cv) in cvs.into_iter().enumerate() {
+                                self.push_cv(cv, counter + i as u64 + 1);
+                            }
+                        }
+                    } else if let Ok(a) = a.try_into() {
+                        let cvs = unsafe { hash4_chunks(a, &self.key, counter, self.flags) };
+                        for (i, cv) in cvs.into_iter().enumerate() {
+                            self.push_cv(cv, counter + i as u64 + 1);
+                        }
+                    }
+                    self.chunk = ChunkState::new(&self.key, counter + lanes as u64, self.flags);
+                }
+            }
+            let take = (CHUNK - self.chunk.len()).min(v.len());
+            let (a, b) = v.split_at(take);
+            self.chunk.write(a);
+            v = b;
+        }
+    }
+
+    fn root_output(&self) -> Output {
+        let mut out = self.chunk.output();
+        for cv in self.cv_stack[..self.cv_len].iter().rev() {
+            out = Output::parent(cv, &out.chaining_value(), &self.key, self.flags);
+        }
+        out
+    }
+
+    pub fn hash(mut self, v: &[u8]) -> [u8; BLAKE3_OUTPUT_SIZE] {
+        self.write(v);
+        self.finish()
+    }
+
+    pub fn finish(self) -> [u8; BLAKE3_OUTPUT_SIZE] {
+        let mut out = [0u8; BLAKE3_OUTPUT_SIZE];
+        out.copy_from_slice(&self.root_output().root_block(0)[..BLAKE3_OUTPUT_SIZE]);
+        out
+    }
+
+    ///
+    /// Finishes the hash and returns a reader for an arbitrary amount of output.  The first 32
+    /// bytes are the same as [`BLAKE3::finish`].
+    #[must_use]
+    pub fn finish_xof(self) -> XofReader {
+        XofReader {
+            output: self.root_output(),
+            next_block: 0,
+            block: [0; BLOCK],
+            pos: BLOCK,
+        }
+    }
+}
+impl HashDigest<BLAKE3_BLOCK_SIZE, BLAKE3_OUTPUT_SIZE> for BLAKE3 {
+    fn write(&mut self, bytes: &[u8]) {
+        BLAKE3::write(self, bytes)
+    }
+
+    fn hash(self, bytes: &[u8]) -> [u8; BLAKE3_OUTPUT_SIZE] {
+        BLAKE3::hash(self, bytes)
+    }
+
+    fn finish(self) -> [u8; BLAKE3_OUTPUT_SIZE] {
+        BLAKE3::finish(self)
+    }
+}
+impl_io_write!(BLAKE3 => [u8; BLAKE3_OUTPUT_SIZE]);
+impl_digest!(BLAKE3, U32);
+#[cfg(feature = "digest")]
+impl digest::ExtendableOutput for BLAKE3 {
+    type Reader = XofReader;
+
+    fn finalize_xof(self) -> XofReader {
+        self.finish_xof()
+    }
+}
+
+///
+/// Extendable output from a finished [`BLAKE3`], each root block is generated on demand.
+#[derive(Clone)]
+pub struct XofReader {
+    output: Output,
+    next_block: u64,
+    block: [u8; BLOCK],
+    pos: usize,
+}
+impl XofReader {
+    ///
+    /// Fills `out` with the next `out.len()` bytes of output.
+    pub fn fill(&mut self, mut out: &mut [u8]) {
+        while !out.is_empty() {
+            if self.pos == BLOCK {
+                self.block = self.output.root_block(self.next_block);
+                self.next_block += 1;
+                self.pos = 0;
+            }
+            let take = (BLOCK - self.pos).min(out.len());
+            let (a, b) = out.split_at_mut(take);
+            a.copy_from_slice(&self.block[self.pos..self.pos + take]);
+            self.pos += take;
+            out = b;
+        }
+    }
+
+    ///
+    /// The number of bytes of output produced so far
+    #[must_use]
+    pub fn position(&self) -> u64 {
+        self.next_block * BLOCK as u64 - (BLOCK - self.pos) as u64
+    }
+}
+#[cfg(feature = "digest")]
+impl digest::XofReader for XofReader {
+    fn read(&mut self, buffer: &mut [u8]) {
+        self.fill(buffer);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::blake3::{BLAKE3, BLAKE3_CHUNK_SIZE};
+    use irox_tools::{assert_eq_hex_slice, hex};
+
+    #[test]
+    pub fn test_abc() {
+        assert_eq_hex_slice!(
+            hex!("af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262"),
+            BLAKE3::new().hash(&[])
+        );
+        assert_eq_hex_slice!(
+            hex!("6437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d85"),
+            BLAKE3::new().hash(b"abc")
+        );
+    }
+
+    #[test]
+    pub fn test_lanes_and_boundaries() {
+        let mut inp = vec![0u8; BLAKE3_CHUNK_SIZE * 20 + 100];
+        for (i, v) in inp.iter_mut().enumerate() {
+            *v = (i as u8).wrapping_mul(13).wrapping_add(1);
+        }
+        for len in (0..inp.len())
+            .step_by(511)
+            .chain([1024, 4096, 4097, 8192, 8193, 16385])
+        {
+            let msg = &inp[..len];
+            let mut h = BLAKE3::new();
+            h.lanes = 4;
+            let exp = h.hash(msg);
+            assert_eq_hex_slice!(exp, BLAKE3::new().hash(msg));
+            for step in [1, 63, 64, 1023, 1024, 5000] {
+                let mut h = BLAKE3::new();
+                for c in msg.chunks(step) {
+                    h.write(c);
+                }
+                assert_eq_hex_slice!(exp, h.finish());
+            }
+        }
+    }
+
+    #[test]
+    pub fn test_xof_reads() {
+        let msg = [0xA5u8; 3000];
+        let mut h = BLAKE3::new();
+        h.write(&msg);
+        let mut exp = [0u8; 300];
+        h.finish_xof().fill(&mut exp);
+        let mut h = BLAKE3::new();
+        h.write(&msg);
+        let mut rdr = h.finish_xof();
+        let mut out = [0u8; 300];
+        for c in out.chunks_mut(7) {
+            rdr.fill(c);
+        }
+        assert_eq!(300, rdr.position());
+        assert_eq_hex_slice!(exp, out);
+        assert_eq_hex_slice!(exp[..32], BLAKE3::new().hash(&msg));
+    }
+}
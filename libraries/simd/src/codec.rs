@@ -0,0 +1,74 @@
+// SPDX-License-Identifier: MIT
+// Copyright 2025 IROX Contributors
+//
+
+//!
+//! Text encodings of binary data: hex and base64.  Encoding produces the same output as the
+//! scalar codecs in [`irox_tools`], decoding is strict and reports where the input went wrong.
+//!
+
+use crate::dispatch::{self, Level};
+use core::fmt::{Display, Formatter};
+
+pub mod base64;
+pub mod hex;
+
+///
+/// Why an input couldn't be decoded
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum DecodeError {
+    /// The byte at `offset` isn't valid there - not in the alphabet, padding before the end, or
+    /// a final symbol with bits set that don't fit in the output.
+    InvalidByte { offset: usize, byte: u8 },
+    /// No valid encoding has this many bytes
+    InvalidLength { len: usize },
+}
+
+impl Display for DecodeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            DecodeError::InvalidByte { offset, byte } => {
+                write!(f, "invalid byte 0x{byte:02X} at offset {offset}")
+            }
+            DecodeError::InvalidLength { len } => write!(f, "invalid encoded length {len}"),
+        }
+    }
+}
+
+impl core::error::Error for DecodeError {}
+
+///
+/// The instruction set a codec runs its bulk loop on
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub(crate) enum Kernel {
+    Scalar,
+    #[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+    Ssse3,
+    #[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+    Avx2,
+}
+
+impl Kernel {
+    pub(crate) fn detect() -> Self {
+        Kernel::for_level(dispatch::level())
+    }
+
+    pub(crate) fn for_level(level: Level) -> Self {
+        match level {
+            #[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+            Level::Sse41 => Kernel::Ssse3,
+            #[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+            Level::Avx2 | Level::Avx512 => Kernel::Avx2,
+            _ => Kernel::Scalar,
+        }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn available() -> Vec<Self> {
+        let mut out: Vec<Self> = dispatch::supported_levels()
+            .map(Kernel::for_level)
+            .collect();
+        out.dedup();
+        out
+    }
+}
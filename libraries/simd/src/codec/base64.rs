@@ -0,0 +1,616 @@
+// SPDX-License-Identifier: MIT
+// Copyright 2025 IROX Contributors
+//
+
+//!
+//! Base64 (RFC 4648) in the standard and URL-safe alphabets.  The vector kernels follow Muła and
+//! Lemire, "Faster Base64 Encoding and Decoding using AVX2 Instructions": 3 bytes are spread
+//! across a 32-bit lane and split into four 6-bit indices with multiplies, and decoding packs
+//! them back with `pmaddubsw`/`pmaddwd`.
+//!
+//! Encoding always pads with `=`.  Decoding accepts padded or unpadded input, but padding only
+//! where it completes the final 4 characters, and rejects final symbols with leftover bits set.
+//!
+
+#![allow(clippy::indexing_slicing)]
+
+use super::{DecodeError, Kernel};
+use alloc::string::String;
+use alloc::vec::Vec;
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+use core::arch::x86_64::{
+    __m128i, __m256i, _mm256_add_epi8, _mm256_and_si256, _mm256_castsi128_si256, _mm256_cmpeq_epi8,
+    _mm256_cmpgt_epi8, _mm256_inserti128_si256, _mm256_loadu_si256, _mm256_madd_epi16,
+    _mm256_maddubs_epi16, _mm256_movemask_epi8, _mm256_mulhi_epu16, _mm256_mullo_epi16,
+    _mm256_or_si256, _mm256_permutevar8x32_epi32, _mm256_set1_epi32, _mm256_set1_epi8,
+    _mm256_setr_epi32, _mm256_setr_epi8, _mm256_shuffle_epi8, _mm256_storeu_si256,
+    _mm256_subs_epu8, _mm_add_epi8, _mm_and_si128, _mm_cmpeq_epi8, _mm_cmpgt_epi8, _mm_loadu_si128,
+    _mm_madd_epi16, _mm_maddubs_epi16, _mm_movemask_epi8, _mm_mulhi_epu16, _mm_mullo_epi16,
+    _mm_or_si128, _mm_set1_epi32, _mm_set1_epi8, _mm_setr_epi8, _mm_shuffle_epi8, _mm_storeu_si128,
+    _mm_subs_epu8,
+};
+
+const PAD: u8 = b'=';
+/// Marks a byte outside the alphabet in the decode tables
+const INVALID: u8 = 0xFF;
+
+///
+/// The two RFC 4648 alphabets, which differ only in the symbols for 62 and 63
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Alphabet {
+    /// `+` and `/`
+    Standard,
+    /// `-` and `_`, safe in URLs and file names
+    UrlSafe,
+}
+
+impl Alphabet {
+    fn symbols(self) -> &'static [u8; 64] {
+        match self {
+            Alphabet::Standard => &STANDARD,
+            Alphabet::UrlSafe => &URL_SAFE,
+        }
+    }
+    fn reverse(self) -> &'static [u8; 256] {
+        match self {
+            Alphabet::Standard => &STANDARD_REVERSE,
+            Alphabet::UrlSafe => &URL_SAFE_REVERSE,
+        }
+    }
+    /// The characters for 62 and 63
+    #[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+    fn last_two(self) -> (u8, u8) {
+        let s = self.symbols();
+        (s[62], s[63])
+    }
+}
+
+static STANDARD: [u8; 64] = *b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+static URL_SAFE: [u8; 64] = *b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+static STANDARD_REVERSE: [u8; 256] = reverse(&STANDARD);
+static URL_SAFE_REVERSE: [u8; 256] = reverse(&URL_SAFE);
+
+const fn reverse(alphabet: &[u8; 64]) -> [u8; 256] {
+    let mut out = [INVALID; 256];
+    let mut i = 0;
+    while i < 64 {
+        out[alphabet[i] as usize] = i as u8;
+        i += 1;
+    }
+    out
+}
+
+///
+/// Encodes `input` with padding
+#[must_use]
+pub fn encode(alphabet: Alphabet, input: &[u8]) -> String {
+    let mut out = Vec::with_capacity(input.len().div_ceil(3) * 4);
+    encode_with(Kernel::detect(), alphabet, input, &mut out);
+    // SAFETY: every byte written came from the ASCII alphabet or is padding
+    unsafe { String::from_utf8_unchecked(out) }
+}
+
+///
+/// Appends the padded encoding of `input` to `out`
+pub fn encode_into(alphabet: Alphabet, input: &[u8], out: &mut Vec<u8>) {
+    encode_with(Kernel::detect(), alphabet, input, out);
+}
+
+///
+/// Decodes padded or unpadded base64
+pub fn decode(alphabet: Alphabet, input: &[u8]) -> Result<Vec<u8>, DecodeError> {
+    let mut out = Vec::new();
+    decode_into(alphabet, input, &mut out)?;
+    Ok(out)
+}
+
+///
+/// Decodes onto the end of `out`, returning the number of bytes appended.  On error `out` is left
+/// as it was.
+pub fn decode_into(
+    alphabet: Alphabet,
+    input: &[u8],
+    out: &mut Vec<u8>,
+) -> Result<usize, DecodeError> {
+    decode_with(Kernel::detect(), alphabet, input, out)
+}
+
+pub(crate) fn encode_with(kernel: Kernel, alphabet: Alphabet, input: &[u8], out: &mut Vec<u8>) {
+    let start = out.len();
+    out.resize(start + input.len().div_ceil(3) * 4, 0);
+    let dst = &mut out[start..];
+    let done = match kernel {
+        #[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+        Kernel::Avx2 => unsafe { encode_avx2(alphabet, input, dst) },
+        #[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+        Kernel::Ssse3 => unsafe { encode_ssse3(alphabet, input, dst) },
+        Kernel::Scalar => 0,
+    };
+    let sym = alphabet.symbols();
+    let mut chunks = input[done..].chunks_exact(3);
+    let mut o = done / 3 * 4;
+    for c in chunks.by_ref() {
+        let v = (u32::from(c[0]) << 16) | (u32::from(c[1]) << 8) | u32::from(c[2]);
+        for (i, d) in dst[o..o + 4].iter_mut().enumerate() {
+            *d = sym[((v >> (18 - 6 * i)) & 0x3F) as usize];
+        }
+        o += 4;
+    }
+    match *chunks.remainder() {
+        [a] => {
+            dst[o] = sym[(a >> 2) as usize];
+            dst[o + 1] = sym[((a & 0x3) << 4) as usize];
+            dst[o + 2..o + 4].fill(PAD);
+        }
+        [a, b] => {
+            dst[o] = sym[(a >> 2) as usize];
+            dst[o + 1] = sym[(((a & 0x3) << 4) | (b >> 4)) as usize];
+            dst[o + 2] = sym[((b & 0xF) << 2) as usize];
+            dst[o + 3] = PAD;
+        }
+        _ => {}
+    }
+}
+
+pub(crate) fn decode_with(
+    kernel: Kernel,
+    alphabet: Alphabet,
+    input: &[u8],
+    out: &mut Vec<u8>,
+) -> Result<usize, DecodeError> {
+    // padding is only stripped where it completes a 4 character group, anywhere else it's left
+    // in to be reported as an invalid byte
+    let mut body = input;
+    if input.len() % 4 == 0 {
+        for _ in 0..2 {
+            if let Some((&PAD, rest)) = body.split_last() {
+                body = rest;
+            }
+        }
+    }
+    if body.len() % 4 == 1 {
+        return Err(DecodeError::InvalidLength { len: input.len() });
+    }
+
+    let start = out.len();
+    let len = body.len() / 4 * 3 + (body.len() % 4).saturating_sub(1);
+    // the vector stores write a little past the bytes they produce
+    out.resize(start + len + 32, 0);
+    let res = decode_body(kernel, alphabet, body, &mut out[start..]);
+    match res {
+        Ok(()) => {
+            out.truncate(start + len);
+            Ok(len)
+        }
+        Err(e) => {
+            out.truncate(start);
+            Err(e)
+        }
+    }
+}
+
+fn decode_body(
+    kernel: Kernel,
+    alphabet: Alphabet,
+    input: &[u8],
+    out: &mut [u8],
+) -> Result<(), DecodeError> {
+    let done = match kernel {
+        #[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+        Kernel::Avx2 => unsafe { decode_avx2(alphabet, input, out) }?,
+        #[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+        Kernel::Ssse3 => unsafe { decode_ssse3(alphabet, input, out) }?,
+        Kernel::Scalar => 0,
+    };
+    let rev = alphabet.reverse();
+    let symbol = |offset: usize| -> Result<u32, DecodeError> {
+        let byte = input[offset];
+        match rev[byte as usize] {
+            INVALID => Err(DecodeError::InvalidByte { offset, byte }),
+            v => Ok(u32::from(v)),
+        }
+    };
+
+    let mut i = done;
+    let mut o = done / 4 * 3;
+    while i + 4 <= input.len() {
+        let v =
+            (symbol(i)? << 18) | (symbol(i + 1)? << 12) | (symbol(i + 2)? << 6) | symbol(i + 3)?;
+        out[o..o + 3].copy_from_slice(&v.to_be_bytes()[1..]);
+        i += 4;
+        o += 3;
+    }
+    let rem = input.len() - i;
+    if rem >= 2 {
+        let mut v = (symbol(i)? << 18) | (symbol(i + 1)? << 12);
+        if rem == 3 {
+            v |= symbol(i + 2)? << 6;
+        }
+        // the bits of the last symbol past the final byte must be zero
+        let bytes = rem - 1;
+        if v & (0xFF_FFFF >> (8 * bytes)) != 0 {
+            let offset = input.len() - 1;
+            return Err(DecodeError::InvalidByte {
+                offset,
+                byte: input[offset],
+            });
+        }
+        out[o..o + bytes].copy_from_slice(&v.to_be_bytes()[1..=bytes]);
+    }
+    Ok(())
+}
+
+///
+/// Spreads each 3 byte group across a 32 bit lane as `[b1, b0, b2, b1]` and pulls out the four
+/// 6-bit indices, in order, into the lane's bytes.
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+macro_rules! split_indices {
+    ($v:expr, $and:ident, $or:ident, $mulhi:ident, $mullo:ident, $set1_32:ident) => {{
+        let v = $v;
+        // a and c of each `abcd` group, shifted to the bottom of their 16 bit halves
+        let ac = $mulhi($and(v, $set1_32(0x0FC0_FC00)), $set1_32(0x0400_0040));
+        // b and d, shifted up into the top byte of their halves
+        let bd = $mullo($and(v, $set1_32(0x003F_03F0)), $set1_32(0x0100_0010));
+        $or(ac, bd)
+    }};
+}
+
+///
+/// Maps 6-bit indices to their symbols by adding the offset for the range each falls in: the
+/// range is found by a saturating subtract and a compare, and picks the offset with `pshufb`.
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+macro_rules! lookup {
+    ($idx:expr, $lut:expr, $set1:ident, $subs:ident, $cmpgt:ident, $and:ident, $or:ident, $shuffle:ident, $add:ident) => {{
+        let idx = $idx;
+        // 0 for 0..=51, 1..=12 for 52..=63, then 13 for the uppercase letters
+        let range = $subs(idx, $set1(51));
+        let upper = $cmpgt($set1(26), idx);
+        let range = $or(range, $and(upper, $set1(13)));
+        $add(idx, $shuffle($lut, range))
+    }};
+}
+
+/// The offsets from index to symbol for each range [`lookup`] finds
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+fn encode_offsets(alphabet: Alphabet) -> [i8; 16] {
+    let (s62, s63) = alphabet.last_two();
+    let digit = b'0' as i8 - 52;
+    [
+        b'a' as i8 - 26,
+        digit,
+        digit,
+        digit,
+        digit,
+        digit,
+        digit,
+        digit,
+        digit,
+        digit,
+        digit,
+        s62 as i8 - 62,
+        s63 as i8 - 63,
+        b'A' as i8,
+        0,
+        0,
+    ]
+}
+
+///
+/// Encodes 24 bytes to 32 symbols per step.  Each 128 bit lane takes 12 bytes, loaded from 16 so
+/// this stops 4 bytes short of the end.
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+#[target_feature(enable = "avx2")]
+unsafe fn encode_avx2(alphabet: Alphabet, input: &[u8], out: &mut [u8]) -> usize {
+    let o = encode_offsets(alphabet);
+    let lut = _mm256_setr_epi8(
+        o[0], o[1], o[2], o[3], o[4], o[5], o[6], o[7], o[8], o[9], o[10], o[11], o[12], o[13],
+        o[14], o[15], o[0], o[1], o[2], o[3], o[4], o[5], o[6], o[7], o[8], o[9], o[10], o[11],
+        o[12], o[13], o[14], o[15],
+    );
+    let spread = _mm256_setr_epi8(
+        1, 0, 2, 1, 4, 3, 5, 4, 7, 6, 8, 7, 10, 9, 11, 10, 1, 0, 2, 1, 4, 3, 5, 4, 7, 6, 8, 7, 10,
+        9, 11, 10,
+    );
+    let mut i = 0;
+    let mut o = 0;
+    while i + 28 <= input.len() {
+        let lo = _mm_loadu_si128(input[i..].as_ptr() as *const __m128i);
+        let hi = _mm_loadu_si128(input[i + 12..].as_ptr() as *const __m128i);
+        let v = _mm256_inserti128_si256::<1>(_mm256_castsi128_si256(lo), hi);
+        let v = _mm256_shuffle_epi8(v, spread);
+        let idx = split_indices!(
+            v,
+            _mm256_and_si256,
+            _mm256_or_si256,
+            _mm256_mulhi_epu16,
+            _mm256_mullo_epi16,
+            _mm256_set1_epi32
+        );
+        let sym = lookup!(
+            idx,
+            lut,
+            _mm256_set1_epi8,
+            _mm256_subs_epu8,
+            _mm256_cmpgt_epi8,
+            _mm256_and_si256,
+            _mm256_or_si256,
+            _mm256_shuffle_epi8,
+            _mm256_add_epi8
+        );
+        _mm256_storeu_si256(out[o..].as_mut_ptr() as *mut __m256i, sym);
+        i += 24;
+        o += 32;
+    }
+    i
+}
+
+///
+/// Encodes 12 bytes to 16 symbols per step, loading 16
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+#[target_feature(enable = "ssse3")]
+unsafe fn encode_ssse3(alphabet: Alphabet, input: &[u8], out: &mut [u8]) -> usize {
+    let o = encode_offsets(alphabet);
+    let lut = _mm_setr_epi8(
+        o[0], o[1], o[2], o[3], o[4], o[5], o[6], o[7], o[8], o[9], o[10], o[11], o[12], o[13],
+        o[14], o[15],
+    );
+    let spread = _mm_setr_epi8(1, 0, 2, 1, 4, 3, 5, 4, 7, 6, 8, 7, 10, 9, 11, 10);
+    let mut i = 0;
+    let mut o = 0;
+    while i + 16 <= input.len() {
+        let v = _mm_loadu_si128(input[i..].as_ptr() as *const __m128i);
+        let v = _mm_shuffle_epi8(v, spread);
+        let idx = split_indices!(
+            v,
+            _mm_and_si128,
+            _mm_or_si128,
+            _mm_mulhi_epu16,
+            _mm_mullo_epi16,
+            _mm_set1_epi32
+        );
+        let sym = lookup!(
+            idx,
+            lut,
+            _mm_set1_epi8,
+            _mm_subs_epu8,
+            _mm_cmpgt_epi8,
+            _mm_and_si128,
+            _mm_or_si128,
+            _mm_shuffle_epi8,
+            _mm_add_epi8
+        );
+        _mm_storeu_si128(out[o..].as_mut_ptr() as *mut __m128i, sym);
+        i += 12;
+        o += 16;
+    }
+    i
+}
+
+///
+/// Maps symbols back to their 6-bit values with range compares, returning the values and a mask
+/// of which bytes were in the alphabet.
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+macro_rules! symbol_values {
+    ($c:expr, $s62:expr, $s63:expr, $set1:ident, $cmpgt:ident, $cmpeq:ident, $and:ident, $or:ident, $add:ident) => {{
+        let c = $c;
+        let range = |lo: u8, hi: u8| {
+            $and(
+                $cmpgt(c, $set1(lo as i8 - 1)),
+                $cmpgt($set1(hi as i8 + 1), c),
+            )
+        };
+        let upper = range(b'A', b'Z');
+        let lower = range(b'a', b'z');
+        let digit = range(b'0', b'9');
+        let s62 = $cmpeq(c, $set1($s62 as i8));
+        let s63 = $cmpeq(c, $set1($s63 as i8));
+        let offset = $or(
+            $or(
+                $and(upper, $set1(-(b'A' as i8))),
+                $and(lower, $set1(26 - b'a' as i8)),
+            ),
+            $or(
+                $and(digit, $set1(52 - b'0' as i8)),
+                $or(
+                    $and(s62, $set1(62 - $s62 as i8)),
+                    $and(s63, $set1(63u8.wrapping_sub($s63) as i8)),
+                ),
+            ),
+        );
+        let valid = $or($or(upper, lower), $or(digit, $or(s62, s63)));
+        ($add(c, offset), valid)
+    }};
+}
+
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+fn first_invalid(input: &[u8], base: usize, valid_mask: u32) -> DecodeError {
+    let offset = base + (!valid_mask).trailing_zeros() as usize;
+    DecodeError::InvalidByte {
+        offset,
+        byte: input[offset],
+    }
+}
+
+///
+/// Decodes 32 symbols to 24 bytes per step, each 32 bit lane packs its four 6-bit values into 3
+/// bytes which are then gathered to the front.
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+#[target_feature(enable = "avx2")]
+unsafe fn decode_avx2(
+    alphabet: Alphabet,
+    input: &[u8],
+    out: &mut [u8],
+) -> Result<usize, DecodeError> {
+    let (s62, s63) = alphabet.last_two();
+    let gather = _mm256_setr_epi8(
+        2, 1, 0, 6, 5, 4, 10, 9, 8, 14, 13, 12, -1, -1, -1, -1, 2, 1, 0, 6, 5, 4, 10, 9, 8, 14, 13,
+        12, -1, -1, -1, -1,
+    );
+    let lanes = _mm256_setr_epi32(0, 1, 2, 4, 5, 6, 3, 7);
+    let mut i = 0;
+    let mut o = 0;
+    while i + 32 <= input.len() {
+        let c = _mm256_loadu_si256(input[i..].as_ptr() as *const __m256i);
+        let (v, valid) = symbol_values!(
+            c,
+            s62,
+            s63,
+            _mm256_set1_epi8,
+            _mm256_cmpgt_epi8,
+            _mm256_cmpeq_epi8,
+            _mm256_and_si256,
+            _mm256_or_si256,
+            _mm256_add_epi8
+        );
+        let valid = _mm256_movemask_epi8(valid) as u32;
+        if valid != u32::MAX {
+            return Err(first_invalid(input, i, valid));
+        }
+        let v = _mm256_maddubs_epi16(v, _mm256_set1_epi32(0x0140_0140));
+        let v = _mm256_madd_epi16(v, _mm256_set1_epi32(0x0001_1000));
+        let v = _mm256_permutevar8x32_epi32(_mm256_shuffle_epi8(v, gather), lanes);
+        _mm256_storeu_si256(out[o..].as_mut_ptr() as *mut __m256i, v);
+        i += 32;
+        o += 24;
+    }
+    Ok(i)
+}
+
+///
+/// Decodes 16 symbols to 12 bytes per step
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+#[target_feature(enable = "ssse3")]
+unsafe fn decode_ssse3(
+    alphabet: Alphabet,
+    input: &[u8],
+    out: &mut [u8],
+) -> Result<usize, DecodeError> {
+    let (s62, s63) = alphabet.last_two();
+    let gather = _mm_setr_epi8(2, 1, 0, 6, 5, 4, 10, 9, 8, 14, 13, 12, -1, -1, -1, -1);
+    let mut i = 0;
+    let mut o = 0;
+    while i + 16 <= input.len() {
+        let c = _mm_loadu_si128(input[i..].as_ptr() as *const __m128i);
+        let (v, valid) = symbol_values!(
+            c,
+            s62,
+            s63,
+            _mm_set1_epi8,
+            _mm_cmpgt_epi8,
+            _mm_cmpeq_epi8,
+            _mm_and_si128,
+            _mm_or_si128,
+            _mm_add_epi8
+        );
+        let valid = _mm_movemask_epi8(valid) as u32 | 0xFFFF_0000;
+        if valid != u32::MAX {
+            return Err(first_invalid(input, i, valid));
+        }
+        let v = _mm_maddubs_epi16(v, _mm_set1_epi32(0x0140_0140));
+        let v = _mm_madd_epi16(v, _mm_set1_epi32(0x0001_1000));
+        _mm_storeu_si128(
+            out[o..].as_mut_ptr() as *mut __m128i,
+            _mm_shuffle_epi8(v, gather),
+        );
+        i += 16;
+        o += 12;
+    }
+    Ok(i)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::codec::base64::{decode, decode_with, encode, encode_with, Alphabet};
+    use crate::codec::{DecodeError, Kernel};
+    use irox_tools::base64::{base64_encode, base64_encode_safe};
+    use irox_tools::random::{Random, PRNG};
+
+    #[test]
+    pub fn test_rfc4648() {
+        let tests = [
+            ("", ""),
+            ("f", "Zg=="),
+            ("fo", "Zm8="),
+            ("foo", "Zm9v"),
+            ("foob", "Zm9vYg=="),
+            ("fooba", "Zm9vYmE="),
+            ("foobar", "Zm9vYmFy"),
+        ];
+        for (raw, enc) in tests {
+            assert_eq!(enc, encode(Alphabet::Standard, raw.as_bytes()));
+            assert_eq!(
+                Ok(raw.as_bytes().to_vec()),
+                decode(Alphabet::Standard, enc.as_bytes())
+            );
+            let unpadded = enc.trim_end_matches('=');
+            assert_eq!(
+                Ok(raw.as_bytes().to_vec()),
+                decode(Alphabet::UrlSafe, unpadded.as_bytes())
+            );
+        }
+    }
+
+    #[test]
+    pub fn test_strict() {
+        let std = Alphabet::Standard;
+        let invalid = |offset: usize, byte: u8| Err(DecodeError::InvalidByte { offset, byte });
+        assert_eq!(
+            Err(DecodeError::InvalidLength { len: 5 }),
+            decode(std, b"Zm9vY")
+        );
+        assert_eq!(invalid(2, b'='), decode(std, b"Zm=v"));
+        assert_eq!(invalid(2, b'='), decode(std, b"Zm="));
+        assert_eq!(invalid(1, b'='), decode(std, b"Z==="));
+        assert_eq!(invalid(4, b'='), decode(std, b"Zm9v===="));
+        // 'h' leaves a bit set below the single decoded byte
+        assert_eq!(invalid(1, b'h'), decode(std, b"Zh=="));
+        assert_eq!(invalid(2, b'9'), decode(std, b"Zm9="));
+        assert_eq!(invalid(0, b'-'), decode(std, b"-_8="));
+        assert_eq!(Ok(vec![0xFB, 0xFF]), decode(Alphabet::UrlSafe, b"-_8="));
+    }
+
+    #[test]
+    #[allow(clippy::indexing_slicing)]
+    pub fn test_against_irox_tools() {
+        let mut rand = Random::new_seed(0xB64);
+        for _ in 0..2000 {
+            let len = rand.next_u32() as usize % 300;
+            let mut inp = vec![0u8; len];
+            rand.fill(&mut inp);
+            for alphabet in [Alphabet::Standard, Alphabet::UrlSafe] {
+                let mut exp = Vec::new();
+                let _ = match alphabet {
+                    Alphabet::Standard => base64_encode(inp.as_slice(), &mut exp),
+                    Alphabet::UrlSafe => base64_encode_safe(inp.as_slice(), &mut exp),
+                };
+                for kernel in Kernel::available() {
+                    let mut out = Vec::new();
+                    encode_with(kernel, alphabet, &inp, &mut out);
+                    assert_eq!(exp, out);
+
+                    out.clear();
+                    assert_eq!(Ok(len), decode_with(kernel, alphabet, &exp, &mut out));
+                    assert_eq!(inp, out);
+
+                    let data_len = exp.iter().take_while(|c| **c != b'=').count();
+                    if data_len == 0 {
+                        continue;
+                    }
+                    // any one bad character is reported where it is, and nothing is appended
+                    let mut bad = exp.clone();
+                    let pos = rand.next_u32() as usize % data_len;
+                    bad[pos] = [b'=', b'.', b'*', b' ', b'\n', b'@', 0x80, 0xFF][pos % 8];
+                    let mut out = vec![1u8];
+                    assert_eq!(
+                        Err(DecodeError::InvalidByte {
+                            offset: pos,
+                            byte: bad[pos]
+                        }),
+                        decode_with(kernel, alphabet, &bad, &mut out)
+                    );
+                    assert_eq!(vec![1u8], out);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,359 @@
+// SPDX-License-Identifier: MIT
+// Copyright 2025 IROX Contributors
+//
+
+//!
+//! Hex (base16) encoding in either case, and decoding that accepts both.  The nibbles are
+//! translated 32 bytes at a time with `pshufb` on AVX2, 16 on SSSE3.
+//!
+
+#![allow(clippy::indexing_slicing)]
+
+use super::{DecodeError, Kernel};
+use alloc::string::String;
+use alloc::vec::Vec;
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+use core::arch::x86_64::{
+    _mm256_and_si256, _mm256_broadcastsi128_si256, _mm256_cmpgt_epi8, _mm256_loadu_si256,
+    _mm256_maddubs_epi16, _mm256_movemask_epi8, _mm256_or_si256, _mm256_packus_epi16,
+    _mm256_permute2x128_si256, _mm256_permute4x64_epi64, _mm256_set1_epi16, _mm256_set1_epi8,
+    _mm256_shuffle_epi8, _mm256_srli_epi16, _mm256_storeu_si256, _mm256_sub_epi8,
+    _mm256_unpackhi_epi8, _mm256_unpacklo_epi8, _mm_and_si128, _mm_cmpgt_epi8, _mm_loadu_si128,
+    _mm_maddubs_epi16, _mm_movemask_epi8, _mm_or_si128, _mm_packus_epi16, _mm_set1_epi16,
+    _mm_set1_epi8, _mm_shuffle_epi8, _mm_srli_epi16, _mm_storeu_si128, _mm_sub_epi8,
+    _mm_unpackhi_epi8, _mm_unpacklo_epi8,
+};
+
+static LOWER: &[u8; 16] = b"0123456789abcdef";
+static UPPER: &[u8; 16] = b"0123456789ABCDEF";
+
+///
+/// Encodes `input` as lowercase hex
+#[must_use]
+pub fn encode_lower(input: &[u8]) -> String {
+    to_string(input, LOWER)
+}
+
+///
+/// Encodes `input` as uppercase hex
+#[must_use]
+pub fn encode_upper(input: &[u8]) -> String {
+    to_string(input, UPPER)
+}
+
+///
+/// Appends the lowercase hex of `input` to `out`
+pub fn encode_lower_into(input: &[u8], out: &mut Vec<u8>) {
+    encode_with(Kernel::detect(), input, out, LOWER);
+}
+
+///
+/// Appends the uppercase hex of `input` to `out`
+pub fn encode_upper_into(input: &[u8], out: &mut Vec<u8>) {
+    encode_with(Kernel::detect(), input, out, UPPER);
+}
+
+///
+/// Decodes hex of either (or mixed) case.  Whitespace and separators aren't accepted.
+pub fn decode(input: &[u8]) -> Result<Vec<u8>, DecodeError> {
+    let mut out = Vec::new();
+    decode_into(input, &mut out)?;
+    Ok(out)
+}
+
+///
+/// Decodes hex onto the end of `out`, returning the number of bytes appended.  On error `out` is
+/// left as it was.
+pub fn decode_into(input: &[u8], out: &mut Vec<u8>) -> Result<usize, DecodeError> {
+    decode_with(Kernel::detect(), input, out)
+}
+
+fn to_string(input: &[u8], alphabet: &[u8; 16]) -> String {
+    let mut out = Vec::with_capacity(input.len() * 2);
+    encode_with(Kernel::detect(), input, &mut out, alphabet);
+    // SAFETY: every byte written came from the ASCII alphabet
+    unsafe { String::from_utf8_unchecked(out) }
+}
+
+pub(crate) fn encode_with(kernel: Kernel, input: &[u8], out: &mut Vec<u8>, alphabet: &[u8; 16]) {
+    let start = out.len();
+    out.resize(start + input.len() * 2, 0);
+    let dst = &mut out[start..];
+    let done = match kernel {
+        #[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+        Kernel::Avx2 => unsafe { encode_avx2(input, dst, alphabet) },
+        #[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+        Kernel::Ssse3 => unsafe { encode_ssse3(input, dst, alphabet) },
+        Kernel::Scalar => 0,
+    };
+    for (v, o) in input[done..]
+        .iter()
+        .zip(dst[done * 2..].chunks_exact_mut(2))
+    {
+        o[0] = alphabet[(v >> 4) as usize];
+        o[1] = alphabet[(v & 0xF) as usize];
+    }
+}
+
+pub(crate) fn decode_with(
+    kernel: Kernel,
+    input: &[u8],
+    out: &mut Vec<u8>,
+) -> Result<usize, DecodeError> {
+    if input.len() % 2 != 0 {
+        return Err(DecodeError::InvalidLength { len: input.len() });
+    }
+    let start = out.len();
+    let len = input.len() / 2;
+    out.resize(start + len, 0);
+    let dst = &mut out[start..];
+    let res = match kernel {
+        #[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+        Kernel::Avx2 => unsafe { decode_avx2(input, dst) },
+        #[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+        Kernel::Ssse3 => unsafe { decode_ssse3(input, dst) },
+        Kernel::Scalar => Ok(0),
+    }
+    .and_then(|done| {
+        for (i, o) in dst[done / 2..].iter_mut().enumerate() {
+            let offset = done + i * 2;
+            *o = (nibble(input, offset)? << 4) | nibble(input, offset + 1)?;
+        }
+        Ok(len)
+    });
+    if res.is_err() {
+        out.truncate(start);
+    }
+    res
+}
+
+fn nibble(input: &[u8], offset: usize) -> Result<u8, DecodeError> {
+    let byte = input[offset];
+    match byte {
+        b'0'..=b'9' => Ok(byte - b'0'),
+        b'a'..=b'f' => Ok(byte - b'a' + 10),
+        b'A'..=b'F' => Ok(byte - b'A' + 10),
+        _ => Err(DecodeError::InvalidByte { offset, byte }),
+    }
+}
+
+///
+/// The error for the first zero bit of `valid_mask`, which covers the input from `base`
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+fn first_invalid(input: &[u8], base: usize, valid_mask: u64) -> DecodeError {
+    let offset = base + (!valid_mask).trailing_zeros() as usize;
+    DecodeError::InvalidByte {
+        offset,
+        byte: input[offset],
+    }
+}
+
+///
+/// Splits each byte into its nibbles and maps them through the alphabet, 32 bytes per step
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+#[target_feature(enable = "avx2")]
+unsafe fn encode_avx2(input: &[u8], out: &mut [u8], alphabet: &[u8; 16]) -> usize {
+    let lut = _mm256_broadcastsi128_si256(_mm_loadu_si128(alphabet.as_ptr() as *const _));
+    let mask = _mm256_set1_epi8(0x0F);
+    let mut done = 0;
+    for (src, dst) in input.chunks_exact(32).zip(out.chunks_exact_mut(64)) {
+        let v = _mm256_loadu_si256(src.as_ptr() as *const _);
+        let hi = _mm256_shuffle_epi8(lut, _mm256_and_si256(_mm256_srli_epi16::<4>(v), mask));
+        let lo = _mm256_shuffle_epi8(lut, _mm256_and_si256(v, mask));
+        // the unpacks interleave within each 128 bit lane, the permutes put the lanes in order
+        let a = _mm256_unpacklo_epi8(hi, lo);
+        let b = _mm256_unpackhi_epi8(hi, lo);
+        let dp = dst.as_mut_ptr();
+        _mm256_storeu_si256(dp as *mut _, _mm256_permute2x128_si256::<0x20>(a, b));
+        _mm256_storeu_si256(
+            dp.add(32) as *mut _,
+            _mm256_permute2x128_si256::<0x31>(a, b),
+        );
+        done += 32;
+    }
+    done
+}
+
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+#[target_feature(enable = "ssse3")]
+unsafe fn encode_ssse3(input: &[u8], out: &mut [u8], alphabet: &[u8; 16]) -> usize {
+    let lut = _mm_loadu_si128(alphabet.as_ptr() as *const _);
+    let mask = _mm_set1_epi8(0x0F);
+    let mut done = 0;
+    for (src, dst) in input.chunks_exact(16).zip(out.chunks_exact_mut(32)) {
+        let v = _mm_loadu_si128(src.as_ptr() as *const _);
+        let hi = _mm_shuffle_epi8(lut, _mm_and_si128(_mm_srli_epi16::<4>(v), mask));
+        let lo = _mm_shuffle_epi8(lut, _mm_and_si128(v, mask));
+        let dp = dst.as_mut_ptr();
+        _mm_storeu_si128(dp as *mut _, _mm_unpacklo_epi8(hi, lo));
+        _mm_storeu_si128(dp.add(16) as *mut _, _mm_unpackhi_epi8(hi, lo));
+        done += 16;
+    }
+    done
+}
+
+/// Maps a vector of hex characters to their nibble values, and a mask of which were valid.
+/// Letters are matched after setting the 0x20 bit, which folds uppercase onto lowercase.
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+macro_rules! nibbles {
+    ($c:expr, $set1:ident, $cmpgt:ident, $and:ident, $or:ident, $sub:ident) => {{
+        let c = $c;
+        let folded = $or(c, $set1(0x20));
+        let digit = $and(
+            $cmpgt(c, $set1(b'0' as i8 - 1)),
+            $cmpgt($set1(b'9' as i8 + 1), c),
+        );
+        let alpha = $and(
+            $cmpgt(folded, $set1(b'a' as i8 - 1)),
+            $cmpgt($set1(b'f' as i8 + 1), folded),
+        );
+        let value = $or(
+            $and(digit, $sub(c, $set1(b'0' as i8))),
+            $and(alpha, $sub(folded, $set1(b'a' as i8 - 10))),
+        );
+        (value, $or(digit, alpha))
+    }};
+}
+
+///
+/// Decodes 64 characters to 32 bytes per step, returning the characters consumed
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+#[target_feature(enable = "avx2")]
+unsafe fn decode_avx2(input: &[u8], out: &mut [u8]) -> Result<usize, DecodeError> {
+    let weights = _mm256_set1_epi16(0x0110);
+    let mut done = 0;
+    for (src, dst) in input.chunks_exact(64).zip(out.chunks_exact_mut(32)) {
+        let sp = src.as_ptr();
+        let (a, va) = nibbles!(
+            _mm256_loadu_si256(sp as *const _),
+            _mm256_set1_epi8,
+            _mm256_cmpgt_epi8,
+            _mm256_and_si256,
+            _mm256_or_si256,
+            _mm256_sub_epi8
+        );
+        let (b, vb) = nibbles!(
+            _mm256_loadu_si256(sp.add(32) as *const _),
+            _mm256_set1_epi8,
+            _mm256_cmpgt_epi8,
+            _mm256_and_si256,
+            _mm256_or_si256,
+            _mm256_sub_epi8
+        );
+        let valid = u64::from(_mm256_movemask_epi8(va) as u32)
+            | (u64::from(_mm256_movemask_epi8(vb) as u32) << 32);
+        if valid != u64::MAX {
+            return Err(first_invalid(input, done, valid));
+        }
+        // hi * 16 + lo for each pair, then narrow the words back to bytes
+        let a = _mm256_maddubs_epi16(a, weights);
+        let b = _mm256_maddubs_epi16(b, weights);
+        let packed = _mm256_permute4x64_epi64::<0xD8>(_mm256_packus_epi16(a, b));
+        _mm256_storeu_si256(dst.as_mut_ptr() as *mut _, packed);
+        done += 64;
+    }
+    Ok(done)
+}
+
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+#[target_feature(enable = "ssse3")]
+unsafe fn decode_ssse3(input: &[u8], out: &mut [u8]) -> Result<usize, DecodeError> {
+    let weights = _mm_set1_epi16(0x0110);
+    let mut done = 0;
+    for (src, dst) in input.chunks_exact(32).zip(out.chunks_exact_mut(16)) {
+        let sp = src.as_ptr();
+        let (a, va) = nibbles!(
+            _mm_loadu_si128(sp as *const _),
+            _mm_set1_epi8,
+            _mm_cmpgt_epi8,
+            _mm_and_si128,
+            _mm_or_si128,
+            _mm_sub_epi8
+        );
+        let (b, vb) = nibbles!(
+            _mm_loadu_si128(sp.add(16) as *const _),
+            _mm_set1_epi8,
+            _mm_cmpgt_epi8,
+            _mm_and_si128,
+            _mm_or_si128,
+            _mm_sub_epi8
+        );
+        let valid = u64::from(_mm_movemask_epi8(va) as u16)
+            | (u64::from(_mm_movemask_epi8(vb) as u16) << 16)
+            | (u64::MAX << 32);
+        if valid != u64::MAX {
+            return Err(first_invalid(input, done, valid));
+        }
+        let a = _mm_maddubs_epi16(a, weights);
+        let b = _mm_maddubs_epi16(b, weights);
+        _mm_storeu_si128(dst.as_mut_ptr() as *mut _, _mm_packus_epi16(a, b));
+        done += 32;
+    }
+    Ok(done)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::codec::hex::{decode, decode_with, encode_lower, encode_upper, encode_with};
+    use crate::codec::hex::{LOWER, UPPER};
+    use crate::codec::{DecodeError, Kernel};
+    use irox_tools::random::{Random, PRNG};
+
+    #[test]
+    pub fn test_known() {
+        assert_eq!("", encode_lower(&[]));
+        assert_eq!("00ff7f80", encode_lower(&[0, 0xFF, 0x7F, 0x80]));
+        assert_eq!("DEADBEEF", encode_upper(&[0xDE, 0xAD, 0xBE, 0xEF]));
+        assert_eq!(Ok(vec![0xDE, 0xAD, 0xBE, 0xEF]), decode(b"DeAdbEEF"));
+        assert_eq!(Err(DecodeError::InvalidLength { len: 3 }), decode(b"abc"));
+        assert_eq!(
+            Err(DecodeError::InvalidByte {
+                offset: 2,
+                byte: b'g'
+            }),
+            decode(b"abgd")
+        );
+    }
+
+    #[test]
+    #[allow(clippy::indexing_slicing)]
+    pub fn test_against_irox_tools() {
+        let mut rand = Random::new_seed(0x4E58);
+        for _ in 0..2000 {
+            let len = rand.next_u32() as usize % 300;
+            let mut inp = vec![0u8; len];
+            rand.fill(&mut inp);
+            let lower = irox_tools::hex::to_hex_str_lower(&inp);
+            let upper = irox_tools::hex::to_hex_str_upper(&inp);
+            for kernel in Kernel::available() {
+                let mut out = Vec::new();
+                encode_with(kernel, &inp, &mut out, LOWER);
+                assert_eq!(lower.as_bytes(), out);
+                out.clear();
+                encode_with(kernel, &inp, &mut out, UPPER);
+                assert_eq!(upper.as_bytes(), out);
+
+                out.clear();
+                assert_eq!(Ok(len), decode_with(kernel, upper.as_bytes(), &mut out));
+                assert_eq!(inp, out);
+
+                if len == 0 {
+                    continue;
+                }
+                // any one bad character is reported where it is, and nothing is appended
+                let mut bad = lower.clone().into_bytes();
+                let pos = rand.next_u32() as usize % bad.len();
+                bad[pos] = [b'g', b'G', b' ', b'/', b':', b'@', 0x80, 0xFF][pos % 8];
+                let mut out = vec![1u8];
+                assert_eq!(
+                    Err(DecodeError::InvalidByte {
+                        offset: pos,
+                        byte: bad[pos]
+                    }),
+                    decode_with(kernel, &bad, &mut out)
+                );
+                assert_eq!(vec![1u8], out);
+            }
+        }
+    }
+}
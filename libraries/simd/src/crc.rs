@@ -0,0 +1,479 @@
+// SPDX-License-Identifier: MIT
+// Copyright 2025 IROX Contributors
+//
+
+//!
+//! Reflected CRCs: CRC-32 (IEEE 802.3), CRC-32C (Castagnoli), CRC-64/ECMA (the ECMA-182
+//! polynomial in the reflected form used by xz) and CRC-64/NVME.
+//!
+//! Long inputs are folded 64 bytes at a time with `PCLMULQDQ` (Intel, "Fast CRC Computation for
+//! Generic Polynomials Using PCLMULQDQ Instruction"), the 16 bytes left after folding and any
+//! short input go through the byte-wise table.  CRC-32C additionally has the SSE4.2 `crc32`
+//! instruction, which it runs over three interleaved streams for the medium-sized inputs that
+//! don't amortize the folding setup.  Without the instructions, per the [`dispatch::features`],
+//! everything goes through the table.
+//!
+
+#![allow(clippy::indexing_slicing)]
+
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+use crate::dispatch;
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+use core::arch::x86_64::{
+    __m128i, _mm_clmulepi64_si128, _mm_crc32_u64, _mm_crc32_u8, _mm_cvtsi128_si64,
+    _mm_cvtsi64_si128, _mm_loadu_si128, _mm_set_epi64x, _mm_storeu_si128, _mm_xor_si128,
+};
+
+/// Bytes folded per step, 4 independent 128-bit accumulators
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+const FOLD_STEP: usize = 64;
+
+///
+/// The parameters of a reflected CRC with `init` and `xorout` both all-ones, and everything
+/// derived from them at compile time.
+struct CrcParams {
+    /// width in bits, 32 or 64
+    width: u32,
+    /// the lookup table for the byte-wise update
+    table: [u64; 256],
+    /// the keys to fold a 128-bit accumulator 512 bits (4 accumulators) forward
+    #[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+    fold_512: (u64, u64),
+    /// the keys to fold a 128-bit accumulator 128 bits forward
+    #[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+    fold_128: (u64, u64),
+    /// the reflected polynomial
+    poly: u64,
+}
+
+impl CrcParams {
+    const fn new(width: u32, poly: u64) -> Self {
+        let mut table = [0u64; 256];
+        let mut i = 0;
+        while i < 256 {
+            let mut c = i as u64;
+            let mut b = 0;
+            while b < 8 {
+                c = if c & 1 == 1 { (c >> 1) ^ poly } else { c >> 1 };
+                b += 1;
+            }
+            table[i] = c;
+            i += 1;
+        }
+        Self {
+            width,
+            table,
+            #[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+            fold_512: fold_keys(width, poly, 512),
+            #[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+            fold_128: fold_keys(width, poly, 128),
+            poly,
+        }
+    }
+
+    const fn mask(&self) -> u64 {
+        u64::MAX >> (64 - self.width)
+    }
+}
+
+///
+/// Computes `x^e mod P` in the normal (unreflected) bit order, bit `i` is the coefficient of `x^i`.
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+const fn xpow_mod(width: u32, poly: u64, e: u32) -> u64 {
+    // the polynomial without its x^width term, in normal bit order
+    let norm = poly.reverse_bits() >> (64 - width);
+    let top = 1u64 << (width - 1);
+    let mut r = 1u64;
+    let mut i = 0;
+    while i < e {
+        r = if r & top != 0 {
+            (r << 1) ^ norm
+        } else {
+            r << 1
+        };
+        i += 1;
+    }
+    r & (u64::MAX >> (64 - width))
+}
+
+///
+/// The `(lo, hi)` keys to move a 128-bit accumulator `dist` bits forward.  The low half of the
+/// accumulator holds the higher-degree terms, it's multiplied by `x^(dist+64)`, and the high half
+/// by `x^dist`.  Each key is one power lower to cancel the extra `x` a reflected carry-less
+/// multiply introduces.
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+const fn fold_keys(width: u32, poly: u64, dist: u32) -> (u64, u64) {
+    (
+        xpow_mod(width, poly, dist + 63).reverse_bits(),
+        xpow_mod(width, poly, dist - 1).reverse_bits(),
+    )
+}
+
+static CRC32: CrcParams = CrcParams::new(32, 0xEDB8_8320);
+static CRC32C: CrcParams = CrcParams::new(32, 0x82F6_3B78);
+static CRC64_ECMA: CrcParams = CrcParams::new(64, 0xC96C_5795_D787_0F42);
+static CRC64_NVME: CrcParams = CrcParams::new(64, 0x9A6C_9329_AC4B_C9B5);
+
+///
+/// Byte-at-a-time table update of the raw CRC register, this is also the scalar reference the
+/// accelerated paths are tested against.
+fn update_table(p: &CrcParams, mut crc: u64, data: &[u8]) -> u64 {
+    for b in data {
+        crc = p.table[((crc ^ u64::from(*b)) & 0xFF) as usize] ^ (crc >> 8);
+    }
+    crc
+}
+
+///
+/// Multiplies two reflected polynomials mod P, where `1` is the top bit of the register.
+fn mul_mod(p: &CrcParams, mut a: u64, mut b: u64) -> u64 {
+    let mut prod = 0;
+    let mut m = 1u64 << (p.width - 1);
+    while m != 0 && a != 0 {
+        if a & m != 0 {
+            prod ^= b;
+            a ^= m;
+        }
+        b = if b & 1 == 1 {
+            (b >> 1) ^ p.poly
+        } else {
+            b >> 1
+        };
+        m >>= 1;
+    }
+    prod
+}
+
+///
+/// Multiplies the reflected CRC value `crc` by `x^(8 * len)` mod P - the effect on the register of
+/// running `len` zero bytes through it.
+fn shift(p: &CrcParams, crc: u64, mut len: u64) -> u64 {
+    // x^8, reflected
+    let mut sq = 1u64 << (p.width - 9);
+    let mut r = crc;
+    while len != 0 {
+        if len & 1 == 1 {
+            r = mul_mod(p, r, sq);
+        }
+        sq = mul_mod(p, sq, sq);
+        len >>= 1;
+    }
+    r
+}
+
+///
+/// `crc(A || B)` from `crc(A)`, `crc(B)` and `len(B)`.  The all-ones `init` of `B` cancels
+/// against the all-ones `xorout` of `A`, so it's only the shift of `crc(A)` past `B`.
+fn combine(p: &CrcParams, crc_a: u64, crc_b: u64, len_b: u64) -> u64 {
+    shift(p, crc_a, len_b) ^ crc_b
+}
+
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+macro_rules! fold {
+    ($acc:expr, $keys:expr) => {
+        _mm_xor_si128(
+            _mm_clmulepi64_si128::<0x00>($acc, $keys),
+            _mm_clmulepi64_si128::<0x11>($acc, $keys),
+        )
+    };
+}
+
+///
+/// Folds `data` (at least [`FOLD_STEP`] bytes) into a single 128-bit accumulator, starting from
+/// the raw register `crc`.  Returns the accumulator, which has the same CRC as the input, and the
+/// trailing bytes that didn't fill a 16-byte block.
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+#[target_feature(enable = "sse2,pclmulqdq")]
+unsafe fn fold_blocks<'a>(p: &CrcParams, crc: u64, data: &'a [u8]) -> ([u8; 16], &'a [u8]) {
+    let load = |b: &[u8]| _mm_loadu_si128(b.as_ptr() as *const __m128i);
+    let keys = |(lo, hi): (u64, u64)| _mm_set_epi64x(hi as i64, lo as i64);
+
+    let (first, mut rest) = data.split_at(FOLD_STEP);
+    // the register is the first `width` bits of the message, it's xor'd into the first block
+    let mut x0 = _mm_xor_si128(load(first), _mm_cvtsi64_si128(crc as i64));
+    let mut x1 = load(&first[16..]);
+    let mut x2 = load(&first[32..]);
+    let mut x3 = load(&first[48..]);
+
+    let k512 = keys(p.fold_512);
+    while rest.len() >= FOLD_STEP {
+        x0 = _mm_xor_si128(fold!(x0, k512), load(rest));
+        x1 = _mm_xor_si128(fold!(x1, k512), load(&rest[16..]));
+        x2 = _mm_xor_si128(fold!(x2, k512), load(&rest[32..]));
+        x3 = _mm_xor_si128(fold!(x3, k512), load(&rest[48..]));
+        rest = &rest[FOLD_STEP..];
+    }
+
+    let k128 = keys(p.fold_128);
+    let mut x = _mm_xor_si128(fold!(x0, k128), x1);
+    x = _mm_xor_si128(fold!(x, k128), x2);
+    x = _mm_xor_si128(fold!(x, k128), x3);
+    while rest.len() >= 16 {
+        x = _mm_xor_si128(fold!(x, k128), load(rest));
+        rest = &rest[16..];
+    }
+
+    let mut out = [0u8; 16];
+    _mm_storeu_si128(out.as_mut_ptr() as *mut __m128i, x);
+    (out, rest)
+}
+
+///
+/// Updates the raw register with the folding path for long inputs and the table otherwise.
+fn update_folded(p: &CrcParams, crc: u64, data: &[u8]) -> u64 {
+    #[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+    if data.len() >= FOLD_STEP * 2 && dispatch::features().pclmulqdq {
+        let (acc, rest) = unsafe { fold_blocks(p, crc, data) };
+        let crc = update_table(p, 0, &acc);
+        return update_table(p, crc, rest);
+    }
+    update_table(p, crc, data)
+}
+
+/// Bytes per stream for the interleaved CRC-32C instruction path.
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+const CRC32C_STRIPE: usize = 512;
+/// Above this the folding path is faster than the interleaved instructions.
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+const CRC32C_FOLD_MIN: usize = 4096;
+
+///
+/// `x^(8 * CRC32C_STRIPE - 33)`, reflected into the low 32 bits.  A carry-less multiply by this
+/// followed by a `crc32` of the 64-bit product shifts a CRC-32C past one stripe.
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+const CRC32C_STRIPE_KEY: u64 =
+    (xpow_mod(32, 0x82F6_3B78, (8 * CRC32C_STRIPE - 33) as u32) as u32).reverse_bits() as u64;
+
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+#[target_feature(enable = "sse4.2,pclmulqdq")]
+unsafe fn crc32c_shift_stripe(crc: u32) -> u32 {
+    let prod = _mm_clmulepi64_si128::<0x00>(
+        _mm_cvtsi64_si128(i64::from(crc)),
+        _mm_cvtsi64_si128(CRC32C_STRIPE_KEY as i64),
+    );
+    _mm_crc32_u64(0, _mm_cvtsi128_si64(prod) as u64) as u32
+}
+
+///
+/// Runs the `crc32` instruction over `data`, three stripes at a time while there are enough
+/// bytes, as each instruction has a latency of 3 cycles but a throughput of 1.
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+#[target_feature(enable = "sse4.2,pclmulqdq")]
+unsafe fn crc32c_hw(crc: u32, data: &[u8]) -> u32 {
+    let word = |b: &[u8]| u64::from_le_bytes(b[..8].try_into().unwrap_or_default());
+    let mut crc = u64::from(crc);
+    let mut chunks = data.chunks_exact(CRC32C_STRIPE * 3);
+    for chunk in chunks.by_ref() {
+        let (a, bc) = chunk.split_at(CRC32C_STRIPE);
+        let (b, c) = bc.split_at(CRC32C_STRIPE);
+        let (mut ca, mut cb, mut cc) = (crc, 0u64, 0u64);
+        for i in (0..CRC32C_STRIPE).step_by(8) {
+            ca = _mm_crc32_u64(ca, word(&a[i..]));
+            cb = _mm_crc32_u64(cb, word(&b[i..]));
+            cc = _mm_crc32_u64(cc, word(&c[i..]));
+        }
+        let ab = crc32c_shift_stripe(ca as u32) ^ cb as u32;
+        crc = u64::from(crc32c_shift_stripe(ab) ^ cc as u32);
+    }
+    let mut words = chunks.remainder().chunks_exact(8);
+    for w in words.by_ref() {
+        crc = _mm_crc32_u64(crc, word(w));
+    }
+    let mut crc = crc as u32;
+    for b in words.remainder() {
+        crc = _mm_crc32_u8(crc, *b);
+    }
+    crc
+}
+
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+fn update_crc32c(crc: u64, data: &[u8]) -> u64 {
+    let features = dispatch::features();
+    if !features.sse42 || !features.pclmulqdq {
+        return update_table(&CRC32C, crc, data);
+    }
+    if data.len() < CRC32C_FOLD_MIN {
+        return u64::from(unsafe { crc32c_hw(crc as u32, data) });
+    }
+    let (acc, rest) = unsafe { fold_blocks(&CRC32C, crc, data) };
+    let crc = unsafe { crc32c_hw(0, &acc) };
+    u64::from(unsafe { crc32c_hw(crc, rest) })
+}
+
+#[cfg(not(all(target_arch = "x86_64", target_feature = "sse2")))]
+fn update_crc32c(crc: u64, data: &[u8]) -> u64 {
+    update_table(&CRC32C, crc, data)
+}
+
+macro_rules! crc_impl {
+    ($(#[$meta:meta])* $name:ident, $ty:ty, $params:ident, $update:expr) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone)]
+        pub struct $name {
+            crc: u64,
+        }
+        impl Default for $name {
+            fn default() -> Self {
+                Self {
+                    crc: $params.mask(),
+                }
+            }
+        }
+        impl $name {
+            #[must_use]
+            pub fn new() -> Self {
+                Self::default()
+            }
+
+            pub fn write(&mut self, v: &[u8]) {
+                self.crc = $update(self.crc, v);
+            }
+
+            pub fn hash(mut self, v: &[u8]) -> $ty {
+                self.write(v);
+                self.finish()
+            }
+
+            #[must_use]
+            pub fn finish(self) -> $ty {
+                (self.crc ^ $params.mask()) as $ty
+            }
+
+            ///
+            /// Returns the CRC of the concatenation `A || B`, given the CRC of `A`, the CRC of
+            /// `B` and the length of `B` in bytes.
+            #[must_use]
+            pub fn combine(crc_a: $ty, crc_b: $ty, len_b: u64) -> $ty {
+                combine(&$params, crc_a as u64, crc_b as u64, len_b) as $ty
+            }
+        }
+        impl_io_write!($name => $ty);
+    };
+}
+
+crc_impl!(
+    ///
+    /// CRC-32 (IEEE 802.3, zlib, PNG), check value `0xCBF43926`
+    Crc32,
+    u32,
+    CRC32,
+    |crc, v| update_folded(&CRC32, crc, v)
+);
+crc_impl!(
+    ///
+    /// CRC-32C (Castagnoli, iSCSI, ext4), check value `0xE3069283`
+    Crc32c,
+    u32,
+    CRC32C,
+    update_crc32c
+);
+crc_impl!(
+    ///
+    /// CRC-64/ECMA, the ECMA-182 polynomial reflected with all-ones init and xorout as used by
+    /// xz, check value `0x995DC9BBDF1939FA`
+    Crc64Ecma,
+    u64,
+    CRC64_ECMA,
+    |crc, v| update_folded(&CRC64_ECMA, crc, v)
+);
+crc_impl!(
+    ///
+    /// CRC-64/NVME (NVM Express), check value `0xAE8B14860A799888`
+    Crc64Nvme,
+    u64,
+    CRC64_NVME,
+    |crc, v| update_folded(&CRC64_NVME, crc, v)
+);
+
+#[cfg(test)]
+mod tests {
+    use crate::crc::{
+        update_table, Crc32, Crc32c, Crc64Ecma, Crc64Nvme, CrcParams, CRC32, CRC32C, CRC64_ECMA,
+        CRC64_NVME,
+    };
+    use irox_tools::assert_eq_hex;
+
+    fn reference(p: &CrcParams, data: &[u8]) -> u64 {
+        update_table(p, p.mask(), data) ^ p.mask()
+    }
+
+    #[test]
+    pub fn test_check_values() {
+        let check = b"123456789";
+        assert_eq_hex!(0xCBF43926, Crc32::new().hash(check));
+        assert_eq_hex!(0xE3069283, Crc32c::new().hash(check));
+        assert_eq_hex!(0x995DC9BBDF1939FA, Crc64Ecma::new().hash(check));
+        assert_eq_hex!(0xAE8B14860A799888, Crc64Nvme::new().hash(check));
+        assert_eq_hex!(0, Crc32::new().hash(&[]));
+        assert_eq_hex!(0, Crc64Nvme::new().hash(&[]));
+    }
+
+    #[test]
+    pub fn test_differential() {
+        let mut inp = vec![0u8; 10000];
+        for (i, v) in inp.iter_mut().enumerate() {
+            *v = (i as u8).wrapping_mul(73).wrapping_add((i >> 8) as u8);
+        }
+        let lens = (0..300).chain([511, 1024, 1535, 1536, 1537, 4095, 4096, 4097, 6000, 9999]);
+        for len in lens {
+            // offset by one to check unaligned loads
+            let msg = &inp[1..=len];
+            assert_eq_hex!(reference(&CRC32, msg) as u32, Crc32::new().hash(msg));
+            assert_eq_hex!(reference(&CRC32C, msg) as u32, Crc32c::new().hash(msg));
+            assert_eq_hex!(reference(&CRC64_ECMA, msg), Crc64Ecma::new().hash(msg));
+            assert_eq_hex!(reference(&CRC64_NVME, msg), Crc64Nvme::new().hash(msg));
+        }
+    }
+
+    #[test]
+    pub fn test_streaming() {
+        let mut inp = vec![0u8; 5000];
+        for (i, v) in inp.iter_mut().enumerate() {
+            *v = (i as u8).wrapping_mul(29).wrapping_add(11);
+        }
+        for step in [1, 7, 16, 63, 64, 65, 200, 1536, 4096] {
+            let mut a = Crc32::new();
+            let mut b = Crc32c::new();
+            let mut c = Crc64Ecma::new();
+            let mut d = Crc64Nvme::new();
+            for chunk in inp.chunks(step) {
+                a.write(chunk);
+                b.write(chunk);
+                c.write(chunk);
+                d.write(chunk);
+            }
+            assert_eq_hex!(Crc32::new().hash(&inp), a.finish());
+            assert_eq_hex!(Crc32c::new().hash(&inp), b.finish());
+            assert_eq_hex!(Crc64Ecma::new().hash(&inp), c.finish());
+            assert_eq_hex!(Crc64Nvme::new().hash(&inp), d.finish());
+        }
+    }
+
+    #[test]
+    pub fn test_combine() {
+        let mut inp = vec![0u8; 3000];
+        for (i, v) in inp.iter_mut().enumerate() {
+            *v = (i as u8).wrapping_mul(131).wrapping_add(5);
+        }
+        for split in [0, 1, 9, 100, 1000, 2999, 3000] {
+            let (a, b) = inp.split_at(split);
+            let len_b = b.len() as u64;
+            assert_eq_hex!(
+                Crc32::new().hash(&inp),
+                Crc32::combine(Crc32::new().hash(a), Crc32::new().hash(b), len_b)
+            );
+            assert_eq_hex!(
+                Crc32c::new().hash(&inp),
+                Crc32c::combine(Crc32c::new().hash(a), Crc32c::new().hash(b), len_b)
+            );
+            assert_eq_hex!(
+                Crc64Ecma::new().hash(&inp),
+                Crc64Ecma::combine(Crc64Ecma::new().hash(a), Crc64Ecma::new().hash(b), len_b)
+            );
+            assert_eq_hex!(
+                Crc64Nvme::new().hash(&inp),
+                Crc64Nvme::combine(Crc64Nvme::new().hash(a), Crc64Nvme::new().hash(b), len_b)
+            );
+        }
+    }
+}
@@ -0,0 +1,381 @@
+// SPDX-License-Identifier: MIT
+// Copyright 2025 IROX Contributors
+//
+
+//!
+//! Runtime CPU feature dispatch.  The CPU is probed once, on first use, and every algorithm in
+//! this crate picks its implementation from the resulting [`Level`] - nothing runs an instruction
+//! the CPU doesn't have, whatever the crate was compiled with.
+//!
+//! On x86-64 the levels run from [`Level::Scalar`] up to [`Level::Avx512`].  On aarch64 there's
+//! only [`Level::Neon`], which every aarch64 CPU has, and every other target runs the scalar code.
+//! The soft-float x86-64 targets (`x86_64-unknown-none`, `x86_64-unknown-uefi`) are the
+//! exception: they don't have SSE2 in their baseline, so there are no vector registers for the
+//! intrinsics to lower to, and the x86-64 kernels aren't compiled for them at all.
+//!
+//! Without the `std` feature there's no runtime detection, and the level comes from the target
+//! features the crate was compiled with instead (`-C target-cpu`/`-C target-feature`).
+//!
+//! The level can be lowered, never raised past what the CPU supports:
+//! * for the whole process with the `IROX_SIMD_LEVEL` environment variable (`scalar`, `neon`,
+//!   `sse4.1`, `avx2` or `avx512`), read once at detection.  This is how CI runs the tests at
+//!   every level on one machine.  Needs the `std` feature.
+//! * at runtime with [`force_level`].  Every level produces identical output and shares the same
+//!   state layout, so it's safe to change while hashers are in progress.
+//!
+
+use core::fmt::{Display, Formatter};
+use core::sync::atomic::{AtomicU8, Ordering};
+
+///
+/// A rung on the SIMD ladder.  Each level includes everything below it that the target
+/// architecture has - [`Level::Neon`] and the x86-64 levels never apply together.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[repr(u8)]
+pub enum Level {
+    /// Portable code only
+    Scalar = 1,
+    /// aarch64 Advanced SIMD, 128-bit vectors
+    Neon = 2,
+    /// SSE2 through SSE4.1, including SSSE3
+    Sse41 = 3,
+    /// AVX and AVX2
+    Avx2 = 4,
+    /// AVX-512 F, BW and VL.  The AVX-512 intrinsics aren't stable on the toolchain this crate
+    /// is pinned to, so at this level the AVX2 implementations run.
+    Avx512 = 5,
+}
+
+impl Level {
+    /// Every level, lowest first
+    pub const ALL: [Level; 5] = [
+        Level::Scalar,
+        Level::Neon,
+        Level::Sse41,
+        Level::Avx2,
+        Level::Avx512,
+    ];
+
+    /// Whether the target architecture has this level at all
+    #[must_use]
+    pub const fn is_native(self) -> bool {
+        match self {
+            Level::Scalar => true,
+            Level::Neon => cfg!(target_arch = "aarch64"),
+            Level::Sse41 | Level::Avx2 | Level::Avx512 => {
+                cfg!(all(target_arch = "x86_64", target_feature = "sse2"))
+            }
+        }
+    }
+
+    ///
+    /// Parses the names used by `IROX_SIMD_LEVEL`, as printed by [`Display`]
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Level> {
+        let name = name.trim();
+        if name.eq_ignore_ascii_case("sse41") {
+            return Some(Level::Sse41);
+        }
+        Level::ALL
+            .into_iter()
+            .find(|l| name.eq_ignore_ascii_case(l.name()))
+    }
+
+    ///
+    /// The name used by `IROX_SIMD_LEVEL` and [`Display`]
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Level::Scalar => "scalar",
+            Level::Neon => "neon",
+            Level::Sse41 => "sse4.1",
+            Level::Avx2 => "avx2",
+            Level::Avx512 => "avx512",
+        }
+    }
+
+    fn from_bits(bits: u8) -> Level {
+        match bits & LEVEL_MASK {
+            2 => Level::Neon,
+            3 => Level::Sse41,
+            4 => Level::Avx2,
+            5 => Level::Avx512,
+            _ => Level::Scalar,
+        }
+    }
+}
+
+impl Display for Level {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+///
+/// x86-64 extensions outside the [`Level`] ladder that some algorithms use.  They're all
+/// reported absent on other targets, and when the active level is [`Level::Scalar`].
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct Features {
+    /// the `crc32` instruction, for CRC-32C
+    pub sse42: bool,
+    /// carry-less multiply, for folding CRCs
+    pub pclmulqdq: bool,
+    /// the SHA-1 and SHA-256 instructions
+    pub sha: bool,
+}
+
+const LEVEL_MASK: u8 = 0b111;
+const SSE42: u8 = 1 << 3;
+const PCLMULQDQ: u8 = 1 << 4;
+const SHA: u8 = 1 << 5;
+
+/// The detected level and feature bits, zero until the first call to [`detect`]
+static DETECTED: AtomicU8 = AtomicU8::new(0);
+/// The forced level, zero if none
+static FORCED: AtomicU8 = AtomicU8::new(0);
+
+// Runtime detection needs std, without it only the features the crate was compiled with count.
+#[cfg(all(feature = "std", target_arch = "x86_64", target_feature = "sse2"))]
+macro_rules! has {
+    ($feature:tt) => {
+        std::arch::is_x86_feature_detected!($feature)
+    };
+}
+#[cfg(all(feature = "std", target_arch = "aarch64"))]
+macro_rules! has {
+    ($feature:tt) => {
+        std::arch::is_aarch64_feature_detected!($feature)
+    };
+}
+#[cfg(all(
+    not(feature = "std"),
+    any(
+        all(target_arch = "x86_64", target_feature = "sse2"),
+        target_arch = "aarch64"
+    )
+))]
+macro_rules! has {
+    ($feature:tt) => {
+        cfg!(target_feature = $feature)
+    };
+}
+
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+// without std, `has!` expands to constants, which clippy would fold
+#[cfg_attr(not(feature = "std"), allow(clippy::nonminimal_bool, clippy::eq_op))]
+fn probe_arch() -> u8 {
+    let mut level = Level::Scalar;
+    if has!("sse2") && has!("ssse3") && has!("sse4.1") {
+        level = Level::Sse41;
+        if has!("avx") && has!("avx2") {
+            level = Level::Avx2;
+            if has!("avx512f") && has!("avx512bw") && has!("avx512vl") {
+                level = Level::Avx512;
+            }
+        }
+    }
+    let mut bits = level as u8;
+    if has!("sse4.2") {
+        bits |= SSE42;
+    }
+    if has!("pclmulqdq") {
+        bits |= PCLMULQDQ;
+    }
+    if has!("sha") {
+        bits |= SHA;
+    }
+    bits
+}
+
+#[cfg(target_arch = "aarch64")]
+fn probe_arch() -> u8 {
+    if has!("neon") {
+        Level::Neon as u8
+    } else {
+        Level::Scalar as u8
+    }
+}
+
+#[cfg(not(any(
+    all(target_arch = "x86_64", target_feature = "sse2"),
+    target_arch = "aarch64"
+)))]
+fn probe_arch() -> u8 {
+    Level::Scalar as u8
+}
+
+#[cfg(feature = "std")]
+fn probe() -> u8 {
+    let bits = probe_arch();
+    match std::env::var("IROX_SIMD_LEVEL")
+        .ok()
+        .and_then(|v| Level::from_name(&v))
+    {
+        Some(cap) => cap_bits(bits, cap),
+        None => bits,
+    }
+}
+
+#[cfg(not(feature = "std"))]
+fn probe() -> u8 {
+    probe_arch()
+}
+
+///
+/// The highest native level at or below `cap`, keeping the feature bits
+fn cap_bits(bits: u8, cap: Level) -> u8 {
+    let level = Level::from_bits(bits);
+    let capped = Level::ALL
+        .into_iter()
+        .filter(|l| l.is_native() && *l <= level && *l <= cap)
+        .last()
+        .unwrap_or(Level::Scalar);
+    (bits & !LEVEL_MASK) | capped as u8
+}
+
+fn detect() -> u8 {
+    let bits = DETECTED.load(Ordering::Relaxed);
+    if bits != 0 {
+        return bits;
+    }
+    // racing threads all probe the same answer, so there's no need to synchronize them
+    let bits = probe();
+    DETECTED.store(bits, Ordering::Relaxed);
+    bits
+}
+
+///
+/// The highest level this CPU supports, less any cap from `IROX_SIMD_LEVEL`
+#[must_use]
+pub fn detected_level() -> Level {
+    Level::from_bits(detect())
+}
+
+///
+/// The level the algorithms are running at: the detected level, or the highest one at or below
+/// the forced level if that's lower.
+#[must_use]
+pub fn level() -> Level {
+    let bits = detect();
+    match FORCED.load(Ordering::Relaxed) {
+        0 => Level::from_bits(bits),
+        forced => Level::from_bits(cap_bits(bits, Level::from_bits(forced))),
+    }
+}
+
+///
+/// Runs everything at `level` or below from now on, or at the detected level again for `None`.
+/// Returns the level that is now active, which is lower than requested if the CPU doesn't
+/// support it.
+pub fn force_level(level: Option<Level>) -> Level {
+    FORCED.store(level.map_or(0, |l| l as u8), Ordering::Relaxed);
+    self::level()
+}
+
+///
+/// The extensions available at the active level
+#[must_use]
+pub fn features() -> Features {
+    if level() == Level::Scalar {
+        return Features::default();
+    }
+    let bits = detect();
+    Features {
+        sse42: bits & SSE42 != 0,
+        pclmulqdq: bits & PCLMULQDQ != 0,
+        sha: bits & SHA != 0,
+    }
+}
+
+///
+/// Every level this CPU can run, lowest first - for running tests at each of them
+pub fn supported_levels() -> impl Iterator<Item = Level> {
+    let detected = detected_level();
+    Level::ALL
+        .into_iter()
+        .filter(move |l| l.is_native() && *l <= detected)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::blake2::{BLAKE2b512, BLAKE2bp512, BLAKE2s256, BLAKE2sp256};
+    use crate::blake3::BLAKE3;
+    use crate::crc::{Crc32, Crc32c, Crc64Nvme};
+    use crate::dispatch::{
+        detected_level, features, force_level, level, supported_levels, Features, Level,
+    };
+    use crate::murmur3::{Murmur3_128, Murmur3_32};
+    use crate::sha1::SHA1;
+    use crate::sha2::SHA256;
+    use crate::xxhash::XXH3_64;
+
+    #[test]
+    pub fn test_names() {
+        for l in Level::ALL {
+            assert_eq!(Some(l), Level::from_name(&l.to_string()));
+        }
+        assert_eq!(Some(Level::Sse41), Level::from_name(" SSE41 "));
+        assert_eq!(Some(Level::Neon), Level::from_name("NEON"));
+        assert_eq!(None, Level::from_name("altivec"));
+    }
+
+    fn digests(inp: &[u8]) -> Vec<Vec<u8>> {
+        let many: Vec<&[u8]> = inp.chunks(37).collect();
+        vec![
+            BLAKE2s256::default().hash(inp).to_vec(),
+            BLAKE2b512::default().hash(inp).to_vec(),
+            BLAKE2sp256::default().hash(inp).to_vec(),
+            BLAKE2bp512::default().hash(inp).to_vec(),
+            BLAKE2s256::hash_many(&many).concat(),
+            BLAKE3::new().hash(inp).to_vec(),
+            SHA1::new().hash(inp).to_vec(),
+            SHA256::new().hash(inp).to_vec(),
+            Murmur3_32::new().hash(inp).to_le_bytes().to_vec(),
+            Murmur3_128::new().hash(inp).to_le_bytes().to_vec(),
+            Murmur3_32::hash_many(&many)
+                .into_iter()
+                .flat_map(u32::to_le_bytes)
+                .collect(),
+            XXH3_64::default().hash(inp).to_le_bytes().to_vec(),
+            Crc32::new().hash(inp).to_le_bytes().to_vec(),
+            Crc32c::new().hash(inp).to_le_bytes().to_vec(),
+            Crc64Nvme::new().hash(inp).to_le_bytes().to_vec(),
+        ]
+    }
+
+    /// The only test that forces a level - any other would race it on the global.
+    #[test]
+    #[allow(clippy::indexing_slicing)]
+    pub fn test_force() {
+        let detected = detected_level();
+        assert_eq!(
+            Some(&Level::Scalar),
+            supported_levels().collect::<Vec<_>>().first()
+        );
+        // asking for more than the CPU has gets what it has
+        assert_eq!(detected, force_level(Some(Level::Avx512)));
+
+        let mut inp = vec![0u8; 20_000];
+        for (i, v) in inp.iter_mut().enumerate() {
+            *v = (i as u8).wrapping_mul(31).wrapping_add(7);
+        }
+        assert_eq!(Level::Scalar, force_level(Some(Level::Scalar)));
+        assert_eq!(Features::default(), features());
+        let expected: Vec<_> = [0, 1, 63, 64, 65, 1000, 4097, inp.len()]
+            .into_iter()
+            .map(|len| digests(&inp[..len]))
+            .collect();
+        for l in supported_levels() {
+            assert_eq!(l, force_level(Some(l)));
+            for (len, exp) in [0, 1, 63, 64, 65, 1000, 4097, inp.len()]
+                .into_iter()
+                .zip(&expected)
+            {
+                assert_eq!(exp, &digests(&inp[..len]), "{l} {len}");
+            }
+        }
+
+        assert_eq!(detected, force_level(None));
+        assert_eq!(detected, level());
+    }
+}
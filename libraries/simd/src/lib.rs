@@ -2,8 +2,28 @@
 // Copyright 2023 IROX Contributors
 
 //!
-//! SIMD implementations of some algorithms used in IROX
+//! SIMD implementations of some algorithms used in IROX.  The vector kernels are for x86-64,
+//! plus NEON on aarch64 for the BLAKE2s/BLAKE3 compression.  Every algorithm also has a portable
+//! scalar form, so the crate builds for any target.  See [`dispatch`] for how they're chosen.
 //!
 
+#![cfg_attr(not(any(test, feature = "std")), no_std)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[macro_use]
+mod adapters;
+#[macro_use]
 pub mod blake2;
-//pub mod murmur3;
+pub mod blake3;
+#[cfg(feature = "alloc")]
+pub mod codec;
+pub mod crc;
+pub mod dispatch;
+pub mod memchr;
+pub mod murmur3;
+pub mod sha1;
+pub mod sha2;
+pub mod text;
+pub mod xxhash;
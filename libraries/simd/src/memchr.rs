@@ -0,0 +1,370 @@
+// SPDX-License-Identifier: MIT
+// Copyright 2025 IROX Contributors
+//
+
+//!
+//! Byte searches: the first or last occurrence of one, two or three byte values, and iterators
+//! over every occurrence.  Each step compares 32 bytes (AVX2) or 16 bytes (SSE2) against every
+//! needle and takes the first set bit of the `movemask`.  Substring search is in [`memmem`].
+//!
+
+#![allow(clippy::indexing_slicing)]
+
+use crate::dispatch::{self, Level};
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+use core::arch::x86_64::{
+    __m128i, __m256i, _mm256_cmpeq_epi8, _mm256_loadu_si256, _mm256_movemask_epi8, _mm256_or_si256,
+    _mm256_set1_epi8, _mm_cmpeq_epi8, _mm_loadu_si128, _mm_movemask_epi8, _mm_or_si128,
+    _mm_set1_epi8,
+};
+
+pub mod memmem;
+
+///
+/// The instruction set a search runs its bulk loop on
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub(crate) enum Kernel {
+    Scalar,
+    #[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+    Sse2,
+    #[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+    Avx2,
+}
+
+impl Kernel {
+    pub(crate) fn detect() -> Self {
+        Kernel::for_level(dispatch::level())
+    }
+
+    pub(crate) fn for_level(level: Level) -> Self {
+        match level {
+            #[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+            Level::Sse41 => Kernel::Sse2,
+            #[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+            Level::Avx2 | Level::Avx512 => Kernel::Avx2,
+            _ => Kernel::Scalar,
+        }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn available() -> Vec<Self> {
+        let mut out: Vec<Self> = dispatch::supported_levels()
+            .map(Kernel::for_level)
+            .collect();
+        out.dedup();
+        out
+    }
+}
+
+///
+/// The index of the first `needle` in `haystack`
+#[must_use]
+pub fn memchr(needle: u8, haystack: &[u8]) -> Option<usize> {
+    find_with(Kernel::detect(), haystack, [needle])
+}
+
+///
+/// The index of the first byte in `haystack` equal to either needle
+#[must_use]
+pub fn memchr2(n1: u8, n2: u8, haystack: &[u8]) -> Option<usize> {
+    find_with(Kernel::detect(), haystack, [n1, n2])
+}
+
+///
+/// The index of the first byte in `haystack` equal to any of the needles
+#[must_use]
+pub fn memchr3(n1: u8, n2: u8, n3: u8, haystack: &[u8]) -> Option<usize> {
+    find_with(Kernel::detect(), haystack, [n1, n2, n3])
+}
+
+///
+/// The index of the last `needle` in `haystack`
+#[must_use]
+pub fn memrchr(needle: u8, haystack: &[u8]) -> Option<usize> {
+    rfind_with(Kernel::detect(), haystack, [needle])
+}
+
+///
+/// Iterates over the index of every `needle` in `haystack`, front to back
+#[must_use]
+pub fn memchr_iter(needle: u8, haystack: &[u8]) -> Memchr<'_, 1> {
+    Memchr::new([needle], haystack)
+}
+
+///
+/// Iterates over the index of every byte in `haystack` equal to either needle
+#[must_use]
+pub fn memchr2_iter(n1: u8, n2: u8, haystack: &[u8]) -> Memchr<'_, 2> {
+    Memchr::new([n1, n2], haystack)
+}
+
+///
+/// Iterates over the index of every byte in `haystack` equal to any of the needles
+#[must_use]
+pub fn memchr3_iter(n1: u8, n2: u8, n3: u8, haystack: &[u8]) -> Memchr<'_, 3> {
+    Memchr::new([n1, n2, n3], haystack)
+}
+
+///
+/// Iterates over the index of every `needle` in `haystack`, back to front
+pub fn memrchr_iter(needle: u8, haystack: &[u8]) -> core::iter::Rev<Memchr<'_, 1>> {
+    Memchr::new([needle], haystack).rev()
+}
+
+///
+/// An iterator over the positions of `N` needle bytes in a haystack.  It can be consumed from
+/// either end.
+#[derive(Debug, Clone)]
+pub struct Memchr<'a, const N: usize> {
+    kernel: Kernel,
+    needles: [u8; N],
+    haystack: &'a [u8],
+    /// the unsearched part is `haystack[start..end]`
+    start: usize,
+    end: usize,
+}
+
+impl<'a, const N: usize> Memchr<'a, N> {
+    fn new(needles: [u8; N], haystack: &'a [u8]) -> Self {
+        Self {
+            kernel: Kernel::detect(),
+            needles,
+            haystack,
+            start: 0,
+            end: haystack.len(),
+        }
+    }
+}
+
+impl<const N: usize> Iterator for Memchr<'_, N> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let found = self.start
+            + find_with(
+                self.kernel,
+                &self.haystack[self.start..self.end],
+                self.needles,
+            )?;
+        self.start = found + 1;
+        Some(found)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.end - self.start))
+    }
+}
+
+impl<const N: usize> DoubleEndedIterator for Memchr<'_, N> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let found = self.start
+            + rfind_with(
+                self.kernel,
+                &self.haystack[self.start..self.end],
+                self.needles,
+            )?;
+        self.end = found;
+        Some(found)
+    }
+}
+
+pub(crate) fn find_with<const N: usize>(
+    kernel: Kernel,
+    haystack: &[u8],
+    needles: [u8; N],
+) -> Option<usize> {
+    let (done, found) = match kernel {
+        #[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+        Kernel::Avx2 => unsafe { find_avx2(haystack, needles) },
+        #[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+        Kernel::Sse2 => unsafe { find_sse2(haystack, needles) },
+        Kernel::Scalar => (0, None),
+    };
+    found.or_else(|| {
+        haystack[done..]
+            .iter()
+            .position(|v| needles.contains(v))
+            .map(|i| done + i)
+    })
+}
+
+pub(crate) fn rfind_with<const N: usize>(
+    kernel: Kernel,
+    haystack: &[u8],
+    needles: [u8; N],
+) -> Option<usize> {
+    let (rest, found) = match kernel {
+        #[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+        Kernel::Avx2 => unsafe { rfind_avx2(haystack, needles) },
+        #[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+        Kernel::Sse2 => unsafe { rfind_sse2(haystack, needles) },
+        Kernel::Scalar => (haystack.len(), None),
+    };
+    found.or_else(|| haystack[..rest].iter().rposition(|v| needles.contains(v)))
+}
+
+///
+/// The bytes of `v` equal to any of the needles
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+#[target_feature(enable = "avx2")]
+#[inline]
+unsafe fn matches_avx2<const N: usize>(v: __m256i, needles: &[__m256i; N]) -> u32 {
+    let mut eq = _mm256_cmpeq_epi8(v, needles[0]);
+    for n in &needles[1..] {
+        eq = _mm256_or_si256(eq, _mm256_cmpeq_epi8(v, *n));
+    }
+    _mm256_movemask_epi8(eq) as u32
+}
+
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+#[target_feature(enable = "sse2")]
+#[inline]
+unsafe fn matches_sse2<const N: usize>(v: __m128i, needles: &[__m128i; N]) -> u32 {
+    let mut eq = _mm_cmpeq_epi8(v, needles[0]);
+    for n in &needles[1..] {
+        eq = _mm_or_si128(eq, _mm_cmpeq_epi8(v, *n));
+    }
+    _mm_movemask_epi8(eq) as u32
+}
+
+///
+/// Searches whole 32 byte blocks front to back, returning the bytes searched and the first match
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+#[target_feature(enable = "avx2")]
+unsafe fn find_avx2<const N: usize>(haystack: &[u8], needles: [u8; N]) -> (usize, Option<usize>) {
+    let needles = needles.map(|n| _mm256_set1_epi8(n as i8));
+    let mut done = 0;
+    for block in haystack.chunks_exact(32) {
+        let m = matches_avx2(
+            _mm256_loadu_si256(block.as_ptr() as *const __m256i),
+            &needles,
+        );
+        if m != 0 {
+            return (done, Some(done + m.trailing_zeros() as usize));
+        }
+        done += 32;
+    }
+    (done, None)
+}
+
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+#[target_feature(enable = "sse2")]
+unsafe fn find_sse2<const N: usize>(haystack: &[u8], needles: [u8; N]) -> (usize, Option<usize>) {
+    let needles = needles.map(|n| _mm_set1_epi8(n as i8));
+    let mut done = 0;
+    for block in haystack.chunks_exact(16) {
+        let m = matches_sse2(_mm_loadu_si128(block.as_ptr() as *const __m128i), &needles);
+        if m != 0 {
+            return (done, Some(done + m.trailing_zeros() as usize));
+        }
+        done += 16;
+    }
+    (done, None)
+}
+
+///
+/// Searches whole 32 byte blocks back to front, returning the length of the unsearched prefix
+/// and the last match
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+#[target_feature(enable = "avx2")]
+unsafe fn rfind_avx2<const N: usize>(haystack: &[u8], needles: [u8; N]) -> (usize, Option<usize>) {
+    let needles = needles.map(|n| _mm256_set1_epi8(n as i8));
+    let mut rest = haystack.len();
+    for block in haystack.rchunks_exact(32) {
+        rest -= 32;
+        let m = matches_avx2(
+            _mm256_loadu_si256(block.as_ptr() as *const __m256i),
+            &needles,
+        );
+        if m != 0 {
+            return (rest, Some(rest + 31 - m.leading_zeros() as usize));
+        }
+    }
+    (rest, None)
+}
+
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+#[target_feature(enable = "sse2")]
+unsafe fn rfind_sse2<const N: usize>(haystack: &[u8], needles: [u8; N]) -> (usize, Option<usize>) {
+    let needles = needles.map(|n| _mm_set1_epi8(n as i8));
+    let mut rest = haystack.len();
+    for block in haystack.rchunks_exact(16) {
+        rest -= 16;
+        let m = matches_sse2(_mm_loadu_si128(block.as_ptr() as *const __m128i), &needles);
+        if m != 0 {
+            // only the low 16 bits of the mask are used
+            return (rest, Some(rest + 31 - m.leading_zeros() as usize));
+        }
+    }
+    (rest, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::memchr::{
+        find_with, memchr, memchr2, memchr3, memchr3_iter, memchr_iter, memrchr, memrchr_iter,
+        rfind_with, Kernel,
+    };
+    use irox_tools::random::{Random, PRNG};
+
+    #[test]
+    pub fn test_known() {
+        assert_eq!(None, memchr(b'a', b""));
+        assert_eq!(Some(0), memchr(b'a', b"abca"));
+        assert_eq!(Some(3), memrchr(b'a', b"abca"));
+        assert_eq!(Some(1), memchr2(b'c', b'b', b"abca"));
+        assert_eq!(None, memchr3(b'x', b'y', b'z', b"abca"));
+        assert_eq!(vec![0, 3], memchr_iter(b'a', b"abca").collect::<Vec<_>>());
+        assert_eq!(vec![3, 0], memrchr_iter(b'a', b"abca").collect::<Vec<_>>());
+
+        let mut line = vec![b'.'; 100];
+        line[40] = b'\n';
+        line[99] = b'\r';
+        assert_eq!(
+            vec![40, 99],
+            memchr3_iter(b'\n', b'\r', b'\0', &line).collect::<Vec<_>>()
+        );
+        // consuming from both ends meets in the middle without repeats
+        let mut it = memchr_iter(b'.', &line[..10]);
+        assert_eq!(Some(0), it.next());
+        assert_eq!(Some(9), it.next_back());
+        assert_eq!(vec![1, 2, 3, 4, 5, 6, 7, 8], it.collect::<Vec<_>>());
+    }
+
+    #[test]
+    #[allow(clippy::indexing_slicing)]
+    pub fn test_against_scalar() {
+        let mut rand = Random::new_seed(0x3E3C);
+        for _ in 0..2000 {
+            let len = rand.next_u32() as usize % 300;
+            let mut hay = vec![0u8; len];
+            rand.fill(&mut hay);
+            // a sparse alphabet, so some searches hit and some don't
+            for v in &mut hay {
+                *v %= 64;
+            }
+            let n = [
+                rand.next_u8() % 80,
+                rand.next_u8() % 80,
+                rand.next_u8() % 80,
+            ];
+            let first = hay.iter().position(|v| *v == n[0]);
+            let last = hay.iter().rposition(|v| *v == n[0]);
+            let first3 = hay.iter().position(|v| n.contains(v));
+            let last3 = hay.iter().rposition(|v| n.contains(v));
+            for kernel in Kernel::available() {
+                assert_eq!(first, find_with(kernel, &hay, [n[0]]), "{kernel:?}");
+                assert_eq!(last, rfind_with(kernel, &hay, [n[0]]), "{kernel:?}");
+                assert_eq!(first3, find_with(kernel, &hay, n), "{kernel:?}");
+                assert_eq!(last3, rfind_with(kernel, &hay, n), "{kernel:?}");
+            }
+            let all = (0..len)
+                .filter(|i| n.contains(&hay[*i]))
+                .collect::<Vec<_>>();
+            assert_eq!(
+                all,
+                memchr3_iter(n[0], n[1], n[2], &hay).collect::<Vec<_>>()
+            );
+        }
+    }
+}
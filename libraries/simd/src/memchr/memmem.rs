@@ -0,0 +1,206 @@
+// SPDX-License-Identifier: MIT
+// Copyright 2025 IROX Contributors
+//
+
+//!
+//! Substring search with the "generic SIMD" filter from Muła, "SIMD-friendly algorithms for
+//! substring searching".  Two vectors are loaded per step, one at the candidate positions and
+//! one at the candidate positions plus the needle length less one.  Comparing them against the
+//! first and last byte of the needle leaves a mask of positions where both ends match, and only
+//! those are compared in full.
+//!
+
+#![allow(clippy::indexing_slicing)]
+
+use super::{find_with as memchr_with, Kernel};
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+use core::arch::x86_64::{
+    __m128i, __m256i, _mm256_and_si256, _mm256_cmpeq_epi8, _mm256_loadu_si256,
+    _mm256_movemask_epi8, _mm256_set1_epi8, _mm_and_si128, _mm_cmpeq_epi8, _mm_loadu_si128,
+    _mm_movemask_epi8, _mm_set1_epi8,
+};
+
+///
+/// The index of the first occurrence of `needle` in `haystack`.  An empty needle is found at 0.
+#[must_use]
+pub fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    find_with(Kernel::detect(), haystack, needle)
+}
+
+///
+/// Iterates over the start of every non-overlapping occurrence of `needle` in `haystack`.  An
+/// empty needle matches at every position, including `haystack.len()`.
+#[must_use]
+pub fn find_iter<'a, 'n>(haystack: &'a [u8], needle: &'n [u8]) -> FindIter<'a, 'n> {
+    FindIter {
+        kernel: Kernel::detect(),
+        haystack,
+        needle,
+        pos: 0,
+    }
+}
+
+///
+/// An iterator over the non-overlapping occurrences of a needle, see [`find_iter`]
+#[derive(Debug, Clone)]
+pub struct FindIter<'a, 'n> {
+    kernel: Kernel,
+    haystack: &'a [u8],
+    needle: &'n [u8],
+    /// where the next search starts, past the end once the iterator is done
+    pos: usize,
+}
+
+impl Iterator for FindIter<'_, '_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let rest = self.haystack.get(self.pos..)?;
+        let Some(found) = find_with(self.kernel, rest, self.needle) else {
+            self.pos = self.haystack.len() + 1;
+            return None;
+        };
+        let found = self.pos + found;
+        self.pos = found + self.needle.len().max(1);
+        Some(found)
+    }
+}
+
+pub(crate) fn find_with(kernel: Kernel, haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    match needle.len() {
+        0 => return Some(0),
+        1 => return memchr_with(kernel, haystack, [needle[0]]),
+        n if n > haystack.len() => return None,
+        _ => {}
+    }
+    let (done, found) = match kernel {
+        #[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+        Kernel::Avx2 => unsafe { find_avx2(haystack, needle) },
+        #[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+        Kernel::Sse2 => unsafe { find_sse2(haystack, needle) },
+        Kernel::Scalar => (0, None),
+    };
+    found.or_else(|| {
+        haystack[done..]
+            .windows(needle.len())
+            .position(|w| w == needle)
+            .map(|i| done + i)
+    })
+}
+
+///
+/// Checks each candidate in `mask`, a bit per position from `base`, against the middle of the
+/// needle - the first and last bytes already matched.
+#[inline]
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+fn verify(haystack: &[u8], needle: &[u8], base: usize, mut mask: u32) -> Option<usize> {
+    let last = needle.len() - 1;
+    while mask != 0 {
+        let pos = base + mask.trailing_zeros() as usize;
+        if haystack[pos + 1..pos + last] == needle[1..last] {
+            return Some(pos);
+        }
+        mask &= mask - 1;
+    }
+    None
+}
+
+///
+/// Filters 32 candidate positions per step, returning the positions checked and the first match.
+/// The needle must be at least 2 bytes long and no longer than the haystack.
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+#[target_feature(enable = "avx2")]
+unsafe fn find_avx2(haystack: &[u8], needle: &[u8]) -> (usize, Option<usize>) {
+    let last = needle.len() - 1;
+    let first_byte = _mm256_set1_epi8(needle[0] as i8);
+    let last_byte = _mm256_set1_epi8(needle[last] as i8);
+    let hp = haystack.as_ptr();
+    let mut done = 0;
+    while done + last + 32 <= haystack.len() {
+        let a = _mm256_loadu_si256(hp.add(done) as *const __m256i);
+        let b = _mm256_loadu_si256(hp.add(done + last) as *const __m256i);
+        let eq = _mm256_and_si256(
+            _mm256_cmpeq_epi8(a, first_byte),
+            _mm256_cmpeq_epi8(b, last_byte),
+        );
+        let found = verify(haystack, needle, done, _mm256_movemask_epi8(eq) as u32);
+        if found.is_some() {
+            return (done, found);
+        }
+        done += 32;
+    }
+    (done, None)
+}
+
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+#[target_feature(enable = "sse2")]
+unsafe fn find_sse2(haystack: &[u8], needle: &[u8]) -> (usize, Option<usize>) {
+    let last = needle.len() - 1;
+    let first_byte = _mm_set1_epi8(needle[0] as i8);
+    let last_byte = _mm_set1_epi8(needle[last] as i8);
+    let hp = haystack.as_ptr();
+    let mut done = 0;
+    while done + last + 16 <= haystack.len() {
+        let a = _mm_loadu_si128(hp.add(done) as *const __m128i);
+        let b = _mm_loadu_si128(hp.add(done + last) as *const __m128i);
+        let eq = _mm_and_si128(_mm_cmpeq_epi8(a, first_byte), _mm_cmpeq_epi8(b, last_byte));
+        let found = verify(haystack, needle, done, _mm_movemask_epi8(eq) as u32);
+        if found.is_some() {
+            return (done, found);
+        }
+        done += 16;
+    }
+    (done, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::memchr::memmem::{find, find_iter, find_with};
+    use crate::memchr::Kernel;
+    use irox_tools::random::{Random, PRNG};
+
+    #[test]
+    pub fn test_known() {
+        assert_eq!(Some(0), find(b"", b""));
+        assert_eq!(Some(0), find(b"abc", b""));
+        assert_eq!(None, find(b"ab", b"abc"));
+        assert_eq!(Some(3), find(b"abc\r\ndef", b"\r\n"));
+        assert_eq!(Some(2), find(b"aaaab", b"aab"));
+        assert_eq!(vec![0, 2], find_iter(b"aaaa", b"aa").collect::<Vec<_>>());
+        assert_eq!(vec![0, 1, 2], find_iter(b"ab", b"").collect::<Vec<_>>());
+
+        let mut hay = vec![b'-'; 1000];
+        hay[500..508].copy_from_slice(b"boundary");
+        hay[992..].copy_from_slice(b"boundary");
+        assert_eq!(
+            vec![500, 992],
+            find_iter(&hay, b"boundary").collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    #[allow(clippy::indexing_slicing)]
+    pub fn test_against_scalar() {
+        let mut rand = Random::new_seed(0x3E33);
+        for _ in 0..3000 {
+            let len = rand.next_u32() as usize % 300;
+            let mut hay = vec![0u8; len];
+            rand.fill(&mut hay);
+            // a tiny alphabet, so the first and last bytes often match without the middle
+            for v in &mut hay {
+                *v = b'a' + *v % 3;
+            }
+            let nlen = 1 + rand.next_u32() as usize % 12;
+            let needle = if len >= nlen && rand.next_u8() % 2 == 0 {
+                let at = rand.next_u32() as usize % (len - nlen + 1);
+                hay[at..at + nlen].to_vec()
+            } else {
+                (0..nlen).map(|_| b'a' + rand.next_u8() % 3).collect()
+            };
+            let expected = hay.windows(nlen).position(|w| w == needle);
+            for kernel in Kernel::available() {
+                assert_eq!(expected, find_with(kernel, &hay, &needle), "{kernel:?}");
+            }
+        }
+    }
+}
@@ -1,13 +1,34 @@
-use crate::blake2::_mm_shuffle;
-use irox_bits::{array_split_8, MutBits};
-use irox_tools::buf::{Buffer, FixedU8Buf};
-use std::arch::x86_64::{
-    __m256i, _mm256_add_epi32, _mm256_cvtsi256_si32, _mm256_loadu_si256, _mm256_mul_epu32,
-    _mm256_or_si256, _mm256_set1_epi32, _mm256_set_epi32, _mm256_setzero_si256,
-    _mm256_shuffle_epi32, _mm256_slli_si256, _mm256_srli_epi32, _mm256_srli_si256,
-    _mm256_unpacklo_epi32, _mm256_xor_si256,
+// SPDX-License-Identifier: MIT
+// Copyright 2025 IROX Contributors
+//
+
+//!
+//! AVX2 Murmur3 hashers, producing output identical to [`irox_tools::hash::murmur3`].
+//!
+//! The hash state is updated serially one block at a time, so there's no parallelism across
+//! blocks to exploit there - instead the per-block key mixing (the multiply/rotate/multiply of
+//! each `k`) is done for 32 bytes of input at once, and only the cheap state update is scalar.
+//! Below [`Level::Avx2`] the whole block is mixed in scalar code.
+//!
+
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+use crate::dispatch::{self, Level};
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+use core::arch::x86_64::{
+    __m256i, _mm256_add_epi32, _mm256_add_epi64, _mm256_loadu_si256, _mm256_mul_epu32,
+    _mm256_mullo_epi32, _mm256_or_si256, _mm256_set1_epi32, _mm256_set_epi64x,
+    _mm256_shuffle_epi32, _mm256_slli_epi32, _mm256_slli_epi64, _mm256_sllv_epi64,
+    _mm256_srli_epi32, _mm256_srli_epi64, _mm256_srlv_epi64, _mm256_storeu_si256,
+};
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2", feature = "alloc"))]
+use core::arch::x86_64::{
+    _mm256_blendv_epi8, _mm256_cmpgt_epi32, _mm256_setzero_si256, _mm256_xor_si256,
 };
-use std::ops::BitXorAssign;
+use core::ops::BitXorAssign;
+use irox_bits::MutBits;
+use irox_tools::buf::{Buffer, FixedU8Buf};
 
 const C1: u64 = 0x87c3_7b91_1142_53d5;
 const C2: u64 = 0x4cf5_ad43_2745_937f;
@@ -16,6 +37,9 @@ const C4: u64 = 0xc4ce_b9fe_1a85_ec53;
 const C5: u32 = 0xCC9E2D51;
 const C6: u32 = 0x1B873593;
 
+/// Bytes of input mixed per AVX2 step, 8 blocks of Murmur3_32 or 2 blocks of Murmur3_128
+const STEP: usize = 32;
+
 macro_rules! fmix64 {
     ($k:expr) => {
         $k.bitxor_assign($k >> 33);
@@ -33,48 +57,68 @@ macro_rules! round32 {
         *$h = $h.wrapping_mul(5).wrapping_add(0xe6546b64);
     };
 }
-macro_rules! C5 {
-    () => {
-        _mm256_set1_epi32(C5)
-    };
-}
+/// [`round32`] over the 8 blocks in `$buf`, the keys are mixed together and then folded into the
+/// state in order.
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
 macro_rules! round32_8x_simd {
-    ($h:expr, $k:expr) => {
-        *$h ^= $k.wrapping_mul(C5).rotate_left(15).wrapping_mul(C6);
-        *$h = $h.rotate_left(13);
-        *$h = $h.wrapping_mul(5).wrapping_add(0xe6546b64);
-    };
+    ($h:expr, $buf:expr) => {{
+        let k = _mm256_loadu_si256($buf.as_ptr() as *const _);
+        let k = mul32!(k, _mm256_set1_epi32(C5 as i32));
+        let k = rotl32!(k, 15);
+        let k = mul32!(k, _mm256_set1_epi32(C6 as i32));
+        let mut ks = [0u32; 8];
+        _mm256_storeu_si256(ks.as_mut_ptr() as *mut _, k);
+        for k in ks {
+            *$h ^= k;
+            *$h = $h.rotate_left(13);
+            *$h = $h.wrapping_mul(5).wrapping_add(0xe6546b64);
+        }
+    }};
 }
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
 macro_rules! mul32 {
     ($a:expr,$b:expr) => {
-        _mm256_unpacklo_epi32(
-            _mm256_shuffle_epi32::<{ _mm_shuffle(0, 0, 2, 0) }>(_mm256_mul_epu32($a, $b)),
-            _mm256_shuffle_epi32::<{ _mm_shuffle(0, 0, 2, 0) }>(_mm256_mul_epu32(
-                _mm256_srli_si256::<4>($a),
-                _mm256_srli_si256::<4>($b),
-            )),
-        )
+        _mm256_mullo_epi32($a, $b)
     };
 }
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
 macro_rules! rotl32 {
     ($a:expr,$b:literal) => {
         _mm256_or_si256(
-            _mm256_slli_si256::<$b>($a),
-            _mm256_srli_si256::<{ 32 - $b }>($a),
+            _mm256_slli_epi32::<$b>($a),
+            _mm256_srli_epi32::<{ 32 - $b }>($a),
         )
     };
 }
+/// Wrapping 64-bit multiply of each lane, AVX2 only has 32x32->64 multiplies.
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+macro_rules! mul64 {
+    ($a:expr,$b:expr) => {{
+        let (a, b) = ($a, $b);
+        let cross = _mm256_mullo_epi32(a, _mm256_shuffle_epi32::<0xB1>(b));
+        let cross = _mm256_add_epi32(cross, _mm256_srli_epi64::<32>(cross));
+        _mm256_add_epi64(_mm256_mul_epu32(a, b), _mm256_slli_epi64::<32>(cross))
+    }};
+}
+
+#[derive(Clone)]
 pub struct Murmur3_32 {
-    h: __m256i,
-    buf: FixedU8Buf<32>,
+    h: u32,
+    buf: FixedU8Buf<STEP>,
     total_len: u64,
 }
+impl Default for Murmur3_32 {
+    fn default() -> Self {
+        Self::new_seeded(0)
+    }
+}
 impl Murmur3_32 {
+    #[must_use]
     pub fn new() -> Self {
-        Self::new_seeded(0)
+        Self::default()
     }
+    #[must_use]
     pub fn new_seeded(seed: u32) -> Self {
-        let seed = unsafe { _mm256_set1_epi32(seed as i32) };
         Self {
             h: seed,
             total_len: 0,
@@ -82,114 +126,218 @@ impl Murmur3_32 {
         }
     }
     pub fn write(&mut self, mut key: &[u8]) {
-        let align = 4 - self.buf.len();
-        if align < 4 && align < key.len() {
-            let (a, b) = key.split_at(align);
+        self.total_len += key.len() as u64;
+        if !self.buf.is_empty() {
+            let rem = STEP - self.buf.len();
+            let (a, b) = key.split_at(rem.min(key.len()));
             key = b;
-            for val in a {
-                let _ = self.buf.write_u8(*val);
-                self.total_len += 1;
-                if self.buf.is_full() {
-                    self.try_chomp();
-                }
+            let _ = self.buf.write_all_bytes(a);
+            if !self.buf.is_full() {
+                return;
             }
+            let k = self.buf.as_buf_default();
+            self.buf.clear();
+            self.chomp8(&k);
         }
-        let mut chunks = key.chunks_exact(32);
+        let mut chunks = key.chunks_exact(STEP);
         for c in chunks.by_ref() {
-            unsafe {
-                self.chomp8(&c);
-            }
-            self.total_len += 32;
-        }
-        for b in chunks.remainder() {
-            let _ = self.buf.push_back(*b);
-            self.total_len += 1;
-            self.try_chomp();
+            self.chomp8(c);
         }
+        let _ = self.buf.write_all_bytes(chunks.remainder());
     }
-    fn try_chomp(&mut self) {
-        if !self.buf.is_full() {
+    fn chomp8(&mut self, buf: &[u8]) {
+        if buf.len() < STEP {
             return;
         }
-        let k = self.buf.as_buf_default();
-        unsafe {
-            self.chomp8(&k);
-        }
-    }
-    unsafe fn chomp8(&mut self, buf: &[u8]) {
-        if buf.len() < 32 {
+        #[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+        if dispatch::level() >= Level::Avx2 {
+            unsafe { self.chomp8_avx2(buf) };
             return;
         }
-        let e1 = _mm256_set_epi32(
-            0xA329EB99u32 as i32,
-            0xBE6214AEu32 as i32,
-            0x4DC33A4D,
-            0x1FCC49A2,
-            0xC9031C00u32 as i32,
-            0x4093AEE0,
-            0xB33F1B01u32 as i32,
-            0xB19CE1AAu32 as i32,
-        );
-        let e2 = _mm256_set_epi32(
-            0xC3059BF0u32 as i32,
-            0x4A99D300,
-            0x55FEC879,
-            0x82A03CCEu32 as i32,
-            0x91BB3DDDu32 as i32,
-            0x1E919393,
-            0xA39B1CE1u32 as i32,
-            0xDE33BB89u32 as i32,
-        );
-        let k = _mm256_loadu_si256(buf.as_ptr() as *const _);
-        let k = mul32!(k, e1);
-        let k = rotl32!(k, 17);
-        let k = mul32!(k, e2);
-        self.h = _mm256_xor_si256(self.h, k);
-        self.h = rotl32!(self.h, 13);
-        self.h = mul32!(self.h, _mm256_set1_epi32(5));
-        self.h = _mm256_add_epi32(self.h, _mm256_set1_epi32(0x3B11A33C));
+        for c in buf.chunks_exact(4).take(STEP / 4) {
+            let k = u32::from_le_bytes(c.try_into().unwrap_or_default());
+            round32!(&mut self.h, k);
+        }
+    }
+    #[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+    #[target_feature(enable = "avx2")]
+    unsafe fn chomp8_avx2(&mut self, buf: &[u8]) {
+        round32_8x_simd!(&mut self.h, buf);
     }
     pub fn hash(mut self, key: &[u8]) -> u32 {
         self.write(key);
         self.finish()
     }
     pub fn finish(mut self) -> u32 {
-        if !self.buf.is_empty() {
-            let b = self.buf.as_buf_default();
-            unsafe {
-                self.chomp8(&b);
-            }
+        let mut chunks = self.buf.as_ref_used().chunks_exact(4);
+        for c in chunks.by_ref() {
+            let k = u32::from_le_bytes(c.try_into().unwrap_or_default());
+            round32!(&mut self.h, k);
+        }
+        let mut tail = [0u8; 4];
+        for (t, v) in tail.iter_mut().zip(chunks.remainder()) {
+            *t = *v;
         }
+        let k = u32::from_le_bytes(tail);
         let mut h = self.h;
-        let l = self.total_len;
-        unsafe {
-            h = _mm256_xor_si256(h, _mm256_set1_epi32(l as i32));
-            h = _mm256_xor_si256(h, _mm256_srli_epi32::<16>(h));
-            h = mul32!(h, _mm256_set1_epi32(0xC2AD39BBu32 as i32));
-            h = _mm256_xor_si256(h, _mm256_srli_epi32::<13>(h));
-            h = mul32!(h, _mm256_set1_epi32(0xE037A692u32 as i32));
-            h = _mm256_xor_si256(h, _mm256_srli_epi32::<16>(h));
-            let mut out = 0u32;
-            for _ in 0..8 {
-                out ^= _mm256_cvtsi256_si32(h) as u32;
-                h = _mm256_srli_si256::<4>(h);
+        h ^= k.wrapping_mul(C5).rotate_left(15).wrapping_mul(C6);
+        h ^= self.total_len as u32;
+        h ^= h >> 16;
+        h = h.wrapping_mul(0x85ebca6b);
+        h ^= h >> 13;
+        h = h.wrapping_mul(0xc2b2ae35);
+        h ^= h >> 16;
+        h
+    }
+}
+impl_io_write!(Murmur3_32 => u32);
+impl_hasher!(
+    ///
+    /// Creates [`Murmur3_32`] hashers with a fixed seed, for `HashMap` and friends
+    BuildMurmur3_32,
+    Murmur3_32,
+    u32
+);
+
+/// Messages hashed per pass by [`Murmur3_32::hash_many`], one per 32-bit AVX2 lane
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2", feature = "alloc"))]
+const LANES: usize = 8;
+
+#[cfg(feature = "alloc")]
+impl Murmur3_32 {
+    ///
+    /// Hashes each of `inputs` independently, identical to calling [`Murmur3_32::hash`] on each.
+    #[must_use]
+    pub fn hash_many(inputs: &[&[u8]]) -> Vec<u32> {
+        Self::hash_many_seeded(0, inputs)
+    }
+
+    ///
+    /// Seeded variant of [`Murmur3_32::hash_many`], every input is hashed with the same seed.
+    #[must_use]
+    pub fn hash_many_seeded(seed: u32, inputs: &[&[u8]]) -> Vec<u32> {
+        #[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+        if dispatch::level() >= Level::Avx2 {
+            let mut out = Vec::with_capacity(inputs.len());
+            for group in inputs.chunks(LANES) {
+                let mut lanes: [&[u8]; LANES] = [&[]; LANES];
+                for (l, g) in lanes.iter_mut().zip(group) {
+                    *l = g;
+                }
+                let hashes = unsafe { hash8(seed, &lanes) };
+                out.extend(hashes.into_iter().take(group.len()));
             }
-            out
+            return out;
+        }
+        inputs
+            .iter()
+            .map(|v| Self::new_seeded(seed).hash(v))
+            .collect()
+    }
+}
+
+///
+/// Hashes the 8 messages in lockstep, transposed so each lane holds one message's state.  Lanes
+/// whose message has run out of blocks keep their state through the blend.
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2", feature = "alloc"))]
+#[target_feature(enable = "avx2")]
+#[allow(clippy::indexing_slicing)]
+unsafe fn hash8(seed: u32, lanes: &[&[u8]; LANES]) -> [u32; LANES] {
+    let nblocks = lanes.map(|l| (l.len() / 4) as u32);
+    let max_blocks = nblocks.iter().copied().max().unwrap_or_default() as usize;
+    // unsigned compare via the signed one, by flipping the sign bits.
+    let sign = _mm256_set1_epi32(i32::MIN);
+    let limit = _mm256_xor_si256(_mm256_loadu_si256(nblocks.as_ptr() as *const _), sign);
+
+    let mut h = _mm256_set1_epi32(seed as i32);
+    let mut rows = [_mm256_setzero_si256(); LANES];
+    let mut scratch = [0u8; STEP];
+    for blk in (0..max_blocks).step_by(LANES) {
+        let off = blk * 4;
+        for (row, msg) in rows.iter_mut().zip(lanes) {
+            let src = if msg.len() >= off + STEP {
+                &msg[off..off + STEP]
+            } else {
+                scratch.fill(0);
+                let rem = &msg[off.min(msg.len())..];
+                scratch[..rem.len()].copy_from_slice(rem);
+                &scratch
+            };
+            *row = _mm256_loadu_si256(src.as_ptr() as *const _);
+        }
+        let ks = crate::blake2::transpose8(rows);
+        for (i, k) in ks.into_iter().enumerate() {
+            let idx = _mm256_xor_si256(_mm256_set1_epi32((blk + i) as i32), sign);
+            let active = _mm256_cmpgt_epi32(limit, idx);
+            let k = mul32!(k, _mm256_set1_epi32(C5 as i32));
+            let k = rotl32!(k, 15);
+            let k = mul32!(k, _mm256_set1_epi32(C6 as i32));
+            let n = _mm256_xor_si256(h, k);
+            let n = rotl32!(n, 13);
+            let n = _mm256_add_epi32(
+                mul32!(n, _mm256_set1_epi32(5)),
+                _mm256_set1_epi32(0xe6546b64u32 as i32),
+            );
+            h = _mm256_blendv_epi8(h, n, active);
         }
     }
+
+    let mut tail = [0u32; LANES];
+    let mut len = [0u32; LANES];
+    for ((t, l), msg) in tail.iter_mut().zip(len.iter_mut()).zip(lanes) {
+        let mut b = [0u8; 4];
+        let rem = &msg[msg.len() & !3..];
+        b[..rem.len()].copy_from_slice(rem);
+        *t = u32::from_le_bytes(b);
+        *l = msg.len() as u32;
+    }
+    let k = _mm256_loadu_si256(tail.as_ptr() as *const _);
+    let k = mul32!(k, _mm256_set1_epi32(C5 as i32));
+    let k = rotl32!(k, 15);
+    let k = mul32!(k, _mm256_set1_epi32(C6 as i32));
+    h = _mm256_xor_si256(h, k);
+    h = _mm256_xor_si256(h, _mm256_loadu_si256(len.as_ptr() as *const _));
+    h = _mm256_xor_si256(h, _mm256_srli_epi32::<16>(h));
+    h = mul32!(h, _mm256_set1_epi32(0x85ebca6bu32 as i32));
+    h = _mm256_xor_si256(h, _mm256_srli_epi32::<13>(h));
+    h = mul32!(h, _mm256_set1_epi32(0xc2b2ae35u32 as i32));
+    h = _mm256_xor_si256(h, _mm256_srli_epi32::<16>(h));
+
+    let mut out = [0u32; LANES];
+    _mm256_storeu_si256(out.as_mut_ptr() as *mut _, h);
+    out
 }
 
-#[derive(Default)]
+macro_rules! round128 {
+    ($h1:expr, $h2:expr, $k1:expr, $k2:expr) => {
+        $h1.bitxor_assign($k1);
+        $h1 = $h1
+            .rotate_left(27)
+            .wrapping_add($h2)
+            .wrapping_mul(5)
+            .wrapping_add(0x52dce729);
+        $h2.bitxor_assign($k2);
+        $h2 = $h2
+            .rotate_left(31)
+            .wrapping_add($h1)
+            .wrapping_mul(5)
+            .wrapping_add(0x38495ab5);
+    };
+}
+
+#[derive(Default, Clone)]
 pub struct Murmur3_128 {
     h1: u64,
     h2: u64,
-    buf: FixedU8Buf<16>,
+    buf: FixedU8Buf<STEP>,
     total_len: u64,
 }
 impl Murmur3_128 {
+    #[must_use]
     pub fn new() -> Self {
         Default::default()
     }
+    #[must_use]
     pub fn new_seeded(seed: u64) -> Self {
         Self {
             h1: seed,
@@ -203,96 +351,95 @@ impl Murmur3_128 {
         self.finish()
     }
     pub fn write(&mut self, mut key: &[u8]) {
-        let align = 16 - self.buf.len();
-        if align < 16 && align < key.len() {
-            let (a, b) = key.split_at(align);
+        self.total_len += key.len() as u64;
+        if !self.buf.is_empty() {
+            let rem = STEP - self.buf.len();
+            let (a, b) = key.split_at(rem.min(key.len()));
             key = b;
-            for val in a {
-                let _ = self.buf.write_u8(*val);
-                self.total_len += 1;
-                if self.buf.is_full() {
-                    self.try_chomp();
-                }
+            let _ = self.buf.write_all_bytes(a);
+            if !self.buf.is_full() {
+                return;
             }
+            let k = self.buf.as_buf_default();
+            self.buf.clear();
+            self.chomp2(&k);
         }
-        let mut chunks = key.chunks_exact(16);
+        let mut chunks = key.chunks_exact(STEP);
         for c in chunks.by_ref() {
+            self.chomp2(c);
+        }
+        let _ = self.buf.write_all_bytes(chunks.remainder());
+    }
+    fn chomp2(&mut self, buf: &[u8]) {
+        if buf.len() < STEP {
+            return;
+        }
+        #[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+        if dispatch::level() >= Level::Avx2 {
+            unsafe { self.chomp2_avx2(buf) };
+            return;
+        }
+        for c in buf.chunks_exact(16).take(STEP / 16) {
             let (k1, k2) = c.split_at(8);
             let k1 = u64::from_le_bytes(k1.try_into().unwrap_or_default());
             let k2 = u64::from_le_bytes(k2.try_into().unwrap_or_default());
-            self.h1
-                .bitxor_assign(k1.wrapping_mul(C1).rotate_left(31).wrapping_mul(C2));
-            self.h1 = self
-                .h1
-                .rotate_left(27)
-                .wrapping_add(self.h2)
-                .wrapping_mul(5)
-                .wrapping_add(0x52dce729);
-            self.h2
-                .bitxor_assign(k2.wrapping_mul(C2).rotate_left(33).wrapping_mul(C1));
-            self.h2 = self
-                .h2
-                .rotate_left(31)
-                .wrapping_add(self.h1)
-                .wrapping_mul(5)
-                .wrapping_add(0x38495ab5);
-            self.total_len += 16;
-        }
-        for b in chunks.remainder() {
-            let _ = self.buf.push_back(*b);
-            self.total_len += 1;
-            self.try_chomp();
+            round128!(
+                self.h1,
+                self.h2,
+                k1.wrapping_mul(C1).rotate_left(31).wrapping_mul(C2),
+                k2.wrapping_mul(C2).rotate_left(33).wrapping_mul(C1)
+            );
         }
     }
-    fn try_chomp(&mut self) {
-        if !self.buf.is_full() {
-            return;
-        }
-        let chunk_16 = self.buf.as_buf_default();
-        let (k1, k2) = array_split_8(chunk_16);
-        let k1 = u64::from_le_bytes(k1);
-        let k2 = u64::from_le_bytes(k2);
-        self.h1
-            .bitxor_assign(k1.wrapping_mul(C1).rotate_left(31).wrapping_mul(C2));
-        self.h1 = self
-            .h1
-            .rotate_left(27)
-            .wrapping_add(self.h2)
-            .wrapping_mul(5)
-            .wrapping_add(0x52dce729);
-        self.h2
-            .bitxor_assign(k2.wrapping_mul(C2).rotate_left(33).wrapping_mul(C1));
-        self.h2 = self
-            .h2
-            .rotate_left(31)
-            .wrapping_add(self.h1)
-            .wrapping_mul(5)
-            .wrapping_add(0x38495ab5);
+    ///
+    /// Mixes the keys of two 16-byte blocks at once, lanes `[k1, k2, k1, k2]`, then folds them
+    /// into the state in order.
+    #[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+    #[target_feature(enable = "avx2")]
+    unsafe fn chomp2_avx2(&mut self, buf: &[u8]) {
+        let k = _mm256_loadu_si256(buf.as_ptr() as *const _);
+        let k = mul64!(
+            k,
+            _mm256_set_epi64x(C2 as i64, C1 as i64, C2 as i64, C1 as i64)
+        );
+        let k = _mm256_or_si256(
+            _mm256_sllv_epi64(k, _mm256_set_epi64x(33, 31, 33, 31)),
+            _mm256_srlv_epi64(k, _mm256_set_epi64x(31, 33, 31, 33)),
+        );
+        let k = mul64!(
+            k,
+            _mm256_set_epi64x(C1 as i64, C2 as i64, C1 as i64, C2 as i64)
+        );
+        let mut ks = [0u64; 4];
+        _mm256_storeu_si256(ks.as_mut_ptr() as *mut __m256i, k);
+        let [k1a, k2a, k1b, k2b] = ks;
+        round128!(self.h1, self.h2, k1a, k2a);
+        round128!(self.h1, self.h2, k1b, k2b);
     }
     pub fn finish(mut self) -> u128 {
-        if !self.buf.is_empty() {
-            let mut k1: u64 = 0;
-            let mut k2: u64 = 0;
-            let len = self.buf.len();
-            let mut iter = self.buf.iter();
-
-            let mut shift: u32 = 0;
-            for i in 0..len {
-                let Some(val) = iter.next() else {
-                    break;
-                };
-                let val: u64 = *val as u64;
-                if i == 8 {
-                    shift = 0;
-                }
-                if i >= 8 {
-                    k2.bitxor_assign(val.wrapping_shl(shift));
-                } else {
-                    k1.bitxor_assign(val.wrapping_shl(shift));
-                }
-                shift += 8;
+        let used = self.buf.as_ref_used();
+        let mut chunks = used.chunks_exact(16);
+        for c in chunks.by_ref() {
+            let (k1, k2) = c.split_at(8);
+            let k1 = u64::from_le_bytes(k1.try_into().unwrap_or_default());
+            let k2 = u64::from_le_bytes(k2.try_into().unwrap_or_default());
+            round128!(
+                self.h1,
+                self.h2,
+                k1.wrapping_mul(C1).rotate_left(31).wrapping_mul(C2),
+                k2.wrapping_mul(C2).rotate_left(33).wrapping_mul(C1)
+            );
+        }
+        let rem = chunks.remainder();
+        if !rem.is_empty() {
+            let mut tail = [0u8; 16];
+            for (t, v) in tail.iter_mut().zip(rem) {
+                *t = *v;
             }
-            if len > 8 {
+            let (k1, k2) = tail.split_at(8);
+            let k1 = u64::from_le_bytes(k1.try_into().unwrap_or_default());
+            let k2 = u64::from_le_bytes(k2.try_into().unwrap_or_default());
+            if rem.len() > 8 {
                 self.h2
                     .bitxor_assign(k2.wrapping_mul(C2).rotate_left(33).wrapping_mul(C1));
             }
@@ -311,6 +458,15 @@ impl Murmur3_128 {
         ((self.h1 as u128) << 64) | self.h2 as u128
     }
 }
+impl_io_write!(Murmur3_128 => u128);
+impl_hasher!(
+    ///
+    /// Creates [`Murmur3_128`] hashers with a fixed seed, for `HashMap` and friends.  The
+    /// hashers keep the low 64 bits of the hash.
+    BuildMurmur3_128,
+    Murmur3_128,
+    u64
+);
 
 #[cfg(test)]
 mod test {
@@ -331,7 +487,7 @@ mod test {
             ("123456", 0xE417CF050BBBD0D6_51A48091002531FE_u128),
             ("1234567", 0x2CDAC5F7F2C623A2_37DC518BCAE1D955_u128),
             ("12345678", 0x3B4A640638B1419C_913B0E676BD42557_u128),
-            ("123456789", 0x3C84645EDB66CCA4_99f8FAC73A1EA105_u128),
+            ("123456789", 0x3C84645EDB66CCA4_99F8FAC73A1EA105_u128),
             ("1234567890", 0xECFA4AE68079870A_C1D017C820EBD22B_u128),
             ("12345678901", 0x2A84FB1385B327D3_DAEB95857DE0DFC1_u128),
             ("123456789012", 0xDDA6E38B7C022914_75A23983FD719D1E_u128),
@@ -401,4 +557,56 @@ mod test {
             assert_eq_hex!(exp, hash);
         }
     }
+
+    #[test]
+    pub fn differential() {
+        let mut inp = [0u8; 300];
+        for (i, v) in inp.iter_mut().enumerate() {
+            *v = (i as u8).wrapping_mul(31).wrapping_add(7);
+        }
+        for seed in [0, 0x9747B28C] {
+            for len in 0..inp.len() {
+                let (msg, _) = inp.split_at(len);
+                let exp32 = irox_tools::hash::murmur3::Murmur3_32::new_seeded(seed).hash(msg);
+                let exp128 =
+                    irox_tools::hash::murmur3::Murmur3_128::new_seeded(seed as u64).hash(msg);
+                assert_eq_hex!(exp32, Murmur3_32::new_seeded(seed).hash(msg));
+                assert_eq_hex!(exp128, Murmur3_128::new_seeded(seed as u64).hash(msg));
+                for step in [1, 3, 4, 15, 16, 31, 32, 33] {
+                    let mut h32 = Murmur3_32::new_seeded(seed);
+                    let mut h128 = Murmur3_128::new_seeded(seed as u64);
+                    for c in msg.chunks(step) {
+                        h32.write(c);
+                        h128.write(c);
+                    }
+                    assert_eq_hex!(exp32, h32.finish());
+                    assert_eq_hex!(exp128, h128.finish());
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[allow(clippy::indexing_slicing)]
+    pub fn test_hash_many() {
+        let mut inp = [0u8; 300];
+        for (i, v) in inp.iter_mut().enumerate() {
+            *v = (i as u8).wrapping_mul(17).wrapping_add(3);
+        }
+        // lengths straddle the 4-byte blocks and the 32-byte transposed rows
+        let lens = [
+            0, 1, 2, 3, 4, 5, 31, 32, 33, 299, 64, 0, 100, 7, 8, 9, 255, 256, 257,
+        ];
+        for seed in [0, 0x9747B28C] {
+            for count in 0..=lens.len() {
+                let msgs: Vec<&[u8]> = lens[..count].iter().map(|l| &inp[..*l]).collect();
+                let res = Murmur3_32::hash_many_seeded(seed, &msgs);
+                assert_eq!(msgs.len(), res.len());
+                for (m, r) in msgs.iter().zip(&res) {
+                    assert_eq_hex!(Murmur3_32::new_seeded(seed).hash(m), *r);
+                }
+            }
+        }
+        assert_eq_hex!(0xBA6BD213, Murmur3_32::hash_many(&[b"test"])[0]);
+    }
 }
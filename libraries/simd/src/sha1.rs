@@ -0,0 +1,308 @@
+// SPDX-License-Identifier: MIT
+// Copyright 2025 IROX Contributors
+//
+
+//!
+//! SHA-1 (RFC 3174) using the Intel SHA extensions (`sha1rnds4`/`sha1nexte`/`sha1msg1`/`sha1msg2`),
+//! with a scalar fallback for CPUs without them.
+//!
+//! *THIS SHOULD NOT BE USED FOR ANYTHING SECURITY RELATED*
+
+#![allow(clippy::indexing_slicing)]
+
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+use crate::dispatch;
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+use core::arch::x86_64::{
+    __m128i, _mm_add_epi32, _mm_extract_epi32, _mm_loadu_si128, _mm_set_epi32, _mm_set_epi64x,
+    _mm_sha1msg1_epu32, _mm_sha1msg2_epu32, _mm_sha1nexte_epu32, _mm_sha1rnds4_epu32,
+    _mm_shuffle_epi32, _mm_shuffle_epi8, _mm_storeu_si128, _mm_xor_si128,
+};
+use irox_bits::MutBits;
+use irox_tools::buf::Buffer;
+use irox_tools::buf::FixedU8Buf;
+use irox_tools::hash::HashDigest;
+
+pub const BLOCK_SIZE: usize = 64;
+pub const OUTPUT_SIZE: usize = 20;
+
+static SHA1_INIT: &[u32; 5] = &[0x67452301, 0xEFCDAB89, 0x98BADCFE, 0x10325476, 0xC3D2E1F0];
+
+/// Runs 4 rounds of round-function `$f` with the message words `$w`.  `$prev` holds the state
+/// from before the previous 4 rounds, which `sha1nexte` derives the next `E` from.
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+macro_rules! quad {
+    ($abcd:ident, $prev:ident, $w:expr, $f:literal) => {{
+        let e = _mm_sha1nexte_epu32($prev, $w);
+        $prev = $abcd;
+        $abcd = _mm_sha1rnds4_epu32::<$f>($abcd, e);
+    }};
+}
+/// Computes the next 4 message schedule words into `$w0` from the previous 16.
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+macro_rules! schedule {
+    ($w0:ident, $w1:ident, $w2:ident, $w3:ident) => {
+        $w0 = _mm_sha1msg2_epu32(_mm_xor_si128(_mm_sha1msg1_epu32($w0, $w1), $w2), $w3);
+    };
+}
+
+///
+/// Runs the SHA-1 compression function over every complete 64-byte block in `blocks`
+fn compress(h: &mut [u32; 5], blocks: &[u8]) {
+    #[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+    if dispatch::features().sha {
+        unsafe { compress_shani(h, blocks) };
+        return;
+    }
+    compress_scalar(h, blocks);
+}
+
+fn compress_scalar(h: &mut [u32; 5], blocks: &[u8]) {
+    for block in blocks.chunks_exact(BLOCK_SIZE) {
+        let mut w = [0u32; 80];
+        for (w, b) in w.iter_mut().zip(block.chunks_exact(4)) {
+            *w = u32::from_be_bytes([b[0], b[1], b[2], b[3]]);
+        }
+        for i in 16..80 {
+            w[i] = (w[i - 3] ^ w[i - 8] ^ w[i - 14] ^ w[i - 16]).rotate_left(1);
+        }
+        let [mut a, mut b, mut c, mut d, mut e] = *h;
+        for (i, w) in w.into_iter().enumerate() {
+            let (f, k) = match i {
+                0..20 => ((b & c) | (!b & d), 0x5A827999),
+                20..40 => (b ^ c ^ d, 0x6ED9EBA1),
+                40..60 => ((b & c) | (b & d) | (c & d), 0x8F1BBCDC),
+                _ => (b ^ c ^ d, 0xCA62C1D6),
+            };
+            let t = a
+                .rotate_left(5)
+                .wrapping_add(f)
+                .wrapping_add(e)
+                .wrapping_add(k)
+                .wrapping_add(w);
+            e = d;
+            d = c;
+            c = b.rotate_left(30);
+            b = a;
+            a = t;
+        }
+        for (h, v) in h.iter_mut().zip([a, b, c, d, e]) {
+            *h = h.wrapping_add(v);
+        }
+    }
+}
+
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+#[target_feature(enable = "sha,sse2,ssse3,sse4.1")]
+unsafe fn compress_shani(h: &mut [u32; 5], blocks: &[u8]) {
+    let bswap = _mm_set_epi64x(0x0001020304050607, 0x08090A0B0C0D0E0F);
+
+    let mut abcd = _mm_shuffle_epi32::<0x1B>(_mm_loadu_si128(h.as_ptr() as *const _));
+    let [_, _, _, _, mut e0] = *h;
+
+    for block in blocks.chunks_exact(BLOCK_SIZE) {
+        let abcd_save = abcd;
+        let e_save = _mm_set_epi32(e0 as i32, 0, 0, 0);
+        let bp = block.as_ptr();
+        let mut w0: __m128i = _mm_shuffle_epi8(_mm_loadu_si128(bp as *const _), bswap);
+        let mut w1: __m128i = _mm_shuffle_epi8(_mm_loadu_si128(bp.offset(16) as *const _), bswap);
+        let mut w2: __m128i = _mm_shuffle_epi8(_mm_loadu_si128(bp.offset(32) as *const _), bswap);
+        let mut w3: __m128i = _mm_shuffle_epi8(_mm_loadu_si128(bp.offset(48) as *const _), bswap);
+
+        // rounds 0-3 take E directly from the saved state rather than through sha1nexte
+        let mut prev = abcd;
+        abcd = _mm_sha1rnds4_epu32::<0>(abcd, _mm_add_epi32(e_save, w0));
+        quad!(abcd, prev, w1, 0);
+        quad!(abcd, prev, w2, 0);
+        quad!(abcd, prev, w3, 0);
+        schedule!(w0, w1, w2, w3);
+        quad!(abcd, prev, w0, 0);
+        schedule!(w1, w2, w3, w0);
+        quad!(abcd, prev, w1, 1);
+        schedule!(w2, w3, w0, w1);
+        quad!(abcd, prev, w2, 1);
+        schedule!(w3, w0, w1, w2);
+        quad!(abcd, prev, w3, 1);
+        schedule!(w0, w1, w2, w3);
+        quad!(abcd, prev, w0, 1);
+        schedule!(w1, w2, w3, w0);
+        quad!(abcd, prev, w1, 1);
+        schedule!(w2, w3, w0, w1);
+        quad!(abcd, prev, w2, 2);
+        schedule!(w3, w0, w1, w2);
+        quad!(abcd, prev, w3, 2);
+        schedule!(w0, w1, w2, w3);
+        quad!(abcd, prev, w0, 2);
+        schedule!(w1, w2, w3, w0);
+        quad!(abcd, prev, w1, 2);
+        schedule!(w2, w3, w0, w1);
+        quad!(abcd, prev, w2, 2);
+        schedule!(w3, w0, w1, w2);
+        quad!(abcd, prev, w3, 3);
+        schedule!(w0, w1, w2, w3);
+        quad!(abcd, prev, w0, 3);
+        schedule!(w1, w2, w3, w0);
+        quad!(abcd, prev, w1, 3);
+        schedule!(w2, w3, w0, w1);
+        quad!(abcd, prev, w2, 3);
+        schedule!(w3, w0, w1, w2);
+        quad!(abcd, prev, w3, 3);
+
+        e0 = (_mm_extract_epi32::<3>(_mm_sha1nexte_epu32(prev, e_save))) as u32;
+        abcd = _mm_add_epi32(abcd, abcd_save);
+    }
+
+    _mm_storeu_si128(h.as_mut_ptr() as *mut _, _mm_shuffle_epi32::<0x1B>(abcd));
+    if let Some(e) = h.get_mut(4) {
+        *e = e0;
+    }
+}
+
+///
+/// Streaming SHA-1 hasher using the SHA-NI instructions.  Produces output identical to
+/// [`irox_tools::hash::SHA1`]
+///
+/// **THIS SHOULD NOT BE USED FOR ANYTHING SECURITY RELATED**
+#[derive(Clone)]
+pub struct SHA1 {
+    h: [u32; 5],
+    written: u64,
+    buf: FixedU8Buf<BLOCK_SIZE>,
+}
+impl Default for SHA1 {
+    fn default() -> Self {
+        Self {
+            h: *SHA1_INIT,
+            written: 0,
+            buf: Default::default(),
+        }
+    }
+}
+impl SHA1 {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn write(&mut self, mut v: &[u8]) {
+        self.written += v.len() as u64;
+        if !self.buf.is_empty() {
+            let rem = BLOCK_SIZE - self.buf.len();
+            let (a, b) = v.split_at(rem.min(v.len()));
+            v = b;
+            let _ = self.buf.write_all_bytes(a);
+            if !self.buf.is_full() {
+                return;
+            }
+            let block = self.buf.as_buf_default();
+            compress(&mut self.h, &block);
+        }
+        let split = v.len() - v.len() % BLOCK_SIZE;
+        let (blocks, rem) = v.split_at(split);
+        if !blocks.is_empty() {
+            compress(&mut self.h, blocks);
+        }
+        let _ = self.buf.write_all_bytes(rem);
+    }
+
+    pub fn hash(mut self, v: &[u8]) -> [u8; OUTPUT_SIZE] {
+        self.write(v);
+        self.finish()
+    }
+
+    pub fn finish(mut self) -> [u8; OUTPUT_SIZE] {
+        let bits = self.written.wrapping_mul(8);
+        let used = self.buf.len();
+        let mut tail = [0u8; BLOCK_SIZE * 2];
+        let tail_len = if used < 56 { 64 } else { 128 };
+        if let Some(t) = tail.get_mut(..used) {
+            t.copy_from_slice(self.buf.as_ref_used());
+        }
+        if let Some(t) = tail.get_mut(used) {
+            *t = 0x80;
+        }
+        if let Some(t) = tail.get_mut(tail_len - 8..tail_len) {
+            t.copy_from_slice(&bits.to_be_bytes());
+        }
+        if let Some(t) = tail.get(..tail_len) {
+            compress(&mut self.h, t);
+        }
+
+        let mut out: FixedU8Buf<OUTPUT_SIZE> = FixedU8Buf::default();
+        for v in self.h {
+            let _ = out.write_be_u32(v);
+        }
+        out.take()
+    }
+}
+impl HashDigest<BLOCK_SIZE, OUTPUT_SIZE> for SHA1 {
+    fn write(&mut self, bytes: &[u8]) {
+        SHA1::write(self, bytes)
+    }
+
+    fn hash(self, bytes: &[u8]) -> [u8; OUTPUT_SIZE] {
+        SHA1::hash(self, bytes)
+    }
+
+    fn finish(self) -> [u8; OUTPUT_SIZE] {
+        SHA1::finish(self)
+    }
+}
+impl_io_write!(SHA1 => [u8; OUTPUT_SIZE]);
+impl_digest!(SHA1, U20);
+
+#[cfg(test)]
+mod tests {
+    use crate::sha1::SHA1;
+    use irox_tools::{assert_eq_hex_slice, hex};
+
+    #[test]
+    pub fn nist_vectors() {
+        let tests: [(&[u8], [u8; 20]); 4] = [
+            (b"", hex!("da39a3ee5e6b4b0d3255bfef95601890afd80709")),
+            (b"abc", hex!("a9993e364706816aba3e25717850c26c9cd0d89d")),
+            (
+                b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq",
+                hex!("84983e441c3bd26ebaae4aa1f95129e5e54670f1"),
+            ),
+            (
+                b"abcdefghbcdefghicdefghijdefghijkefghijklfghijklmghijklmnhijklmnoijklmnopjklmnopqklmnopqrlmnopqrsmnopqrstnopqrstu",
+                hex!("a49b2446a02c645bf419f995b67091253a04a259"),
+            ),
+        ];
+        for (inp, exp) in tests {
+            assert_eq_hex_slice!(exp, SHA1::new().hash(inp));
+        }
+    }
+
+    #[test]
+    pub fn nist_million_a() {
+        let exp = hex!("34aa973cd4c4daa4f61eeb2bdbad27316534016f");
+        let mut h = SHA1::new();
+        let inp = [b'a'; 1000];
+        for _ in 0..1000 {
+            h.write(&inp);
+        }
+        assert_eq_hex_slice!(exp, h.finish());
+    }
+
+    #[test]
+    pub fn differential() {
+        let mut inp = [0u8; 300];
+        for (i, v) in inp.iter_mut().enumerate() {
+            *v = (i as u8).wrapping_mul(31).wrapping_add(7);
+        }
+        for len in 0..inp.len() {
+            let (msg, _) = inp.split_at(len);
+            let exp = irox_tools::hash::SHA1::new().hash(msg);
+            assert_eq_hex_slice!(exp, SHA1::new().hash(msg));
+            for step in [1, 3, 63, 64, 65] {
+                let mut h = SHA1::new();
+                for c in msg.chunks(step) {
+                    h.write(c);
+                }
+                assert_eq_hex_slice!(exp, h.finish());
+            }
+        }
+    }
+}
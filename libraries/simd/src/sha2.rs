@@ -0,0 +1,315 @@
+// SPDX-License-Identifier: MIT
+// Copyright 2025 IROX Contributors
+//
+
+//!
+//! SHA-256 (FIPS 180-4) using the Intel SHA extensions (`sha256rnds2`/`sha256msg1`/`sha256msg2`),
+//! with a scalar fallback for CPUs without them.
+//!
+
+#![allow(clippy::indexing_slicing)]
+
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+use crate::dispatch;
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+use core::arch::x86_64::{
+    __m128i, _mm_add_epi32, _mm_alignr_epi8, _mm_blend_epi16, _mm_loadu_si128, _mm_set_epi64x,
+    _mm_sha256msg1_epu32, _mm_sha256msg2_epu32, _mm_sha256rnds2_epu32, _mm_shuffle_epi32,
+    _mm_shuffle_epi8, _mm_storeu_si128,
+};
+use irox_bits::MutBits;
+use irox_tools::buf::Buffer;
+use irox_tools::buf::FixedU8Buf;
+use irox_tools::hash::HashDigest;
+
+pub const SHA256_BLOCK_SIZE: usize = 64;
+pub const SHA256_OUTPUT_SIZE: usize = 32;
+
+static SHA256_INIT: &[u32; 8] = &[
+    0x6A09E667, 0xBB67AE85, 0x3C6EF372, 0xA54FF53A, 0x510E527F, 0x9B05688C, 0x1F83D9AB, 0x5BE0CD19,
+];
+static SHA256_K: &[u32; 64] = &[
+    0x428A2F98, 0x71374491, 0xB5C0FBCF, 0xE9B5DBA5, 0x3956C25B, 0x59F111F1, 0x923F82A4, 0xAB1C5ED5,
+    0xD807AA98, 0x12835B01, 0x243185BE, 0x550C7DC3, 0x72BE5D74, 0x80DEB1FE, 0x9BDC06A7, 0xC19BF174,
+    0xE49B69C1, 0xEFBE4786, 0x0FC19DC6, 0x240CA1CC, 0x2DE92C6F, 0x4A7484AA, 0x5CB0A9DC, 0x76F988DA,
+    0x983E5152, 0xA831C66D, 0xB00327C8, 0xBF597FC7, 0xC6E00BF3, 0xD5A79147, 0x06CA6351, 0x14292967,
+    0x27B70A85, 0x2E1B2138, 0x4D2C6DFC, 0x53380D13, 0x650A7354, 0x766A0ABB, 0x81C2C92E, 0x92722C85,
+    0xA2BFE8A1, 0xA81A664B, 0xC24B8B70, 0xC76C51A3, 0xD192E819, 0xD6990624, 0xF40E3585, 0x106AA070,
+    0x19A4C116, 0x1E376C08, 0x2748774C, 0x34B0BCB5, 0x391C0CB3, 0x4ED8AA4A, 0x5B9CCA4F, 0x682E6FF3,
+    0x748F82EE, 0x78A5636F, 0x84C87814, 0x8CC70208, 0x90BEFFFA, 0xA4506CEB, 0xBEF9A3F7, 0xC67178F2,
+];
+
+/// Runs 4 rounds (two `sha256rnds2`) with the message words `$w` and the round constants at `$k`
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+macro_rules! quad {
+    ($abef:ident, $cdgh:ident, $w:expr, $k:expr) => {{
+        let msg = _mm_add_epi32($w, _mm_loadu_si128(SHA256_K.as_ptr().add($k) as *const _));
+        $cdgh = _mm_sha256rnds2_epu32($cdgh, $abef, msg);
+        let msg = _mm_shuffle_epi32::<0x0E>(msg);
+        $abef = _mm_sha256rnds2_epu32($abef, $cdgh, msg);
+    }};
+}
+/// Computes the next 4 message schedule words into `$w0` from the previous 16.
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+macro_rules! schedule {
+    ($w0:ident, $w1:ident, $w2:ident, $w3:ident) => {
+        $w0 = _mm_sha256msg2_epu32(
+            _mm_add_epi32(
+                _mm_sha256msg1_epu32($w0, $w1),
+                _mm_alignr_epi8::<4>($w3, $w2),
+            ),
+            $w3,
+        );
+    };
+}
+
+///
+/// Runs the SHA-256 compression function over every complete 64-byte block in `blocks`
+fn compress(h: &mut [u32; 8], blocks: &[u8]) {
+    #[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+    if dispatch::features().sha {
+        unsafe { compress_shani(h, blocks) };
+        return;
+    }
+    compress_scalar(h, blocks);
+}
+
+fn compress_scalar(h: &mut [u32; 8], blocks: &[u8]) {
+    for block in blocks.chunks_exact(SHA256_BLOCK_SIZE) {
+        let mut w = [0u32; 64];
+        for (w, b) in w.iter_mut().zip(block.chunks_exact(4)) {
+            *w = u32::from_be_bytes([b[0], b[1], b[2], b[3]]);
+        }
+        for i in 16..64 {
+            let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+            let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+            w[i] = w[i - 16]
+                .wrapping_add(s0)
+                .wrapping_add(w[i - 7])
+                .wrapping_add(s1);
+        }
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut hh] = *h;
+        for (w, k) in w.into_iter().zip(SHA256_K) {
+            let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+            let ch = (e & f) ^ (!e & g);
+            let t1 = hh
+                .wrapping_add(s1)
+                .wrapping_add(ch)
+                .wrapping_add(*k)
+                .wrapping_add(w);
+            let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+            let maj = (a & b) ^ (a & c) ^ (b & c);
+            let t2 = s0.wrapping_add(maj);
+            hh = g;
+            g = f;
+            f = e;
+            e = d.wrapping_add(t1);
+            d = c;
+            c = b;
+            b = a;
+            a = t1.wrapping_add(t2);
+        }
+        for (h, v) in h.iter_mut().zip([a, b, c, d, e, f, g, hh]) {
+            *h = h.wrapping_add(v);
+        }
+    }
+}
+
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+#[target_feature(enable = "sha,sse2,ssse3,sse4.1")]
+unsafe fn compress_shani(h: &mut [u32; 8], blocks: &[u8]) {
+    let bswap = _mm_set_epi64x(0x0C0D0E0F08090A0Bu64 as i64, 0x0405060700010203u64 as i64);
+
+    let hp = h.as_ptr();
+    let dcba = _mm_shuffle_epi32::<0xB1>(_mm_loadu_si128(hp as *const _));
+    let hgfe = _mm_shuffle_epi32::<0x1B>(_mm_loadu_si128(hp.offset(4) as *const _));
+    let mut abef = _mm_alignr_epi8::<8>(dcba, hgfe);
+    let mut cdgh = _mm_blend_epi16::<0xF0>(hgfe, dcba);
+
+    for block in blocks.chunks_exact(SHA256_BLOCK_SIZE) {
+        let abef_save = abef;
+        let cdgh_save = cdgh;
+        let bp = block.as_ptr();
+        let mut w0: __m128i = _mm_shuffle_epi8(_mm_loadu_si128(bp as *const _), bswap);
+        let mut w1: __m128i = _mm_shuffle_epi8(_mm_loadu_si128(bp.offset(16) as *const _), bswap);
+        let mut w2: __m128i = _mm_shuffle_epi8(_mm_loadu_si128(bp.offset(32) as *const _), bswap);
+        let mut w3: __m128i = _mm_shuffle_epi8(_mm_loadu_si128(bp.offset(48) as *const _), bswap);
+
+        quad!(abef, cdgh, w0, 0);
+        quad!(abef, cdgh, w1, 4);
+        quad!(abef, cdgh, w2, 8);
+        quad!(abef, cdgh, w3, 12);
+        for k in [16, 32, 48] {
+            schedule!(w0, w1, w2, w3);
+            quad!(abef, cdgh, w0, k);
+            schedule!(w1, w2, w3, w0);
+            quad!(abef, cdgh, w1, k + 4);
+            schedule!(w2, w3, w0, w1);
+            quad!(abef, cdgh, w2, k + 8);
+            schedule!(w3, w0, w1, w2);
+            quad!(abef, cdgh, w3, k + 12);
+        }
+
+        abef = _mm_add_epi32(abef, abef_save);
+        cdgh = _mm_add_epi32(cdgh, cdgh_save);
+    }
+
+    let feba = _mm_shuffle_epi32::<0x1B>(abef);
+    let dchg = _mm_shuffle_epi32::<0xB1>(cdgh);
+    let hp = h.as_mut_ptr();
+    _mm_storeu_si128(hp as *mut _, _mm_blend_epi16::<0xF0>(feba, dchg));
+    _mm_storeu_si128(hp.offset(4) as *mut _, _mm_alignr_epi8::<8>(dchg, feba));
+}
+
+///
+/// Streaming SHA-256 hasher using the SHA-NI instructions.  Produces output identical to
+/// [`irox_tools::hash::SHA256`]
+#[derive(Clone)]
+pub struct SHA256 {
+    h: [u32; 8],
+    written: u64,
+    buf: FixedU8Buf<SHA256_BLOCK_SIZE>,
+}
+impl Default for SHA256 {
+    fn default() -> Self {
+        Self {
+            h: *SHA256_INIT,
+            written: 0,
+            buf: Default::default(),
+        }
+    }
+}
+impl SHA256 {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn write(&mut self, mut v: &[u8]) {
+        self.written += v.len() as u64;
+        if !self.buf.is_empty() {
+            let rem = SHA256_BLOCK_SIZE - self.buf.len();
+            let (a, b) = v.split_at(rem.min(v.len()));
+            v = b;
+            let _ = self.buf.write_all_bytes(a);
+            if !self.buf.is_full() {
+                return;
+            }
+            let block = self.buf.as_buf_default();
+            compress(&mut self.h, &block);
+        }
+        let split = v.len() - v.len() % SHA256_BLOCK_SIZE;
+        let (blocks, rem) = v.split_at(split);
+        if !blocks.is_empty() {
+            compress(&mut self.h, blocks);
+        }
+        let _ = self.buf.write_all_bytes(rem);
+    }
+
+    pub fn hash(mut self, v: &[u8]) -> [u8; SHA256_OUTPUT_SIZE] {
+        self.write(v);
+        self.finish()
+    }
+
+    pub fn finish(mut self) -> [u8; SHA256_OUTPUT_SIZE] {
+        let bits = self.written.wrapping_mul(8);
+        let used = self.buf.len();
+        let mut tail = [0u8; SHA256_BLOCK_SIZE * 2];
+        let tail_len = if used < 56 { 64 } else { 128 };
+        if let Some(t) = tail.get_mut(..used) {
+            t.copy_from_slice(self.buf.as_ref_used());
+        }
+        if let Some(t) = tail.get_mut(used) {
+            *t = 0x80;
+        }
+        if let Some(t) = tail.get_mut(tail_len - 8..tail_len) {
+            t.copy_from_slice(&bits.to_be_bytes());
+        }
+        if let Some(t) = tail.get(..tail_len) {
+            compress(&mut self.h, t);
+        }
+
+        let mut out: FixedU8Buf<SHA256_OUTPUT_SIZE> = FixedU8Buf::default();
+        for v in self.h {
+            let _ = out.write_be_u32(v);
+        }
+        out.take()
+    }
+}
+impl HashDigest<SHA256_BLOCK_SIZE, SHA256_OUTPUT_SIZE> for SHA256 {
+    fn write(&mut self, bytes: &[u8]) {
+        SHA256::write(self, bytes)
+    }
+
+    fn hash(self, bytes: &[u8]) -> [u8; SHA256_OUTPUT_SIZE] {
+        SHA256::hash(self, bytes)
+    }
+
+    fn finish(self) -> [u8; SHA256_OUTPUT_SIZE] {
+        SHA256::finish(self)
+    }
+}
+impl_io_write!(SHA256 => [u8; SHA256_OUTPUT_SIZE]);
+impl_digest!(SHA256, U32);
+
+#[cfg(test)]
+mod tests {
+    use crate::sha2::SHA256;
+    use irox_tools::{assert_eq_hex_slice, hex};
+
+    #[test]
+    pub fn nist_vectors() {
+        let tests: [(&[u8], [u8; 32]); 4] = [
+            (
+                b"",
+                hex!("e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"),
+            ),
+            (
+                b"abc",
+                hex!("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"),
+            ),
+            (
+                b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq",
+                hex!("248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1"),
+            ),
+            (
+                b"abcdefghbcdefghicdefghijdefghijkefghijklfghijklmghijklmnhijklmnoijklmnopjklmnopqklmnopqrlmnopqrsmnopqrstnopqrstu",
+                hex!("cf5b16a778af8380036ce59e7b0492370b249b11e8f07a51afac45037afee9d1"),
+            ),
+        ];
+        for (inp, exp) in tests {
+            assert_eq_hex_slice!(exp, SHA256::new().hash(inp));
+        }
+    }
+
+    #[test]
+    pub fn nist_million_a() {
+        let exp = hex!("cdc76e5c9914fb9281a1c7e284d73e67f1809a48a497200e046d39ccc7112cd0");
+        let mut h = SHA256::new();
+        let inp = [b'a'; 1000];
+        for _ in 0..1000 {
+            h.write(&inp);
+        }
+        assert_eq_hex_slice!(exp, h.finish());
+    }
+
+    #[test]
+    pub fn differential() {
+        let mut inp = [0u8; 300];
+        for (i, v) in inp.iter_mut().enumerate() {
+            *v = (i as u8).wrapping_mul(31).wrapping_add(7);
+        }
+        for len in 0..inp.len() {
+            let (msg, _) = inp.split_at(len);
+            let exp = irox_tools::hash::SHA256::new().hash(msg);
+            assert_eq_hex_slice!(exp, SHA256::new().hash(msg));
+            for step in [1, 3, 63, 64, 65] {
+                let mut h = SHA256::new();
+                for c in msg.chunks(step) {
+                    h.write(c);
+                }
+                assert_eq_hex_slice!(exp, h.finish());
+            }
+        }
+    }
+}
@@ -0,0 +1,49 @@
+// SPDX-License-Identifier: MIT
+// Copyright 2025 IROX Contributors
+//
+
+//!
+//! Text validation and scanning: UTF-8 validation that reports errors the same way
+//! [`core::str::from_utf8`] does, and ASCII checks, case mapping and byte counting.
+//!
+
+use crate::dispatch::{self, Level};
+
+pub mod ascii;
+pub mod utf8;
+
+///
+/// The instruction set a text routine runs its bulk loop on
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub(crate) enum Kernel {
+    Scalar,
+    #[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+    Sse41,
+    #[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+    Avx2,
+}
+
+impl Kernel {
+    pub(crate) fn detect() -> Self {
+        Kernel::for_level(dispatch::level())
+    }
+
+    pub(crate) fn for_level(level: Level) -> Self {
+        match level {
+            #[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+            Level::Sse41 => Kernel::Sse41,
+            #[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+            Level::Avx2 | Level::Avx512 => Kernel::Avx2,
+            _ => Kernel::Scalar,
+        }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn available() -> Vec<Self> {
+        let mut out: Vec<Self> = dispatch::supported_levels()
+            .map(Kernel::for_level)
+            .collect();
+        out.dedup();
+        out
+    }
+}
@@ -0,0 +1,311 @@
+// SPDX-License-Identifier: MIT
+// Copyright 2025 IROX Contributors
+//
+
+//!
+//! ASCII routines over byte slices: checking for non-ASCII bytes, case mapping in place, and
+//! counting occurrences of a byte.  32 bytes per step on AVX2, 16 on SSE4.1.
+//!
+
+#![allow(clippy::indexing_slicing)]
+
+use super::Kernel;
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+use core::arch::x86_64::{
+    __m128i, __m256i, _mm256_and_si256, _mm256_cmpeq_epi8, _mm256_cmpgt_epi8,
+    _mm256_extracti128_si256, _mm256_loadu_si256, _mm256_movemask_epi8, _mm256_or_si256,
+    _mm256_sad_epu8, _mm256_set1_epi8, _mm256_setzero_si256, _mm256_storeu_si256, _mm256_sub_epi8,
+    _mm256_xor_si256, _mm_add_epi64, _mm_and_si128, _mm_cmpeq_epi8, _mm_cmpgt_epi8,
+    _mm_cvtsi128_si64, _mm_extract_epi64, _mm_loadu_si128, _mm_movemask_epi8, _mm_or_si128,
+    _mm_sad_epu8, _mm_set1_epi8, _mm_setzero_si128, _mm_storeu_si128, _mm_sub_epi8, _mm_xor_si128,
+};
+
+///
+/// Returns true if every byte of `input` is below 0x80
+#[must_use]
+pub fn is_ascii(input: &[u8]) -> bool {
+    is_ascii_with(Kernel::detect(), input)
+}
+
+///
+/// Converts `A-Z` to `a-z` in place, leaving every other byte alone
+pub fn make_ascii_lowercase(input: &mut [u8]) {
+    flip_case_with(Kernel::detect(), input, b'A');
+}
+
+///
+/// Converts `a-z` to `A-Z` in place, leaving every other byte alone
+pub fn make_ascii_uppercase(input: &mut [u8]) {
+    flip_case_with(Kernel::detect(), input, b'a');
+}
+
+///
+/// Counts the `\n` bytes in `input`, so a `\r\n` line ending counts once
+#[must_use]
+pub fn count_newlines(input: &[u8]) -> usize {
+    count_with(Kernel::detect(), input, b'\n')
+}
+
+///
+/// Counts the bytes in `input` equal to `needle`
+#[must_use]
+pub fn count_byte(input: &[u8], needle: u8) -> usize {
+    count_with(Kernel::detect(), input, needle)
+}
+
+pub(crate) fn is_ascii_with(kernel: Kernel, input: &[u8]) -> bool {
+    let (done, ascii) = match kernel {
+        #[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+        Kernel::Avx2 => unsafe { is_ascii_avx2(input) },
+        #[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+        Kernel::Sse41 => unsafe { is_ascii_sse41(input) },
+        Kernel::Scalar => (0, true),
+    };
+    ascii && input[done..].is_ascii()
+}
+
+///
+/// Toggles the 0x20 bit of the 26 letters starting at `first`, which is either `A` or `a`
+pub(crate) fn flip_case_with(kernel: Kernel, input: &mut [u8], first: u8) {
+    let done = match kernel {
+        #[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+        Kernel::Avx2 => unsafe { flip_case_avx2(input, first) },
+        #[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+        Kernel::Sse41 => unsafe { flip_case_sse41(input, first) },
+        Kernel::Scalar => 0,
+    };
+    for v in &mut input[done..] {
+        if v.wrapping_sub(first) < 26 {
+            *v ^= 0x20;
+        }
+    }
+}
+
+pub(crate) fn count_with(kernel: Kernel, input: &[u8], needle: u8) -> usize {
+    let (done, count) = match kernel {
+        #[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+        Kernel::Avx2 => unsafe { count_avx2(input, needle) },
+        #[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+        Kernel::Sse41 => unsafe { count_sse41(input, needle) },
+        Kernel::Scalar => (0, 0),
+    };
+    count + input[done..].iter().filter(|v| **v == needle).count()
+}
+
+///
+/// ORs the blocks together and looks at the top bits once every 256 bytes, returning the bytes
+/// checked and whether they were all ASCII.
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+#[target_feature(enable = "avx2")]
+unsafe fn is_ascii_avx2(input: &[u8]) -> (usize, bool) {
+    let mut done = 0;
+    for group in input.chunks_exact(256) {
+        let mut acc = _mm256_setzero_si256();
+        for block in group.chunks_exact(32) {
+            acc = _mm256_or_si256(acc, _mm256_loadu_si256(block.as_ptr() as *const __m256i));
+        }
+        if _mm256_movemask_epi8(acc) != 0 {
+            return (done, false);
+        }
+        done += 256;
+    }
+    for block in input[done..].chunks_exact(32) {
+        if _mm256_movemask_epi8(_mm256_loadu_si256(block.as_ptr() as *const __m256i)) != 0 {
+            return (done, false);
+        }
+        done += 32;
+    }
+    (done, true)
+}
+
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+#[target_feature(enable = "sse4.1")]
+unsafe fn is_ascii_sse41(input: &[u8]) -> (usize, bool) {
+    let mut done = 0;
+    for group in input.chunks_exact(128) {
+        let mut acc = _mm_setzero_si128();
+        for block in group.chunks_exact(16) {
+            acc = _mm_or_si128(acc, _mm_loadu_si128(block.as_ptr() as *const __m128i));
+        }
+        if _mm_movemask_epi8(acc) != 0 {
+            return (done, false);
+        }
+        done += 128;
+    }
+    for block in input[done..].chunks_exact(16) {
+        if _mm_movemask_epi8(_mm_loadu_si128(block.as_ptr() as *const __m128i)) != 0 {
+            return (done, false);
+        }
+        done += 16;
+    }
+    (done, true)
+}
+
+/// The bytes in `[first, first + 26)` of `v`, with 0x20 set in each.  The compares are signed,
+/// so bytes of 0x80 and above are never in range.
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+macro_rules! case_bits {
+    ($v:expr, $first:expr, $set1:ident, $cmpgt:ident, $and:ident) => {{
+        let v = $v;
+        let in_range = $and(
+            $cmpgt(v, $set1($first as i8 - 1)),
+            $cmpgt($set1($first as i8 + 26), v),
+        );
+        $and(in_range, $set1(0x20))
+    }};
+}
+
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+#[target_feature(enable = "avx2")]
+unsafe fn flip_case_avx2(input: &mut [u8], first: u8) -> usize {
+    let mut done = 0;
+    for block in input.chunks_exact_mut(32) {
+        let v = _mm256_loadu_si256(block.as_ptr() as *const __m256i);
+        let bits = case_bits!(
+            v,
+            first,
+            _mm256_set1_epi8,
+            _mm256_cmpgt_epi8,
+            _mm256_and_si256
+        );
+        _mm256_storeu_si256(
+            block.as_mut_ptr() as *mut __m256i,
+            _mm256_xor_si256(v, bits),
+        );
+        done += 32;
+    }
+    done
+}
+
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+#[target_feature(enable = "sse4.1")]
+unsafe fn flip_case_sse41(input: &mut [u8], first: u8) -> usize {
+    let mut done = 0;
+    for block in input.chunks_exact_mut(16) {
+        let v = _mm_loadu_si128(block.as_ptr() as *const __m128i);
+        let bits = case_bits!(v, first, _mm_set1_epi8, _mm_cmpgt_epi8, _mm_and_si128);
+        _mm_storeu_si128(block.as_mut_ptr() as *mut __m128i, _mm_xor_si128(v, bits));
+        done += 16;
+    }
+    done
+}
+
+///
+/// Each matching byte's lane is decremented by the all-ones compare result.  The 8-bit lanes
+/// would overflow after 255 blocks, so every 255 blocks they're summed into 64-bit lanes with
+/// `psadbw` against zero.
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+#[target_feature(enable = "avx2")]
+unsafe fn count_avx2(input: &[u8], needle: u8) -> (usize, usize) {
+    let n = _mm256_set1_epi8(needle as i8);
+    let zero = _mm256_setzero_si256();
+    let mut total = _mm_setzero_si128();
+    let mut done = 0;
+    for group in input.chunks(32 * 255) {
+        let mut acc = zero;
+        for block in group.chunks_exact(32) {
+            let v = _mm256_loadu_si256(block.as_ptr() as *const __m256i);
+            acc = _mm256_sub_epi8(acc, _mm256_cmpeq_epi8(v, n));
+            done += 32;
+        }
+        let sums = _mm256_sad_epu8(acc, zero);
+        total = _mm_add_epi64(total, _mm256_extracti128_si256::<0>(sums));
+        total = _mm_add_epi64(total, _mm256_extracti128_si256::<1>(sums));
+    }
+    let count = _mm_cvtsi128_si64(total) as u64 + _mm_extract_epi64::<1>(total) as u64;
+    (done, count as usize)
+}
+
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+#[target_feature(enable = "sse4.1")]
+unsafe fn count_sse41(input: &[u8], needle: u8) -> (usize, usize) {
+    let n = _mm_set1_epi8(needle as i8);
+    let zero = _mm_setzero_si128();
+    let mut total = zero;
+    let mut done = 0;
+    for group in input.chunks(16 * 255) {
+        let mut acc = zero;
+        for block in group.chunks_exact(16) {
+            let v = _mm_loadu_si128(block.as_ptr() as *const __m128i);
+            acc = _mm_sub_epi8(acc, _mm_cmpeq_epi8(v, n));
+            done += 16;
+        }
+        total = _mm_add_epi64(total, _mm_sad_epu8(acc, zero));
+    }
+    let count = _mm_cvtsi128_si64(total) as u64 + _mm_extract_epi64::<1>(total) as u64;
+    (done, count as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::text::ascii::{
+        count_byte, count_newlines, count_with, flip_case_with, is_ascii, is_ascii_with,
+        make_ascii_lowercase, make_ascii_uppercase,
+    };
+    use crate::text::Kernel;
+    use irox_tools::random::{Random, PRNG};
+
+    #[test]
+    pub fn test_known() {
+        assert!(is_ascii(b""));
+        assert!(is_ascii(b"hello, world\r\n\x7F"));
+        assert!(!is_ascii("h\u{E9}llo".as_bytes()));
+
+        let mut v = *b"Hello, World! [@`{] \xC3\x89";
+        make_ascii_lowercase(&mut v);
+        assert_eq!(b"hello, world! [@`{] \xC3\x89", &v);
+        make_ascii_uppercase(&mut v);
+        assert_eq!(b"HELLO, WORLD! [@`{] \xC3\x89", &v);
+
+        assert_eq!(3, count_newlines(b"a\nb\r\nc\n"));
+        assert_eq!(0, count_newlines(b""));
+        assert_eq!(2, count_byte(b"a,b,c", b','));
+    }
+
+    #[test]
+    #[allow(clippy::indexing_slicing)]
+    pub fn test_against_core() {
+        let mut rand = Random::new_seed(0xA5C1);
+        for _ in 0..1000 {
+            let len = rand.next_u32() as usize % 600;
+            let mut inp = vec![0u8; len];
+            rand.fill(&mut inp);
+            // mostly printable ASCII, with the odd newline and high byte
+            for v in &mut inp {
+                *v = match *v {
+                    0..=9 => b'\n',
+                    10..=11 => *v | 0x80,
+                    _ => b' ' + *v % 95,
+                };
+            }
+            let needle = inp.first().copied().unwrap_or_default();
+            for kernel in Kernel::available() {
+                assert_eq!(inp.is_ascii(), is_ascii_with(kernel, &inp));
+                if let Some(pos) = inp.iter().position(|v| *v >= 0x80) {
+                    assert!(is_ascii_with(kernel, &inp[..pos]));
+                }
+
+                let mut lower = inp.clone();
+                flip_case_with(kernel, &mut lower, b'A');
+                assert_eq!(inp.to_ascii_lowercase(), lower);
+                let mut upper = inp.clone();
+                flip_case_with(kernel, &mut upper, b'a');
+                assert_eq!(inp.to_ascii_uppercase(), upper);
+
+                let expected = inp.iter().filter(|v| **v == needle).count();
+                assert_eq!(expected, count_with(kernel, &inp, needle));
+            }
+        }
+    }
+
+    #[test]
+    pub fn test_count_long() {
+        // long enough to overflow the 8-bit lanes many times over
+        let mut inp = vec![b'\n'; 100_000];
+        inp[12_345] = b'x';
+        for kernel in Kernel::available() {
+            assert_eq!(99_999, count_with(kernel, &inp, b'\n'));
+            assert_eq!(1, count_with(kernel, &inp, b'x'));
+        }
+    }
+}
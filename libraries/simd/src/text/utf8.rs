@@ -0,0 +1,490 @@
+// SPDX-License-Identifier: MIT
+// Copyright 2025 IROX Contributors
+//
+
+//!
+//! UTF-8 validation with the lookup algorithm from Keiser and Lemire, "Validating UTF-8 In Less
+//! Than One Instruction Per Byte".  Every byte is classified by three 16-entry tables indexed by
+//! its high nibble, the previous byte's high nibble and the previous byte's low nibble - the `AND`
+//! of the three lookups is non-zero exactly where a two-byte pattern is invalid.  A second check
+//! on the bytes two and three back catches the missing or surplus continuations of 3 and 4 byte
+//! sequences.  Blocks that are entirely ASCII skip all of it.
+//!
+//! The vector loop only answers "is there an error in this block", so once it finds one the
+//! scalar [`core::str::from_utf8`] is restarted from the last character boundary before the block
+//! to pin down exactly where, and the error reported is the same one it would give for the whole
+//! input.
+//!
+
+#![allow(clippy::indexing_slicing)]
+
+use super::Kernel;
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+use core::arch::x86_64::{
+    __m128i, __m256i, _mm256_alignr_epi8, _mm256_and_si256, _mm256_broadcastsi128_si256,
+    _mm256_loadu_si256, _mm256_movemask_epi8, _mm256_or_si256, _mm256_permute2x128_si256,
+    _mm256_set1_epi8, _mm256_setzero_si256, _mm256_shuffle_epi8, _mm256_srli_epi16,
+    _mm256_subs_epu8, _mm256_testz_si256, _mm256_xor_si256, _mm_alignr_epi8, _mm_and_si128,
+    _mm_loadu_si128, _mm_movemask_epi8, _mm_or_si128, _mm_set1_epi8, _mm_setzero_si128,
+    _mm_shuffle_epi8, _mm_srli_epi16, _mm_subs_epu8, _mm_testz_si128, _mm_xor_si128,
+};
+use core::fmt::{Display, Formatter};
+
+///
+/// Why the input isn't UTF-8.  This carries the same information as [`core::str::Utf8Error`],
+/// which can't be constructed outside of `core`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Utf8Error {
+    valid_up_to: usize,
+    error_len: Option<u8>,
+}
+
+impl Utf8Error {
+    ///
+    /// The length of the longest prefix of the input that is valid UTF-8
+    #[must_use]
+    pub fn valid_up_to(&self) -> usize {
+        self.valid_up_to
+    }
+
+    ///
+    /// The length of the invalid sequence after [`Self::valid_up_to`], or `None` if the input
+    /// ended in the middle of an otherwise valid character.
+    #[must_use]
+    pub fn error_len(&self) -> Option<usize> {
+        self.error_len.map(usize::from)
+    }
+
+    /// Moves an error found in `input[base..]` to its position in `input`
+    fn offset(err: core::str::Utf8Error, base: usize) -> Self {
+        Self {
+            valid_up_to: base + err.valid_up_to(),
+            error_len: err.error_len().map(|v| v as u8),
+        }
+    }
+}
+
+impl Display for Utf8Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self.error_len {
+            Some(len) => write!(
+                f,
+                "invalid utf-8 sequence of {len} bytes from index {}",
+                self.valid_up_to
+            ),
+            None => write!(
+                f,
+                "incomplete utf-8 byte sequence from index {}",
+                self.valid_up_to
+            ),
+        }
+    }
+}
+
+impl core::error::Error for Utf8Error {}
+
+///
+/// Converts a slice of bytes to a string slice, as [`core::str::from_utf8`]
+pub fn from_utf8(input: &[u8]) -> Result<&str, Utf8Error> {
+    validate_with(Kernel::detect(), input)?;
+    // SAFETY: just validated
+    Ok(unsafe { core::str::from_utf8_unchecked(input) })
+}
+
+///
+/// Converts a mutable slice of bytes to a mutable string slice, as [`core::str::from_utf8_mut`]
+pub fn from_utf8_mut(input: &mut [u8]) -> Result<&mut str, Utf8Error> {
+    validate_with(Kernel::detect(), input)?;
+    // SAFETY: just validated
+    Ok(unsafe { core::str::from_utf8_unchecked_mut(input) })
+}
+
+///
+/// Returns true if `input` is entirely valid UTF-8
+#[must_use]
+pub fn is_valid(input: &[u8]) -> bool {
+    validate_with(Kernel::detect(), input).is_ok()
+}
+
+pub(crate) fn validate_with(kernel: Kernel, input: &[u8]) -> Result<(), Utf8Error> {
+    let checked = match kernel {
+        #[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+        Kernel::Avx2 => unsafe { validate_avx2(input) },
+        #[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+        Kernel::Sse41 => unsafe { validate_sse41(input) },
+        Kernel::Scalar => 0,
+    };
+    let start = boundary_before(input, checked);
+    core::str::from_utf8(&input[start..])
+        .map(|_| ())
+        .map_err(|e| Utf8Error::offset(e, start))
+}
+
+///
+/// Everything before `pos` is valid except maybe a truncated final character, which can only
+/// have started in the last three bytes.  Returns where that character starts, or `pos` if the
+/// prefix ends on a boundary.
+fn boundary_before(input: &[u8], pos: usize) -> usize {
+    (pos.saturating_sub(3)..pos)
+        .rev()
+        .find(|i| input[*i] & 0xC0 != 0x80)
+        .unwrap_or(pos)
+}
+
+// The error classes, one bit each, named for the first byte and the one following it.
+
+/// `11______ 0_______` or `11______ 11______`: a lead byte without its continuation
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+const TOO_SHORT: u8 = 1 << 0;
+/// `0_______ 10______`: a continuation after an ASCII byte
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+const TOO_LONG: u8 = 1 << 1;
+/// `11100000 100_____`: a 3 byte character that would fit in 2
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+const OVERLONG_3: u8 = 1 << 2;
+/// `11110100 1001____`, `11110100 101_____` or `11110101+ 10______`: above U+10FFFF
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+const TOO_LARGE: u8 = 1 << 3;
+/// `11101101 101_____`: U+D800 to U+DFFF
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+const SURROGATE: u8 = 1 << 4;
+/// `1100000_ 10______`: a 2 byte character that would fit in 1
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+const OVERLONG_2: u8 = 1 << 5;
+/// `11110101+ 1000____`, the rest of the values above U+10FFFF
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+const TOO_LARGE_1000: u8 = 1 << 6;
+/// `11110000 1000____`: a 4 byte character that would fit in 3, shares a bit with
+/// [`TOO_LARGE_1000`] as the first byte's low nibble tells them apart
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+const OVERLONG_4: u8 = 1 << 6;
+/// `10______ 10______`: two continuations in a row, which is only an error if they aren't the
+/// 2nd and 3rd or 3rd and 4th bytes of a longer character
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+const TWO_CONTS: u8 = 1 << 7;
+/// The classes that don't depend on the first byte's low nibble
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+const CARRY: u8 = TOO_SHORT | TOO_LONG | TWO_CONTS;
+
+/// Indexed by the high nibble of the first byte
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+static BYTE_1_HIGH: [u8; 16] = [
+    // 0_______ ASCII
+    TOO_LONG,
+    TOO_LONG,
+    TOO_LONG,
+    TOO_LONG,
+    TOO_LONG,
+    TOO_LONG,
+    TOO_LONG,
+    TOO_LONG,
+    // 10______ continuation
+    TWO_CONTS,
+    TWO_CONTS,
+    TWO_CONTS,
+    TWO_CONTS,
+    // 1100____ 2 byte lead
+    TOO_SHORT | OVERLONG_2,
+    // 1101____ 2 byte lead
+    TOO_SHORT,
+    // 1110____ 3 byte lead
+    TOO_SHORT | OVERLONG_3 | SURROGATE,
+    // 1111____ 4 byte lead
+    TOO_SHORT | TOO_LARGE | TOO_LARGE_1000 | OVERLONG_4,
+];
+
+/// Indexed by the low nibble of the first byte
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+static BYTE_1_LOW: [u8; 16] = [
+    // ____0000
+    CARRY | OVERLONG_3 | OVERLONG_2 | OVERLONG_4,
+    // ____0001
+    CARRY | OVERLONG_2,
+    // ____001_
+    CARRY,
+    CARRY,
+    // ____0100
+    CARRY | TOO_LARGE,
+    // ____0101 and above
+    CARRY | TOO_LARGE | TOO_LARGE_1000,
+    CARRY | TOO_LARGE | TOO_LARGE_1000,
+    CARRY | TOO_LARGE | TOO_LARGE_1000,
+    CARRY | TOO_LARGE | TOO_LARGE_1000,
+    CARRY | TOO_LARGE | TOO_LARGE_1000,
+    CARRY | TOO_LARGE | TOO_LARGE_1000,
+    CARRY | TOO_LARGE | TOO_LARGE_1000,
+    CARRY | TOO_LARGE | TOO_LARGE_1000,
+    // ____1101
+    CARRY | TOO_LARGE | TOO_LARGE_1000 | SURROGATE,
+    CARRY | TOO_LARGE | TOO_LARGE_1000,
+    CARRY | TOO_LARGE | TOO_LARGE_1000,
+];
+
+/// Indexed by the high nibble of the second byte
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+static BYTE_2_HIGH: [u8; 16] = [
+    // 0_______ ASCII
+    TOO_SHORT,
+    TOO_SHORT,
+    TOO_SHORT,
+    TOO_SHORT,
+    TOO_SHORT,
+    TOO_SHORT,
+    TOO_SHORT,
+    TOO_SHORT,
+    // 1000____
+    TOO_LONG | OVERLONG_2 | TWO_CONTS | OVERLONG_3 | TOO_LARGE_1000 | OVERLONG_4,
+    // 1001____
+    TOO_LONG | OVERLONG_2 | TWO_CONTS | OVERLONG_3 | TOO_LARGE,
+    // 101_____
+    TOO_LONG | OVERLONG_2 | TWO_CONTS | SURROGATE | TOO_LARGE,
+    TOO_LONG | OVERLONG_2 | TWO_CONTS | SURROGATE | TOO_LARGE,
+    // 11______ lead
+    TOO_SHORT,
+    TOO_SHORT,
+    TOO_SHORT,
+    TOO_SHORT,
+];
+
+/// A block's tail needs more bytes if its last byte is `>= 0xC0`, its second to last `>= 0xE0`
+/// or its third to last `>= 0xF0`.  Saturating subtraction of this leaves non-zero bytes there.
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+static INCOMPLETE_MAX: [u8; 32] = [
+    0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
+    0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xEF, 0xDF, 0xBF,
+];
+
+/// The error bits for a non-ASCII block `v`, given the three bytes before it in `prev1`, `prev2`
+/// and `prev3` - the block shifted back by one, two and three bytes.
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+macro_rules! block_errors {
+    ($v:expr, $prev1:expr, $prev2:expr, $prev3:expr, $tables:expr,
+     $set1:ident, $and:ident, $or:ident, $xor:ident, $srli:ident, $shuffle:ident, $subs:ident) => {{
+        let (t1h, t1l, t2h) = $tables;
+        let nib = $set1(0x0F);
+        let special = $and(
+            $and(
+                $shuffle(t1h, $and($srli::<4>($prev1), nib)),
+                $shuffle(t1l, $and($prev1, nib)),
+            ),
+            $shuffle(t2h, $and($srli::<4>($v), nib)),
+        );
+        // only 111_____ two back and 1111____ three back come out with the top bit set, and those
+        // are the places `TWO_CONTS` is expected rather than an error
+        let must_continue = $or(
+            $subs($prev2, $set1((0xE0 - 0x80) as i8)),
+            $subs($prev3, $set1((0xF0 - 0x80) as i8)),
+        );
+        $xor($and(must_continue, $set1(0x80u8 as i8)), special)
+    }};
+}
+
+///
+/// Validates whole 32 byte blocks, returning the offset of the first block with an error in it,
+/// or of the unvalidated tail.
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+#[target_feature(enable = "avx2")]
+unsafe fn validate_avx2(input: &[u8]) -> usize {
+    let table = |t: &[u8; 16]| _mm256_broadcastsi128_si256(_mm_loadu_si128(t.as_ptr() as *const _));
+    let tables = (table(&BYTE_1_HIGH), table(&BYTE_1_LOW), table(&BYTE_2_HIGH));
+    let max = _mm256_loadu_si256(INCOMPLETE_MAX.as_ptr() as *const __m256i);
+    let mut prev = _mm256_setzero_si256();
+    let mut prev_incomplete = _mm256_setzero_si256();
+    let mut done = 0;
+    for chunk in input.chunks_exact(32) {
+        let v = _mm256_loadu_si256(chunk.as_ptr() as *const __m256i);
+        let error = if _mm256_movemask_epi8(v) == 0 {
+            // an ASCII block is fine, unless the last one wanted continuations
+            prev_incomplete
+        } else {
+            // the alignr works within 128 bit lanes, so it needs the previous block's high lane
+            // lined up under this block's low lane
+            let shifted = _mm256_permute2x128_si256::<0x21>(prev, v);
+            let err = block_errors!(
+                v,
+                _mm256_alignr_epi8::<15>(v, shifted),
+                _mm256_alignr_epi8::<14>(v, shifted),
+                _mm256_alignr_epi8::<13>(v, shifted),
+                tables,
+                _mm256_set1_epi8,
+                _mm256_and_si256,
+                _mm256_or_si256,
+                _mm256_xor_si256,
+                _mm256_srli_epi16,
+                _mm256_shuffle_epi8,
+                _mm256_subs_epu8
+            );
+            prev_incomplete = _mm256_subs_epu8(v, max);
+            err
+        };
+        if _mm256_testz_si256(error, error) == 0 {
+            return done;
+        }
+        prev = v;
+        done += 32;
+    }
+    done
+}
+
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+#[target_feature(enable = "sse4.1")]
+unsafe fn validate_sse41(input: &[u8]) -> usize {
+    let table = |t: &[u8; 16]| _mm_loadu_si128(t.as_ptr() as *const __m128i);
+    let tables = (table(&BYTE_1_HIGH), table(&BYTE_1_LOW), table(&BYTE_2_HIGH));
+    let max = _mm_loadu_si128(INCOMPLETE_MAX[16..].as_ptr() as *const __m128i);
+    let mut prev = _mm_setzero_si128();
+    let mut prev_incomplete = _mm_setzero_si128();
+    let mut done = 0;
+    for chunk in input.chunks_exact(16) {
+        let v = _mm_loadu_si128(chunk.as_ptr() as *const __m128i);
+        let error = if _mm_movemask_epi8(v) == 0 {
+            prev_incomplete
+        } else {
+            let err = block_errors!(
+                v,
+                _mm_alignr_epi8::<15>(v, prev),
+                _mm_alignr_epi8::<14>(v, prev),
+                _mm_alignr_epi8::<13>(v, prev),
+                tables,
+                _mm_set1_epi8,
+                _mm_and_si128,
+                _mm_or_si128,
+                _mm_xor_si128,
+                _mm_srli_epi16,
+                _mm_shuffle_epi8,
+                _mm_subs_epu8
+            );
+            prev_incomplete = _mm_subs_epu8(v, max);
+            err
+        };
+        if _mm_testz_si128(error, error) == 0 {
+            return done;
+        }
+        prev = v;
+        done += 16;
+    }
+    done
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::text::utf8::{from_utf8, validate_with, Utf8Error};
+    use crate::text::Kernel;
+    use irox_tools::random::{Random, PRNG};
+
+    fn expected(input: &[u8]) -> Result<(), (usize, Option<usize>)> {
+        core::str::from_utf8(input)
+            .map(|_| ())
+            .map_err(|e| (e.valid_up_to(), e.error_len()))
+    }
+
+    fn check(input: &[u8]) {
+        for kernel in Kernel::available() {
+            let res = validate_with(kernel, input).map_err(|e| (e.valid_up_to(), e.error_len()));
+            assert_eq!(expected(input), res, "{kernel:?} {input:02X?}");
+        }
+    }
+
+    #[test]
+    pub fn test_known() {
+        assert_eq!(Ok("hello"), from_utf8(b"hello"));
+        assert_eq!(
+            Ok("h\u{E9}llo \u{1F600}"),
+            from_utf8("h\u{E9}llo \u{1F600}".as_bytes())
+        );
+        let bad: &[&[u8]] = &[
+            b"\x80",
+            b"\xBF",
+            b"\xC0\x80",
+            b"\xC1\xBF",
+            b"\xC2",
+            b"\xC2\x41",
+            b"\xE0\x80\x80",
+            b"\xE0\x9F\xBF",
+            b"\xE0\xA0",
+            b"\xED\xA0\x80",
+            b"\xED\xBF\xBF",
+            b"\xF0\x80\x80\x80",
+            b"\xF0\x8F\xBF\xBF",
+            b"\xF0\x90\x80",
+            b"\xF4\x90\x80\x80",
+            b"\xF5\x80\x80\x80",
+            b"\xF8\x88\x80\x80\x80",
+            b"\xFF",
+            b"\xC2\x80\x80",
+            b"\xE2\x82\xAC\x80",
+        ];
+        for b in bad {
+            check(b);
+            // and again at every position across the vector block boundaries
+            for pad in 0..40 {
+                let mut v = vec![b'a'; pad];
+                v.extend_from_slice(b);
+                v.extend_from_slice(&[b'z'; 40]);
+                check(&v);
+                v.truncate(pad + b.len());
+                check(&v);
+            }
+        }
+        assert_eq!(
+            Err("invalid utf-8 sequence of 1 bytes from index 2".to_string()),
+            from_utf8(b"ab\xFFcd").map_err(|e: Utf8Error| e.to_string())
+        );
+        assert_eq!(
+            Err("incomplete utf-8 byte sequence from index 2".to_string()),
+            from_utf8(b"ab\xE2\x82").map_err(|e: Utf8Error| e.to_string())
+        );
+    }
+
+    /// A random mix of 1 to 4 byte characters
+    fn random_text(rand: &mut Random, chars: usize) -> Vec<u8> {
+        let mut out = String::new();
+        let mut count = 0;
+        while count < chars {
+            let cp = match rand.next_u32() % 8 {
+                0..=3 => rand.next_u32() % 0x80,
+                4 => 0x80 + rand.next_u32() % 0x780,
+                5 | 6 => 0x800 + rand.next_u32() % 0xF800,
+                _ => 0x10000 + rand.next_u32() % 0x100000,
+            };
+            if let Some(c) = char::from_u32(cp) {
+                out.push(c);
+                count += 1;
+            }
+        }
+        out.into_bytes()
+    }
+
+    #[test]
+    #[allow(clippy::indexing_slicing)]
+    pub fn test_against_core() {
+        let mut rand = Random::new_seed(0x07F8);
+        for _ in 0..3000 {
+            let len = rand.next_u32() as usize % 200;
+            let mut inp = random_text(&mut rand, len);
+            check(&inp);
+            if inp.is_empty() {
+                continue;
+            }
+            // corrupt one or two bytes, or cut the text short
+            for _ in 0..=rand.next_u32() % 2 {
+                let pos = rand.next_u32() as usize % inp.len();
+                inp[pos] = rand.next_u32() as u8;
+            }
+            check(&inp);
+            let cut = rand.next_u32() as usize % inp.len();
+            check(&inp[..cut]);
+        }
+    }
+
+    #[test]
+    pub fn test_long_ascii_then_error() {
+        let mut inp = vec![b'x'; 4096];
+        inp.extend_from_slice("\u{10FFFF}".as_bytes());
+        check(&inp);
+        inp.push(0xF4);
+        check(&inp);
+        inp.extend_from_slice(&[b'x'; 64]);
+        check(&inp);
+    }
+}
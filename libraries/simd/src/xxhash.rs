@@ -0,0 +1,232 @@
+// SPDX-License-Identifier: MIT
+// Copyright 2025 IROX Contributors
+//
+
+//!
+//! xxHash non-cryptographic hashes: XXH64, and XXH3 in its 64 and 128 bit forms.
+//!
+//! XXH3's long-input accumulate and scramble loops run on SSE2 or AVX2.  XXH64 stays scalar,
+//! each of its 4 lanes needs a full 64x64 bit multiply per word, which neither instruction set
+//! has and four scalar multiplies outrun any emulation of it.
+//!
+//! *THIS SHOULD NOT BE USED FOR ANYTHING SECURITY RELATED*
+//!
+
+#![allow(clippy::indexing_slicing)]
+
+use irox_bits::MutBits;
+use irox_tools::buf::{Buffer, FixedU8Buf};
+
+mod xxh3;
+pub use xxh3::*;
+
+const PRIME32_1: u64 = 0x9E37_79B1;
+const PRIME32_2: u64 = 0x85EB_CA77;
+const PRIME32_3: u64 = 0xC2B2_AE3D;
+const PRIME64_1: u64 = 0x9E37_79B1_85EB_CA87;
+const PRIME64_2: u64 = 0xC2B2_AE3D_27D4_EB4F;
+const PRIME64_3: u64 = 0x1656_67B1_9E37_79F9;
+const PRIME64_4: u64 = 0x85EB_CA77_C2B2_AE63;
+const PRIME64_5: u64 = 0x27D4_EB2F_1656_67C5;
+
+/// Bytes consumed per XXH64 round, one `u64` for each of the 4 lanes
+const XXH64_STRIPE: usize = 32;
+
+fn read64(b: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(b[offset..offset + 8].try_into().unwrap_or_default())
+}
+fn read32(b: &[u8], offset: usize) -> u64 {
+    u64::from(u32::from_le_bytes(
+        b[offset..offset + 4].try_into().unwrap_or_default(),
+    ))
+}
+
+fn round64(acc: u64, v: u64) -> u64 {
+    acc.wrapping_add(v.wrapping_mul(PRIME64_2))
+        .rotate_left(31)
+        .wrapping_mul(PRIME64_1)
+}
+fn merge64(acc: u64, v: u64) -> u64 {
+    (acc ^ round64(0, v))
+        .wrapping_mul(PRIME64_1)
+        .wrapping_add(PRIME64_4)
+}
+/// The XXH64 final mix, XXH3 also uses it for its shortest inputs.
+fn avalanche64(mut h: u64) -> u64 {
+    h ^= h >> 33;
+    h = h.wrapping_mul(PRIME64_2);
+    h ^= h >> 29;
+    h = h.wrapping_mul(PRIME64_3);
+    h ^ (h >> 32)
+}
+
+///
+/// Streaming XXH64 hasher
+#[derive(Clone)]
+pub struct XXH64 {
+    v: [u64; 4],
+    seed: u64,
+    buf: FixedU8Buf<XXH64_STRIPE>,
+    total_len: u64,
+}
+impl Default for XXH64 {
+    fn default() -> Self {
+        Self::new_seeded(0)
+    }
+}
+impl XXH64 {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+    #[must_use]
+    pub fn new_seeded(seed: u64) -> Self {
+        Self {
+            v: [
+                seed.wrapping_add(PRIME64_1).wrapping_add(PRIME64_2),
+                seed.wrapping_add(PRIME64_2),
+                seed,
+                seed.wrapping_sub(PRIME64_1),
+            ],
+            seed,
+            buf: Default::default(),
+            total_len: 0,
+        }
+    }
+
+    fn stripe(&mut self, s: &[u8]) {
+        for (i, v) in self.v.iter_mut().enumerate() {
+            *v = round64(*v, read64(s, i * 8));
+        }
+    }
+
+    pub fn write(&mut self, mut v: &[u8]) {
+        self.total_len += v.len() as u64;
+        if !self.buf.is_empty() {
+            let rem = XXH64_STRIPE - self.buf.len();
+            let (a, b) = v.split_at(rem.min(v.len()));
+            v = b;
+            let _ = self.buf.write_all_bytes(a);
+            if !self.buf.is_full() {
+                return;
+            }
+            let s = self.buf.as_buf_default();
+            self.buf.clear();
+            self.stripe(&s);
+        }
+        let mut chunks = v.chunks_exact(XXH64_STRIPE);
+        for c in chunks.by_ref() {
+            self.stripe(c);
+        }
+        let _ = self.buf.write_all_bytes(chunks.remainder());
+    }
+
+    pub fn hash(mut self, v: &[u8]) -> u64 {
+        self.write(v);
+        self.finish()
+    }
+
+    #[must_use]
+    pub fn finish(self) -> u64 {
+        let [v1, v2, v3, v4] = self.v;
+        let mut h = if self.total_len >= XXH64_STRIPE as u64 {
+            let h = v1
+                .rotate_left(1)
+                .wrapping_add(v2.rotate_left(7))
+                .wrapping_add(v3.rotate_left(12))
+                .wrapping_add(v4.rotate_left(18));
+            self.v.into_iter().fold(h, merge64)
+        } else {
+            self.seed.wrapping_add(PRIME64_5)
+        };
+        h = h.wrapping_add(self.total_len);
+
+        let tail = self.buf.as_ref_used();
+        let mut words = tail.chunks_exact(8);
+        for w in words.by_ref() {
+            h ^= round64(0, read64(w, 0));
+            h = h
+                .rotate_left(27)
+                .wrapping_mul(PRIME64_1)
+                .wrapping_add(PRIME64_4);
+        }
+        let mut rest = words.remainder();
+        if rest.len() >= 4 {
+            h ^= read32(rest, 0).wrapping_mul(PRIME64_1);
+            h = h
+                .rotate_left(23)
+                .wrapping_mul(PRIME64_2)
+                .wrapping_add(PRIME64_3);
+            rest = &rest[4..];
+        }
+        for b in rest {
+            h ^= u64::from(*b).wrapping_mul(PRIME64_5);
+            h = h.rotate_left(11).wrapping_mul(PRIME64_1);
+        }
+        avalanche64(h)
+    }
+}
+impl_io_write!(XXH64 => u64);
+impl_hasher!(
+    ///
+    /// Creates [`XXH64`] hashers with a fixed seed, for `HashMap` and friends
+    BuildXXH64,
+    XXH64,
+    u64
+);
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use crate::xxhash::XXH64;
+    use irox_tools::assert_eq_hex;
+
+    pub(crate) const PRIME32: u64 = 2654435761;
+    pub(crate) const PRIME64: u64 = 11400714785074694797;
+
+    ///
+    /// The reference `xxhsum` sanity buffer
+    pub(crate) fn sanity_buffer() -> Vec<u8> {
+        let mut gen = PRIME32;
+        (0..=4096 + 64)
+            .map(|_| {
+                let b = (gen >> 56) as u8;
+                gen = gen.wrapping_mul(PRIME64);
+                b
+            })
+            .collect()
+    }
+
+    #[test]
+    pub fn test_sanity() {
+        let buf = sanity_buffer();
+        let tests = [
+            (0, 0, 0xEF46DB3751D8E999u64),
+            (0, PRIME32, 0xAC75FDA2929B17EF),
+            (1, 0, 0xE934A84ADB052768),
+            (1, PRIME32, 0x5014607643A9B4C3),
+            (4, 0, 0x9136A0DCA57457EE),
+            (14, 0, 0x8282DCC4994E35C8),
+            (14, PRIME32, 0xC3BD6BF63DEB6DF0),
+            (222, 0, 0xB641AE8CB691C174),
+            (222, PRIME32, 0x20CB8AB7AE10C14A),
+        ];
+        for (len, seed, exp) in tests {
+            assert_eq_hex!(exp, XXH64::new_seeded(seed).hash(&buf[..len]));
+        }
+    }
+
+    #[test]
+    pub fn test_streaming() {
+        let buf = sanity_buffer();
+        for len in [0, 5, 31, 32, 33, 100, 1000] {
+            let exp = XXH64::new_seeded(PRIME32).hash(&buf[..len]);
+            for step in [1, 3, 8, 31, 32, 33] {
+                let mut h = XXH64::new_seeded(PRIME32);
+                for c in buf[..len].chunks(step) {
+                    h.write(c);
+                }
+                assert_eq_hex!(exp, h.finish());
+            }
+        }
+    }
+}
@@ -0,0 +1,813 @@
+// SPDX-License-Identifier: MIT
+// Copyright 2025 IROX Contributors
+//
+
+//!
+//! XXH3, 64 and 128 bit.  Inputs up to 240 bytes are hashed with scalar mixes of 16-byte pieces,
+//! longer ones run through 8 `u64` accumulators 64-byte stripe by stripe, which is where the
+//! SSE2/AVX2 kernels come in.  [`Level::Scalar`] runs the portable form of the same loop.
+//!
+
+use super::{
+    avalanche64, read32, read64, PRIME32_1, PRIME32_2, PRIME32_3, PRIME64_1, PRIME64_2, PRIME64_3,
+    PRIME64_4, PRIME64_5,
+};
+use crate::dispatch::{self, Level};
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+use core::arch::x86_64::{
+    __m128i, __m256i, _mm256_add_epi64, _mm256_loadu_si256, _mm256_mul_epu32, _mm256_set1_epi32,
+    _mm256_shuffle_epi32, _mm256_slli_epi64, _mm256_srli_epi64, _mm256_storeu_si256,
+    _mm256_xor_si256, _mm_add_epi64, _mm_loadu_si128, _mm_mul_epu32, _mm_set1_epi32,
+    _mm_shuffle_epi32, _mm_slli_epi64, _mm_srli_epi64, _mm_storeu_si128, _mm_xor_si128,
+};
+
+/// Minimum length of a custom secret
+pub const XXH3_SECRET_SIZE_MIN: usize = 136;
+/// Length of the default secret, and of the secrets derived from a seed
+pub const XXH3_SECRET_DEFAULT_SIZE: usize = 192;
+
+const PRIME_MX1: u64 = 0x1656_6791_9E37_79F9;
+const PRIME_MX2: u64 = 0x9FB2_1C65_1E98_DF25;
+
+const STRIPE_LEN: usize = 64;
+/// Secret bytes advanced per stripe
+const SECRET_CONSUME_RATE: usize = 8;
+const MIDSIZE_MAX: usize = 240;
+const MIDSIZE_START_OFFSET: usize = 3;
+const MIDSIZE_LAST_OFFSET: usize = 17;
+const SECRET_LASTACC_START: usize = 7;
+const SECRET_MERGEACCS_START: usize = 11;
+/// Input buffered by the streaming state, 4 stripes
+const BUFFER_SIZE: usize = 256;
+
+const INIT_ACC: [u64; 8] = [
+    PRIME32_3, PRIME64_1, PRIME64_2, PRIME64_3, PRIME64_4, PRIME32_2, PRIME64_5, PRIME32_1,
+];
+
+#[rustfmt::skip]
+static DEFAULT_SECRET: [u8; XXH3_SECRET_DEFAULT_SIZE] = [
+    0xb8, 0xfe, 0x6c, 0x39, 0x23, 0xa4, 0x4b, 0xbe, 0x7c, 0x01, 0x81, 0x2c, 0xf7, 0x21, 0xad, 0x1c,
+    0xde, 0xd4, 0x6d, 0xe9, 0x83, 0x90, 0x97, 0xdb, 0x72, 0x40, 0xa4, 0xa4, 0xb7, 0xb3, 0x67, 0x1f,
+    0xcb, 0x79, 0xe6, 0x4e, 0xcc, 0xc0, 0xe5, 0x78, 0x82, 0x5a, 0xd0, 0x7d, 0xcc, 0xff, 0x72, 0x21,
+    0xb8, 0x08, 0x46, 0x74, 0xf7, 0x43, 0x24, 0x8e, 0xe0, 0x35, 0x90, 0xe6, 0x81, 0x3a, 0x26, 0x4c,
+    0x3c, 0x28, 0x52, 0xbb, 0x91, 0xc3, 0x00, 0xcb, 0x88, 0xd0, 0x65, 0x8b, 0x1b, 0x53, 0x2e, 0xa3,
+    0x71, 0x64, 0x48, 0x97, 0xa2, 0x0d, 0xf9, 0x4e, 0x38, 0x19, 0xef, 0x46, 0xa9, 0xde, 0xac, 0xd8,
+    0xa8, 0xfa, 0x76, 0x3f, 0xe3, 0x9c, 0x34, 0x3f, 0xf9, 0xdc, 0xbb, 0xc7, 0xc7, 0x0b, 0x4f, 0x1d,
+    0x8a, 0x51, 0xe0, 0x4b, 0xcd, 0xb4, 0x59, 0x31, 0xc8, 0x9f, 0x7e, 0xc9, 0xd9, 0x78, 0x73, 0x64,
+    0xea, 0xc5, 0xac, 0x83, 0x34, 0xd3, 0xeb, 0xc3, 0xc5, 0x81, 0xa0, 0xff, 0xfa, 0x13, 0x63, 0xeb,
+    0x17, 0x0d, 0xdd, 0x51, 0xb7, 0xf0, 0xda, 0x49, 0xd3, 0x16, 0x55, 0x26, 0x29, 0xd4, 0x68, 0x9e,
+    0x2b, 0x16, 0xbe, 0x58, 0x7d, 0x47, 0xa1, 0xfc, 0x8f, 0xf8, 0xb8, 0xd1, 0x7a, 0xd0, 0x31, 0xce,
+    0x45, 0xcb, 0x3a, 0x8f, 0x95, 0x16, 0x04, 0x28, 0xaf, 0xd7, 0xfb, 0xca, 0xbb, 0x4b, 0x40, 0x7e,
+];
+
+///
+/// Derives the secret that seeded hashing uses for long inputs, hashing with it through
+/// [`XXH3_64::new_with_secret`] matches [`XXH3_64::new_seeded`] for inputs over 240 bytes.
+#[must_use]
+pub fn secret_from_seed(seed: u64) -> [u8; XXH3_SECRET_DEFAULT_SIZE] {
+    let mut out = [0u8; XXH3_SECRET_DEFAULT_SIZE];
+    for (i, o) in out.chunks_exact_mut(16).enumerate() {
+        let lo = read64(&DEFAULT_SECRET, i * 16).wrapping_add(seed);
+        let hi = read64(&DEFAULT_SECRET, i * 16 + 8).wrapping_sub(seed);
+        o[..8].copy_from_slice(&lo.to_le_bytes());
+        o[8..].copy_from_slice(&hi.to_le_bytes());
+    }
+    out
+}
+
+fn mul128_fold64(a: u64, b: u64) -> u64 {
+    let p = u128::from(a) * u128::from(b);
+    (p as u64) ^ ((p >> 64) as u64)
+}
+fn mul128(a: u64, b: u64) -> (u64, u64) {
+    let p = u128::from(a) * u128::from(b);
+    (p as u64, (p >> 64) as u64)
+}
+fn avalanche(mut h: u64) -> u64 {
+    h ^= h >> 37;
+    h = h.wrapping_mul(PRIME_MX1);
+    h ^ (h >> 32)
+}
+fn rrmxmx(mut h: u64, len: u64) -> u64 {
+    h ^= h.rotate_left(49) ^ h.rotate_left(24);
+    h = h.wrapping_mul(PRIME_MX2);
+    h ^= (h >> 35).wrapping_add(len);
+    h = h.wrapping_mul(PRIME_MX2);
+    h ^ (h >> 28)
+}
+fn mix16(input: &[u8], secret: &[u8], seed: u64) -> u64 {
+    mul128_fold64(
+        read64(input, 0) ^ read64(secret, 0).wrapping_add(seed),
+        read64(input, 8) ^ read64(secret, 8).wrapping_sub(seed),
+    )
+}
+/// The 128 bit mix of two 16 byte pieces of input into the accumulator pair
+fn mix32(acc: (u64, u64), a: &[u8], b: &[u8], secret: &[u8], seed: u64) -> (u64, u64) {
+    let mut lo = acc.0.wrapping_add(mix16(a, secret, seed));
+    lo ^= read64(b, 0).wrapping_add(read64(b, 8));
+    let mut hi = acc.1.wrapping_add(mix16(b, &secret[16..], seed));
+    hi ^= read64(a, 0).wrapping_add(read64(a, 8));
+    (lo, hi)
+}
+/// Packs the first, middle and last bytes and the length of a 1-3 byte input.
+fn combine_1to3(input: &[u8]) -> u64 {
+    let len = input.len();
+    let c1 = u32::from(input[0]);
+    let c2 = u32::from(input[len >> 1]);
+    let c3 = u32::from(input[len - 1]);
+    u64::from((c1 << 16) | (c2 << 24) | c3 | ((len as u32) << 8))
+}
+/// Folds the byte-swapped low half of the seed into its high half, for 4-8 byte inputs
+fn seed_4to8(seed: u64) -> u64 {
+    seed ^ (u64::from((seed as u32).swap_bytes()) << 32)
+}
+
+fn hash64_short(input: &[u8], secret: &[u8], seed: u64) -> u64 {
+    let len = input.len();
+    if len > 128 {
+        let mut acc = (len as u64).wrapping_mul(PRIME64_1);
+        for i in 0..8 {
+            acc = acc.wrapping_add(mix16(&input[16 * i..], &secret[16 * i..], seed));
+        }
+        acc = avalanche(acc);
+        for i in 8..len / 16 {
+            let s = &secret[16 * (i - 8) + MIDSIZE_START_OFFSET..];
+            acc = acc.wrapping_add(mix16(&input[16 * i..], s, seed));
+        }
+        let s = &secret[XXH3_SECRET_SIZE_MIN - MIDSIZE_LAST_OFFSET..];
+        acc = acc.wrapping_add(mix16(&input[len - 16..], s, seed));
+        avalanche(acc)
+    } else if len > 16 {
+        let mut acc = (len as u64).wrapping_mul(PRIME64_1);
+        // the input is covered from both ends inward, 16 bytes at a time
+        for i in 0..=(len - 1) / 32 {
+            acc = acc
+                .wrapping_add(mix16(&input[16 * i..], &secret[32 * i..], seed))
+                .wrapping_add(mix16(
+                    &input[len - 16 * (i + 1)..],
+                    &secret[32 * i + 16..],
+                    seed,
+                ));
+        }
+        avalanche(acc)
+    } else if len > 8 {
+        let flip1 = (read64(secret, 24) ^ read64(secret, 32)).wrapping_add(seed);
+        let flip2 = (read64(secret, 40) ^ read64(secret, 48)).wrapping_sub(seed);
+        let lo = read64(input, 0) ^ flip1;
+        let hi = read64(input, len - 8) ^ flip2;
+        avalanche(
+            (len as u64)
+                .wrapping_add(lo.swap_bytes())
+                .wrapping_add(hi)
+                .wrapping_add(mul128_fold64(lo, hi)),
+        )
+    } else if len >= 4 {
+        let seed = seed_4to8(seed);
+        let flip = (read64(secret, 8) ^ read64(secret, 16)).wrapping_sub(seed);
+        let v = read32(input, len - 4).wrapping_add(read32(input, 0) << 32);
+        rrmxmx(v ^ flip, len as u64)
+    } else if len > 0 {
+        let flip = (read32(secret, 0) ^ read32(secret, 4)).wrapping_add(seed);
+        avalanche64(combine_1to3(input) ^ flip)
+    } else {
+        avalanche64(seed ^ read64(secret, 56) ^ read64(secret, 64))
+    }
+}
+
+fn finish128(acc: (u64, u64), len: u64, seed: u64) -> u128 {
+    let lo = acc.0.wrapping_add(acc.1);
+    let hi = acc
+        .0
+        .wrapping_mul(PRIME64_1)
+        .wrapping_add(acc.1.wrapping_mul(PRIME64_4))
+        .wrapping_add(len.wrapping_sub(seed).wrapping_mul(PRIME64_2));
+    to_u128(avalanche(lo), 0u64.wrapping_sub(avalanche(hi)))
+}
+fn to_u128(lo: u64, hi: u64) -> u128 {
+    (u128::from(hi) << 64) | u128::from(lo)
+}
+
+fn hash128_short(input: &[u8], secret: &[u8], seed: u64) -> u128 {
+    let len = input.len();
+    if len > 128 {
+        let mut acc = ((len as u64).wrapping_mul(PRIME64_1), 0);
+        for i in 0..4 {
+            let a = &input[32 * i..];
+            acc = mix32(acc, a, &a[16..], &secret[32 * i..], seed);
+        }
+        acc = (avalanche(acc.0), avalanche(acc.1));
+        for i in 4..len / 32 {
+            let a = &input[32 * i..];
+            let s = &secret[32 * (i - 4) + MIDSIZE_START_OFFSET..];
+            acc = mix32(acc, a, &a[16..], s, seed);
+        }
+        let s = &secret[XXH3_SECRET_SIZE_MIN - MIDSIZE_LAST_OFFSET - 16..];
+        acc = mix32(
+            acc,
+            &input[len - 16..],
+            &input[len - 32..],
+            s,
+            0u64.wrapping_sub(seed),
+        );
+        finish128(acc, len as u64, seed)
+    } else if len > 16 {
+        let mut acc = ((len as u64).wrapping_mul(PRIME64_1), 0);
+        for i in (0..=(len - 1) / 32).rev() {
+            let a = &input[16 * i..];
+            let b = &input[len - 16 * (i + 1)..];
+            acc = mix32(acc, a, b, &secret[32 * i..], seed);
+        }
+        finish128(acc, len as u64, seed)
+    } else if len > 8 {
+        let flip_lo = (read64(secret, 32) ^ read64(secret, 40)).wrapping_sub(seed);
+        let flip_hi = (read64(secret, 48) ^ read64(secret, 56)).wrapping_add(seed);
+        let in_lo = read64(input, 0);
+        let in_hi = read64(input, len - 8) ^ flip_hi;
+        let (mut m_lo, mut m_hi) = mul128(in_lo ^ read64(input, len - 8) ^ flip_lo, PRIME64_1);
+        m_lo = m_lo.wrapping_add(((len - 1) as u64) << 54);
+        m_hi = m_hi
+            .wrapping_add(in_hi)
+            .wrapping_add((in_hi & 0xFFFF_FFFF).wrapping_mul(PRIME32_2 - 1));
+        m_lo ^= m_hi.swap_bytes();
+        let (h_lo, h_hi) = mul128(m_lo, PRIME64_2);
+        let h_hi = h_hi.wrapping_add(m_hi.wrapping_mul(PRIME64_2));
+        to_u128(avalanche(h_lo), avalanche(h_hi))
+    } else if len >= 4 {
+        let seed = seed_4to8(seed);
+        let v = read32(input, 0).wrapping_add(read32(input, len - 4) << 32);
+        let flip = (read64(secret, 16) ^ read64(secret, 24)).wrapping_add(seed);
+        let (mut lo, mut hi) = mul128(v ^ flip, PRIME64_1.wrapping_add((len as u64) << 2));
+        hi = hi.wrapping_add(lo << 1);
+        lo ^= hi >> 3;
+        lo ^= lo >> 35;
+        lo = lo.wrapping_mul(PRIME_MX2);
+        lo ^= lo >> 28;
+        to_u128(lo, avalanche(hi))
+    } else if len > 0 {
+        let lo = combine_1to3(input);
+        let hi = u64::from((lo as u32).swap_bytes().rotate_left(13));
+        let flip_lo = (read32(secret, 0) ^ read32(secret, 4)).wrapping_add(seed);
+        let flip_hi = (read32(secret, 8) ^ read32(secret, 12)).wrapping_sub(seed);
+        to_u128(avalanche64(lo ^ flip_lo), avalanche64(hi ^ flip_hi))
+    } else {
+        to_u128(
+            avalanche64(seed ^ read64(secret, 64) ^ read64(secret, 72)),
+            avalanche64(seed ^ read64(secret, 80) ^ read64(secret, 88)),
+        )
+    }
+}
+
+///
+/// Accumulates `n` stripes of `input`, stripe `i` keyed by the secret from `8 * i`:
+/// `acc[j] += lo32(k) * hi32(k)` with `k = input ^ secret`, and `acc[j ^ 1] += input`.
+fn accumulate_scalar(acc: &mut [u64; 8], input: &[u8], secret: &[u8], n: usize) {
+    for s in 0..n {
+        let stripe = &input[s * STRIPE_LEN..];
+        let key = &secret[s * SECRET_CONSUME_RATE..];
+        for j in 0..8 {
+            let data = read64(stripe, j * 8);
+            let k = data ^ read64(key, j * 8);
+            acc[j ^ 1] = acc[j ^ 1].wrapping_add(data);
+            acc[j] = acc[j].wrapping_add((k & 0xFFFF_FFFF).wrapping_mul(k >> 32));
+        }
+    }
+}
+
+///
+/// `acc = (acc ^ (acc >> 47) ^ secret) * PRIME32_1`
+fn scramble_scalar(acc: &mut [u64; 8], secret: &[u8]) {
+    for (j, a) in acc.iter_mut().enumerate() {
+        let k = *a ^ (*a >> 47) ^ read64(secret, j * 8);
+        *a = k.wrapping_mul(PRIME32_1);
+    }
+}
+
+///
+/// The AVX2 form of [`accumulate_scalar`]
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+#[target_feature(enable = "avx2")]
+unsafe fn accumulate_avx2(acc: &mut [u64; 8], input: &[u8], secret: &[u8], n: usize) {
+    let ap = acc.as_mut_ptr() as *mut __m256i;
+    let mut a = [_mm256_loadu_si256(ap), _mm256_loadu_si256(ap.add(1))];
+    for s in 0..n {
+        let ip = input[s * STRIPE_LEN..].as_ptr() as *const __m256i;
+        let sp = secret[s * SECRET_CONSUME_RATE..].as_ptr() as *const __m256i;
+        for (i, a) in a.iter_mut().enumerate() {
+            let data = _mm256_loadu_si256(ip.add(i));
+            let key = _mm256_xor_si256(data, _mm256_loadu_si256(sp.add(i)));
+            let prod = _mm256_mul_epu32(key, _mm256_srli_epi64::<32>(key));
+            let swapped = _mm256_shuffle_epi32::<0x4E>(data);
+            *a = _mm256_add_epi64(_mm256_add_epi64(*a, swapped), prod);
+        }
+    }
+    _mm256_storeu_si256(ap, a[0]);
+    _mm256_storeu_si256(ap.add(1), a[1]);
+}
+
+///
+/// The AVX2 form of [`scramble_scalar`], with the 64x32 bit multiply split in two
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+#[target_feature(enable = "avx2")]
+unsafe fn scramble_avx2(acc: &mut [u64; 8], secret: &[u8]) {
+    let ap = acc.as_mut_ptr() as *mut __m256i;
+    let sp = secret.as_ptr() as *const __m256i;
+    let prime = _mm256_set1_epi32(PRIME32_1 as i32);
+    for i in 0..2 {
+        let a = _mm256_loadu_si256(ap.add(i));
+        let a = _mm256_xor_si256(a, _mm256_srli_epi64::<47>(a));
+        let k = _mm256_xor_si256(a, _mm256_loadu_si256(sp.add(i)));
+        let lo = _mm256_mul_epu32(k, prime);
+        let hi = _mm256_mul_epu32(_mm256_srli_epi64::<32>(k), prime);
+        _mm256_storeu_si256(ap.add(i), _mm256_add_epi64(lo, _mm256_slli_epi64::<32>(hi)));
+    }
+}
+
+///
+/// The SSE2 form of [`accumulate_avx2`]
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+#[target_feature(enable = "sse2")]
+unsafe fn accumulate_sse2(acc: &mut [u64; 8], input: &[u8], secret: &[u8], n: usize) {
+    let ap = acc.as_mut_ptr() as *mut __m128i;
+    let mut a = [
+        _mm_loadu_si128(ap),
+        _mm_loadu_si128(ap.add(1)),
+        _mm_loadu_si128(ap.add(2)),
+        _mm_loadu_si128(ap.add(3)),
+    ];
+    for s in 0..n {
+        let ip = input[s * STRIPE_LEN..].as_ptr() as *const __m128i;
+        let sp = secret[s * SECRET_CONSUME_RATE..].as_ptr() as *const __m128i;
+        for (i, a) in a.iter_mut().enumerate() {
+            let data = _mm_loadu_si128(ip.add(i));
+            let key = _mm_xor_si128(data, _mm_loadu_si128(sp.add(i)));
+            let prod = _mm_mul_epu32(key, _mm_srli_epi64::<32>(key));
+            let swapped = _mm_shuffle_epi32::<0x4E>(data);
+            *a = _mm_add_epi64(_mm_add_epi64(*a, swapped), prod);
+        }
+    }
+    for (i, a) in a.into_iter().enumerate() {
+        _mm_storeu_si128(ap.add(i), a);
+    }
+}
+
+///
+/// The SSE2 form of [`scramble_avx2`]
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+#[target_feature(enable = "sse2")]
+unsafe fn scramble_sse2(acc: &mut [u64; 8], secret: &[u8]) {
+    let ap = acc.as_mut_ptr() as *mut __m128i;
+    let sp = secret.as_ptr() as *const __m128i;
+    let prime = _mm_set1_epi32(PRIME32_1 as i32);
+    for i in 0..4 {
+        let a = _mm_loadu_si128(ap.add(i));
+        let a = _mm_xor_si128(a, _mm_srli_epi64::<47>(a));
+        let k = _mm_xor_si128(a, _mm_loadu_si128(sp.add(i)));
+        let lo = _mm_mul_epu32(k, prime);
+        let hi = _mm_mul_epu32(_mm_srli_epi64::<32>(k), prime);
+        _mm_storeu_si128(ap.add(i), _mm_add_epi64(lo, _mm_slli_epi64::<32>(hi)));
+    }
+}
+
+fn merge_accs(acc: &[u64; 8], secret: &[u8], start: u64) -> u64 {
+    let mut r = start;
+    for i in 0..4 {
+        r = r.wrapping_add(mul128_fold64(
+            acc[2 * i] ^ read64(secret, 16 * i),
+            acc[2 * i + 1] ^ read64(secret, 16 * i + 8),
+        ));
+    }
+    avalanche(r)
+}
+
+#[derive(Clone)]
+enum Secret {
+    Default,
+    Seeded([u8; XXH3_SECRET_DEFAULT_SIZE]),
+    #[cfg(feature = "alloc")]
+    Custom(Box<[u8]>),
+}
+
+///
+/// The streaming state shared by [`XXH3_64`] and [`XXH3_128`].  Input is held back in `buf` until
+/// more arrives, so at the end there's always at least one byte - and the whole input, if it's
+/// short enough not to use the accumulators.
+#[derive(Clone)]
+struct State {
+    acc: [u64; 8],
+    secret: Secret,
+    seed: u64,
+    buf: [u8; BUFFER_SIZE],
+    buf_len: usize,
+    /// the last stripe consumed, for the final stripe that overlaps the previous input
+    last_stripe: [u8; STRIPE_LEN],
+    stripes_in_block: usize,
+    total_len: u64,
+    /// the highest level the kernels run at, on top of the [`dispatch::level`]
+    level: Level,
+}
+impl State {
+    fn new(secret: Secret, seed: u64) -> Self {
+        Self {
+            acc: INIT_ACC,
+            secret,
+            seed,
+            buf: [0; BUFFER_SIZE],
+            buf_len: 0,
+            last_stripe: [0; STRIPE_LEN],
+            stripes_in_block: 0,
+            total_len: 0,
+            level: Level::Avx512,
+        }
+    }
+
+    fn with_seed(seed: u64) -> Self {
+        if seed == 0 {
+            Self::new(Secret::Default, 0)
+        } else {
+            Self::new(Secret::Seeded(secret_from_seed(seed)), seed)
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    fn with_secret(secret: &[u8]) -> Option<Self> {
+        if secret.len() < XXH3_SECRET_SIZE_MIN {
+            return None;
+        }
+        Some(Self::new(Secret::Custom(secret.into()), 0))
+    }
+
+    /// The secret for the accumulator loop
+    fn long_secret(&self) -> &[u8] {
+        match &self.secret {
+            Secret::Default => &DEFAULT_SECRET,
+            Secret::Seeded(s) => s,
+            #[cfg(feature = "alloc")]
+            Secret::Custom(s) => s,
+        }
+    }
+
+    /// The secret and seed for inputs of up to 240 bytes, seeded hashing uses the default
+    /// secret and mixes the seed in directly.
+    fn short_secret(&self) -> (&[u8], u64) {
+        #[cfg(feature = "alloc")]
+        if let Secret::Custom(s) = &self.secret {
+            return (s, 0);
+        }
+        (&DEFAULT_SECRET, self.seed)
+    }
+
+    fn stripes_per_block(&self) -> usize {
+        (self.long_secret().len() - STRIPE_LEN) / SECRET_CONSUME_RATE
+    }
+
+    fn accumulate(&self, acc: &mut [u64; 8], input: &[u8], secret: &[u8], n: usize) {
+        match self.level.min(dispatch::level()) {
+            #[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+            Level::Sse41 => unsafe { accumulate_sse2(acc, input, secret, n) },
+            #[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+            Level::Avx2 | Level::Avx512 => unsafe { accumulate_avx2(acc, input, secret, n) },
+            _ => accumulate_scalar(acc, input, secret, n),
+        }
+    }
+
+    fn scramble(&self, acc: &mut [u64; 8]) {
+        let secret = self.long_secret();
+        let secret = &secret[secret.len() - STRIPE_LEN..];
+        match self.level.min(dispatch::level()) {
+            #[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+            Level::Sse41 => unsafe { scramble_sse2(acc, secret) },
+            #[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+            Level::Avx2 | Level::Avx512 => unsafe { scramble_avx2(acc, secret) },
+            _ => scramble_scalar(acc, secret),
+        }
+    }
+
+    ///
+    /// Runs the whole stripes of `input` into `acc`, scrambling at each block boundary.
+    fn consume(&self, acc: &mut [u64; 8], stripes_in_block: &mut usize, mut input: &[u8]) {
+        let per_block = self.stripes_per_block();
+        let mut n = input.len() / STRIPE_LEN;
+        while n > 0 {
+            let take = n.min(per_block - *stripes_in_block);
+            let secret = &self.long_secret()[*stripes_in_block * SECRET_CONSUME_RATE..];
+            self.accumulate(acc, input, secret, take);
+            *stripes_in_block += take;
+            if *stripes_in_block == per_block {
+                self.scramble(acc);
+                *stripes_in_block = 0;
+            }
+            input = &input[take * STRIPE_LEN..];
+            n -= take;
+        }
+    }
+
+    fn consume_self(&mut self, input: &[u8]) {
+        let (mut acc, mut stripes) = (self.acc, self.stripes_in_block);
+        self.consume(&mut acc, &mut stripes, input);
+        (self.acc, self.stripes_in_block) = (acc, stripes);
+        self.last_stripe
+            .copy_from_slice(&input[input.len() - STRIPE_LEN..]);
+    }
+
+    fn write(&mut self, mut v: &[u8]) {
+        self.total_len += v.len() as u64;
+        if self.buf_len + v.len() <= BUFFER_SIZE {
+            self.buf[self.buf_len..self.buf_len + v.len()].copy_from_slice(v);
+            self.buf_len += v.len();
+            return;
+        }
+        if self.buf_len > 0 {
+            let (a, b) = v.split_at(BUFFER_SIZE - self.buf_len);
+            self.buf[self.buf_len..].copy_from_slice(a);
+            let buf = self.buf;
+            self.consume_self(&buf);
+            self.buf_len = 0;
+            v = b;
+        }
+        if v.len() > BUFFER_SIZE {
+            // everything but the last (partial or whole) stripe
+            let n = (v.len() - 1) / STRIPE_LEN * STRIPE_LEN;
+            let (a, b) = v.split_at(n);
+            self.consume_self(a);
+            v = b;
+        }
+        self.buf[..v.len()].copy_from_slice(v);
+        self.buf_len = v.len();
+    }
+
+    ///
+    /// Finishes the accumulators for an input longer than 240 bytes.
+    fn finish_long(&self) -> [u64; 8] {
+        let (mut acc, mut stripes) = (self.acc, self.stripes_in_block);
+        let buffered = &self.buf[..self.buf_len];
+        let whole = (self.buf_len - 1) / STRIPE_LEN * STRIPE_LEN;
+        self.consume(&mut acc, &mut stripes, &buffered[..whole]);
+
+        let mut last = [0u8; STRIPE_LEN];
+        if self.buf_len >= STRIPE_LEN {
+            last.copy_from_slice(&buffered[self.buf_len - STRIPE_LEN..]);
+        } else {
+            let from_prev = STRIPE_LEN - self.buf_len;
+            last[..from_prev].copy_from_slice(&self.last_stripe[self.buf_len..]);
+            last[from_prev..].copy_from_slice(buffered);
+        }
+        let secret = self.long_secret();
+        let secret = &secret[secret.len() - STRIPE_LEN - SECRET_LASTACC_START..];
+        self.accumulate(&mut acc, &last, secret, 1);
+        acc
+    }
+
+    fn finish64(&self) -> u64 {
+        if self.total_len <= MIDSIZE_MAX as u64 {
+            let (secret, seed) = self.short_secret();
+            return hash64_short(&self.buf[..self.buf_len], secret, seed);
+        }
+        let acc = self.finish_long();
+        let start = self.total_len.wrapping_mul(PRIME64_1);
+        merge_accs(&acc, &self.long_secret()[SECRET_MERGEACCS_START..], start)
+    }
+
+    fn finish128(&self) -> u128 {
+        if self.total_len <= MIDSIZE_MAX as u64 {
+            let (secret, seed) = self.short_secret();
+            return hash128_short(&self.buf[..self.buf_len], secret, seed);
+        }
+        let acc = self.finish_long();
+        let secret = self.long_secret();
+        let lo = merge_accs(
+            &acc,
+            &secret[SECRET_MERGEACCS_START..],
+            self.total_len.wrapping_mul(PRIME64_1),
+        );
+        let hi = merge_accs(
+            &acc,
+            &secret[secret.len() - STRIPE_LEN - SECRET_MERGEACCS_START..],
+            !self.total_len.wrapping_mul(PRIME64_2),
+        );
+        to_u128(lo, hi)
+    }
+}
+
+macro_rules! xxh3_impl {
+    ($(#[$meta:meta])* $name:ident, $ty:ty, $finish:ident) => {
+        $(#[$meta])*
+        #[derive(Clone)]
+        pub struct $name {
+            state: State,
+        }
+        impl Default for $name {
+            fn default() -> Self {
+                Self::new_seeded(0)
+            }
+        }
+        impl $name {
+            #[must_use]
+            pub fn new() -> Self {
+                Self::default()
+            }
+
+            #[must_use]
+            pub fn new_seeded(seed: u64) -> Self {
+                Self {
+                    state: State::with_seed(seed),
+                }
+            }
+
+            ///
+            /// Creates a hasher keyed by a custom secret, which should look random, such as one
+            /// from [`secret_from_seed`].  Returns `None` if it's shorter than
+            /// [`XXH3_SECRET_SIZE_MIN`].
+            #[must_use]
+            #[cfg(feature = "alloc")]
+            pub fn new_with_secret(secret: &[u8]) -> Option<Self> {
+                Some(Self {
+                    state: State::with_secret(secret)?,
+                })
+            }
+
+            pub fn write(&mut self, v: &[u8]) {
+                self.state.write(v);
+            }
+
+            pub fn hash(mut self, v: &[u8]) -> $ty {
+                self.write(v);
+                self.finish()
+            }
+
+            #[must_use]
+            pub fn finish(self) -> $ty {
+                self.state.$finish()
+            }
+        }
+    };
+}
+
+xxh3_impl!(
+    ///
+    /// Streaming XXH3 64 bit hasher
+    XXH3_64,
+    u64,
+    finish64
+);
+xxh3_impl!(
+    ///
+    /// Streaming XXH3 128 bit hasher, the `u128` holds the canonical high half in its upper bits
+    XXH3_128,
+    u128,
+    finish128
+);
+impl_io_write!(XXH3_64 => u64);
+impl_io_write!(XXH3_128 => u128);
+impl_hasher!(
+    ///
+    /// Creates [`XXH3_64`] hashers with a fixed seed, for `HashMap` and friends
+    BuildXXH3_64,
+    XXH3_64,
+    u64
+);
+impl_hasher!(
+    ///
+    /// Creates [`XXH3_128`] hashers with a fixed seed, for `HashMap` and friends.  The hashers
+    /// keep the low 64 bits of the hash.
+    BuildXXH3_128,
+    XXH3_128,
+    u64
+);
+
+#[cfg(test)]
+mod tests {
+    use crate::dispatch::Level;
+    use crate::xxhash::tests::{sanity_buffer, PRIME32, PRIME64};
+    use crate::xxhash::{secret_from_seed, XXH3_128, XXH3_64, XXH3_SECRET_SIZE_MIN};
+    use irox_tools::assert_eq_hex;
+
+    const LENS: [usize; 13] = [0, 1, 6, 12, 24, 48, 80, 195, 403, 512, 2048, 2240, 2367];
+
+    #[test]
+    pub fn test_sanity_64() {
+        let buf = sanity_buffer();
+        let tests: [(u64, u64); 13] = [
+            (0x2D06800538D394C2, 0xA8A6B918B2F0364A),
+            (0xC44BDFF4074EECDB, 0x032BE332DD766EF8),
+            (0x27B56A84CD2D7325, 0x84589C116AB59AB9),
+            (0xA713DAF0DFBB77E7, 0xE7303E1B2336DE0E),
+            (0xA3FE70BF9D3510EB, 0x850E80FC35BDD690),
+            (0x397DA259ECBA1F11, 0xADC2CBAA44ACC616),
+            (0xBCDEFBBB2C47C90A, 0xC6DD0CB699532E73),
+            (0xCD94217EE362EC3A, 0xBA68003D370CB3D9),
+            (0xCDEB804D65C6DEA4, 0x6259F6ECFD6443FD),
+            (0x617E49599013CB6B, 0x3CE457DE14C27708),
+            (0xDD59E2C3A5F038E0, 0x66F81670669ABABC),
+            (0x6E73A90539CF2948, 0x757BA8487D1B5247),
+            (0xCB37AEB9E5D361ED, 0xD2DB3415B942B42A),
+        ];
+        for (len, (unseeded, seeded)) in LENS.into_iter().zip(tests) {
+            assert_eq_hex!(unseeded, XXH3_64::new().hash(&buf[..len]));
+            assert_eq_hex!(seeded, XXH3_64::new_seeded(PRIME64).hash(&buf[..len]));
+        }
+    }
+
+    #[test]
+    pub fn test_sanity_128() {
+        let buf = sanity_buffer();
+        let tests: [(usize, u64, u128); 26] = [
+            (0, 0, 0x99AA06D3014798D8_6001C324468D497F),
+            (0, PRIME32, 0x92220AE55E14AB50_5444F7869C671AB0),
+            (1, 0, 0xA6CD5E9392000F6A_C44BDFF4074EECDB),
+            (1, PRIME32, 0x89B99554BA22467C_B53D5557E7F76F8D),
+            (6, 0, 0x082AFE0B8162D12A_3E7039BDDA43CFC6),
+            (6, PRIME32, 0x5A865B5389ABD2B1_269D8F70BE98856E),
+            (12, 0, 0x6E3EFD8FC7802B18_061A192713F69AD9),
+            (12, PRIME32, 0xD7E09D518A3405D3_9BE9F9A67F3C7DFB),
+            (24, 0, 0x0CE966E4678D3761_1E7044D28B1B901D),
+            (24, PRIME32, 0x3162026714A6A243_D7304C54EBAD40A9),
+            (48, 0, 0xA002AC4E5478227E_F942219AED80F67B),
+            (48, PRIME32, 0x163ADDE36C072295_7BA3C3E453A1934E),
+            (81, 0, 0x4952F58181AB0042_5E8BAFB9F95FB803),
+            (81, PRIME32, 0x2724EC7ADC750FB6_703FBB3D7A5F755C),
+            (222, 0, 0x337E09641B948717_F1AEBD597CEC6B3A),
+            (222, PRIME32, 0x91820016621E97F1_AE995BB8AF917A8D),
+            (403, 0, 0x1B6DE21E332DD73D_CDEB804D65C6DEA4),
+            (403, PRIME64, 0xBED311971E0BE8F2_6259F6ECFD6443FD),
+            (512, 0, 0x18D2D110DCC9BCA1_617E49599013CB6B),
+            (512, PRIME64, 0x925D06B8EC5B8040_3CE457DE14C27708),
+            (2048, 0, 0xF736557FD47073A5_DD59E2C3A5F038E0),
+            (2048, PRIME32, 0x7FB03F7E7186C3EA_230D43F30206260B),
+            (2240, 0, 0xCCB134FBFA7CE49D_6E73A90539CF2948),
+            (2240, PRIME32, 0x50A1FE17B338995F_ED385111126FBA6F),
+            (2367, 0, 0xE89C0F6FF369B427_CB37AEB9E5D361ED),
+            (2367, PRIME32, 0xD23AAE4B76C31ECB_6F5360AE69C2F406),
+        ];
+        for (len, seed, exp) in tests {
+            assert_eq_hex!(exp, XXH3_128::new_seeded(seed).hash(&buf[..len]));
+        }
+    }
+
+    #[test]
+    pub fn test_sanity_secret() {
+        let buf = sanity_buffer();
+        let secret = &buf[7..7 + XXH3_SECRET_SIZE_MIN + 11];
+        let tests: [(u64, u128); 13] = [
+            (0x3559D64878C5C66C, 0x5F70F4EA232F1D38_005923CCEECBE8AE),
+            (0x8A52451418B2DA4D, 0x3A66AF5A9819198E_8A52451418B2DA4D),
+            (0x82C90AB0519369AD, 0x376BD91B6432F36D_0B61C8ACA7D4778F),
+            (0x14631E773B78EC57, 0x90A3C2D839F57D0F_AF82F6EBA263D7D8),
+            (0xCDD5542E4A9D9FE8, 0x3476C01AB8B8E821_D9ED8351E0BB5526),
+            (0x33ABD54D094B2534, 0x2C599633A4D78138_D3488D14A0FC9147),
+            (0xE687BA1684965297, 0xCA4A09CE396FEACC_66C0D63926FF0562),
+            (0xA057273F5EECFB20, 0x10A43149CC10FD05_51088F6C8FD00277),
+            (0x14546019124D43B8, 0xE14EEDF084A487F3_14546019124D43B8),
+            (0x7564693DD526E28D, 0x918C0F2C7656AB6D_7564693DD526E28D),
+            (0xD32E975821D6519F, 0xE862D841C07049AF_D32E975821D6519F),
+            (0xB26C938C7AF3A71F, 0x1E89EE710A768055_B26C938C7AF3A71F),
+            (0x293FA8E5173BB5E7, 0x343654A35ACF0DAE_293FA8E5173BB5E7),
+        ];
+        // the 128 bit vectors are at lengths 81 and 222 instead of 80 and 195
+        let lens_128 = [0, 1, 6, 12, 24, 48, 81, 222, 403, 512, 2048, 2240, 2367];
+        for ((len, len_128), (exp64, exp128)) in LENS.into_iter().zip(lens_128).zip(tests) {
+            let h = XXH3_64::new_with_secret(secret).unwrap_or_default();
+            assert_eq_hex!(exp64, h.hash(&buf[..len]));
+            let h = XXH3_128::new_with_secret(secret).unwrap_or_default();
+            assert_eq_hex!(exp128, h.hash(&buf[..len_128]));
+        }
+        assert!(XXH3_64::new_with_secret(&secret[..XXH3_SECRET_SIZE_MIN - 1]).is_none());
+    }
+
+    #[test]
+    pub fn test_seed_matches_derived_secret() {
+        let buf = sanity_buffer();
+        let secret = secret_from_seed(PRIME64);
+        for len in [241, 1024, 1025, 4000] {
+            let exp = XXH3_64::new_seeded(PRIME64).hash(&buf[..len]);
+            let h = XXH3_64::new_with_secret(&secret).unwrap_or_default();
+            assert_eq_hex!(exp, h.hash(&buf[..len]));
+        }
+    }
+
+    #[test]
+    pub fn test_streaming() {
+        let buf = sanity_buffer();
+        for len in [
+            0, 17, 240, 241, 255, 256, 257, 300, 1023, 1024, 1025, 2048, 4160,
+        ] {
+            let exp64 = XXH3_64::new_seeded(PRIME32).hash(&buf[..len]);
+            let exp128 = XXH3_128::new_seeded(PRIME32).hash(&buf[..len]);
+            for step in [1, 7, 63, 64, 65, 255, 256, 257, 1000] {
+                for level in Level::ALL {
+                    let mut h64 = XXH3_64::new_seeded(PRIME32);
+                    let mut h128 = XXH3_128::new_seeded(PRIME32);
+                    h64.state.level = level;
+                    h128.state.level = level;
+                    for c in buf[..len].chunks(step) {
+                        h64.write(c);
+                        h128.write(c);
+                    }
+                    assert_eq_hex!(exp64, h64.finish());
+                    assert_eq_hex!(exp128, h128.finish());
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,265 @@
+// SPDX-License-Identifier: MIT
+// Copyright 2025 IROX Contributors
+//
+
+//!
+//! Runs `irox-b2sum` against check files made by GNU `b2sum` (blake2b) and by Python's
+//! `hashlib` (blake2s, and the blake2sp/bp trees built from it).  The inputs are regenerated here,
+//! each file's bytes are `i % 251`.
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+const VECTORS: &str = "./doc/b2sumtestvectors";
+
+/// Names and lengths of the hashed files, in the order the check files list them.  `large.bin`
+/// is big enough to be hashed through a memory mapping.
+const FILES: &[(&str, usize)] = &[
+    ("back\\slash", 4097),
+    ("block", 128),
+    ("empty", 0),
+    ("large.bin", 3 * 1024 * 1024 + 17),
+    ("odd size.bin", 1000),
+    ("one", 1),
+];
+
+fn data(len: usize) -> Vec<u8> {
+    (0..len).map(|i| (i % 251) as u8).collect()
+}
+
+///
+/// A scratch directory holding the inputs, removed on drop
+struct Scratch {
+    dir: PathBuf,
+}
+
+impl Scratch {
+    fn new(name: &str) -> std::io::Result<Scratch> {
+        let dir = std::env::temp_dir().join(format!("irox-b2sum-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir)?;
+        let scratch = Scratch { dir };
+        for (file, len) in FILES {
+            std::fs::write(scratch.dir.join(file), data(*len))?;
+        }
+        Ok(scratch)
+    }
+
+    fn command(&self) -> Command {
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_irox-b2sum"));
+        cmd.current_dir(&self.dir);
+        cmd
+    }
+
+    fn run(&self, args: &[&str]) -> std::io::Result<Output> {
+        self.command().args(args).output()
+    }
+
+    fn path(&self, name: &str) -> PathBuf {
+        self.dir.join(name)
+    }
+}
+
+impl Drop for Scratch {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+fn vectors(name: &str) -> std::io::Result<String> {
+    std::fs::read_to_string(Path::new(VECTORS).join(name))
+}
+
+fn stdout(out: &Output) -> String {
+    String::from_utf8_lossy(&out.stdout).into_owned()
+}
+
+///
+/// Runs `irox-b2sum` with `args`, returning its stdout if it succeeded
+fn run_ok(scratch: &Scratch, args: &[&str]) -> Result<String, String> {
+    let out = scratch.run(args).map_err(|e| e.to_string())?;
+    if !out.status.success() {
+        return Err(format!("{args:?} failed: {out:?}"));
+    }
+    Ok(stdout(&out))
+}
+
+///
+/// Hashes [`FILES`] with `args` and expects exactly the reference check file, then checks the
+/// reference file and expects every file to be OK.
+fn assert_matches_reference(args: &[&str], reference: &str) {
+    let scratch = Scratch::new(reference);
+    assert!(scratch.is_ok(), "{:?}", scratch.err());
+    let Ok(scratch) = scratch else {
+        return;
+    };
+    let expected = vectors(reference).map_err(|e| e.to_string());
+    let mut all = args.to_vec();
+    all.extend(FILES.iter().map(|(f, _)| *f));
+    assert_eq!(expected, run_ok(&scratch, &all));
+
+    let check = std::fs::canonicalize(Path::new(VECTORS).join(reference));
+    assert!(check.is_ok(), "{check:?}");
+    let Ok(check) = check else {
+        return;
+    };
+    let check = check.to_string_lossy();
+    let mut all = args
+        .iter()
+        .copied()
+        .filter(|a| *a != "--tag")
+        .collect::<Vec<_>>();
+    all.extend(["-c", check.as_ref()]);
+    let expected = FILES
+        .iter()
+        .map(|(f, _)| match f.contains('\\') {
+            true => format!("\\{}: OK\n", f.replace('\\', "\\\\")),
+            false => format!("{f}: OK\n"),
+        })
+        .collect::<String>();
+    assert_eq!(Ok(expected), run_ok(&scratch, &all));
+}
+
+#[test]
+pub fn test_blake2b() {
+    assert_matches_reference(&[], "blake2b.txt");
+}
+
+#[test]
+pub fn test_blake2b_256() {
+    assert_matches_reference(&["-l", "256"], "blake2b-256.txt");
+}
+
+#[test]
+pub fn test_blake2b_160_tag() {
+    assert_matches_reference(&["--tag", "--length=160"], "blake2b-160-tag.txt");
+}
+
+#[test]
+pub fn test_blake2s() {
+    assert_matches_reference(&["-a", "blake2s"], "blake2s.txt");
+}
+
+#[test]
+pub fn test_blake2sp() {
+    assert_matches_reference(&["-aBLAKE2sp"], "blake2sp.txt");
+}
+
+#[test]
+pub fn test_blake2bp() {
+    assert_matches_reference(&["--algorithm", "blake2bp"], "blake2bp.txt");
+}
+
+#[test]
+pub fn test_stdin() {
+    let scratch = Scratch::new("stdin");
+    assert!(scratch.is_ok(), "{:?}", scratch.err());
+    let Ok(scratch) = scratch else {
+        return;
+    };
+    let out = std::fs::File::open(scratch.path("odd size.bin")).and_then(|input| {
+        scratch
+            .command()
+            .args(["-a", "blake2s", "-"])
+            .stdin(input)
+            .output()
+    });
+    let expected = vectors("blake2s.txt").map(|v| {
+        v.lines()
+            .find_map(|l| l.strip_suffix("  odd size.bin"))
+            .map(|hash| format!("{hash}  -\n"))
+    });
+    assert_eq!(
+        expected.map_err(|e| e.kind()),
+        out.map(|o| Some(stdout(&o))).map_err(|e| e.kind())
+    );
+}
+
+#[test]
+pub fn test_check_failures() {
+    let scratch = Scratch::new("failures");
+    assert!(scratch.is_ok(), "{:?}", scratch.err());
+    let Ok(scratch) = scratch else {
+        return;
+    };
+    let hash = |name: &str| run_ok(&scratch, &["-l", "128", name]).unwrap_or_default();
+    let good = hash("one");
+    let bad = hash("block").replace("block", "one");
+    let missing = hash("empty").replace("empty", "missing");
+    assert!(!good.is_empty() && !bad.is_empty() && !missing.is_empty());
+    let list = format!("{good}{bad}{missing}not a checksum line\n");
+    let written = std::fs::write(scratch.path("list"), list);
+    assert!(written.is_ok(), "{written:?}");
+
+    let out = scratch.run(&["-c", "list"]);
+    assert!(out.as_ref().is_ok_and(|o| !o.status.success()), "{out:?}");
+    assert_eq!(
+        Some("one: OK\none: FAILED\nmissing: FAILED open or read\n".to_string()),
+        out.as_ref().ok().map(stdout)
+    );
+    let stderr = out
+        .map(|o| String::from_utf8_lossy(&o.stderr).into_owned())
+        .unwrap_or_default();
+    assert!(
+        stderr.contains("1 line is improperly formatted"),
+        "{stderr}"
+    );
+    assert!(
+        stderr.contains("1 listed file could not be read"),
+        "{stderr}"
+    );
+    assert!(
+        stderr.contains("1 computed checksum did NOT match"),
+        "{stderr}"
+    );
+
+    let out = scratch.run(&["-c", "--quiet", "list"]);
+    assert_eq!(
+        Some("one: FAILED\nmissing: FAILED open or read\n".to_string()),
+        out.as_ref().ok().map(stdout)
+    );
+    let out = scratch.run(&["-c", "--status", "list"]);
+    assert!(
+        out.as_ref()
+            .is_ok_and(|o| !o.status.success() && o.stdout.is_empty() && o.stderr.is_empty()),
+        "{out:?}"
+    );
+
+    let written = std::fs::write(scratch.path("list"), &good);
+    assert!(written.is_ok(), "{written:?}");
+    assert_eq!(
+        Ok(String::new()),
+        run_ok(&scratch, &["-c", "--status", "list"])
+    );
+}
+
+#[test]
+pub fn test_bad_args() {
+    let scratch = Scratch::new("args");
+    assert!(scratch.is_ok(), "{:?}", scratch.err());
+    let Ok(scratch) = scratch else {
+        return;
+    };
+    for args in [
+        &["-a", "md5", "one"][..],
+        &["-l", "12", "one"],
+        &["-a", "blake2s", "-l", "512", "one"],
+        &["--tag", "-c", "one"],
+        &["--status", "one"],
+        &["--bogus"],
+        &["-a"],
+    ] {
+        let out = scratch.run(args);
+        assert!(
+            out.as_ref()
+                .is_ok_and(|o| !o.status.success() && o.stdout.is_empty()),
+            "{args:?}: {out:?}"
+        );
+    }
+    let out = scratch.run(&["missing", "one"]);
+    assert!(
+        out.as_ref()
+            .is_ok_and(|o| !o.status.success() && stdout(o).ends_with("  one\n")),
+        "{out:?}"
+    );
+}
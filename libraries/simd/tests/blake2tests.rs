@@ -2,75 +2,105 @@
 // Copyright 2025 IROX Contributors
 //
 
+//!
+//! Known-answer tests from `doc/blake2testvectors`:
+//!
+//! * `blake2-kat.json` holds the reference implementation's known answers, laid out like its
+//!   `testvectors/blake2-kat.json`: 256 unkeyed and 256 keyed vectors for each of blake2s,
+//!   blake2sp, blake2b and blake2bp, then keyed blake2xs and blake2xb vectors of every output
+//!   length from 1 to 256 bytes.
+//! * `blake2s-truncated-kat.json` has the same layout and covers the shorter BLAKE2s and
+//!   BLAKE2sp digests, named like `blake2s-128`.  The reference KAT only has full-length ones.
+//!
+
 use irox_bits::Error;
-use irox_simd::blake2::BLAKE2s256;
+use irox_simd::blake2::{
+    BLAKE2Xb, BLAKE2Xs, BLAKE2b512, BLAKE2bp, BLAKE2bp512, BLAKE2s128, BLAKE2s160, BLAKE2s224,
+    BLAKE2s256, BLAKE2sp, BLAKE2sp128, BLAKE2sp160, BLAKE2sp224, BLAKE2sp256, Blake2sParams,
+};
 use irox_tools::assert_eq_hex_slice;
-// use irox_tools::hash::{BLAKE2s256};
 use std::io::{BufRead, BufReader};
 
+const KAT: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/doc/blake2testvectors/blake2-kat.json"
+);
+const TRUNCATED_KAT: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/doc/blake2testvectors/blake2s-truncated-kat.json"
+);
+
 struct Test {
     msg: Vec<u8>,
     key: Vec<u8>,
     hash: Vec<u8>,
 }
 
-fn parse_kat_file(path: &str) -> Result<Vec<Test>, Error> {
+fn hex_field(val: &str) -> Result<Vec<u8>, Error> {
+    let mut buf = Vec::<u8>::new();
+    irox_tools::hex::from_hex_into(val.trim_matches('"'), &mut buf)?;
+    Ok(buf)
+}
+
+///
+/// Line-based reader for the KAT json files, which have one field per line.  Returns the tests
+/// for the hash named `hash`.
+fn parse_kat_file(path: &str, hash: &str) -> Result<Vec<Test>, Error> {
     let file = std::fs::OpenOptions::new().read(true).open(path)?;
     let file = BufReader::new(file);
 
     let mut tests = Vec::<Test>::new();
-
-    let mut keyb: Option<Vec<u8>> = None;
+    let mut name: Option<String> = None;
     let mut msg: Option<Vec<u8>> = None;
+    let mut keyb: Option<Vec<u8>> = None;
 
     for line in file.lines() {
         let line = line?;
-        let line = line.trim();
+        let line = line.trim().trim_end_matches(',');
 
-        let Some((key, val)) = line.split_once(":") else {
+        let Some((field, val)) = line.split_once(":") else {
             continue;
         };
         let val = val.trim();
-        if key.starts_with("in") {
-            let mut buf = Vec::<u8>::new();
-            let res = irox_tools::hex::from_hex_into(val, &mut buf)?;
-            msg = Some(buf);
-        } else if key.starts_with("key") {
-            let mut buf = Vec::<u8>::new();
-            let res = irox_tools::hex::from_hex_into(val, &mut buf)?;
-            if res > 0 {
-                keyb = Some(buf);
-            }
-        } else if key.starts_with("hash") {
-            let mut buf = Vec::<u8>::new();
-            let res = irox_tools::hex::from_hex_into(val, &mut buf)?;
-            if res > 0 {
-                let Some(key) = keyb.take() else {
+        match field.trim_matches('"') {
+            "hash" => name = Some(val.trim_matches('"').to_string()),
+            "in" => msg = Some(hex_field(val)?),
+            "key" => keyb = Some(hex_field(val)?),
+            "out" => {
+                let (Some(name), Some(msg), Some(key)) = (name.take(), msg.take(), keyb.take())
+                else {
                     continue;
                 };
-                let Some(msg) = msg.take() else {
-                    continue;
-                };
-                tests.push(Test {
-                    msg,
-                    key,
-                    hash: buf,
-                })
+                if name == hash {
+                    tests.push(Test {
+                        msg,
+                        key,
+                        hash: hex_field(val)?,
+                    });
+                }
             }
+            _ => {}
         }
     }
     Ok(tests)
 }
+
 macro_rules! impl_test {
-    ($name:ident, $filepath:literal, $testcount:literal, $test:ty) => {
+    ($name:ident, $filepath:expr, $hash:literal, $testcount:literal, $test:ty) => {
         #[test]
         pub fn $name() -> Result<(), Error> {
-            let tests = parse_kat_file($filepath)?;
+            let tests = parse_kat_file($filepath, $hash)?;
 
             assert_eq!($testcount, tests.len());
             for (idx, test) in tests.iter().enumerate() {
                 let res = <$test>::new(test.key.as_slice()).hash(test.msg.as_slice());
                 assert_eq_hex_slice!(test.hash.as_slice(), res, format!("{idx}: failed"));
+
+                let mut h = <$test>::new(test.key.as_slice());
+                for c in test.msg.chunks(idx % 67 + 1) {
+                    h.write(c);
+                }
+                assert_eq_hex_slice!(test.hash.as_slice(), h.finish(), format!("{idx}: failed"));
                 println!("{} {idx}: passed", stringify!($name));
             }
 
@@ -79,9 +109,120 @@ macro_rules! impl_test {
     };
 }
 
+impl_test!(test_blake2s, KAT, "blake2s", 512, BLAKE2s256);
+impl_test!(test_blake2b, KAT, "blake2b", 512, BLAKE2b512);
+impl_test!(test_blake2sp, KAT, "blake2sp", 512, BLAKE2sp256);
+impl_test!(test_blake2bp, KAT, "blake2bp", 512, BLAKE2bp512);
+impl_test!(
+    test_blake2s128,
+    TRUNCATED_KAT,
+    "blake2s-128",
+    22,
+    BLAKE2s128
+);
+impl_test!(
+    test_blake2s160,
+    TRUNCATED_KAT,
+    "blake2s-160",
+    22,
+    BLAKE2s160
+);
+impl_test!(
+    test_blake2s224,
+    TRUNCATED_KAT,
+    "blake2s-224",
+    22,
+    BLAKE2s224
+);
 impl_test!(
-    test_blake2s,
-    "./doc/blake2testvectors/blake2s-kat.txt",
-    256,
-    BLAKE2s256
+    test_blake2sp128,
+    TRUNCATED_KAT,
+    "blake2sp-128",
+    22,
+    BLAKE2sp128
 );
+impl_test!(
+    test_blake2sp160,
+    TRUNCATED_KAT,
+    "blake2sp-160",
+    22,
+    BLAKE2sp160
+);
+impl_test!(
+    test_blake2sp224,
+    TRUNCATED_KAT,
+    "blake2sp-224",
+    22,
+    BLAKE2sp224
+);
+
+#[test]
+pub fn test_blake2sp_multithreaded() -> Result<(), Error> {
+    let tests = parse_kat_file(KAT, "blake2sp")?;
+    assert_eq!(512, tests.len());
+    for (idx, test) in tests.iter().enumerate() {
+        let res = BLAKE2sp::<32>::hash_multithreaded(test.key.as_slice(), test.msg.as_slice());
+        assert_eq_hex_slice!(test.hash.as_slice(), res, format!("{idx}: failed"));
+    }
+    Ok(())
+}
+
+#[test]
+pub fn test_blake2bp_multithreaded() -> Result<(), Error> {
+    let tests = parse_kat_file(KAT, "blake2bp")?;
+    assert_eq!(512, tests.len());
+    for (idx, test) in tests.iter().enumerate() {
+        let res = BLAKE2bp::<64>::hash_multithreaded(test.key.as_slice(), test.msg.as_slice());
+        assert_eq_hex_slice!(test.hash.as_slice(), res, format!("{idx}: failed"));
+    }
+    Ok(())
+}
+
+#[test]
+pub fn test_blake2s_params_mac() -> Result<(), Error> {
+    let tests = parse_kat_file(KAT, "blake2s")?;
+    assert_eq!(512, tests.len());
+    let params = Blake2sParams::new();
+    for (idx, test) in tests.iter().enumerate() {
+        let mut h = BLAKE2s256::new_with_params(test.key.as_slice(), &params);
+        h.write(test.msg.as_slice());
+        assert!(h.verify_mac(test.hash.as_slice()), "{idx}: failed");
+    }
+    Ok(())
+}
+
+#[test]
+pub fn test_blake2xs() -> Result<(), Error> {
+    let tests = parse_kat_file(KAT, "blake2xs")?;
+    assert_eq!(256, tests.len());
+    for (idx, test) in tests.iter().enumerate() {
+        let mut h = BLAKE2Xs::new(test.key.as_slice(), test.hash.len() as u16);
+        h.write(test.msg.as_slice());
+        let mut out = vec![0u8; test.hash.len()];
+        assert_eq!(out.len(), h.finish_xof().fill(&mut out));
+        assert_eq_hex_slice!(
+            test.hash.as_slice(),
+            out.as_slice(),
+            format!("{idx}: failed")
+        );
+    }
+    Ok(())
+}
+
+#[test]
+pub fn test_blake2xb() -> Result<(), Error> {
+    let tests = parse_kat_file(KAT, "blake2xb")?;
+    assert_eq!(256, tests.len());
+    for (idx, test) in tests.iter().enumerate() {
+        let mut h = BLAKE2Xb::new(test.key.as_slice(), test.hash.len() as u32);
+        h.write(test.msg.as_slice());
+        let mut out = vec![0u8; test.hash.len()];
+        assert_eq!(out.len(), h.finish_xof().fill(&mut out));
+        assert_eq_hex_slice!(
+            test.hash.as_slice(),
+            out.as_slice(),
+            format!("{idx}: failed")
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,113 @@
+// SPDX-License-Identifier: MIT
+// Copyright 2025 IROX Contributors
+//
+
+#![allow(clippy::tests_outside_test_module)]
+
+use irox_bits::Error;
+use irox_simd::blake3::BLAKE3;
+use irox_tools::assert_eq_hex_slice;
+use std::io::{BufRead, BufReader};
+
+const CONTEXT: &str = "BLAKE3 2019-12-27 16:29:52 test vectors context";
+
+struct Test {
+    input_len: usize,
+    hash: Vec<u8>,
+    keyed_hash: Vec<u8>,
+    derive_key: Vec<u8>,
+}
+
+fn hex_field(val: &str) -> Result<Vec<u8>, Error> {
+    let mut buf = Vec::<u8>::new();
+    irox_tools::hex::from_hex_into(val.trim_matches('"'), &mut buf)?;
+    Ok(buf)
+}
+
+///
+/// Line-based reader for the official `test_vectors.json`, which has one field per line.
+fn parse_vectors_file(path: &str) -> Result<([u8; 32], Vec<Test>), Error> {
+    let file = std::fs::OpenOptions::new().read(true).open(path)?;
+    let file = BufReader::new(file);
+
+    let mut key = [0u8; 32];
+    let mut tests = Vec::<Test>::new();
+    let mut input_len: Option<usize> = None;
+    let mut hash: Option<Vec<u8>> = None;
+    let mut keyed_hash: Option<Vec<u8>> = None;
+
+    for line in file.lines() {
+        let line = line?;
+        let line = line.trim().trim_end_matches(',');
+
+        let Some((name, val)) = line.split_once(":") else {
+            continue;
+        };
+        let val = val.trim();
+        match name.trim_matches('"') {
+            "key" => key.copy_from_slice(val.trim_matches('"').as_bytes()),
+            "input_len" => input_len = val.parse().ok(),
+            "hash" => hash = Some(hex_field(val)?),
+            "keyed_hash" => keyed_hash = Some(hex_field(val)?),
+            "derive_key" => {
+                let (Some(input_len), Some(hash), Some(keyed_hash)) =
+                    (input_len.take(), hash.take(), keyed_hash.take())
+                else {
+                    continue;
+                };
+                tests.push(Test {
+                    input_len,
+                    hash,
+                    keyed_hash,
+                    derive_key: hex_field(val)?,
+                });
+            }
+            _ => {}
+        }
+    }
+    Ok((key, tests))
+}
+
+fn check(idx: usize, mode: &str, expected: &[u8], hasher: BLAKE3, input: &[u8]) {
+    let mut h = hasher.clone();
+    h.write(input);
+    let mut out = vec![0u8; expected.len()];
+    h.finish_xof().fill(&mut out);
+    assert_eq_hex_slice!(expected, out.as_slice(), format!("{idx} {mode}: failed"));
+    assert_eq_hex_slice!(
+        expected.get(..32).unwrap_or_default(),
+        hasher.hash(input),
+        format!("{idx} {mode}: failed")
+    );
+}
+
+#[test]
+pub fn test_official_vectors() {
+    let vectors = parse_vectors_file(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/doc/blake3testvectors/test_vectors.json"
+    ));
+    assert!(vectors.is_ok(), "{:?}", vectors.err());
+    let Ok((key, tests)) = vectors else {
+        return;
+    };
+    assert!(!tests.is_empty());
+    for (idx, test) in tests.iter().enumerate() {
+        let input: Vec<u8> = (0..test.input_len).map(|i| (i % 251) as u8).collect();
+        check(idx, "hash", &test.hash, BLAKE3::new(), &input);
+        check(
+            idx,
+            "keyed_hash",
+            &test.keyed_hash,
+            BLAKE3::new_keyed(&key),
+            &input,
+        );
+        check(
+            idx,
+            "derive_key",
+            &test.derive_key,
+            BLAKE3::new_derive_key(CONTEXT),
+            &input,
+        );
+    }
+}
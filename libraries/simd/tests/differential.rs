@@ -0,0 +1,215 @@
+// SPDX-License-Identifier: MIT
+// Copyright 2025 IROX Contributors
+//
+
+//!
+//! Differential tests: random inputs, split at random write boundaries, hashed by the SIMD
+//! hashers and by a scalar reference.  The reference is the irox-tools hasher where there is one,
+//! a bitwise CRC for the CRCs, and otherwise the same hasher given the whole input in one call
+//! (or BLAKE2sp/bp's multithreaded path), which takes different routes through the buffering.
+//!
+
+use irox_simd::blake2::{BLAKE2b, BLAKE2bp, BLAKE2s, BLAKE2sp};
+use irox_simd::blake3::BLAKE3;
+use irox_simd::crc::{Crc32, Crc32c, Crc64Ecma, Crc64Nvme};
+use irox_simd::murmur3::{Murmur3_128, Murmur3_32};
+use irox_simd::sha1::SHA1;
+use irox_simd::sha2::SHA256;
+use irox_simd::xxhash::{XXH3_128, XXH3_64, XXH64};
+use proptest::collection::vec;
+use proptest::prelude::*;
+use proptest::sample::Index;
+
+///
+/// Random input up to `max` bytes, and the points to split it at
+fn input(max: usize) -> impl Strategy<Value = (Vec<u8>, Vec<Index>)> {
+    (vec(any::<u8>(), 0..=max), vec(any::<Index>(), 0..16))
+}
+
+///
+/// Splits `data` at the `cuts`, which may repeat and so produce empty writes
+fn split<'a>(data: &'a [u8], cuts: &[Index]) -> Vec<&'a [u8]> {
+    let mut cuts: Vec<usize> = cuts.iter().map(|c| c.index(data.len() + 1)).collect();
+    cuts.sort_unstable();
+    let mut out = Vec::with_capacity(cuts.len() + 1);
+    let mut rest = data;
+    let mut at = 0;
+    for cut in cuts {
+        let (a, b) = rest.split_at(cut - at);
+        out.push(a);
+        rest = b;
+        at = cut;
+    }
+    out.push(rest);
+    out
+}
+
+///
+/// Writes each chunk to the hasher, then finishes it
+macro_rules! streamed {
+    ($hasher:expr, $chunks:expr) => {{
+        let mut h = $hasher;
+        for c in $chunks {
+            h.write(c);
+        }
+        h.finish()
+    }};
+}
+
+///
+/// Bitwise reflected CRC, the slowest and plainest there is
+fn crc_bitwise(poly: u64, mask: u64, data: &[u8]) -> u64 {
+    let mut crc = mask;
+    for v in data {
+        crc ^= u64::from(*v);
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ poly
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    crc ^ mask
+}
+
+fn check_blake2s<const NN: usize>(
+    key: &[u8],
+    data: &[u8],
+    chunks: &[&[u8]],
+) -> Result<(), TestCaseError> {
+    let exp = irox_tools::hash::BLAKE2s::<NN>::new(key).hash(data);
+    prop_assert_eq!(exp, BLAKE2s::<NN>::new(key).hash(data));
+    prop_assert_eq!(exp, streamed!(BLAKE2s::<NN>::new(key), chunks));
+    Ok(())
+}
+
+fn check_blake2b<const NN: usize>(
+    key: &[u8],
+    data: &[u8],
+    chunks: &[&[u8]],
+) -> Result<(), TestCaseError> {
+    let exp = irox_tools::hash::BLAKE2b::<NN>::new(key).hash(data);
+    prop_assert_eq!(exp, BLAKE2b::<NN>::new(key).hash(data));
+    prop_assert_eq!(exp, streamed!(BLAKE2b::<NN>::new(key), chunks));
+    Ok(())
+}
+
+fn check_blake2sp<const NN: usize>(
+    key: &[u8],
+    data: &[u8],
+    chunks: &[&[u8]],
+) -> Result<(), TestCaseError> {
+    let exp = BLAKE2sp::<NN>::hash_multithreaded(key, data);
+    prop_assert_eq!(exp, BLAKE2sp::<NN>::new(key).hash(data));
+    prop_assert_eq!(exp, streamed!(BLAKE2sp::<NN>::new(key), chunks));
+    Ok(())
+}
+
+fn check_blake2bp<const NN: usize>(
+    key: &[u8],
+    data: &[u8],
+    chunks: &[&[u8]],
+) -> Result<(), TestCaseError> {
+    let exp = BLAKE2bp::<NN>::hash_multithreaded(key, data);
+    prop_assert_eq!(exp, BLAKE2bp::<NN>::new(key).hash(data));
+    prop_assert_eq!(exp, streamed!(BLAKE2bp::<NN>::new(key), chunks));
+    Ok(())
+}
+
+proptest! {
+    #[test]
+    fn test_blake2s(key in vec(any::<u8>(), 0..=32), (data, cuts) in input(1500)) {
+        let chunks = split(&data, &cuts);
+        check_blake2s::<16>(&key, &data, &chunks)?;
+        check_blake2s::<20>(&key, &data, &chunks)?;
+        check_blake2s::<28>(&key, &data, &chunks)?;
+        check_blake2s::<32>(&key, &data, &chunks)?;
+    }
+
+    #[test]
+    fn test_blake2b(key in vec(any::<u8>(), 0..=64), (data, cuts) in input(1500)) {
+        let chunks = split(&data, &cuts);
+        check_blake2b::<20>(&key, &data, &chunks)?;
+        check_blake2b::<32>(&key, &data, &chunks)?;
+        check_blake2b::<48>(&key, &data, &chunks)?;
+        check_blake2b::<64>(&key, &data, &chunks)?;
+    }
+
+    #[test]
+    fn test_blake2sp(key in vec(any::<u8>(), 0..=32), (data, cuts) in input(5000)) {
+        let chunks = split(&data, &cuts);
+        check_blake2sp::<16>(&key, &data, &chunks)?;
+        check_blake2sp::<32>(&key, &data, &chunks)?;
+    }
+
+    #[test]
+    fn test_blake2bp(key in vec(any::<u8>(), 0..=64), (data, cuts) in input(5000)) {
+        let chunks = split(&data, &cuts);
+        check_blake2bp::<32>(&key, &data, &chunks)?;
+        check_blake2bp::<64>(&key, &data, &chunks)?;
+    }
+
+    #[test]
+    fn test_blake3(key in any::<[u8; 32]>(), (data, cuts) in input(10_000)) {
+        let chunks = split(&data, &cuts);
+        prop_assert_eq!(BLAKE3::new().hash(&data), streamed!(BLAKE3::new(), &chunks));
+        prop_assert_eq!(
+            BLAKE3::new_keyed(&key).hash(&data),
+            streamed!(BLAKE3::new_keyed(&key), &chunks)
+        );
+    }
+
+    #[test]
+    fn test_sha(data in vec(any::<u8>(), 0..=1500), cuts in vec(any::<Index>(), 0..16)) {
+        let chunks = split(&data, &cuts);
+        let exp = irox_tools::hash::SHA1::new().hash(&data);
+        prop_assert_eq!(exp, SHA1::new().hash(&data));
+        prop_assert_eq!(exp, streamed!(SHA1::new(), &chunks));
+        let exp = irox_tools::hash::SHA256::new().hash(&data);
+        prop_assert_eq!(exp, SHA256::new().hash(&data));
+        prop_assert_eq!(exp, streamed!(SHA256::new(), &chunks));
+    }
+
+    #[test]
+    fn test_murmur3(seed in any::<u32>(), (data, cuts) in input(1500)) {
+        let chunks = split(&data, &cuts);
+        let exp = irox_tools::hash::murmur3::Murmur3_32::new_seeded(seed).hash(&data);
+        prop_assert_eq!(exp, Murmur3_32::new_seeded(seed).hash(&data));
+        prop_assert_eq!(exp, streamed!(Murmur3_32::new_seeded(seed), &chunks));
+        let seed = u64::from(seed);
+        let exp = irox_tools::hash::murmur3::Murmur3_128::new_seeded(seed).hash(&data);
+        prop_assert_eq!(exp, Murmur3_128::new_seeded(seed).hash(&data));
+        prop_assert_eq!(exp, streamed!(Murmur3_128::new_seeded(seed), &chunks));
+    }
+
+    #[test]
+    fn test_xxhash(seed in any::<u64>(), (data, cuts) in input(3000)) {
+        let chunks = split(&data, &cuts);
+        prop_assert_eq!(
+            XXH64::new_seeded(seed).hash(&data),
+            streamed!(XXH64::new_seeded(seed), &chunks)
+        );
+        prop_assert_eq!(
+            XXH3_64::new_seeded(seed).hash(&data),
+            streamed!(XXH3_64::new_seeded(seed), &chunks)
+        );
+        prop_assert_eq!(
+            XXH3_128::new_seeded(seed).hash(&data),
+            streamed!(XXH3_128::new_seeded(seed), &chunks)
+        );
+    }
+
+    #[test]
+    fn test_crc((data, cuts) in input(5000)) {
+        let chunks = split(&data, &cuts);
+        let exp = crc_bitwise(0xEDB8_8320, 0xFFFF_FFFF, &data);
+        prop_assert_eq!(exp, u64::from(streamed!(Crc32::new(), &chunks)));
+        let exp = crc_bitwise(0x82F6_3B78, 0xFFFF_FFFF, &data);
+        prop_assert_eq!(exp, u64::from(streamed!(Crc32c::new(), &chunks)));
+        let exp = crc_bitwise(0xC96C_5795_D787_0F42, u64::MAX, &data);
+        prop_assert_eq!(exp, streamed!(Crc64Ecma::new(), &chunks));
+        let exp = crc_bitwise(0x9A6C_9329_AC4B_C9B5, u64::MAX, &data);
+        prop_assert_eq!(exp, streamed!(Crc64Nvme::new(), &chunks));
+    }
+}